@@ -26,6 +26,8 @@ criterion_group!(
     bench_compile_and_validate,
     bench_compile_and_validate_v0,
     bench_compile_and_validate_v1,
+    bench_compile_and_validate_large_v1,
+    bench_compile_and_validate_large_parallel_v1,
 );
 criterion_group!(
     bench_instantiate,
@@ -38,10 +40,16 @@ criterion_group!(
     bench_execute_tiny_keccak_v1,
     bench_execute_rev_comp_v0,
     bench_execute_rev_comp_v1,
+    bench_execute_keccak256_v0,
+    bench_execute_keccak256_v1,
+    bench_execute_revcomp_v0,
+    bench_execute_revcomp_v1,
     bench_execute_regex_redux_v0,
     bench_execute_regex_redux_v1,
     bench_execute_count_until_v0,
     bench_execute_count_until_v1,
+    bench_execute_br_table_v0,
+    bench_execute_br_table_v1,
     bench_execute_fac_recursive_v0,
     bench_execute_fac_recursive_v1,
     bench_execute_fac_opt_v0,
@@ -54,6 +62,12 @@ criterion_group!(
     bench_execute_host_calls_v1,
     bench_execute_fibonacci_recursive_v0,
     bench_execute_fibonacci_recursive_v1,
+    bench_execute_fibonacci_iterative_v0,
+    bench_execute_fibonacci_iterative_v1,
+    bench_execute_memory_sum_v0,
+    bench_execute_memory_sum_v1,
+    bench_execute_memory_vec_add_v0,
+    bench_execute_memory_vec_add_v1,
 );
 
 criterion_main!(bench_compile_and_validate, bench_instantiate, bench_execute);
@@ -77,6 +91,51 @@ fn bench_compile_and_validate_v1(c: &mut Criterion) {
     });
 }
 
+/// Returns a large Wasm module with many independent function bodies.
+fn large_module_wasm() -> Vec<u8> {
+    let mut wat = String::from("(module\n");
+    for n in 0..2_000 {
+        wat.push_str(&format!(
+            r#"
+            (func (export "func_{n}") (param $n i64) (result i64) (local $acc i64)
+                (block $exit
+                    (loop $continue
+                        (br_if $exit (i64.eqz (local.get $n)))
+                        (local.set $acc (i64.add (local.get $acc) (i64.mul (local.get $n) (i64.const {n}))))
+                        (local.set $n (i64.sub (local.get $n) (i64.const 1)))
+                        (br $continue)
+                    )
+                )
+                (local.get $acc)
+            )
+            "#
+        ));
+    }
+    wat.push(')');
+    wat2wasm(wat.as_bytes())
+}
+
+fn bench_compile_and_validate_large_v1(c: &mut Criterion) {
+    let wasm_bytes = large_module_wasm();
+    c.bench_function("compile_and_validate/large/v1", |b| {
+        b.iter(|| {
+            let engine = v1::Engine::default();
+            let _module = v1::Module::new(&engine, &wasm_bytes[..]).unwrap();
+        })
+    });
+}
+
+fn bench_compile_and_validate_large_parallel_v1(c: &mut Criterion) {
+    let wasm_bytes = large_module_wasm();
+    let config = v1::Config::default().enable_parallel_compilation(true);
+    c.bench_function("compile_and_validate/large/parallel/v1", |b| {
+        b.iter(|| {
+            let engine = v1::Engine::new(&config);
+            let _module = v1::Module::new(&engine, &wasm_bytes[..]).unwrap();
+        })
+    });
+}
+
 fn bench_instantiate_v0(c: &mut Criterion) {
     let wasm_kernel = load_module_from_file_v0(WASM_KERNEL);
 
@@ -280,6 +339,110 @@ fn bench_execute_rev_comp_v1(c: &mut Criterion) {
     }
 }
 
+/// The offset of the input of the `wat` ports of the `wasm_kernel` workloads.
+const WAT_KERNEL_INPUT: i32 = 1024;
+const KECCAK256_LEN: i32 = 4096;
+/// The first 8 bytes of the Keccak-256 hash of `KECCAK256_LEN` zero bytes.
+const KECCAK256_HASH: i64 = 0xe49d_7951_17e1_baa8_u64 as i64;
+
+fn bench_execute_keccak256_v0(c: &mut Criterion) {
+    let instance = load_instance_from_wat_v0(include_bytes!("wat/keccak.wat"));
+    let args = [Value::I32(WAT_KERNEL_INPUT), Value::I32(KECCAK256_LEN)];
+    c.bench_function("execute/keccak256/v0", |b| {
+        b.iter(|| {
+            let value = instance.invoke_export("keccak256", &args, &mut v0::NopExternals);
+            assert_matches!(value, Ok(Some(Value::I64(KECCAK256_HASH))));
+        })
+    });
+}
+
+fn bench_execute_keccak256_v1(c: &mut Criterion) {
+    let (mut store, instance) = load_instance_from_wat_v1(include_bytes!("wat/keccak.wat"));
+    let keccak256 = instance
+        .get_export(&store, "keccak256")
+        .and_then(v1::Extern::into_func)
+        .unwrap();
+    let args = [Value::I32(WAT_KERNEL_INPUT), Value::I32(KECCAK256_LEN)];
+    let mut result = [Value::I64(0)];
+    c.bench_function("execute/keccak256/v1", |b| {
+        b.iter(|| {
+            keccak256.call(&mut store, &args, &mut result).unwrap();
+            assert_matches!(result, [Value::I64(KECCAK256_HASH)]);
+        })
+    });
+}
+
+/// Returns a pseudo random DNA sequence and its reverse complement.
+fn revcomp_input() -> (Vec<u8>, Vec<u8>) {
+    let mut seed = 1_u32;
+    let input = (0..100_000)
+        .map(|_| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            b"ACGTacgtNn"[(seed >> 16) as usize % 10]
+        })
+        .collect::<Vec<_>>();
+    let output = input
+        .iter()
+        .rev()
+        .map(|byte| match byte.to_ascii_uppercase() {
+            b'A' => b'T',
+            b'C' => b'G',
+            b'G' => b'C',
+            b'T' => b'A',
+            other => other,
+        })
+        .collect::<Vec<_>>();
+    (input, output)
+}
+
+fn bench_execute_revcomp_v0(c: &mut Criterion) {
+    let instance = load_instance_from_wat_v0(include_bytes!("wat/rev_complement.wat"));
+    let (input, output) = revcomp_input();
+    let memory = instance
+        .export_by_name("memory")
+        .and_then(|export| export.as_memory().cloned())
+        .unwrap();
+    memory.set(WAT_KERNEL_INPUT as u32, &input).unwrap();
+    let args = [Value::I32(WAT_KERNEL_INPUT), Value::I32(input.len() as i32)];
+    let rev_complement = || {
+        instance
+            .invoke_export("rev_complement", &args, &mut v0::NopExternals)
+            .unwrap();
+    };
+    rev_complement();
+    let mut result = vec![0x00_u8; output.len()];
+    memory.get_into(WAT_KERNEL_INPUT as u32, &mut result).unwrap();
+    assert_eq!(result, output);
+    c.bench_function("execute/revcomp/v0", |b| b.iter(rev_complement));
+}
+
+fn bench_execute_revcomp_v1(c: &mut Criterion) {
+    let (mut store, instance) =
+        load_instance_from_wat_v1(include_bytes!("wat/rev_complement.wat"));
+    let (input, output) = revcomp_input();
+    let memory = instance
+        .get_export(&store, "memory")
+        .and_then(v1::Extern::into_memory)
+        .unwrap();
+    memory
+        .write(&mut store, WAT_KERNEL_INPUT as usize, &input)
+        .unwrap();
+    let rev_complement = instance
+        .get_export(&store, "rev_complement")
+        .and_then(v1::Extern::into_func)
+        .unwrap();
+    let args = [Value::I32(WAT_KERNEL_INPUT), Value::I32(input.len() as i32)];
+    rev_complement.call(&mut store, &args, &mut []).unwrap();
+    let mut result = vec![0x00_u8; output.len()];
+    memory
+        .read(&store, WAT_KERNEL_INPUT as usize, &mut result)
+        .unwrap();
+    assert_eq!(result, output);
+    c.bench_function("execute/revcomp/v1", |b| {
+        b.iter(|| rev_complement.call(&mut store, &args, &mut []).unwrap())
+    });
+}
+
 fn bench_execute_regex_redux_v0(c: &mut Criterion) {
     let instance = load_instance_from_file_v0(WASM_KERNEL);
 
@@ -407,6 +570,111 @@ fn bench_execute_count_until_v1(c: &mut Criterion) {
     });
 }
 
+const BR_TABLE_LEN: i32 = 100_000;
+/// The sum of `i % 8 + 1` for all `i` in `0..BR_TABLE_LEN`.
+const BR_TABLE_SUM: i32 = 450_000;
+
+fn bench_execute_br_table_v0(c: &mut Criterion) {
+    let instance = load_instance_from_wat_v0(include_bytes!("wat/br_table.wat"));
+    c.bench_function("execute/br_table/v0", |b| {
+        b.iter(|| {
+            let value = instance.invoke_export(
+                "br_table",
+                &[Value::I32(BR_TABLE_LEN)],
+                &mut v0::NopExternals,
+            );
+            assert_matches!(value, Ok(Some(Value::I32(BR_TABLE_SUM))));
+        })
+    });
+}
+
+fn bench_execute_br_table_v1(c: &mut Criterion) {
+    let (mut store, instance) = load_instance_from_wat_v1(include_bytes!("wat/br_table.wat"));
+    let br_table = instance
+        .get_export(&store, "br_table")
+        .and_then(v1::Extern::into_func)
+        .unwrap();
+    let mut result = [Value::I32(0)];
+    c.bench_function("execute/br_table/v1", |b| {
+        b.iter(|| {
+            br_table
+                .call(&mut store, &[Value::I32(BR_TABLE_LEN)], &mut result)
+                .unwrap();
+            assert_matches!(result, [Value::I32(BR_TABLE_SUM)]);
+        })
+    });
+}
+
+const MEMORY_SUM_LEN: i32 = 200_000;
+/// The sum of all bytes `i % 256` for `i` in `0..MEMORY_SUM_LEN`.
+const MEMORY_SUM: i64 = 25_493_856;
+
+fn bench_execute_memory_sum_v0(c: &mut Criterion) {
+    let instance = load_instance_from_wat_v0(include_bytes!("wat/memory_sum.wat"));
+    c.bench_function("execute/memory_sum/v0", |b| {
+        b.iter(|| {
+            let value = instance.invoke_export(
+                "fill_and_sum",
+                &[Value::I32(MEMORY_SUM_LEN)],
+                &mut v0::NopExternals,
+            );
+            assert_matches!(value, Ok(Some(Value::I64(MEMORY_SUM))));
+        })
+    });
+}
+
+fn bench_execute_memory_sum_v1(c: &mut Criterion) {
+    let (mut store, instance) = load_instance_from_wat_v1(include_bytes!("wat/memory_sum.wat"));
+    let fill_and_sum = instance
+        .get_export(&store, "fill_and_sum")
+        .and_then(v1::Extern::into_func)
+        .unwrap();
+    let mut result = [Value::I64(0)];
+    c.bench_function("execute/memory_sum/v1", |b| {
+        b.iter(|| {
+            fill_and_sum
+                .call(&mut store, &[Value::I32(MEMORY_SUM_LEN)], &mut result)
+                .unwrap();
+            assert_matches!(result, [Value::I64(MEMORY_SUM)]);
+        })
+    });
+}
+
+const MEMORY_VEC_ADD_LEN: i32 = 100_000;
+/// The sum of all `i + 2 * i` for `i` in `0..MEMORY_VEC_ADD_LEN`.
+const MEMORY_VEC_ADD_SUM: i64 = 14_999_850_000;
+
+fn bench_execute_memory_vec_add_v0(c: &mut Criterion) {
+    let instance = load_instance_from_wat_v0(include_bytes!("wat/memory_vec_add.wat"));
+    c.bench_function("execute/memory_vec_add/v0", |b| {
+        b.iter(|| {
+            let value = instance.invoke_export(
+                "vec_add",
+                &[Value::I32(MEMORY_VEC_ADD_LEN)],
+                &mut v0::NopExternals,
+            );
+            assert_matches!(value, Ok(Some(Value::I64(MEMORY_VEC_ADD_SUM))));
+        })
+    });
+}
+
+fn bench_execute_memory_vec_add_v1(c: &mut Criterion) {
+    let (mut store, instance) = load_instance_from_wat_v1(include_bytes!("wat/memory_vec_add.wat"));
+    let vec_add = instance
+        .get_export(&store, "vec_add")
+        .and_then(v1::Extern::into_func)
+        .unwrap();
+    let mut result = [Value::I64(0)];
+    c.bench_function("execute/memory_vec_add/v1", |b| {
+        b.iter(|| {
+            vec_add
+                .call(&mut store, &[Value::I32(MEMORY_VEC_ADD_LEN)], &mut result)
+                .unwrap();
+            assert_matches!(result, [Value::I64(MEMORY_VEC_ADD_SUM)]);
+        })
+    });
+}
+
 fn bench_execute_fac_recursive_v0(c: &mut Criterion) {
     let instance = load_instance_from_wat_v0(include_bytes!("wat/recursive_factorial.wat"));
     c.bench_function("execute/factorial_recursive/v0", |b| {
@@ -679,3 +947,42 @@ fn bench_execute_fibonacci_recursive_v1(c: &mut Criterion) {
         assert_eq!(result, [Value::I32(75025)]);
     });
 }
+
+const FIBONACCI_ITERATIVE_N: i32 = 100_000;
+/// The wrapped `i32` result of `fib_iterative(FIBONACCI_ITERATIVE_N)`.
+const FIBONACCI_ITERATIVE: i32 = 873_876_091;
+
+fn bench_execute_fibonacci_iterative_v0(c: &mut Criterion) {
+    let instance = load_instance_from_wat_v0(include_bytes!("wat/fibonacci.wat"));
+    c.bench_function("execute/fib_iterative/v0", |b| {
+        b.iter(|| {
+            let result = instance.invoke_export(
+                "fib_iterative",
+                &[Value::I32(FIBONACCI_ITERATIVE_N)],
+                &mut v0::NopExternals,
+            );
+            assert_matches!(result, Ok(Some(Value::I32(FIBONACCI_ITERATIVE))));
+        })
+    });
+}
+
+fn bench_execute_fibonacci_iterative_v1(c: &mut Criterion) {
+    let (mut store, instance) = load_instance_from_wat_v1(include_bytes!("wat/fibonacci.wat"));
+    let bench_call = instance
+        .get_export(&store, "fib_iterative")
+        .and_then(v1::Extern::into_func)
+        .unwrap();
+    let mut result = [Value::I32(0)];
+    c.bench_function("execute/fib_iterative/v1", |b| {
+        b.iter(|| {
+            bench_call
+                .call(
+                    &mut store,
+                    &[Value::I32(FIBONACCI_ITERATIVE_N)],
+                    &mut result,
+                )
+                .unwrap();
+            assert_matches!(result, [Value::I32(FIBONACCI_ITERATIVE)]);
+        })
+    });
+}
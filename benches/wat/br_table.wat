@@ -0,0 +1,41 @@
+;; Exports a function `br_table` that takes an input `n`.
+;; The exported function dispatches `n` times over a `br_table` with 8 targets
+;; selected by the loop counter and returns the sum of the values of all taken cases.
+;; The case `k` adds `k + 1` to the sum.
+(module
+  (func (export "br_table") (param $n i32) (result i32)
+    (local $i i32)
+    (local $acc i32)
+    (block $exit
+      (loop $continue
+        (br_if $exit (i32.eq (local.get $i) (local.get $n)))
+        (block $done
+          (block $c7 (block $c6 (block $c5 (block $c4
+          (block $c3 (block $c2 (block $c1 (block $c0
+            (br_table $c0 $c1 $c2 $c3 $c4 $c5 $c6 $c7
+              (i32.and (local.get $i) (i32.const 7))
+            )
+          )
+          (local.set $acc (i32.add (local.get $acc) (i32.const 1)))
+          (br $done))
+          (local.set $acc (i32.add (local.get $acc) (i32.const 2)))
+          (br $done))
+          (local.set $acc (i32.add (local.get $acc) (i32.const 3)))
+          (br $done))
+          (local.set $acc (i32.add (local.get $acc) (i32.const 4)))
+          (br $done))
+          (local.set $acc (i32.add (local.get $acc) (i32.const 5)))
+          (br $done))
+          (local.set $acc (i32.add (local.get $acc) (i32.const 6)))
+          (br $done))
+          (local.set $acc (i32.add (local.get $acc) (i32.const 7)))
+          (br $done))
+          (local.set $acc (i32.add (local.get $acc) (i32.const 8)))
+        )
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $continue)
+      )
+    )
+    (local.get $acc)
+  )
+)
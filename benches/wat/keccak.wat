@@ -0,0 +1,208 @@
+;; Exports a function `keccak256` that computes the Keccak-256 hash of the
+;; `len` bytes at `ptr` and returns the first 8 bytes of the hash.
+;;
+;; This is a port of the `tiny_keccak` workload of the `wasm_kernel` benchmarks.
+;;
+;; Memory layout:
+;;
+;; - `0..200`: the Keccak state of 25 lanes
+;; - `256..448`: the round constants
+;; - `512..608`: the rotation offsets of the rho step
+;; - `640..736`: the byte offsets of the lanes permuted by the pi step
+;; - `768..808`: the column parities of the theta step
+;; - `816..952`: the padded final block
+;; - `1024..`: free for the input
+(module
+  (memory (export "memory") 1)
+  (data (i32.const 256)
+    "\01\00\00\00\00\00\00\00\82\80\00\00\00\00\00\00"
+    "\8a\80\00\00\00\00\00\80\00\80\00\80\00\00\00\80"
+    "\8b\80\00\00\00\00\00\00\01\00\00\80\00\00\00\00"
+    "\81\80\00\80\00\00\00\80\09\80\00\00\00\00\00\80"
+    "\8a\00\00\00\00\00\00\00\88\00\00\00\00\00\00\00"
+    "\09\80\00\80\00\00\00\00\0a\00\00\80\00\00\00\00"
+    "\8b\80\00\80\00\00\00\00\8b\00\00\00\00\00\00\80"
+    "\89\80\00\00\00\00\00\80\03\80\00\00\00\00\00\80"
+    "\02\80\00\00\00\00\00\80\80\00\00\00\00\00\00\80"
+    "\0a\80\00\00\00\00\00\00\0a\00\00\80\00\00\00\80"
+    "\81\80\00\80\00\00\00\80\80\80\00\00\00\00\00\80"
+    "\01\00\00\80\00\00\00\00\08\80\00\80\00\00\00\80")
+  (data (i32.const 512)
+    "\01\00\00\00\03\00\00\00\06\00\00\00\0a\00\00\00"
+    "\0f\00\00\00\15\00\00\00\1c\00\00\00\24\00\00\00"
+    "\2d\00\00\00\37\00\00\00\02\00\00\00\0e\00\00\00"
+    "\1b\00\00\00\29\00\00\00\38\00\00\00\08\00\00\00"
+    "\19\00\00\00\2b\00\00\00\3e\00\00\00\12\00\00\00"
+    "\27\00\00\00\3d\00\00\00\14\00\00\00\2c\00\00\00")
+  (data (i32.const 640)
+    "\50\00\00\00\38\00\00\00\58\00\00\00\88\00\00\00"
+    "\90\00\00\00\18\00\00\00\28\00\00\00\80\00\00\00"
+    "\40\00\00\00\a8\00\00\00\c0\00\00\00\20\00\00\00"
+    "\78\00\00\00\b8\00\00\00\98\00\00\00\68\00\00\00"
+    "\60\00\00\00\10\00\00\00\a0\00\00\00\70\00\00\00"
+    "\b0\00\00\00\48\00\00\00\30\00\00\00\08\00\00\00")
+  ;; Applies the Keccak-f[1600] permutation to the state.
+  (func $keccak_f
+    (local $round i32)
+    (local $i i32)
+    (local $j i32)
+    (local $t i64)
+    (local $lane i64)
+    (local $a0 i64)
+    (local $a1 i64)
+    (local $a2 i64)
+    (local $a3 i64)
+    (local $a4 i64)
+    (loop $rounds
+      ;; Theta: compute the parities of all columns.
+      (local.set $i (i32.const 0))
+      (loop $parities
+        (i64.store offset=768
+          (local.get $i)
+          (i64.xor
+            (i64.xor
+              (i64.xor (i64.load (local.get $i)) (i64.load offset=40 (local.get $i)))
+              (i64.xor (i64.load offset=80 (local.get $i)) (i64.load offset=120 (local.get $i)))
+            )
+            (i64.load offset=160 (local.get $i))
+          )
+        )
+        (br_if $parities
+          (i32.lt_u (local.tee $i (i32.add (local.get $i) (i32.const 8))) (i32.const 40))
+        )
+      )
+      ;; Theta: mix the parities of the neighbouring columns into every column.
+      (local.set $i (i32.const 0))
+      (loop $theta
+        (local.set $t
+          (i64.xor
+            (i64.load offset=768 (i32.rem_u (i32.add (local.get $i) (i32.const 32)) (i32.const 40)))
+            (i64.rotl
+              (i64.load offset=768 (i32.rem_u (i32.add (local.get $i) (i32.const 8)) (i32.const 40)))
+              (i64.const 1)
+            )
+          )
+        )
+        (local.set $j (local.get $i))
+        (loop $column
+          (i64.store (local.get $j) (i64.xor (i64.load (local.get $j)) (local.get $t)))
+          (br_if $column
+            (i32.lt_u (local.tee $j (i32.add (local.get $j) (i32.const 40))) (i32.const 200))
+          )
+        )
+        (br_if $theta
+          (i32.lt_u (local.tee $i (i32.add (local.get $i) (i32.const 8))) (i32.const 40))
+        )
+      )
+      ;; Rho and pi: rotate and permute the lanes.
+      (local.set $t (i64.load offset=8 (i32.const 0)))
+      (local.set $i (i32.const 0))
+      (loop $rho_pi
+        (local.set $j (i32.load offset=640 (local.get $i)))
+        (local.set $lane (i64.load (local.get $j)))
+        (i64.store
+          (local.get $j)
+          (i64.rotl (local.get $t) (i64.load32_u offset=512 (local.get $i)))
+        )
+        (local.set $t (local.get $lane))
+        (br_if $rho_pi
+          (i32.lt_u (local.tee $i (i32.add (local.get $i) (i32.const 4))) (i32.const 96))
+        )
+      )
+      ;; Chi: mix the lanes of every row.
+      (local.set $j (i32.const 0))
+      (loop $chi
+        (local.set $a0 (i64.load (local.get $j)))
+        (local.set $a1 (i64.load offset=8 (local.get $j)))
+        (local.set $a2 (i64.load offset=16 (local.get $j)))
+        (local.set $a3 (i64.load offset=24 (local.get $j)))
+        (local.set $a4 (i64.load offset=32 (local.get $j)))
+        (i64.store
+          (local.get $j)
+          (i64.xor (local.get $a0) (i64.and (i64.xor (local.get $a1) (i64.const -1)) (local.get $a2)))
+        )
+        (i64.store offset=8
+          (local.get $j)
+          (i64.xor (local.get $a1) (i64.and (i64.xor (local.get $a2) (i64.const -1)) (local.get $a3)))
+        )
+        (i64.store offset=16
+          (local.get $j)
+          (i64.xor (local.get $a2) (i64.and (i64.xor (local.get $a3) (i64.const -1)) (local.get $a4)))
+        )
+        (i64.store offset=24
+          (local.get $j)
+          (i64.xor (local.get $a3) (i64.and (i64.xor (local.get $a4) (i64.const -1)) (local.get $a0)))
+        )
+        (i64.store offset=32
+          (local.get $j)
+          (i64.xor (local.get $a4) (i64.and (i64.xor (local.get $a0) (i64.const -1)) (local.get $a1)))
+        )
+        (br_if $chi
+          (i32.lt_u (local.tee $j (i32.add (local.get $j) (i32.const 40))) (i32.const 200))
+        )
+      )
+      ;; Iota: add the round constant.
+      (i64.store
+        (i32.const 0)
+        (i64.xor (i64.load (i32.const 0)) (i64.load offset=256 (local.get $round)))
+      )
+      (br_if $rounds
+        (i32.lt_u (local.tee $round (i32.add (local.get $round) (i32.const 8))) (i32.const 192))
+      )
+    )
+  )
+
+  ;; Absorbs the 136 bytes block at `ptr` into the state.
+  (func $absorb (param $ptr i32)
+    (local $i i32)
+    (loop $lanes
+      (i64.store
+        (local.get $i)
+        (i64.xor (i64.load (local.get $i)) (i64.load (i32.add (local.get $ptr) (local.get $i))))
+      )
+      (br_if $lanes
+        (i32.lt_u (local.tee $i (i32.add (local.get $i) (i32.const 8))) (i32.const 136))
+      )
+    )
+    (call $keccak_f)
+  )
+
+  (func (export "keccak256") (param $ptr i32) (param $len i32) (result i64)
+    (local $i i32)
+    ;; Reset the state and the padded final block.
+    (loop $reset
+      (i64.store (local.get $i) (i64.const 0))
+      (i64.store offset=816 (local.get $i) (i64.const 0))
+      (br_if $reset
+        (i32.lt_u (local.tee $i (i32.add (local.get $i) (i32.const 8))) (i32.const 200))
+      )
+    )
+    ;; Absorb all full blocks.
+    (block $done
+      (loop $blocks
+        (br_if $done (i32.lt_u (local.get $len) (i32.const 136)))
+        (call $absorb (local.get $ptr))
+        (local.set $ptr (i32.add (local.get $ptr) (i32.const 136)))
+        (local.set $len (i32.sub (local.get $len) (i32.const 136)))
+        (br $blocks)
+      )
+    )
+    ;; Pad and absorb the remaining bytes.
+    (local.set $i (i32.const 0))
+    (block $copied
+      (loop $copy
+        (br_if $copied (i32.eq (local.get $i) (local.get $len)))
+        (i32.store8 offset=816
+          (local.get $i)
+          (i32.load8_u (i32.add (local.get $ptr) (local.get $i)))
+        )
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $copy)
+      )
+    )
+    (i32.store8 offset=816 (local.get $len) (i32.const 0x01))
+    (i32.store8 offset=951 (i32.const 0) (i32.or (i32.load8_u offset=951 (i32.const 0)) (i32.const 0x80)))
+    (call $absorb (i32.const 816))
+    (i64.load (i32.const 0))
+  )
+)
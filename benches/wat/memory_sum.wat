@@ -0,0 +1,33 @@
+;; Exports a function `fill_and_sum` that takes an input `n`.
+;; The exported function fills the first `n` bytes of the linear memory
+;; with their indices modulo 256, sums them up again and returns the sum.
+(module
+  (memory 4)
+  (func (export "fill_and_sum") (param $n i32) (result i64)
+    (local $i i32)
+    (local $sum i64)
+    (block
+      (loop
+        (br_if 1 (i32.ge_u (local.get $i) (local.get $n)))
+        (i32.store8 (local.get $i) (local.get $i))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br 0)
+      )
+    )
+    (local.set $i (i32.const 0))
+    (block
+      (loop
+        (br_if 1 (i32.ge_u (local.get $i) (local.get $n)))
+        (local.set $sum
+          (i64.add
+            (local.get $sum)
+            (i64.load8_u (local.get $i))
+          )
+        )
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br 0)
+      )
+    )
+    (local.get $sum)
+  )
+)
@@ -0,0 +1,76 @@
+;; Exports a function `vec_add` that takes an input `n`.
+;; The exported function fills two vectors `a` and `b` of `n` 64-bit
+;; integers each with `a[i] = i` and `b[i] = 2 * i`, stores their
+;; element-wise sum into a third vector `c`, sums up `c` and returns the sum.
+;;
+;; The vectors are laid out consecutively starting at address 0 so that
+;; the linear memory must be able to hold `3 * 8 * n` bytes.
+(module
+  (memory 40)
+  (func (export "vec_add") (param $n i32) (result i64)
+    (local $i i32)
+    (local $a i32)
+    (local $b i32)
+    (local $c i32)
+    (local $sum i64)
+    (local.set $b (i32.mul (local.get $n) (i32.const 8)))
+    (local.set $c (i32.mul (local.get $n) (i32.const 16)))
+    ;; Fill the vectors `a` and `b`.
+    (block
+      (loop
+        (br_if 1 (i32.ge_u (local.get $i) (local.get $n)))
+        (i64.store
+          (local.get $a)
+          (i64.extend_i32_u (local.get $i))
+        )
+        (i64.store
+          (local.get $b)
+          (i64.extend_i32_u (i32.mul (local.get $i) (i32.const 2)))
+        )
+        (local.set $a (i32.add (local.get $a) (i32.const 8)))
+        (local.set $b (i32.add (local.get $b) (i32.const 8)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br 0)
+      )
+    )
+    ;; Compute `c = a + b` element-wise.
+    (local.set $i (i32.const 0))
+    (local.set $a (i32.const 0))
+    (local.set $b (i32.mul (local.get $n) (i32.const 8)))
+    (block
+      (loop
+        (br_if 1 (i32.ge_u (local.get $i) (local.get $n)))
+        (i64.store
+          (local.get $c)
+          (i64.add
+            (i64.load (local.get $a))
+            (i64.load (local.get $b))
+          )
+        )
+        (local.set $a (i32.add (local.get $a) (i32.const 8)))
+        (local.set $b (i32.add (local.get $b) (i32.const 8)))
+        (local.set $c (i32.add (local.get $c) (i32.const 8)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br 0)
+      )
+    )
+    ;; Sum up the elements of `c`.
+    (local.set $i (i32.const 0))
+    (local.set $c (i32.mul (local.get $n) (i32.const 16)))
+    (block
+      (loop
+        (br_if 1 (i32.ge_u (local.get $i) (local.get $n)))
+        (local.set $sum
+          (i64.add
+            (local.get $sum)
+            (i64.load (local.get $c))
+          )
+        )
+        (local.set $c (i32.add (local.get $c) (i32.const 8)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br 0)
+      )
+    )
+    (local.get $sum)
+  )
+)
@@ -0,0 +1,56 @@
+;; Exports a function `rev_complement` that replaces the DNA sequence of
+;; `len` bytes at `ptr` by its reverse complement in place.
+;;
+;; This is a port of the `rev_complement` workload of the `wasm_kernel` benchmarks.
+;; Nucleotide codes are complemented to upper case codes, all other bytes are kept.
+;;
+;; Memory layout:
+;;
+;; - `0..256`: the complement of every byte
+;; - `1024..`: free for the input
+(module
+  (memory (export "memory") 4)
+  (data (i32.const 0)
+    "\00\01\02\03\04\05\06\07\08\09\0a\0b\0c\0d\0e\0f"
+    "\10\11\12\13\14\15\16\17\18\19\1a\1b\1c\1d\1e\1f"
+    "\20\21\22\23\24\25\26\27\28\29\2a\2b\2c\2d\2e\2f"
+    "\30\31\32\33\34\35\36\37\38\39\3a\3b\3c\3d\3e\3f"
+    "\40\54\56\47\48\45\46\43\44\49\4a\4d\4c\4b\4e\4f"
+    "\50\51\59\53\41\41\42\57\58\52\5a\5b\5c\5d\5e\5f"
+    "\60\54\56\47\48\65\66\43\44\69\6a\4d\6c\4b\4e\6f"
+    "\70\71\59\53\41\41\42\57\78\52\7a\7b\7c\7d\7e\7f"
+    "\80\81\82\83\84\85\86\87\88\89\8a\8b\8c\8d\8e\8f"
+    "\90\91\92\93\94\95\96\97\98\99\9a\9b\9c\9d\9e\9f"
+    "\a0\a1\a2\a3\a4\a5\a6\a7\a8\a9\aa\ab\ac\ad\ae\af"
+    "\b0\b1\b2\b3\b4\b5\b6\b7\b8\b9\ba\bb\bc\bd\be\bf"
+    "\c0\c1\c2\c3\c4\c5\c6\c7\c8\c9\ca\cb\cc\cd\ce\cf"
+    "\d0\d1\d2\d3\d4\d5\d6\d7\d8\d9\da\db\dc\dd\de\df"
+    "\e0\e1\e2\e3\e4\e5\e6\e7\e8\e9\ea\eb\ec\ed\ee\ef"
+    "\f0\f1\f2\f3\f4\f5\f6\f7\f8\f9\fa\fb\fc\fd\fe\ff")
+
+  (func (export "rev_complement") (param $ptr i32) (param $len i32)
+    (local $hi i32)
+    (local $byte i32)
+    (if (i32.eqz (local.get $len))
+      (then (return))
+    )
+    (local.set $hi (i32.sub (i32.add (local.get $ptr) (local.get $len)) (i32.const 1)))
+    (block $done
+      (loop $swap
+        (br_if $done (i32.ge_u (local.get $ptr) (local.get $hi)))
+        (local.set $byte (i32.load8_u (local.get $ptr)))
+        (i32.store8 (local.get $ptr) (i32.load8_u (i32.load8_u (local.get $hi))))
+        (i32.store8 (local.get $hi) (i32.load8_u (local.get $byte)))
+        (local.set $ptr (i32.add (local.get $ptr) (i32.const 1)))
+        (local.set $hi (i32.sub (local.get $hi) (i32.const 1)))
+        (br $swap)
+      )
+    )
+    ;; Complement the middle byte of sequences with odd length.
+    (if (i32.eq (local.get $ptr) (local.get $hi))
+      (then
+        (i32.store8 (local.get $ptr) (i32.load8_u (i32.load8_u (local.get $ptr))))
+      )
+    )
+  )
+)
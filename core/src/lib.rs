@@ -7,6 +7,13 @@ mod value;
 #[cfg(feature = "virtual_memory")]
 mod vmem;
 
+#[cfg(all(
+    feature = "virtual_memory",
+    target_os = "linux",
+    target_arch = "x86_64"
+))]
+pub mod trap_handler;
+
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 
@@ -14,7 +21,7 @@ extern crate alloc;
 extern crate std as alloc;
 
 #[cfg(feature = "virtual_memory")]
-pub use self::vmem::{VirtualMemory, VirtualMemoryError};
+pub use self::vmem::{VirtualMemory, VirtualMemoryError, VirtualMemoryImage};
 
 /// WebAssembly-specific sizes and units.
 pub mod memory_units {
@@ -0,0 +1,408 @@
+//! Turns faults on guard pages of virtual linear memory into Wasm traps.
+//!
+//! Linear memories based on virtual memory reserve more address space than
+//! a 32-bit effective address plus the size of any access could ever reach.
+//! Only the pages within the length of the linear memory are accessible, so
+//! every out of bounds access faults on an inaccessible page instead of
+//! touching foreign memory. This allows loads and stores to skip their
+//! explicit bounds checks:
+//!
+//! - Guarded accesses are performed via [`load`] and [`store`] which access
+//!   the memory with a single instruction in inline assembly.
+//! - Guarded accesses must only happen within [`catch_traps`] which records
+//!   a resumption point like `setjmp` does.
+//! - The linear memory that is accessed must be announced beforehand via
+//!   [`set_guard_region`].
+//! - Upon a fault within the guard region the installed `SIGSEGV` handler
+//!   resumes execution at the recorded resumption point like `longjmp` does
+//!   and [`catch_traps`] returns [`TrapCode::MemoryAccessOutOfBounds`].
+//!
+//! # Note
+//!
+//! The stack frames between [`catch_traps`] and the faulting access are
+//! discarded without running their destructors. Therefore the closure given
+//! to [`catch_traps`] must not own values with side effects upon being dropped.
+//! Leaking them is memory safe but may leak resources.
+
+use crate::TrapCode;
+use core::{
+    arch::{asm, naked_asm},
+    cell::Cell,
+    mem::{self, MaybeUninit},
+    ptr,
+};
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+};
+
+/// The registers required to resume execution after a fault.
+///
+/// # Note
+///
+/// The layout is relied upon by [`call_with_resume_point`].
+#[repr(C)]
+#[derive(Debug, Default)]
+struct ResumePoint {
+    rbx: usize,
+    rbp: usize,
+    r12: usize,
+    r13: usize,
+    r14: usize,
+    r15: usize,
+    /// The stack pointer of the caller of [`call_with_resume_point`].
+    rsp: usize,
+    /// The return address of [`call_with_resume_point`].
+    rip: usize,
+}
+
+/// The per thread state of the trap handler.
+#[derive(Debug, Copy, Clone)]
+struct TrapState {
+    /// The resume point of the innermost active [`catch_traps`] or null.
+    resume: *mut ResumePoint,
+    /// The start address of the guard region.
+    start: usize,
+    /// The end address of the guard region.
+    end: usize,
+}
+
+impl TrapState {
+    /// The state while no guarded accesses are happening.
+    const INACTIVE: Self = Self {
+        resume: ptr::null_mut(),
+        start: 0,
+        end: 0,
+    };
+}
+
+std::thread_local! {
+    static STATE: Cell<TrapState> = const { Cell::new(TrapState::INACTIVE) };
+}
+
+/// Ensures that the trap handler is installed only once.
+static INSTALL: Once = Once::new();
+
+/// Is `true` if the trap handler has been installed successfully.
+static mut INSTALLED: bool = false;
+
+/// The `SIGSEGV` handler that was installed before the trap handler.
+static mut PREVIOUS_SIGSEGV: MaybeUninit<libc::sigaction> = MaybeUninit::uninit();
+
+/// The `SIGBUS` handler that was installed before the trap handler.
+static mut PREVIOUS_SIGBUS: MaybeUninit<libc::sigaction> = MaybeUninit::uninit();
+
+/// Installs the process wide trap handler if it has not been installed already.
+///
+/// Returns `true` if the trap handler is installed.
+///
+/// # Note
+///
+/// Signals that are not caused by guarded accesses are forwarded to the
+/// handlers that have been installed before.
+pub fn install() -> bool {
+    INSTALL.call_once(|| {
+        // # Safety
+        //
+        // The statics are only written here which happens exactly once
+        // and before the trap handler that reads them is installed.
+        unsafe {
+            let mut action: libc::sigaction = mem::zeroed();
+            action.sa_sigaction = handle_fault as *const () as usize;
+            action.sa_flags = libc::SA_SIGINFO | libc::SA_ONSTACK;
+            libc::sigemptyset(&mut action.sa_mask);
+            let segv = libc::sigaction(
+                libc::SIGSEGV,
+                &action,
+                ptr::addr_of_mut!(PREVIOUS_SIGSEGV).cast(),
+            );
+            if segv != 0 {
+                return;
+            }
+            let bus = libc::sigaction(
+                libc::SIGBUS,
+                &action,
+                ptr::addr_of_mut!(PREVIOUS_SIGBUS).cast(),
+            );
+            if bus != 0 {
+                libc::sigaction(
+                    libc::SIGSEGV,
+                    ptr::addr_of!(PREVIOUS_SIGSEGV).cast(),
+                    ptr::null_mut(),
+                );
+                return;
+            }
+            INSTALLED = true;
+        }
+    });
+    // # Safety
+    //
+    // `INSTALLED` is no longer written after `INSTALL` has completed.
+    unsafe { INSTALLED }
+}
+
+/// Sets the guard region of the current thread to `len` bytes starting at `start`.
+///
+/// Faults caused by guarded accesses within [`catch_traps`] on the current
+/// thread are turned into traps if they happen within the guard region.
+///
+/// # Note
+///
+/// The guard region stays in effect until it is set again.
+pub fn set_guard_region(start: *const u8, len: usize) {
+    STATE.with(|state| {
+        let start = start as usize;
+        state.set(TrapState {
+            start,
+            end: start.saturating_add(len),
+            ..state.get()
+        })
+    })
+}
+
+/// Calls `f` and returns [`TrapCode::MemoryAccessOutOfBounds`] if a guarded access faults.
+///
+/// # Note
+///
+/// - Panics of `f` are propagated to the caller.
+/// - Requires the trap handler to be [installed](install).
+pub fn catch_traps<F, R>(f: F) -> Result<R, TrapCode>
+where
+    F: FnOnce() -> R,
+{
+    /// The closure and its result that are passed through [`call_with_resume_point`].
+    struct Payload<F, R> {
+        f: Option<F>,
+        result: Option<std::thread::Result<R>>,
+    }
+
+    /// Calls the closure of the [`Payload`] behind `payload`.
+    extern "C" fn call<F, R>(payload: *mut u8)
+    where
+        F: FnOnce() -> R,
+    {
+        // # Safety
+        //
+        // `payload` is the pointer to the `Payload<F, R>` given to
+        // [`call_with_resume_point`] by [`catch_traps`].
+        let payload = unsafe { &mut *payload.cast::<Payload<F, R>>() };
+        if let Some(f) = payload.f.take() {
+            // Note: Unwinding out of this function is not supported.
+            payload.result = Some(panic::catch_unwind(AssertUnwindSafe(f)));
+        }
+    }
+
+    let mut payload = Payload {
+        f: Some(f),
+        result: None,
+    };
+    let mut resume = ResumePoint::default();
+    let resume = ptr::addr_of_mut!(resume);
+    let previous = STATE.with(|state| {
+        let previous = state.get();
+        state.set(TrapState { resume, ..previous });
+        previous.resume
+    });
+    // # Safety
+    //
+    // - `resume` outlives the call and is registered as the resume point
+    //   of the current thread so that the trap handler resumes here.
+    // - `call::<F, R>` matches the type behind the payload pointer.
+    let trapped =
+        unsafe { call_with_resume_point(resume, call::<F, R>, ptr::addr_of_mut!(payload).cast()) };
+    STATE.with(|state| {
+        state.set(TrapState {
+            resume: previous,
+            ..state.get()
+        })
+    });
+    if trapped != 0 {
+        return Err(TrapCode::MemoryAccessOutOfBounds);
+    }
+    match payload.result {
+        Some(Ok(result)) => Ok(result),
+        Some(Err(panic)) => panic::resume_unwind(panic),
+        None => unreachable!("the closure of `catch_traps` has not been called"),
+    }
+}
+
+/// Records the resume point into `resume` and calls `f` with `payload`.
+///
+/// Returns `0` if `f` returns normally or `1` if the trap handler resumed execution.
+///
+/// # Safety
+///
+/// - `resume` must be the resume point of the current thread while `f` executes.
+/// - `f` must not unwind.
+#[unsafe(naked)]
+unsafe extern "C" fn call_with_resume_point(
+    resume: *mut ResumePoint,
+    f: extern "C" fn(*mut u8),
+    payload: *mut u8,
+) -> usize {
+    naked_asm!(
+        // Record the callee-saved registers.
+        "mov [rdi], rbx",
+        "mov [rdi + 8], rbp",
+        "mov [rdi + 16], r12",
+        "mov [rdi + 24], r13",
+        "mov [rdi + 32], r14",
+        "mov [rdi + 40], r15",
+        // Record the stack pointer and return address as they are after returning.
+        "lea rax, [rsp + 8]",
+        "mov [rdi + 48], rax",
+        "mov rax, [rsp]",
+        "mov [rdi + 56], rax",
+        // Keep the stack 16 bytes aligned for the call.
+        "sub rsp, 8",
+        "mov rdi, rdx",
+        "call rsi",
+        "add rsp, 8",
+        "xor eax, eax",
+        "ret",
+    )
+}
+
+/// Handles `SIGSEGV` and `SIGBUS` signals.
+///
+/// Resumes execution at the resume point of the current thread if the
+/// signal has been caused by a guarded access within the guard region.
+/// Otherwise forwards the signal to the previously installed handler.
+unsafe extern "C" fn handle_fault(
+    signal: libc::c_int,
+    info: *mut libc::siginfo_t,
+    context: *mut libc::c_void,
+) {
+    let address = (*info).si_addr() as usize;
+    let state = STATE.with(Cell::get);
+    if !state.resume.is_null() && state.start <= address && address < state.end {
+        let resume = &*state.resume;
+        let gregs = &mut (*context.cast::<libc::ucontext_t>()).uc_mcontext.gregs;
+        gregs[libc::REG_RBX as usize] = resume.rbx as i64;
+        gregs[libc::REG_RBP as usize] = resume.rbp as i64;
+        gregs[libc::REG_R12 as usize] = resume.r12 as i64;
+        gregs[libc::REG_R13 as usize] = resume.r13 as i64;
+        gregs[libc::REG_R14 as usize] = resume.r14 as i64;
+        gregs[libc::REG_R15 as usize] = resume.r15 as i64;
+        gregs[libc::REG_RSP as usize] = resume.rsp as i64;
+        gregs[libc::REG_RIP as usize] = resume.rip as i64;
+        gregs[libc::REG_RAX as usize] = 1;
+        return;
+    }
+    let previous = match signal {
+        libc::SIGSEGV => &*ptr::addr_of!(PREVIOUS_SIGSEGV).cast::<libc::sigaction>(),
+        _ => &*ptr::addr_of!(PREVIOUS_SIGBUS).cast::<libc::sigaction>(),
+    };
+    match previous.sa_sigaction {
+        libc::SIG_DFL | libc::SIG_IGN => {
+            // Note: Restoring the previous handler lets the fault
+            //       happen again once this handler returns.
+            libc::sigaction(signal, previous, ptr::null_mut());
+        }
+        handler if previous.sa_flags & libc::SA_SIGINFO != 0 => {
+            let handler: extern "C" fn(libc::c_int, *mut libc::siginfo_t, *mut libc::c_void) =
+                mem::transmute(handler);
+            handler(signal, info, context)
+        }
+        handler => {
+            let handler: extern "C" fn(libc::c_int) = mem::transmute(handler);
+            handler(signal)
+        }
+    }
+}
+
+/// Loads `N` bytes from `src` with a single instruction.
+///
+/// # Safety
+///
+/// - `N` must be 1, 2, 4 or 8.
+/// - `src` must either point to `N` readable bytes or into the guard region
+///   of the current thread while being called within [`catch_traps`].
+#[inline(always)]
+pub unsafe fn load<const N: usize>(src: *const u8) -> [u8; N] {
+    let value: u64;
+    match N {
+        1 => {
+            asm!("movzx {0:e}, byte ptr [{1}]", out(reg) value, in(reg) src, options(nostack, preserves_flags, readonly))
+        }
+        2 => {
+            asm!("movzx {0:e}, word ptr [{1}]", out(reg) value, in(reg) src, options(nostack, preserves_flags, readonly))
+        }
+        4 => {
+            asm!("mov {0:e}, dword ptr [{1}]", out(reg) value, in(reg) src, options(nostack, preserves_flags, readonly))
+        }
+        8 => {
+            asm!("mov {0}, qword ptr [{1}]", out(reg) value, in(reg) src, options(nostack, preserves_flags, readonly))
+        }
+        _ => unreachable!("unsupported guarded load of {} bytes", N),
+    }
+    let mut bytes = [0x00_u8; N];
+    bytes.copy_from_slice(&value.to_le_bytes()[..N]);
+    bytes
+}
+
+/// Stores the `N` bytes to `dst` with a single instruction.
+///
+/// # Safety
+///
+/// - `N` must be 1, 2, 4 or 8.
+/// - `dst` must either point to `N` writable bytes or into the guard region
+///   of the current thread while being called within [`catch_traps`].
+#[inline(always)]
+pub unsafe fn store<const N: usize>(dst: *mut u8, bytes: [u8; N]) {
+    let mut buffer = [0x00_u8; 8];
+    buffer[..N].copy_from_slice(&bytes);
+    let value = u64::from_le_bytes(buffer);
+    match N {
+        1 => {
+            asm!("mov byte ptr [{0}], {1:l}", in(reg) dst, in(reg) value, options(nostack, preserves_flags))
+        }
+        2 => {
+            asm!("mov word ptr [{0}], {1:x}", in(reg) dst, in(reg) value, options(nostack, preserves_flags))
+        }
+        4 => {
+            asm!("mov dword ptr [{0}], {1:e}", in(reg) dst, in(reg) value, options(nostack, preserves_flags))
+        }
+        8 => {
+            asm!("mov qword ptr [{0}], {1}", in(reg) dst, in(reg) value, options(nostack, preserves_flags))
+        }
+        _ => unreachable!("unsupported guarded store of {} bytes", N),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VirtualMemory;
+
+    #[test]
+    fn guarded_accesses_work() {
+        assert!(install());
+        let mut memory = VirtualMemory::reserve(1 << 20).unwrap();
+        memory.resize(65536).unwrap();
+        let base = memory.data_mut().as_mut_ptr();
+        set_guard_region(base, 1 << 20);
+        let result = catch_traps(|| unsafe {
+            store::<4>(base.add(8), 42_u32.to_le_bytes());
+            u32::from_le_bytes(load::<4>(base.add(8)))
+        });
+        assert_eq!(result.ok(), Some(42));
+        // Accesses beyond the accessible bytes trap.
+        let result = catch_traps(|| unsafe { load::<8>(base.add(65532)) });
+        assert!(matches!(result, Err(TrapCode::MemoryAccessOutOfBounds)));
+        let result = catch_traps(|| unsafe { store::<1>(base.add(100_000), [1]) });
+        assert!(matches!(result, Err(TrapCode::MemoryAccessOutOfBounds)));
+        // Still works after a trap.
+        let result = catch_traps(|| unsafe { load::<1>(base.add(8)) });
+        assert_eq!(result.ok(), Some([42]));
+    }
+
+    #[test]
+    fn catch_traps_propagates_panics() {
+        assert!(install());
+        let result = panic::catch_unwind(|| catch_traps(|| panic!("expected panic")));
+        assert!(result.is_err());
+        // The trap state has been reset.
+        assert!(STATE.with(Cell::get).resume.is_null());
+    }
+}
@@ -11,12 +11,14 @@ use core::{
     slice,
 };
 use region::{Allocation, Protection};
+use std::io;
 
 /// Dummy error for fallible `Vec`-based virtual memory operations.
 #[derive(Debug)]
 pub enum VirtualMemoryError {
     Region(region::Error),
     AllocationOutOfBounds,
+    Image(io::Error),
 }
 
 impl From<region::Error> for VirtualMemoryError {
@@ -26,6 +28,13 @@ impl From<region::Error> for VirtualMemoryError {
     }
 }
 
+impl From<io::Error> for VirtualMemoryError {
+    #[inline]
+    fn from(error: io::Error) -> Self {
+        Self::Image(error)
+    }
+}
+
 impl Display for VirtualMemoryError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -35,6 +44,11 @@ impl Display for VirtualMemoryError {
                 error
             ),
             Self::AllocationOutOfBounds => write!(f, "virtual memory allocation is too big"),
+            Self::Image(error) => write!(
+                f,
+                "encountered failure while operating with a virtual memory image: {}",
+                error
+            ),
         }
     }
 }
@@ -43,19 +57,30 @@ impl Display for VirtualMemoryError {
 pub struct VirtualMemory {
     /// The virtual memory allocation.
     allocation: Allocation,
+    /// The number of bytes at the start of the allocation that are accessible.
+    ///
+    /// # Note
+    ///
+    /// All bytes that follow are inaccessible and accessing them faults.
+    accessible: usize,
 }
 
 impl Debug for VirtualMemory {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("VirtualMemory")
             .field("len", &self.allocation.len())
+            .field("accessible", &self.accessible)
             .finish()
     }
 }
 
 impl VirtualMemory {
     /// The maximum allocation size for a `wasmi` virtual memory.
-    const MAX_ALLOCATION_SIZE: usize = u32::MAX as usize;
+    ///
+    /// # Note
+    ///
+    /// This allows to reserve the whole 32-bit address space plus guard pages.
+    const MAX_ALLOCATION_SIZE: usize = 1 << 33;
 
     /// Create a new virtual memory allocation.
     ///
@@ -69,34 +94,292 @@ impl VirtualMemory {
     /// - If `len` should be greater than 0.
     /// - If the operating system returns an error upon virtual memory allocation.
     pub fn new(len: usize) -> Result<Self, VirtualMemoryError> {
+        Self::alloc(len, Protection::READ_WRITE, len)
+    }
+
+    /// Reserve a new virtual memory allocation without making any of it accessible.
+    ///
+    /// # Note
+    ///
+    /// - Use [`VirtualMemory::resize`] to make the start of the allocation accessible.
+    /// - Reserved bytes are not backed by physical memory until they are made accessible.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`VirtualMemory::new`].
+    pub fn reserve(len: usize) -> Result<Self, VirtualMemoryError> {
+        Self::alloc(len, Protection::NONE, 0)
+    }
+
+    /// Allocates `len` bytes with the given protection of which `accessible` bytes are accessible.
+    fn alloc(
+        len: usize,
+        protection: Protection,
+        accessible: usize,
+    ) -> Result<Self, VirtualMemoryError> {
         assert_ne!(len, 0, "cannot allocate empty virtual memory");
         if len > Self::MAX_ALLOCATION_SIZE {
             return Err(VirtualMemoryError::AllocationOutOfBounds);
         }
-        let allocation = region::alloc(len, Protection::READ_WRITE)?;
-        Ok(Self { allocation })
+        let allocation = region::alloc(len, protection)?;
+        Ok(Self {
+            allocation,
+            accessible,
+        })
+    }
+
+    /// Resizes the accessible part at the start of the allocation to `new_len` bytes.
+    ///
+    /// # Note
+    ///
+    /// Bytes that become inaccessible are zeroed first so that they are
+    /// zero once they become accessible again.
+    ///
+    /// # Errors
+    ///
+    /// - If `new_len` exceeds the length of the allocation.
+    /// - If the operating system fails to change the protection of the allocation.
+    pub fn resize(&mut self, new_len: usize) -> Result<(), VirtualMemoryError> {
+        if new_len > self.allocation.len() {
+            return Err(VirtualMemoryError::AllocationOutOfBounds);
+        }
+        if new_len < self.accessible {
+            self.data_mut()[new_len..].fill(0x00_u8);
+        }
+        // Note: Protection can only be changed for whole pages. Therefore the page
+        //       that contains the last accessible byte stays accessible as a whole.
+        let page_size = region::page::size();
+        let current_end = self.accessible.div_ceil(page_size) * page_size;
+        let new_end = new_len.div_ceil(page_size) * page_size;
+        let base = self.allocation.as_mut_ptr::<u8>();
+        // # SAFETY
+        //
+        // Both page aligned ranges are within the allocation exclusively owned by `self`
+        // and no slices to the bytes that become inaccessible exist at this point.
+        unsafe {
+            if new_end > current_end {
+                region::protect(
+                    base.add(current_end),
+                    new_end - current_end,
+                    Protection::READ_WRITE,
+                )?;
+            }
+            if new_end < current_end {
+                region::protect(base.add(new_end), current_end - new_end, Protection::NONE)?;
+            }
+        }
+        self.accessible = new_len;
+        Ok(())
+    }
+
+    /// Reserve a new virtual memory allocation that starts with the contents of `image`.
+    ///
+    /// # Note
+    ///
+    /// - Only the bytes of the `image` are accessible afterwards.
+    ///   Use [`VirtualMemory::resize`] to make more bytes accessible.
+    /// - On Linux the `image` is mapped copy-on-write into the allocation so that
+    ///   creating the allocation does not depend on the size of the `image` and
+    ///   unmodified pages are shared between all allocations of the same `image`.
+    /// - On other platforms the contents of the `image` are copied.
+    ///
+    /// # Errors
+    ///
+    /// - If the `image` does not fit into `len` bytes.
+    /// - Same as [`VirtualMemory::new`].
+    pub fn with_image(len: usize, image: &VirtualMemoryImage) -> Result<Self, VirtualMemoryError> {
+        if image.len() > len {
+            return Err(VirtualMemoryError::AllocationOutOfBounds);
+        }
+        let mut memory = Self::reserve(len)?;
+        if !image.is_empty() {
+            memory.resize(image.len())?;
+            image.map_into(&mut memory)?;
+        }
+        Ok(memory)
     }
 
-    /// Returns a shared slice over the bytes of the virtual memory allocation.
+    /// Returns a shared slice over the accessible bytes of the virtual memory allocation.
     #[inline]
     pub fn data(&self) -> &[u8] {
         // # SAFETY
         //
         // The operation is safe since we assume that the virtual memory allocation
-        // has been successful and allocated exactly `self.allocation.len()` bytes.
-        // Therefore creating a slice with `self.len` elements is valid.
+        // has been successful and the first `self.accessible` bytes of it are accessible.
+        // Therefore creating a slice with `self.accessible` elements is valid.
         // Aliasing guarantees are not violated since `self` is the only owner
         // of the underlying virtual memory allocation.
-        unsafe { slice::from_raw_parts(self.allocation.as_ptr(), self.allocation.len()) }
+        unsafe { slice::from_raw_parts(self.allocation.as_ptr(), self.accessible) }
     }
 
-    /// Returns an exclusive slice over the bytes of the virtual memory allocation.
+    /// Returns an exclusive slice over the accessible bytes of the virtual memory allocation.
     #[inline]
     pub fn data_mut(&mut self) -> &mut [u8] {
         // # SAFETY
         //
         // See safety proof of the `as_slice` method.
         // Additionally, it is not possible to obtain two mutable references for the same memory area.
-        unsafe { slice::from_raw_parts_mut(self.allocation.as_mut_ptr(), self.allocation.len()) }
+        unsafe { slice::from_raw_parts_mut(self.allocation.as_mut_ptr(), self.accessible) }
+    }
+}
+
+/// The initial contents of a [`VirtualMemory`] that can be shared by many allocations.
+///
+/// # Note
+///
+/// On Linux the contents are kept in an anonymous in-memory file that is mapped
+/// copy-on-write into every [`VirtualMemory`] created via [`VirtualMemory::with_image`].
+pub struct VirtualMemoryImage {
+    /// The anonymous in-memory file holding the contents of the image.
+    #[cfg(target_os = "linux")]
+    file: std::fs::File,
+    /// The contents of the image.
+    #[cfg(not(target_os = "linux"))]
+    bytes: std::boxed::Box<[u8]>,
+    /// The length of the image in bytes.
+    len: usize,
+}
+
+impl Debug for VirtualMemoryImage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VirtualMemoryImage")
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl VirtualMemoryImage {
+    /// Creates a new [`VirtualMemoryImage`] with the given contents.
+    ///
+    /// # Errors
+    ///
+    /// If the operating system fails to create the in-memory file of the image.
+    #[cfg(target_os = "linux")]
+    pub fn new(bytes: &[u8]) -> Result<Self, VirtualMemoryError> {
+        use std::{io::Write, os::unix::io::FromRawFd};
+        // # SAFETY
+        //
+        // The name is a valid C string and the flags are valid.
+        let fd = unsafe { libc::memfd_create(c"wasmi_image".as_ptr(), libc::MFD_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        // # SAFETY
+        //
+        // The file descriptor has just been created and is exclusively owned by the file.
+        let mut file = unsafe { std::fs::File::from_raw_fd(fd) };
+        file.write_all(bytes)?;
+        // Note: Mapping pages beyond the end of the file is not allowed.
+        //       Therefore the file is padded with zeros up to the next page boundary.
+        file.set_len(Self::mapped_len(bytes.len()) as u64)?;
+        Ok(Self {
+            file,
+            len: bytes.len(),
+        })
+    }
+
+    /// Creates a new [`VirtualMemoryImage`] with the given contents.
+    ///
+    /// # Errors
+    ///
+    /// This never fails on platforms other than Linux.
+    #[cfg(not(target_os = "linux"))]
+    pub fn new(bytes: &[u8]) -> Result<Self, VirtualMemoryError> {
+        Ok(Self {
+            bytes: bytes.into(),
+            len: bytes.len(),
+        })
+    }
+
+    /// Returns the length of the image in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the image is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the length of the image rounded up to the page size of the operating system.
+    #[cfg(target_os = "linux")]
+    fn mapped_len(len: usize) -> usize {
+        let page_size = region::page::size();
+        len.div_ceil(page_size) * page_size
+    }
+
+    /// Maps the image copy-on-write to the start of the `memory` allocation.
+    ///
+    /// # Errors
+    ///
+    /// If the operating system fails to map the image.
+    #[cfg(target_os = "linux")]
+    fn map_into(&self, memory: &mut VirtualMemory) -> Result<(), VirtualMemoryError> {
+        use std::os::unix::io::AsRawFd;
+        let mapped_len = Self::mapped_len(self.len);
+        if mapped_len > memory.allocation.len() {
+            return Err(VirtualMemoryError::AllocationOutOfBounds);
+        }
+        let addr = memory.allocation.as_mut_ptr::<u8>();
+        // # SAFETY
+        //
+        // The mapping replaces the first `mapped_len` bytes of the allocation that is
+        // exclusively owned by `memory`. The private mapping ensures that writes to the
+        // allocation never modify the image. Dropping the allocation unmaps its whole
+        // range and therefore also the mapping of the image.
+        let mapped = unsafe {
+            libc::mmap(
+                addr.cast(),
+                mapped_len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_FIXED,
+                self.file.as_raw_fd(),
+                0,
+            )
+        };
+        if mapped == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error().into());
+        }
+        Ok(())
+    }
+
+    /// Copies the image to the start of the `memory` allocation.
+    #[cfg(not(target_os = "linux"))]
+    fn map_into(&self, memory: &mut VirtualMemory) -> Result<(), VirtualMemoryError> {
+        memory.data_mut()[..self.len].copy_from_slice(&self.bytes);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resize_works() {
+        let mut memory = VirtualMemory::reserve(1 << 20).unwrap();
+        assert!(memory.data().is_empty());
+        memory.resize(100_000).unwrap();
+        assert_eq!(memory.data().len(), 100_000);
+        memory.data_mut().fill(0xFF);
+        // Shrinking zeroes the bytes that become inaccessible.
+        memory.resize(10).unwrap();
+        memory.resize(200_000).unwrap();
+        assert!(memory.data()[..10].iter().all(|byte| *byte == 0xFF));
+        assert!(memory.data()[10..].iter().all(|byte| *byte == 0x00));
+        assert!(memory.resize((1 << 20) + 1).is_err());
+    }
+
+    #[test]
+    fn with_image_works() {
+        let image = VirtualMemoryImage::new(b"wasmi").unwrap();
+        let mut a = VirtualMemory::with_image(1 << 20, &image).unwrap();
+        let mut b = VirtualMemory::with_image(1 << 20, &image).unwrap();
+        a.resize(65536).unwrap();
+        b.resize(65536).unwrap();
+        a.data_mut()[0] = b'W';
+        assert_eq!(&a.data()[..5], b"Wasmi");
+        assert_eq!(&b.data()[..5], b"wasmi");
+        assert!(b.data()[5..].iter().all(|byte| *byte == 0x00));
     }
 }
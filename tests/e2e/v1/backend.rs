@@ -0,0 +1,209 @@
+//! Tests for user provided linear memory backends in `wasmi_v1`.
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use wasmi_core::memory_units::Pages;
+use wasmi_v1::{
+    errors::MemoryError,
+    Config,
+    Engine,
+    Extern,
+    LinearMemory,
+    Linker,
+    Memory,
+    MemoryCreator,
+    MemoryType,
+    Module,
+    Store,
+};
+
+/// A Wasm module that grows its linear memory and stores into the new page.
+const WAT: &str = r#"
+    (module
+        (memory (export "memory") 1 3)
+        (data (i32.const 0) "wasmi")
+        (func (export "grow") (result i32)
+            (memory.grow (i32.const 1))
+            (i32.store8 (i32.const 65536) (i32.const 42))
+        )
+    )
+"#;
+
+/// A [`MemoryCreator`] that creates [`Vec`] backends and counts its invocations.
+#[derive(Default)]
+struct VecCreator {
+    created: Arc<AtomicUsize>,
+}
+
+impl MemoryCreator for VecCreator {
+    fn new_memory(&self, memory_type: MemoryType) -> Result<Box<dyn LinearMemory>, MemoryError> {
+        self.created.fetch_add(1, Ordering::SeqCst);
+        let len = memory_type.initial_pages().0 * 65536;
+        Ok(Box::new(vec![0x00_u8; len]))
+    }
+}
+
+/// A [`MemoryCreator`] that always fails.
+struct FailingCreator;
+
+impl MemoryCreator for FailingCreator {
+    fn new_memory(&self, _memory_type: MemoryType) -> Result<Box<dyn LinearMemory>, MemoryError> {
+        Err(MemoryError::OutOfBoundsAllocation)
+    }
+}
+
+/// A [`LinearMemory`] that moves its bytes to a new buffer upon every exclusive access.
+struct MovingMemory {
+    bytes: Vec<u8>,
+}
+
+impl LinearMemory for MovingMemory {
+    fn data(&self) -> &[u8] {
+        &self.bytes[..]
+    }
+
+    fn data_mut(&mut self) -> &mut [u8] {
+        self.bytes = self.bytes.clone();
+        &mut self.bytes[..]
+    }
+
+    fn resize(&mut self, new_len: usize) -> Result<(), MemoryError> {
+        self.bytes.resize(new_len, 0x00_u8);
+        Ok(())
+    }
+}
+
+#[test]
+fn moving_backend_works() {
+    for dirty_page_tracking in [false, true] {
+        let config = Config::default().enable_dirty_page_tracking(dirty_page_tracking);
+        let engine = Engine::new(&config);
+        let mut store = Store::new(&engine, ());
+        let memory_type = MemoryType::new(1, Some(2));
+        let backend = MovingMemory {
+            bytes: vec![0x00_u8; 65536],
+        };
+        let memory = Memory::new_with_backend(&mut store, memory_type, backend).unwrap();
+        let wasm = wat::parse_str(
+            r#"
+            (module
+                (import "env" "memory" (memory 1 2))
+                (func (export "store_load") (param i32 i64) (result i64)
+                    (i64.store (local.get 0) (local.get 1))
+                    (i64.store (i32.add (local.get 0) (i32.const 8)) (local.get 1))
+                    (i64.add
+                        (i64.load (local.get 0))
+                        (i64.load (i32.add (local.get 0) (i32.const 8)))
+                    )
+                )
+            )
+            "#,
+        )
+        .unwrap();
+        let module = Module::new(&engine, &wasm[..]).unwrap();
+        let mut linker = <Linker<()>>::new();
+        linker.define("env", "memory", memory).unwrap();
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .unwrap()
+            .ensure_no_start(&mut store)
+            .unwrap();
+        let store_load = instance
+            .get_export(&store, "store_load")
+            .and_then(Extern::into_func)
+            .unwrap()
+            .typed::<(i32, i64), i64, _>(&store)
+            .unwrap();
+        assert_eq!(store_load.call(&mut store, (16, 21)).unwrap(), 42);
+        assert_eq!(memory.data(&store)[16], 21);
+        assert!(store_load.call(&mut store, (65528, 1)).is_err());
+    }
+}
+
+#[test]
+fn vec_backend_grows() {
+    let mut store = Store::new(&Engine::default(), ());
+    let memory_type = MemoryType::new(1, Some(2));
+    let memory = Memory::new_with_backend(&mut store, memory_type, vec![0x00_u8; 65536]).unwrap();
+    memory.data_mut(&mut store)[0] = 1;
+    assert_eq!(memory.grow(&mut store, Pages(1)).unwrap(), Pages(1));
+    assert_eq!(memory.data(&store).len(), 2 * 65536);
+    assert_eq!(memory.data(&store)[0], 1);
+    assert!(memory.data(&store)[1..].iter().all(|byte| *byte == 0));
+    assert!(memory.grow(&mut store, Pages(1)).is_err());
+}
+
+#[test]
+fn static_backend_cannot_grow() {
+    let mut store = Store::new(&Engine::default(), ());
+    let buffer: &'static mut [u8] = Box::leak(vec![0x00_u8; 65536].into_boxed_slice());
+    let ptr = buffer.as_ptr();
+    let memory = Memory::new_with_backend(&mut store, MemoryType::new(1, None), buffer).unwrap();
+    memory.data_mut(&mut store)[..5].copy_from_slice(b"wasmi");
+    // The linear memory operates directly on the host buffer.
+    assert_eq!(memory.data(&store).as_ptr(), ptr);
+    assert!(memory.grow(&mut store, Pages(1)).is_err());
+    assert_eq!(memory.current_pages(&store), Pages(1));
+    assert_eq!(&memory.data(&store)[..5], b"wasmi");
+}
+
+#[test]
+fn backend_with_invalid_length_fails() {
+    let mut store = Store::new(&Engine::default(), ());
+    let result = Memory::new_with_backend(&mut store, MemoryType::new(1, None), vec![0x00_u8; 10]);
+    assert!(matches!(
+        result,
+        Err(MemoryError::InvalidBackendLength {
+            expected: 65536,
+            actual: 10
+        })
+    ));
+}
+
+#[test]
+fn memory_creator_is_used_for_instances() {
+    // Copy-on-write memory images must not bypass the installed memory creator.
+    for copy_on_write in [false, true] {
+        let config = Config::default().enable_copy_on_write_memory_init(copy_on_write);
+        let engine = Engine::new(&config);
+        let mut store = Store::new(&engine, ());
+        let creator = VecCreator::default();
+        let created = creator.created.clone();
+        store.memory_creator(creator);
+        let wasm = wat::parse_str(WAT).unwrap();
+        let module = Module::new(&engine, &wasm[..]).unwrap();
+        let instance = <Linker<()>>::new()
+            .instantiate(&mut store, &module)
+            .unwrap()
+            .ensure_no_start(&mut store)
+            .unwrap();
+        assert_eq!(created.load(Ordering::SeqCst), 1);
+        let memory = instance
+            .get_export(&store, "memory")
+            .and_then(Extern::into_memory)
+            .unwrap();
+        assert_eq!(&memory.data(&store)[..5], b"wasmi");
+        let grow = instance
+            .get_export(&store, "grow")
+            .and_then(Extern::into_func)
+            .unwrap()
+            .typed::<(), i32, _>(&store)
+            .unwrap();
+        assert_eq!(grow.call(&mut store, ()).unwrap(), 1);
+        assert_eq!(memory.data(&store)[65536], 42);
+    }
+}
+
+#[test]
+fn failing_memory_creator_fails_instantiation() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    store.memory_creator(FailingCreator);
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    assert!(<Linker<()>>::new()
+        .instantiate(&mut store, &module)
+        .is_err());
+}
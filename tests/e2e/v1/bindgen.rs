@@ -0,0 +1,65 @@
+//! Tests for the bindings generated by `wasmi_v1::bindgen`.
+
+use wasmi_core::{Trap, F32, F64};
+use wasmi_v1::{Caller, Engine, Linker, Module, Store};
+
+/// The bindings generated for `bindgen/guest.wat`.
+mod guest {
+    include!("bindgen/guest.rs");
+}
+
+use guest::{Guest, GuestImports};
+
+/// The host state implementing the imports of `bindgen/guest.wat`.
+#[derive(Debug, Default)]
+struct Host {
+    /// The number of calls to the imported `log` function.
+    logged: usize,
+}
+
+impl GuestImports for Host {
+    fn env_add_one(_caller: Caller<'_, Self>, value: i64) -> Result<i64, Trap> {
+        Ok(value + 1)
+    }
+
+    fn env_log(mut caller: Caller<'_, Self>, _ptr: i32, _len: i32) -> Result<(), Trap> {
+        caller.host_data_mut().logged += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn bindgen_fixture_is_up_to_date() {
+    let wasm = wat::parse_str(include_str!("bindgen/guest.wat")).unwrap();
+    let bindings = wasmi_v1::bindgen::generate("Guest", &wasm).unwrap();
+    assert_eq!(bindings, include_str!("bindgen/guest.rs"));
+}
+
+#[test]
+fn bindgen_generated_bindings_work() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, Host::default());
+    let mut linker = <Linker<Host>>::new();
+    Host::add_to_linker(&mut store, &mut linker).unwrap();
+    let wasm = wat::parse_str(include_str!("bindgen/guest.wat")).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .ensure_no_start(&mut store)
+        .unwrap();
+    let guest = Guest::new(&store, instance).unwrap();
+    assert!(guest.instance().get_export(&store, "do").is_some());
+    assert_eq!(guest.do_(&mut store, 41).unwrap(), 42);
+    assert_eq!(store.state().logged, 1);
+    assert_eq!(
+        guest
+            .new_1(&mut store, F32::from(1.5), F64::from(2.0))
+            .unwrap(),
+        F64::from(3.5)
+    );
+    assert_eq!(guest.override_(&mut store).unwrap(), (1, 0));
+    assert_eq!(guest.override_(&mut store).unwrap(), (2, 0));
+    assert_eq!(guest.memory().current_pages(&store).0, 1);
+    assert_eq!(guest.gen_().get(&store), wasmi_core::Value::I32(2));
+}
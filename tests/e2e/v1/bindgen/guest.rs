@@ -0,0 +1,131 @@
+/// Typed bindings to the exports of a `Guest` instance.
+#[derive(Debug, Copy, Clone)]
+pub struct Guest {
+    instance: ::wasmi_v1::Instance,
+    memory: ::wasmi_v1::Memory,
+    gen_: ::wasmi_v1::Global,
+    do_: ::wasmi_v1::TypedFunc<i64, i64>,
+    new_1: ::wasmi_v1::TypedFunc<(::wasmi_v1::core::F32,::wasmi_v1::core::F64,), ::wasmi_v1::core::F64>,
+    override_: ::wasmi_v1::TypedFunc<(), (i32,i64,)>,
+}
+
+impl Guest {
+    /// Creates the bindings for the exports of the `instance`.
+    ///
+    /// # Errors
+    ///
+    /// If the `instance` is missing an export or its type does not match.
+    pub fn new(
+        ctx: impl ::wasmi_v1::AsContext,
+        instance: ::wasmi_v1::Instance,
+    ) -> ::core::result::Result<Self, ::wasmi_v1::Error> {
+        let missing = |name: &str| {
+            ::wasmi_v1::Error::from(::wasmi_v1::errors::InstantiationError::MissingExport {
+                name: name.into(),
+            })
+        };
+        let memory = instance
+            .get_export(&ctx, "memory")
+            .and_then(::wasmi_v1::Extern::into_memory)
+            .ok_or_else(|| missing("memory"))?;
+        let gen_ = instance
+            .get_export(&ctx, "gen")
+            .and_then(::wasmi_v1::Extern::into_global)
+            .ok_or_else(|| missing("gen"))?;
+        let do_ = instance
+            .get_export(&ctx, "do")
+            .and_then(::wasmi_v1::Extern::into_func)
+            .ok_or_else(|| missing("do"))?;
+        let do_ = do_.typed(&ctx)?;
+        let new_1 = instance
+            .get_export(&ctx, "new")
+            .and_then(::wasmi_v1::Extern::into_func)
+            .ok_or_else(|| missing("new"))?;
+        let new_1 = new_1.typed(&ctx)?;
+        let override_ = instance
+            .get_export(&ctx, "override")
+            .and_then(::wasmi_v1::Extern::into_func)
+            .ok_or_else(|| missing("override"))?;
+        let override_ = override_.typed(&ctx)?;
+        ::core::result::Result::Ok(Self {
+            instance,
+            memory,
+            gen_,
+            do_,
+            new_1,
+            override_,
+        })
+    }
+
+    /// Returns the underlying instance.
+    pub fn instance(&self) -> ::wasmi_v1::Instance {
+        self.instance
+    }
+
+    /// Returns the exported Memory "memory".
+    pub fn memory(&self) -> ::wasmi_v1::Memory {
+        self.memory
+    }
+
+    /// Returns the exported Global "gen".
+    pub fn gen_(&self) -> ::wasmi_v1::Global {
+        self.gen_
+    }
+
+    /// Calls the exported function "do".
+    pub fn do_(
+        &self,
+        ctx: impl ::wasmi_v1::AsContextMut, param0: i64,
+    ) -> ::core::result::Result<i64, ::wasmi_v1::core::Trap> {
+        self.do_.call(ctx, param0)
+    }
+
+    /// Calls the exported function "new".
+    pub fn new_1(
+        &self,
+        ctx: impl ::wasmi_v1::AsContextMut, param0: ::wasmi_v1::core::F32, param1: ::wasmi_v1::core::F64,
+    ) -> ::core::result::Result<::wasmi_v1::core::F64, ::wasmi_v1::core::Trap> {
+        self.new_1.call(ctx, (param0,param1,))
+    }
+
+    /// Calls the exported function "override".
+    pub fn override_(
+        &self,
+        ctx: impl ::wasmi_v1::AsContextMut,
+    ) -> ::core::result::Result<(i32,i64,), ::wasmi_v1::core::Trap> {
+        self.override_.call(ctx, ())
+    }
+}
+
+/// The host functions required by a `Guest` instance.
+pub trait GuestImports: Sized + 'static {
+    /// Implements the imported function "add-one" of module "env".
+    fn env_add_one(
+        caller: ::wasmi_v1::Caller<'_, Self>, param0: i64,
+    ) -> ::core::result::Result<i64, ::wasmi_v1::core::Trap>;
+
+    /// Implements the imported function "log" of module "env".
+    fn env_log(
+        caller: ::wasmi_v1::Caller<'_, Self>, param0: i32, param1: i32,
+    ) -> ::core::result::Result<(), ::wasmi_v1::core::Trap>;
+
+    /// Defines all imported functions in the `linker`.
+    ///
+    /// # Errors
+    ///
+    /// If any of the functions is already defined in the `linker`.
+    fn add_to_linker(
+        mut ctx: impl ::wasmi_v1::AsContextMut<UserState = Self>,
+        linker: &mut ::wasmi_v1::Linker<Self>,
+    ) -> ::core::result::Result<(), ::wasmi_v1::errors::LinkerError> {
+        let func = ::wasmi_v1::Func::wrap(&mut ctx, |caller: ::wasmi_v1::Caller<'_, Self>, param0: i64| {
+            <Self as GuestImports>::env_add_one(caller, param0,)
+        });
+        linker.define("env", "add-one", func)?;
+        let func = ::wasmi_v1::Func::wrap(&mut ctx, |caller: ::wasmi_v1::Caller<'_, Self>, param0: i32, param1: i32| {
+            <Self as GuestImports>::env_log(caller, param0,param1,)
+        });
+        linker.define("env", "log", func)?;
+        ::core::result::Result::Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+(module
+    (import "env" "add-one" (func $add_one (param i64) (result i64)))
+    (import "env" "log" (func $log (param i32 i32)))
+    (memory (export "memory") 1)
+    (global $counter (export "gen") (mut i32) (i32.const 0))
+    (func (export "do") (param i64) (result i64)
+        (call $log (i32.const 0) (i32.const 0))
+        (call $add_one (local.get 0))
+    )
+    (func (export "new") (param f32 f64) (result f64)
+        (f64.add (f64.promote_f32 (local.get 0)) (local.get 1))
+    )
+    (func (export "override") (result i32 i64)
+        (global.set $counter (i32.add (global.get $counter) (i32.const 1)))
+        (global.get $counter)
+        (i64.const 0)
+    )
+)
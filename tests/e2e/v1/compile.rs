@@ -0,0 +1,215 @@
+//! Tests for the compilation modes of `wasmi_v1`.
+
+use wasmi_core::Value;
+use wasmi_v1::{CompilationMode, Config, Engine, Instance, Linker, Module, Store};
+
+/// A Wasm module calling functions directly and indirectly.
+const WAT: &str = r#"
+    (module
+        (type $unary (func (param i32) (result i32)))
+        (table 2 funcref)
+        (elem (i32.const 0) $inc $double)
+        (func $inc (type $unary) (i32.add (local.get 0) (i32.const 1)))
+        (func $double (type $unary) (i32.mul (local.get 0) (i32.const 2)))
+        (func $square (type $unary) (i32.mul (local.get 0) (local.get 0)))
+        (func (export "square_inc") (param i32) (result i32)
+            (call $square (call $inc (local.get 0)))
+        )
+        (func (export "apply") (param $op i32) (param $n i32) (result i32)
+            (call_indirect (type $unary) (local.get $n) (local.get $op))
+        )
+    )
+"#;
+
+/// Translates and instantiates [`WAT`] with an [`Engine`] using the given `mode`.
+fn instantiate(mode: CompilationMode) -> (Engine, Module, Store<()>, Instance) {
+    let engine = Engine::new(&Config::default().set_compilation_mode(mode));
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, ());
+    let instance = <Linker<()>>::new()
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .ensure_no_start(&mut store)
+        .unwrap();
+    (engine, module, store, instance)
+}
+
+/// Calls the exported function `name` of the `instance` with the given `params`.
+fn call(store: &mut Store<()>, instance: Instance, name: &str, params: &[Value]) -> Value {
+    let func = instance
+        .get_export(&*store, name)
+        .and_then(|export| export.into_func())
+        .unwrap();
+    let mut results = [Value::I32(0)];
+    func.call(&mut *store, params, &mut results).unwrap();
+    results[0]
+}
+
+#[test]
+fn lazy_compilation_translates_on_first_call() {
+    let (engine, _module, mut store, instance) = instantiate(CompilationMode::Lazy);
+    assert_eq!(engine.stats().func_bodies, 0);
+    assert_eq!(
+        call(&mut store, instance, "square_inc", &[Value::I32(4)]),
+        Value::I32(25)
+    );
+    // Translated `square_inc`, `$inc` and `$square`.
+    assert_eq!(engine.stats().func_bodies, 3);
+    assert_eq!(
+        call(&mut store, instance, "square_inc", &[Value::I32(2)]),
+        Value::I32(9)
+    );
+    assert_eq!(engine.stats().func_bodies, 3);
+    assert_eq!(
+        call(
+            &mut store,
+            instance,
+            "apply",
+            &[Value::I32(1), Value::I32(21)]
+        ),
+        Value::I32(42)
+    );
+    // Translated `apply` and `$double`.
+    assert_eq!(engine.stats().func_bodies, 5);
+}
+
+#[test]
+fn lazy_compilation_matches_eager_compilation() {
+    let (eager_engine, _, mut eager_store, eager) = instantiate(CompilationMode::Eager);
+    let (lazy_engine, _, mut lazy_store, lazy) = instantiate(CompilationMode::Lazy);
+    for (name, params) in [
+        ("square_inc", [Value::I32(7)].as_slice()),
+        ("apply", &[Value::I32(0), Value::I32(-1)]),
+        ("apply", &[Value::I32(1), Value::I32(5)]),
+    ] {
+        assert_eq!(
+            call(&mut eager_store, eager, name, params),
+            call(&mut lazy_store, lazy, name, params),
+        );
+    }
+    // Note: The allocated bytes of the code map depend on the translation order.
+    let (eager_stats, lazy_stats) = (eager_engine.stats(), lazy_engine.stats());
+    assert_eq!(eager_stats.func_types, lazy_stats.func_types);
+    assert_eq!(eager_stats.func_bodies, lazy_stats.func_bodies);
+    assert_eq!(eager_stats.instructions, lazy_stats.instructions);
+}
+
+#[test]
+fn lazy_compilation_validates_up_front() {
+    let engine = Engine::new(&Config::default().set_compilation_mode(CompilationMode::Lazy));
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (func (result i32) (i64.const 0))
+        )
+    "#,
+    )
+    .unwrap();
+    assert!(Module::new(&engine, &wasm[..]).is_err());
+}
+
+#[test]
+fn lazy_compilation_serialize_works() {
+    let (engine, module, _, _) = instantiate(CompilationMode::Lazy);
+    let bytes = module.serialize();
+    assert_eq!(engine.stats().func_bodies, 5);
+    let (_, eager_module, _, _) = instantiate(CompilationMode::Eager);
+    assert_eq!(bytes, eager_module.serialize());
+}
+
+/// Returns a Wasm module with `len` functions each calling its predecessor.
+fn chained_wat(len: usize) -> String {
+    let mut wat = String::from("(module\n(func $f0 (param i32) (result i32) (local.get 0))\n");
+    for n in 1..len {
+        wat.push_str(&format!(
+            "(func $f{n} (param i32) (result i32) (call $f{} (i32.add (local.get 0) (i32.const {n}))))\n",
+            n - 1
+        ));
+    }
+    wat.push_str(&format!("(export \"last\" (func $f{}))\n)", len - 1));
+    wat
+}
+
+#[test]
+fn parallel_compilation_matches_sequential_compilation() {
+    let wasm = wat::parse_str(chained_wat(100)).unwrap();
+    let run = |config: &Config| {
+        let engine = Engine::new(config);
+        // Compiling twice makes sure that function bodies are relocated properly.
+        Module::new(&engine, &wasm[..]).unwrap();
+        let module = Module::new(&engine, &wasm[..]).unwrap();
+        let mut store = Store::new(&engine, ());
+        let instance = <Linker<()>>::new()
+            .instantiate(&mut store, &module)
+            .unwrap()
+            .ensure_no_start(&mut store)
+            .unwrap();
+        let result = call(&mut store, instance, "last", &[Value::I32(0)]);
+        let stats = engine.stats();
+        (
+            stats.func_bodies,
+            stats.instructions,
+            module.serialize(),
+            result,
+        )
+    };
+    let sequential = run(&Config::default());
+    let parallel = run(&Config::default().enable_parallel_compilation(true));
+    assert_eq!(parallel.3, Value::I32((0..100).sum()));
+    assert_eq!(sequential, parallel);
+}
+
+#[test]
+fn parallel_compilation_validates() {
+    let mut wat = chained_wat(50);
+    wat.insert_str(wat.len() - 1, "(func (result i32) (i64.const 0))\n");
+    let wasm = wat::parse_str(wat).unwrap();
+    let engine = Engine::new(&Config::default().enable_parallel_compilation(true));
+    assert!(Module::new(&engine, &wasm[..]).is_err());
+    // Failed compilation does not leave translated function bodies behind.
+    assert_eq!(engine.stats().func_bodies, 0);
+}
+
+#[test]
+fn compilation_cache_shares_translation() {
+    let engine = Engine::new(&Config::default().enable_compilation_cache(true));
+    let wasm = wat::parse_str(WAT).unwrap();
+    let first = Module::new(&engine, &wasm[..]).unwrap();
+    let stats = engine.stats();
+    assert_eq!(stats.cached_modules, 1);
+    let second = Module::new(&engine, &wasm[..]).unwrap();
+    // The second module reuses the translated function bodies of the first.
+    assert_eq!(engine.stats(), stats);
+    assert_eq!(first.serialize(), second.serialize());
+    let mut store = Store::new(&engine, ());
+    let instance = <Linker<()>>::new()
+        .instantiate(&mut store, &second)
+        .unwrap()
+        .ensure_no_start(&mut store)
+        .unwrap();
+    assert_eq!(
+        call(&mut store, instance, "square_inc", &[Value::I32(4)]),
+        Value::I32(25)
+    );
+    // Different bytes are translated separately.
+    let other = wat::parse_str(r#"(module (func (export "f")))"#).unwrap();
+    Module::new(&engine, &other[..]).unwrap();
+    assert_eq!(engine.stats().cached_modules, 2);
+    assert_eq!(engine.stats().func_bodies, stats.func_bodies + 1);
+    engine.clear_compilation_cache();
+    assert_eq!(engine.stats().cached_modules, 0);
+    Module::new(&engine, &wasm[..]).unwrap();
+    assert_eq!(engine.stats().func_bodies, 2 * stats.func_bodies + 1);
+}
+
+#[test]
+fn compilation_cache_is_opt_in() {
+    let engine = Engine::default();
+    let wasm = wat::parse_str(WAT).unwrap();
+    Module::new(&engine, &wasm[..]).unwrap();
+    let func_bodies = engine.stats().func_bodies;
+    Module::new(&engine, &wasm[..]).unwrap();
+    assert_eq!(engine.stats().func_bodies, 2 * func_bodies);
+    assert_eq!(engine.stats().cached_modules, 0);
+}
@@ -1,8 +1,8 @@
 //! Tests for the `Func` type in `wasmi_v1`.
 
 use assert_matches::assert_matches;
-use wasmi_core::{Value, F32, F64};
-use wasmi_v1::{errors::FuncError, Engine, Error, Func, Store};
+use wasmi_core::{Trap, TrapCode, Value, ValueType, F32, F64};
+use wasmi_v1::{errors::FuncError, Caller, Config, Engine, Error, Extern, Func, Store};
 
 fn test_setup() -> Store<()> {
     let engine = Engine::default();
@@ -419,3 +419,255 @@ fn static_type_check_works() {
         Err(Error::Func(FuncError::MismatchingResults { .. }))
     );
 }
+
+/// Instantiates a module that forwards its exports to the given borrowing host functions.
+fn setup_borrowing(store: &mut Store<()>, str_len: Func, copy: Func) -> wasmi_v1::Instance {
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (import "env" "str_len" (func $str_len (param i32 i32) (result i32)))
+            (import "env" "copy" (func $copy (param i32 i32 i32 i32) (result i32)))
+            (memory (export "memory") 1)
+            (data (i32.const 0) "hello\ff")
+            (func (export "str_len") (param i32 i32) (result i32)
+                (call $str_len (local.get 0) (local.get 1))
+            )
+            (func (export "copy") (param i32 i32 i32 i32) (result i32)
+                (call $copy (local.get 0) (local.get 1) (local.get 2) (local.get 3))
+            )
+        )
+        "#,
+    )
+    .unwrap();
+    let module = wasmi_v1::Module::new(store.engine(), &wasm[..]).unwrap();
+    let mut linker = <wasmi_v1::Linker<()>>::new();
+    linker.define("env", "str_len", str_len).unwrap();
+    linker.define("env", "copy", copy).unwrap();
+    linker
+        .instantiate(&mut *store, &module)
+        .unwrap()
+        .ensure_no_start(&mut *store)
+        .unwrap()
+}
+
+fn setup_borrowing_funcs() -> (Store<()>, wasmi_v1::Instance) {
+    let mut store = test_setup();
+    let str_len = Func::wrap(&mut store, |s: &str| s.len() as i32);
+    let copy = Func::wrap(&mut store, |dst: &mut [u8], src: &[u8]| {
+        let len = dst.len().min(src.len());
+        dst[..len].copy_from_slice(&src[..len]);
+        len as i32
+    });
+    let instance = setup_borrowing(&mut store, str_len, copy);
+    (store, instance)
+}
+
+#[test]
+fn borrowed_params_signature_works() {
+    let mut store = test_setup();
+    let str_len = Func::wrap(&mut store, |_: &str, _: i64| 0_i32);
+    assert_eq!(
+        str_len.func_type(&store).params(),
+        &[ValueType::I32, ValueType::I32, ValueType::I64]
+    );
+}
+
+#[test]
+fn borrowed_params_work() {
+    let (mut store, instance) = setup_borrowing_funcs();
+    let str_len = instance
+        .get_export(&store, "str_len")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<(i32, i32), i32, _>(&store)
+        .unwrap();
+    let copy = instance
+        .get_export(&store, "copy")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<(i32, i32, i32, i32), i32, _>(&store)
+        .unwrap();
+    assert_eq!(str_len.call(&mut store, (0, 5)).unwrap(), 5);
+    assert_eq!(copy.call(&mut store, (100, 5, 0, 5)).unwrap(), 5);
+    let memory = instance
+        .get_export(&store, "memory")
+        .and_then(Extern::into_memory)
+        .unwrap();
+    assert_eq!(&memory.data(&store)[100..105], b"hello");
+}
+
+#[test]
+fn borrowed_params_trap() {
+    let (mut store, instance) = setup_borrowing_funcs();
+    let str_len = instance
+        .get_export(&store, "str_len")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<(i32, i32), i32, _>(&store)
+        .unwrap();
+    let copy = instance
+        .get_export(&store, "copy")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<(i32, i32, i32, i32), i32, _>(&store)
+        .unwrap();
+    // Out of bounds of the linear memory.
+    assert_matches!(
+        str_len.call(&mut store, (65536 - 2, 5)),
+        Err(Trap::Code(TrapCode::MemoryAccessOutOfBounds))
+    );
+    // Invalid UTF-8 encoding.
+    assert_matches!(str_len.call(&mut store, (0, 6)), Err(Trap::Host(_)));
+    // Exclusive borrow overlaps with shared borrow.
+    assert_matches!(copy.call(&mut store, (2, 4, 0, 4)), Err(Trap::Host(_)));
+    // Empty borrows never overlap.
+    assert_eq!(copy.call(&mut store, (0, 0, 0, 4)).unwrap(), 0);
+}
+
+fn setup_borrowing_caller_funcs(mut store: Store<()>) -> (Store<()>, wasmi_v1::Instance) {
+    let str_len = Func::wrap(&mut store, |caller: Caller<()>, s: &str| {
+        // The caller remains usable alongside the borrowed views.
+        assert!(caller.get_export("memory").is_some());
+        s.len() as i32
+    });
+    let copy = Func::wrap(
+        &mut store,
+        |_caller: Caller<()>, dst: &mut [u8], src: &[u8]| {
+            let len = dst.len().min(src.len());
+            dst[..len].copy_from_slice(&src[..len]);
+            len as i32
+        },
+    );
+    let instance = setup_borrowing(&mut store, str_len, copy);
+    (store, instance)
+}
+
+#[test]
+fn borrowed_params_with_caller_work() {
+    let (mut store, instance) = setup_borrowing_caller_funcs(test_setup());
+    let str_len = instance
+        .get_export(&store, "str_len")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<(i32, i32), i32, _>(&store)
+        .unwrap();
+    let copy = instance
+        .get_export(&store, "copy")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<(i32, i32, i32, i32), i32, _>(&store)
+        .unwrap();
+    assert_eq!(str_len.call(&mut store, (0, 5)).unwrap(), 5);
+    assert_eq!(copy.call(&mut store, (100, 5, 0, 5)).unwrap(), 5);
+    let memory = instance
+        .get_export(&store, "memory")
+        .and_then(Extern::into_memory)
+        .unwrap();
+    assert_eq!(&memory.data(&store)[100..105], b"hello");
+    // Out of bounds of the linear memory.
+    assert_matches!(
+        str_len.call(&mut store, (65536 - 2, 5)),
+        Err(Trap::Code(TrapCode::MemoryAccessOutOfBounds))
+    );
+    // Invalid UTF-8 encoding.
+    assert_matches!(str_len.call(&mut store, (0, 6)), Err(Trap::Host(_)));
+    // Exclusive borrow overlaps with shared borrow.
+    assert_matches!(copy.call(&mut store, (2, 4, 0, 4)), Err(Trap::Host(_)));
+    assert_eq!(&memory.data(&store)[..5], b"hello");
+}
+
+#[test]
+fn shared_borrowed_params_do_not_mark_pages_dirty() {
+    let engine = Engine::new(&Config::default().enable_dirty_page_tracking(true));
+    let mut store = Store::new(&engine, ());
+    let str_len = Func::wrap(&mut store, |s: &str| s.len() as i32);
+    let copy = Func::wrap(&mut store, |dst: &mut [u8], src: &[u8]| {
+        let len = dst.len().min(src.len());
+        dst[..len].copy_from_slice(&src[..len]);
+        len as i32
+    });
+    let plain = setup_borrowing(&mut store, str_len, copy);
+    let (mut store, with_caller) = setup_borrowing_caller_funcs(store);
+    for instance in [plain, with_caller] {
+        let str_len = instance
+            .get_export(&store, "str_len")
+            .and_then(Extern::into_func)
+            .unwrap()
+            .typed::<(i32, i32), i32, _>(&store)
+            .unwrap();
+        let copy = instance
+            .get_export(&store, "copy")
+            .and_then(Extern::into_func)
+            .unwrap()
+            .typed::<(i32, i32, i32, i32), i32, _>(&store)
+            .unwrap();
+        let memory = instance
+            .get_export(&store, "memory")
+            .and_then(Extern::into_memory)
+            .unwrap();
+        memory.clear_dirty(&mut store);
+        assert_eq!(str_len.call(&mut store, (0, 5)).unwrap(), 5);
+        assert!(memory.dirty_pages(&store).is_empty());
+        assert_eq!(copy.call(&mut store, (100, 5, 0, 5)).unwrap(), 5);
+        assert_eq!(memory.dirty_pages(&store), [0]);
+    }
+}
+
+#[test]
+fn caller_data_and_host_data_mut_works() {
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (import "env" "read" (func $read (param i32 i32) (result i32)))
+            (memory (export "memory") 1)
+            (func (export "read") (param i32 i32) (result i32)
+                (call $read (local.get 0) (local.get 1))
+            )
+        )
+        "#,
+    )
+    .unwrap();
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, b"hello world".to_vec());
+    // Reads from the host provided file directly into the linear memory.
+    let read = Func::wrap(
+        &mut store,
+        |mut caller: wasmi_v1::Caller<Vec<u8>>, ptr: u32, len: u32| -> Result<u32, Trap> {
+            let memory = caller
+                .get_export("memory")
+                .and_then(Extern::into_memory)
+                .ok_or(TrapCode::MemoryAccessOutOfBounds)?;
+            let (data, file) = caller.data_and_host_data_mut(memory);
+            let len = (len as usize).min(file.len());
+            let buffer = data
+                .get_mut(ptr as usize..)
+                .and_then(|data| data.get_mut(..len))
+                .ok_or(TrapCode::MemoryAccessOutOfBounds)?;
+            buffer.copy_from_slice(&file[..len]);
+            file.drain(..len);
+            Ok(len as u32)
+        },
+    );
+    let module = wasmi_v1::Module::new(&engine, &wasm[..]).unwrap();
+    let mut linker = <wasmi_v1::Linker<Vec<u8>>>::new();
+    linker.define("env", "read", read).unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .ensure_no_start(&mut store)
+        .unwrap();
+    let read = instance
+        .get_export(&store, "read")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<(u32, u32), u32, _>(&store)
+        .unwrap();
+    let memory = instance
+        .get_export(&store, "memory")
+        .and_then(Extern::into_memory)
+        .unwrap();
+    assert_eq!(read.call(&mut store, (10, 5)).unwrap(), 5);
+    assert_eq!(read.call(&mut store, (15, 100)).unwrap(), 6);
+    assert_eq!(&memory.data(&store)[10..21], b"hello world");
+    assert!(store.state().is_empty());
+}
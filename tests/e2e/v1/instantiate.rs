@@ -0,0 +1,139 @@
+//! Tests for atomic instantiation of Wasm modules in `wasmi_v1`.
+
+use wasmi_v1::{
+    errors::InstantiationError,
+    Engine,
+    Error,
+    Linker,
+    Memory,
+    MemoryType,
+    Module,
+    Store,
+    StoreLimits,
+    Table,
+    TableType,
+};
+
+/// A Wasm module that writes into the imported table and linear memory
+/// and has the given `start` function if any.
+fn wat(table_offset: u32, memory_offset: u32, start: &str) -> String {
+    format!(
+        r#"
+        (module
+            (import "env" "table" (table 4 funcref))
+            (import "env" "memory" (memory 1))
+            (func $f)
+            {}
+            (elem (i32.const 0) $f $f)
+            (elem (i32.const {}) $f $f)
+            (data (i32.const 0) "wasmi")
+            (data (i32.const {}) "hello")
+        )
+        "#,
+        start, table_offset, memory_offset
+    )
+}
+
+/// Creates a [`Store`] allowing a single instance and a [`Linker`] defining the imports of [`wat`].
+fn setup() -> (Store<StoreLimits>, Linker<StoreLimits>, Table, Memory) {
+    let engine = Engine::default();
+    // Note: Instances of failed instantiations must not count towards the limit.
+    let limits = StoreLimits::new().limit_instances(1);
+    let mut store = Store::new(&engine, limits);
+    store.limiter(|limits| limits);
+    let table = Table::new(&mut store, TableType::new(4, None)).unwrap();
+    let memory = Memory::new(&mut store, MemoryType::new(1, None)).unwrap();
+    let mut linker = <Linker<StoreLimits>>::new();
+    linker.define("env", "table", table).unwrap();
+    linker.define("env", "memory", memory).unwrap();
+    (store, linker, table, memory)
+}
+
+/// Instantiates [`wat`] with the given parameters and runs its `start` function.
+fn instantiate(
+    store: &mut Store<StoreLimits>,
+    linker: &mut Linker<StoreLimits>,
+    wat: &str,
+) -> Result<(), Error> {
+    let wasm = wat::parse_str(wat).unwrap();
+    let module = Module::new(store.engine(), &wasm[..]).unwrap();
+    linker
+        .instantiate(&mut *store, &module)?
+        .start(&mut *store)?;
+    Ok(())
+}
+
+/// Asserts that the imported `table` and `memory` have not been written.
+fn assert_untouched(store: &Store<StoreLimits>, table: Table, memory: Memory) {
+    for index in 0..4 {
+        assert!(table.get(store, index).unwrap().is_none());
+    }
+    assert!(memory.data(store).iter().all(|byte| *byte == 0));
+}
+
+#[test]
+fn element_segment_out_of_bounds_writes_nothing() {
+    let (mut store, mut linker, table, memory) = setup();
+    let error = instantiate(&mut store, &mut linker, &wat(3, 100, "")).unwrap_err();
+    assert!(matches!(
+        error,
+        Error::Instantiation(InstantiationError::ElementSegmentDoesNotFit { offset: 3, .. })
+    ));
+    assert_untouched(&store, table, memory);
+    // The entities of the failed instantiation have been dropped from the store.
+    instantiate(&mut store, &mut linker, &wat(2, 100, "")).unwrap();
+    assert!(table.get(&store, 3).unwrap().is_some());
+    assert_eq!(&memory.data(&store)[100..105], b"hello");
+}
+
+#[test]
+fn data_segment_out_of_bounds_writes_nothing() {
+    let (mut store, mut linker, table, memory) = setup();
+    let error = instantiate(&mut store, &mut linker, &wat(2, 65532, "")).unwrap_err();
+    assert!(matches!(
+        error,
+        Error::Instantiation(InstantiationError::DataSegmentDoesNotFit {
+            offset: 65532,
+            amount: 5,
+            ..
+        })
+    ));
+    assert_untouched(&store, table, memory);
+    instantiate(&mut store, &mut linker, &wat(2, 65531, "")).unwrap();
+}
+
+#[test]
+fn trapping_start_fn_rolls_back() {
+    let (mut store, mut linker, table, memory) = setup();
+    let start = r#"
+        (func $start (i32.store8 (i32.const 1000) (i32.const 1)) unreachable)
+        (start $start)
+    "#;
+    let error = instantiate(&mut store, &mut linker, &wat(2, 100, start)).unwrap_err();
+    assert!(matches!(error, Error::Trap(_)));
+    // The segments are undone but other writes of the `start` function persist.
+    for index in 0..4 {
+        assert!(table.get(&store, index).unwrap().is_none());
+    }
+    assert_eq!(&memory.data(&store)[..5], &[0; 5]);
+    assert_eq!(&memory.data(&store)[100..105], &[0; 5]);
+    assert_eq!(memory.data(&store)[1000], 1);
+    instantiate(&mut store, &mut linker, &wat(2, 100, "")).unwrap();
+}
+
+#[test]
+fn found_start_fn_rolls_back() {
+    let (mut store, mut linker, table, memory) = setup();
+    let wasm = wat::parse_str(wat(2, 100, "(func $start) (start $start)")).unwrap();
+    let module = Module::new(store.engine(), &wasm[..]).unwrap();
+    let result = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .ensure_no_start(&mut store);
+    assert!(matches!(
+        result,
+        Err(InstantiationError::FoundStartFn { .. })
+    ));
+    assert_untouched(&store, table, memory);
+    instantiate(&mut store, &mut linker, &wat(2, 100, "")).unwrap();
+}
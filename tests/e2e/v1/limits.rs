@@ -0,0 +1,177 @@
+//! Tests for the `ResourceLimiter` of a `Store` in `wasmi_v1`.
+
+use assert_matches::assert_matches;
+use wasmi_core::memory_units::Pages;
+use wasmi_v1::{
+    errors::{InstantiationError, MemoryError, TableError},
+    Engine,
+    Error,
+    Extern,
+    Instance,
+    Linker,
+    Memory,
+    MemoryType,
+    Module,
+    Store,
+    StoreLimits,
+    Table,
+    TableType,
+    TABLE_ELEMENT_BYTES,
+};
+
+/// Creates a new [`Store`] with the given `limits` installed.
+fn test_setup(limits: StoreLimits) -> Store<StoreLimits> {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, limits);
+    store.limiter(|limits| limits);
+    store
+}
+
+/// Instantiates the Wasm module given in the text format.
+fn instantiate(store: &mut Store<StoreLimits>, wat: &str) -> Result<Instance, Error> {
+    let wasm = wat::parse_str(wat).unwrap();
+    let module = Module::new(store.engine(), &wasm[..]).unwrap();
+    let mut linker = <Linker<StoreLimits>>::new();
+    let instance = linker
+        .instantiate(&mut *store, &module)?
+        .ensure_no_start(&mut *store)
+        .unwrap();
+    Ok(instance)
+}
+
+#[test]
+fn memory_grow_denied() {
+    let mut store = test_setup(StoreLimits::new().limit_memory_pages(Pages(3)));
+    let instance = instantiate(
+        &mut store,
+        r#"
+        (module
+            (memory 1)
+            (func (export "grow") (param i32) (result i32)
+                (memory.grow (local.get 0))
+            )
+        )
+        "#,
+    )
+    .unwrap();
+    let grow = instance
+        .get_export(&store, "grow")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<i32, i32, _>(&store)
+        .unwrap();
+    assert_eq!(grow.call(&mut store, 2).unwrap(), 1);
+    assert_eq!(grow.call(&mut store, 1).unwrap(), -1);
+    assert_eq!(grow.call(&mut store, 0).unwrap(), 3);
+}
+
+#[test]
+fn instantiation_memory_denied() {
+    let mut store = test_setup(StoreLimits::new().limit_memory_pages(Pages(3)));
+    let result = instantiate(&mut store, "(module (memory 4))");
+    assert_matches!(
+        result,
+        Err(Error::Instantiation(InstantiationError::Memory(
+            MemoryError::ResourceLimitReached
+        )))
+    );
+    assert!(instantiate(&mut store, "(module (memory 3))").is_ok());
+    assert_matches!(
+        Memory::new(&mut store, MemoryType::new(4, None)),
+        Err(MemoryError::ResourceLimitReached)
+    );
+}
+
+#[test]
+fn instantiation_table_denied() {
+    let mut store = test_setup(StoreLimits::new().limit_table_elements(10));
+    let result = instantiate(&mut store, "(module (table 11 funcref))");
+    assert_matches!(
+        result,
+        Err(Error::Instantiation(InstantiationError::Table(
+            TableError::ResourceLimitReached
+        )))
+    );
+    let table = Table::new(&mut store, TableType::new(5, None)).unwrap();
+    table.grow(&mut store, 5).unwrap();
+    assert_matches!(
+        table.grow(&mut store, 1),
+        Err(TableError::ResourceLimitReached)
+    );
+    assert_eq!(table.len(&store), 10);
+}
+
+#[test]
+fn too_many_entities() {
+    let mut store = test_setup(
+        StoreLimits::new()
+            .limit_instances(2)
+            .limit_memories(1)
+            .limit_tables(1),
+    );
+    instantiate(&mut store, "(module (memory 1) (table 1 funcref))").unwrap();
+    assert_matches!(
+        Memory::new(&mut store, MemoryType::new(1, None)),
+        Err(MemoryError::ResourceLimitReached)
+    );
+    assert_matches!(
+        Table::new(&mut store, TableType::new(1, None)),
+        Err(TableError::ResourceLimitReached)
+    );
+    instantiate(&mut store, "(module)").unwrap();
+    assert_matches!(
+        instantiate(&mut store, "(module)"),
+        Err(Error::Instantiation(InstantiationError::TooManyInstances))
+    );
+}
+
+#[test]
+fn total_bytes_denied() {
+    let table_bytes = 10 * TABLE_ELEMENT_BYTES;
+    let mut store = test_setup(StoreLimits::new().limit_total_bytes(2 * 65536 + table_bytes));
+    let instance = instantiate(
+        &mut store,
+        r#"
+        (module
+            (memory 1)
+            (table 5 funcref)
+            (func (export "grow") (param i32) (result i32)
+                (memory.grow (local.get 0))
+            )
+        )
+        "#,
+    )
+    .unwrap();
+    let grow = instance
+        .get_export(&store, "grow")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<i32, i32, _>(&store)
+        .unwrap();
+    // The budget applies to all linear memories and tables of the store combined.
+    assert_matches!(
+        Memory::new(&mut store, MemoryType::new(2, None)),
+        Err(MemoryError::ResourceLimitReached)
+    );
+    let memory = Memory::new(&mut store, MemoryType::new(1, None)).unwrap();
+    assert_eq!(grow.call(&mut store, 1).unwrap(), -1);
+    assert_eq!(grow.call(&mut store, 0).unwrap(), 1);
+    assert_matches!(
+        Table::new(&mut store, TableType::new(6, None)),
+        Err(TableError::ResourceLimitReached)
+    );
+    let table = Table::new(&mut store, TableType::new(5, None)).unwrap();
+    assert_matches!(
+        table.grow(&mut store, 1),
+        Err(TableError::ResourceLimitReached)
+    );
+    assert_matches!(
+        instantiate(&mut store, "(module (memory 0) (table 1 funcref))"),
+        Err(Error::Instantiation(InstantiationError::Table(
+            TableError::ResourceLimitReached
+        )))
+    );
+    assert!(instantiate(&mut store, "(module (memory 0) (table 0 funcref))").is_ok());
+    assert_eq!(memory.current_pages(&store), Pages(1));
+    assert_eq!(table.len(&store), 5);
+}
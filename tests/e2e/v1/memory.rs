@@ -0,0 +1,310 @@
+//! Tests for initializing and accessing linear memories of Wasm modules in `wasmi_v1`.
+
+use assert_matches::assert_matches;
+use wasmi_core::{memory_units::Pages, Trap, TrapCode, Value};
+use wasmi_v1::{
+    Config,
+    Engine,
+    Error,
+    Extern,
+    Func,
+    Global,
+    Instance,
+    Linker,
+    Memory,
+    Module,
+    Mutability,
+    Store,
+};
+
+/// A Wasm module with data segments spanning multiple pages of its linear memory.
+///
+/// The offset of the last data segment is read from an imported global variable.
+fn wat(offset: &str) -> String {
+    format!(
+        r#"
+        (module
+            (import "env" "offset" (global $offset i32))
+            (memory (export "memory") 3 4)
+            (data (i32.const 0) "hello")
+            (data (i32.const 3) "LO")
+            (data (i32.const 70000) "wasmi")
+            (data ({}) "world")
+        )
+        "#,
+        offset
+    )
+}
+
+/// Creates a [`Store`] with copy-on-write memory initialization enabled.
+fn store() -> Store<()> {
+    let config = Config::default().enable_copy_on_write_memory_init(true);
+    let engine = Engine::new(&config);
+    Store::new(&engine, ())
+}
+
+/// Instantiates the `module` and returns its exported linear memory.
+fn instantiate(store: &mut Store<()>, module: &Module) -> (Instance, Memory) {
+    let offset = Global::new(&mut *store, Value::I32(131072), Mutability::Const);
+    let mut linker = <Linker<()>>::new();
+    linker.define("env", "offset", offset).unwrap();
+    let instance = linker
+        .instantiate(&mut *store, module)
+        .unwrap()
+        .ensure_no_start(&mut *store)
+        .unwrap();
+    let memory = instance
+        .get_export(&*store, "memory")
+        .and_then(Extern::into_memory)
+        .unwrap();
+    (instance, memory)
+}
+
+/// Asserts that the `memory` contains exactly the data segments of [`wat`].
+fn assert_initialized(store: &Store<()>, memory: Memory) {
+    let data = memory.data(store);
+    assert_eq!(data.len(), 3 * 65536);
+    assert_eq!(&data[..5], b"helLO");
+    assert_eq!(&data[70000..70005], b"wasmi");
+    assert_eq!(&data[131072..131077], b"world");
+    let zeros = [&data[5..70000], &data[70005..131072], &data[131077..]];
+    assert!(zeros
+        .iter()
+        .all(|bytes| bytes.iter().all(|byte| *byte == 0)));
+}
+
+#[test]
+fn copy_on_write_memory_init_works() {
+    for offset in ["i32.const 131072", "global.get $offset"] {
+        let mut store = store();
+        let wasm = wat::parse_str(wat(offset)).unwrap();
+        let module = Module::new(store.engine(), &wasm[..]).unwrap();
+        let (_, a) = instantiate(&mut store, &module);
+        let (_, b) = instantiate(&mut store, &module);
+        assert_initialized(&store, a);
+        assert_initialized(&store, b);
+        // Modifying one linear memory must not affect others created from the same image.
+        a.data_mut(&mut store)[..5].copy_from_slice(b"HELLO");
+        a.grow(&mut store, Pages(1)).unwrap();
+        assert_initialized(&store, b);
+        let (_, c) = instantiate(&mut store, &module);
+        assert_initialized(&store, c);
+        assert_eq!(&a.data(&store)[..5], b"HELLO");
+    }
+}
+
+#[test]
+fn copy_on_write_memory_init_template_works() {
+    let mut store = store();
+    let wasm = wat::parse_str(wat("i32.const 131072")).unwrap();
+    let module = Module::new(store.engine(), &wasm[..]).unwrap();
+    let offset = Global::new(&mut store, Value::I32(0), Mutability::Const);
+    let mut linker = <Linker<()>>::new();
+    linker.define("env", "offset", offset).unwrap();
+    let mut template = linker.instantiate_template(&mut store, &module).unwrap();
+    for _ in 0..3 {
+        let instance = template
+            .instantiate(&mut store)
+            .unwrap()
+            .ensure_no_start(&mut store)
+            .unwrap();
+        let memory = instance
+            .get_export(&store, "memory")
+            .and_then(Extern::into_memory)
+            .unwrap();
+        assert_initialized(&store, memory);
+        memory.data_mut(&mut store)[100_000] = 1;
+        template.reclaim(&mut store, instance).unwrap();
+    }
+}
+
+#[test]
+fn copy_on_write_memory_init_out_of_bounds_fails() {
+    let mut store = store();
+    let wasm = wat::parse_str(r#"(module (memory 1) (data (i32.const 65535) "ab"))"#).unwrap();
+    let module = Module::new(store.engine(), &wasm[..]).unwrap();
+    assert!(<Linker<()>>::new()
+        .instantiate(&mut store, &module)
+        .is_err());
+}
+
+#[test]
+fn dirty_page_tracking_works() {
+    let config = Config::default().enable_dirty_page_tracking(true);
+    let engine = Engine::new(&config);
+    let mut store = Store::new(&engine, ());
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (memory (export "memory") 4)
+            (func (export "store") (param i32)
+                (i64.store (local.get 0) (i64.const -1))
+            )
+        )
+        "#,
+    )
+    .unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let instance = <Linker<()>>::new()
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .ensure_no_start(&mut store)
+        .unwrap();
+    let memory = instance
+        .get_export(&store, "memory")
+        .and_then(Extern::into_memory)
+        .unwrap();
+    let store_i64 = instance
+        .get_export(&store, "store")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<i32, (), _>(&store)
+        .unwrap();
+    assert_eq!(memory.dirty_pages(&store), [0, 1, 2, 3]);
+    memory.clear_dirty(&mut store);
+    // A store spanning the boundary of pages 1 and 2.
+    store_i64.call(&mut store, 2 * 65536 - 4).unwrap();
+    assert_eq!(memory.dirty_pages(&store), [1, 2]);
+    memory.clear_dirty(&mut store);
+    // A trapping store does not mark any page dirty.
+    assert!(store_i64.call(&mut store, 4 * 65536 - 4).is_err());
+    assert!(memory.dirty_pages(&store).is_empty());
+}
+
+/// The pattern of an out of bounds linear memory access trap.
+macro_rules! OUT_OF_BOUNDS {
+    () => {
+        Error::Trap(Trap::Code(TrapCode::MemoryAccessOutOfBounds))
+    };
+}
+
+/// A Wasm module with functions that access its linear memory at arbitrary addresses.
+const ACCESS_WAT: &str = r#"
+    (module
+        (import "env" "nested" (func $nested))
+        (memory (export "memory") 1 3)
+        (func (export "load") (param i32) (result i64)
+            (i64.load (local.get 0))
+        )
+        (func (export "load_max_offset") (param i32) (result i32)
+            (i32.load offset=4294967295 (local.get 0))
+        )
+        (func (export "store") (param i32 i64)
+            (i64.store (local.get 0) (local.get 1))
+        )
+        (func (export "grow") (param i32) (result i32)
+            (memory.grow (local.get 0))
+        )
+        (func (export "nested_then_load") (param i32) (result i64)
+            (call $nested)
+            (i64.load (local.get 0))
+        )
+    )
+"#;
+
+/// Creates a [`Store`] whose linear memories rely on guard pages for bounds checks if `guard_pages` is set.
+fn access_store(guard_pages: bool) -> Store<()> {
+    let config = Config::default().enable_guard_pages(guard_pages);
+    Store::new(&Engine::new(&config), ())
+}
+
+/// Instantiates [`ACCESS_WAT`] with `nested` as its imported function.
+fn instantiate_access(store: &mut Store<()>, nested: Func) -> Instance {
+    let wasm = wat::parse_str(ACCESS_WAT).unwrap();
+    let module = Module::new(store.engine(), &wasm[..]).unwrap();
+    let mut linker = <Linker<()>>::new();
+    linker.define("env", "nested", nested).unwrap();
+    linker
+        .instantiate(&mut *store, &module)
+        .unwrap()
+        .ensure_no_start(&mut *store)
+        .unwrap()
+}
+
+/// Calls the exported function `name` of the `instance` with `params`.
+fn call(
+    store: &mut Store<()>,
+    instance: Instance,
+    name: &str,
+    params: &[Value],
+) -> Result<Option<Value>, Error> {
+    let func = instance
+        .get_export(&*store, name)
+        .and_then(Extern::into_func)
+        .unwrap();
+    let mut results = func
+        .func_type(&*store)
+        .results()
+        .iter()
+        .copied()
+        .map(Value::default)
+        .collect::<Vec<_>>();
+    func.call(&mut *store, params, &mut results)?;
+    Ok(results.first().copied())
+}
+
+#[test]
+fn out_of_bounds_accesses_trap() {
+    for guard_pages in [false, true] {
+        let mut store = access_store(guard_pages);
+        let nested = Func::wrap(&mut store, || {});
+        let instance = instantiate_access(&mut store, nested);
+        let mut call = |name: &str, params: &[Value]| call(&mut store, instance, name, params);
+        assert_matches!(
+            call("store", &[Value::I32(65528), Value::I64(-1)]),
+            Ok(None)
+        );
+        assert_matches!(call("load", &[Value::I32(65528)]), Ok(Some(Value::I64(-1))));
+        // Accesses that straddle the end of the linear memory trap without side effects.
+        assert_matches!(call("load", &[Value::I32(65529)]), Err(OUT_OF_BOUNDS!()));
+        assert_matches!(
+            call("store", &[Value::I32(65530), Value::I64(0)]),
+            Err(OUT_OF_BOUNDS!())
+        );
+        assert_matches!(call("load", &[Value::I32(65528)]), Ok(Some(Value::I64(-1))));
+        // Accesses at the highest effective addresses trap.
+        assert_matches!(call("load", &[Value::I32(-8)]), Err(OUT_OF_BOUNDS!()));
+        assert_matches!(
+            call("load_max_offset", &[Value::I32(0)]),
+            Err(OUT_OF_BOUNDS!())
+        );
+        assert_matches!(
+            call("load_max_offset", &[Value::I32(1)]),
+            Err(OUT_OF_BOUNDS!())
+        );
+        // Grown linear memory is accessible and zero initialized.
+        assert_matches!(call("grow", &[Value::I32(1)]), Ok(Some(Value::I32(1))));
+        assert_matches!(call("load", &[Value::I32(65536)]), Ok(Some(Value::I64(0))));
+        assert_matches!(call("load", &[Value::I32(131065)]), Err(OUT_OF_BOUNDS!()));
+    }
+}
+
+#[test]
+fn out_of_bounds_accesses_trap_after_nested_execution() {
+    for guard_pages in [false, true] {
+        // The host function traps within an instance of another engine
+        // that has a larger linear memory.
+        let mut store = access_store(guard_pages);
+        let nested = Func::wrap(&mut store, move || {
+            let mut store = access_store(guard_pages);
+            let noop = Func::wrap(&mut store, || {});
+            let inner = instantiate_access(&mut store, noop);
+            call(&mut store, inner, "grow", &[Value::I32(2)]).unwrap();
+            assert_matches!(
+                call(&mut store, inner, "load", &[Value::I32(-1)]),
+                Err(OUT_OF_BOUNDS!())
+            );
+            call(&mut store, inner, "load", &[Value::I32(65536)]).unwrap();
+        });
+        let outer = instantiate_access(&mut store, nested);
+        // The address is in bounds of the inner but not of the outer linear memory.
+        assert_matches!(
+            call(&mut store, outer, "nested_then_load", &[Value::I32(65536)]),
+            Err(OUT_OF_BOUNDS!())
+        );
+        assert_matches!(
+            call(&mut store, outer, "nested_then_load", &[Value::I32(8)]),
+            Ok(Some(Value::I64(0)))
+        );
+    }
+}
@@ -1 +1,11 @@
+mod backend;
+mod bindgen;
+mod compile;
 mod func;
+mod instantiate;
+mod limits;
+mod memory;
+mod serialize;
+mod stats;
+mod store;
+mod template;
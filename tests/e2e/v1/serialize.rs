@@ -0,0 +1,207 @@
+//! Tests for serializing and deserializing translated Wasm modules in `wasmi_v1`.
+
+use wasmi_core::Value;
+use wasmi_v1::{
+    errors::{DeserializeError, ModuleError},
+    Config,
+    Engine,
+    Error,
+    Global,
+    Instance,
+    Linker,
+    Module,
+    Mutability,
+    Store,
+};
+
+/// A Wasm module covering imports, exports, segments and various control flow.
+const WAT: &str = r#"
+    (module
+        (import "env" "base" (global $base i64))
+        (type $binary (func (param i64 i64) (result i64)))
+        (memory (export "memory") 1 4)
+        (table 2 funcref)
+        (global $calls (mut i32) (i32.const 0))
+        (elem (i32.const 0) $add $sub)
+        (data (i32.const 8) "wasmi")
+        (func $add (type $binary) (i64.add (local.get 0) (local.get 1)))
+        (func $sub (type $binary) (i64.sub (local.get 0) (local.get 1)))
+        (func (export "apply") (param $op i32) (param $n i64) (result i64)
+            (global.set $calls (i32.add (global.get $calls) (i32.const 1)))
+            (call_indirect (type $binary)
+                (global.get $base) (local.get $n) (local.get $op)
+            )
+        )
+        (func (export "classify") (param i32) (result i32)
+            (block (block (block
+                (br_table 0 1 2 (local.get 0)))
+                (return (i32.const 10)))
+                (return (i32.const 20)))
+            (i32.const 30)
+        )
+        (func (export "sum") (param $n i64) (result i64) (local $acc i64)
+            (block $exit
+                (loop $continue
+                    (br_if $exit (i64.eqz (local.get $n)))
+                    (local.set $acc (i64.add (local.get $acc) (local.get $n)))
+                    (local.set $n (i64.sub (local.get $n) (i64.const 1)))
+                    (br $continue)
+                )
+            )
+            (local.get $acc)
+        )
+    )
+"#;
+
+/// Translates [`WAT`] with an [`Engine`] using the given `config`.
+fn module(config: &Config) -> Module {
+    let engine = Engine::new(config);
+    let wasm = wat::parse_str(WAT).unwrap();
+    Module::new(&engine, &wasm[..]).unwrap()
+}
+
+/// Instantiates the `module` defining its imports.
+fn instantiate(module: &Module) -> (Store<()>, Instance) {
+    let mut store = Store::new(module.engine(), ());
+    let base = Global::new(&mut store, Value::I64(100), Mutability::Const);
+    let mut linker = <Linker<()>>::new();
+    linker.define("env", "base", base).unwrap();
+    let instance = linker
+        .instantiate(&mut store, module)
+        .unwrap()
+        .ensure_no_start(&mut store)
+        .unwrap();
+    (store, instance)
+}
+
+/// Calls the exported function `name` of the `instance` with the given `params`.
+fn call(store: &mut Store<()>, instance: Instance, name: &str, params: &[Value]) -> Value {
+    let func = instance
+        .get_export(&*store, name)
+        .and_then(|export| export.into_func())
+        .unwrap();
+    let mut results = [Value::I32(0)];
+    func.call(&mut *store, params, &mut results).unwrap();
+    results[0]
+}
+
+/// Asserts that the instantiated [`WAT`] module behaves as expected.
+fn assert_behaves(module: &Module) {
+    let (mut store, instance) = instantiate(module);
+    let apply = |store: &mut Store<()>, op, n| {
+        call(store, instance, "apply", &[Value::I32(op), Value::I64(n)])
+    };
+    assert_eq!(apply(&mut store, 0, 5), Value::I64(105));
+    assert_eq!(apply(&mut store, 1, 5), Value::I64(95));
+    for (input, expected) in [(0, 10), (1, 20), (2, 30), (7, 30)] {
+        assert_eq!(
+            call(&mut store, instance, "classify", &[Value::I32(input)]),
+            Value::I32(expected)
+        );
+    }
+    assert_eq!(
+        call(&mut store, instance, "sum", &[Value::I64(100)]),
+        Value::I64(5050)
+    );
+    let memory = instance
+        .get_export(&store, "memory")
+        .and_then(|export| export.into_memory())
+        .unwrap();
+    assert_eq!(&memory.data(&store)[8..13], b"wasmi");
+}
+
+/// Deserializes the `bytes` with an [`Engine`] using the given `config`.
+fn deserialize(config: &Config, bytes: &[u8]) -> Result<Module, Error> {
+    let engine = Engine::new(config);
+    // Safety: All tested bytes are produced by `Module::serialize`.
+    unsafe { Module::deserialize(&engine, bytes) }
+}
+
+/// Returns the [`DeserializeError`] of deserializing the `bytes`.
+fn deserialize_error(config: &Config, bytes: &[u8]) -> DeserializeError {
+    match deserialize(config, bytes) {
+        Err(Error::Module(ModuleError::Deserialize(error))) => error,
+        Err(error) => panic!("unexpected error: {}", error),
+        Ok(_) => panic!("unexpectedly deserialized module"),
+    }
+}
+
+#[test]
+fn round_trip_works() {
+    let config = Config::default();
+    let bytes = module(&config).serialize();
+    let module = deserialize(&config, &bytes).unwrap();
+    assert_behaves(&module);
+    // Serializing a deserialized module yields the same bytes.
+    assert_eq!(module.serialize(), bytes);
+}
+
+#[test]
+fn round_trip_with_memory_image_works() {
+    let config = Config::default().enable_copy_on_write_memory_init(true);
+    let bytes = module(&config).serialize();
+    assert_behaves(&deserialize(&config, &bytes).unwrap());
+}
+
+#[test]
+fn mismatching_config_is_rejected() {
+    let bytes = module(&Config::default()).serialize();
+    let config = Config::default().enable_sign_extension(false);
+    assert_eq!(
+        deserialize_error(&config, &bytes),
+        DeserializeError::MismatchingConfig
+    );
+}
+
+#[test]
+fn invalid_bytes_are_rejected() {
+    let config = Config::default();
+    let bytes = module(&config).serialize();
+    assert_eq!(
+        deserialize_error(&config, &bytes[..bytes.len() - 1]),
+        DeserializeError::InvalidEncoding
+    );
+    let mut trailing = bytes.clone();
+    trailing.push(0x00);
+    assert_eq!(
+        deserialize_error(&config, &trailing),
+        DeserializeError::InvalidEncoding
+    );
+    let mut magic = bytes.clone();
+    magic[0] ^= 0xFF;
+    assert_eq!(
+        deserialize_error(&config, &magic),
+        DeserializeError::InvalidEncoding
+    );
+    let mut version = bytes;
+    version[8..12].copy_from_slice(&42_u32.to_le_bytes());
+    assert_eq!(
+        deserialize_error(&config, &version),
+        DeserializeError::UnsupportedVersion { version: 42 }
+    );
+}
+
+#[test]
+fn invalid_bytes_do_not_leak_into_engine() {
+    let config = Config::default();
+    let bytes = module(&config).serialize();
+    let engine = Engine::new(&config);
+    let before = engine.stats();
+    // Truncating the input fails only after all function types and bodies have been decoded.
+    for len in [bytes.len() - 1, bytes.len() / 2] {
+        // Safety: The truncated bytes are rejected before any bytecode is executed.
+        let result = unsafe { Module::deserialize(&engine, &bytes[..len]) };
+        assert!(matches!(
+            result,
+            Err(Error::Module(ModuleError::Deserialize(
+                DeserializeError::InvalidEncoding
+            )))
+        ));
+        let after = engine.stats();
+        assert_eq!(after.func_types, before.func_types);
+        assert_eq!(after.func_bodies, before.func_bodies);
+    }
+    // Safety: The bytes are produced by `Module::serialize`.
+    assert_behaves(&unsafe { Module::deserialize(&engine, &bytes) }.unwrap());
+    assert!(engine.stats().func_bodies > before.func_bodies);
+}
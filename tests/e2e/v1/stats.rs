@@ -0,0 +1,66 @@
+//! Tests for resource usage reporting in `wasmi_v1`.
+
+use wasmi_v1::{Engine, Linker, Module, ResourceUsage, Store};
+
+/// A Wasm module with a function, a global variable, a table and a linear memory.
+const WAT: &str = r#"
+    (module
+        (memory 2)
+        (table 3 funcref)
+        (global (mut i32) (i32.const 0))
+        (func (result i32) (i32.add (i32.const 1) (i32.const 2)))
+    )
+"#;
+
+#[test]
+fn resource_usage_works() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    assert_eq!(store.resource_usage(), ResourceUsage::default());
+    let stats_before = engine.stats();
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let stats = engine.stats();
+    assert_eq!(stats.func_bodies, stats_before.func_bodies + 1);
+    assert!(stats.instructions > stats_before.instructions);
+    assert!(stats.code_map_bytes >= stats.instructions);
+    let instance = <Linker<()>>::new()
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .ensure_no_start(&mut store)
+        .unwrap();
+    let usage = store.resource_usage();
+    assert_eq!(usage.instances, 1);
+    assert_eq!(usage.funcs, 1);
+    assert_eq!(usage.globals, 1);
+    assert_eq!(usage.tables, 1);
+    assert_eq!(usage.table_elements, 3);
+    assert_eq!(usage.memories, 1);
+    assert_eq!(usage.memory_bytes, 2 * 65536);
+    store.drop_instance(instance).unwrap();
+    assert_eq!(store.resource_usage(), ResourceUsage::default());
+}
+
+#[test]
+fn write_metrics_works() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    <Linker<()>>::new()
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .ensure_no_start(&mut store)
+        .unwrap();
+    let mut metrics = String::new();
+    store
+        .resource_usage()
+        .write_metrics(&mut metrics, &[("tenant", "a\"b"), ("zone", "eu")])
+        .unwrap();
+    assert!(metrics.contains("# TYPE wasmi_store_memory_bytes gauge\n"));
+    assert!(metrics.contains("wasmi_store_memory_bytes{tenant=\"a\\\"b\",zone=\"eu\"} 131072\n"));
+    assert_eq!(metrics.lines().count(), 7 * 3);
+    let mut metrics = String::new();
+    engine.stats().write_metrics(&mut metrics, &[]).unwrap();
+    assert!(metrics.contains("\nwasmi_engine_func_bodies 1\n"));
+}
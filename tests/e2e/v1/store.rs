@@ -0,0 +1,395 @@
+//! Tests for dropping, snapshotting and restoring entities of a `Store` in `wasmi_v1`.
+
+use assert_matches::assert_matches;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use wasmi_core::{memory_units::Pages, Value};
+use wasmi_v1::{
+    errors::{InstanceError, MemoryError, SnapshotError},
+    Engine,
+    Extern,
+    Instance,
+    LinearMemory,
+    Linker,
+    Memory,
+    MemoryType,
+    Module,
+    Snapshot,
+    Store,
+    StoreLimits,
+    Table,
+    TableType,
+};
+
+/// Instantiates the Wasm module with an exported linear memory.
+///
+/// Imports the linear memory from the host if `import` is given.
+fn instantiate<T>(store: &mut Store<T>, import: Option<Memory>) -> Instance {
+    let wat = match import {
+        Some(_) => r#"(module (import "env" "memory" (memory 1)) (export "memory" (memory 0)))"#,
+        None => r#"(module (memory (export "memory") 1))"#,
+    };
+    let wasm = wat::parse_str(wat).unwrap();
+    let module = Module::new(store.engine(), &wasm[..]).unwrap();
+    let mut linker = <Linker<T>>::new();
+    if let Some(memory) = import {
+        linker.define("env", "memory", memory).unwrap();
+    }
+    linker
+        .instantiate(&mut *store, &module)
+        .unwrap()
+        .ensure_no_start(&mut *store)
+        .unwrap()
+}
+
+/// Returns the linear memory exported by the `instance`.
+fn exported_memory<T>(store: &Store<T>, instance: Instance) -> Memory {
+    instance
+        .get_export(store, "memory")
+        .and_then(Extern::into_memory)
+        .unwrap()
+}
+
+#[test]
+fn drop_instance_reclaims_entities() {
+    let engine = Engine::default();
+    let limits = StoreLimits::new().limit_instances(1).limit_memories(1);
+    let mut store = Store::new(&engine, limits);
+    store.limiter(|limits| limits);
+    for _ in 0..10 {
+        let instance = instantiate(&mut store, None);
+        let memory = exported_memory(&store, instance);
+        memory.data_mut(&mut store)[0] = 42;
+        store.drop_instance(instance).unwrap();
+    }
+}
+
+#[test]
+#[should_panic]
+fn use_after_drop_panics() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    let instance = instantiate(&mut store, None);
+    let memory = exported_memory(&store, instance);
+    store.drop_instance(instance).unwrap();
+    // Reuses the slot of the dropped linear memory.
+    let other = instantiate(&mut store, None);
+    exported_memory(&store, other).data_mut(&mut store)[0] = 42;
+    memory.data(&store);
+}
+
+#[test]
+#[should_panic]
+fn double_drop_panics() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    let instance = instantiate(&mut store, None);
+    store.drop_instance(instance).unwrap();
+    let _ = store.drop_instance(instance);
+}
+
+#[test]
+fn drop_instance_keeps_imports() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    let memory = Memory::new(&mut store, MemoryType::new(1, None)).unwrap();
+    let instance = instantiate(&mut store, Some(memory));
+    exported_memory(&store, instance).data_mut(&mut store)[0] = 42;
+    store.drop_instance(instance).unwrap();
+    assert_eq!(memory.data(&store)[0], 42);
+}
+
+#[test]
+fn drop_instance_with_imported_entities_fails() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    let exporter = instantiate(&mut store, None);
+    let memory = exported_memory(&store, exporter);
+    let importer = instantiate(&mut store, Some(memory));
+    assert_matches!(
+        store.drop_instance(exporter),
+        Err(InstanceError::EntityInUse {
+            entity: Extern::Memory(_)
+        })
+    );
+    // The failed drop leaves the memory intact for the importer.
+    exported_memory(&store, importer).data_mut(&mut store)[0] = 42;
+    assert_eq!(memory.data(&store)[0], 42);
+    store.drop_instance(importer).unwrap();
+    store.drop_instance(exporter).unwrap();
+}
+
+#[test]
+fn drop_instance_with_table_elements_fails() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    let table = Table::new(&mut store, TableType::new(1, None)).unwrap();
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (import "env" "table" (table 1 funcref))
+            (elem (i32.const 0) $f)
+            (func $f)
+        )
+        "#,
+    )
+    .unwrap();
+    let module = Module::new(store.engine(), &wasm[..]).unwrap();
+    let mut linker = <Linker<()>>::new();
+    linker.define("env", "table", table).unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .ensure_no_start(&mut store)
+        .unwrap();
+    assert_matches!(
+        store.drop_instance(instance),
+        Err(InstanceError::EntityInUse {
+            entity: Extern::Func(_)
+        })
+    );
+    table.set(&mut store, 0, None).unwrap();
+    store.drop_instance(instance).unwrap();
+}
+
+/// Instantiates a Wasm module with a linear memory, a mutable global variable and a table.
+fn instantiate_stateful(store: &mut Store<()>) -> Instance {
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (memory (export "memory") 1 4)
+            (global $counter (export "counter") (mut i32) (i32.const 0))
+            (table (export "table") 2 funcref)
+            (elem (i32.const 0) $bump)
+            (func $bump (export "bump") (result i32)
+                (global.set $counter (i32.add (global.get $counter) (i32.const 1)))
+                (i32.store (i32.const 100) (global.get $counter))
+                (global.get $counter)
+            )
+            (func (export "grow") (result i32)
+                (memory.grow (i32.const 1))
+            )
+        )
+        "#,
+    )
+    .unwrap();
+    let module = Module::new(store.engine(), &wasm[..]).unwrap();
+    <Linker<()>>::new()
+        .instantiate(&mut *store, &module)
+        .unwrap()
+        .ensure_no_start(&mut *store)
+        .unwrap()
+}
+
+/// Calls the exported function `name` of the `instance`.
+fn call(store: &mut Store<()>, instance: Instance, name: &str) -> i32 {
+    instance
+        .get_export(&*store, name)
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<(), i32, _>(&*store)
+        .unwrap()
+        .call(&mut *store, ())
+        .unwrap()
+}
+
+/// Returns the state of the `instance` as observed by the host.
+fn observe(store: &Store<()>, instance: Instance) -> (Value, usize, u8, bool) {
+    let memory = exported_memory(store, instance);
+    let counter = instance
+        .get_export(store, "counter")
+        .and_then(Extern::into_global)
+        .unwrap()
+        .get(store);
+    let table = instance
+        .get_export(store, "table")
+        .and_then(Extern::into_table)
+        .unwrap();
+    (
+        counter,
+        memory.current_pages(store).0,
+        memory.data(store)[100],
+        table.get(store, 1).unwrap().is_some(),
+    )
+}
+
+#[test]
+fn snapshot_restore_works() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    let instance = instantiate_stateful(&mut store);
+    assert_eq!(call(&mut store, instance, "bump"), 1);
+    let snapshot = store.snapshot();
+    let expected = observe(&store, instance);
+    assert_eq!(expected, (Value::I32(1), 1, 1, false));
+    // Mutate all kinds of state after taking the snapshot.
+    assert_eq!(call(&mut store, instance, "bump"), 2);
+    assert_eq!(call(&mut store, instance, "grow"), 1);
+    let table = instance
+        .get_export(&store, "table")
+        .and_then(Extern::into_table)
+        .unwrap();
+    let bump = table.get(&store, 0).unwrap();
+    table.set(&mut store, 1, bump).unwrap();
+    assert_eq!(observe(&store, instance), (Value::I32(2), 2, 2, true));
+    // Restoring rolls back all changes, including the memory growth.
+    store.restore(&snapshot).unwrap();
+    assert_eq!(observe(&store, instance), expected);
+    assert_eq!(call(&mut store, instance, "bump"), 2);
+    assert_eq!(call(&mut store, instance, "grow"), 1);
+}
+
+#[test]
+fn snapshot_incremental_works() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    let instance = instantiate_stateful(&mut store);
+    let base = store.snapshot();
+    call(&mut store, instance, "bump");
+    let snapshot = store.snapshot_incremental(&base);
+    let expected = observe(&store, instance);
+    call(&mut store, instance, "bump");
+    store.restore(&snapshot).unwrap();
+    assert_eq!(observe(&store, instance), expected);
+    store.restore(&base).unwrap();
+    assert_eq!(observe(&store, instance), (Value::I32(0), 1, 0, false));
+}
+
+#[test]
+fn snapshot_bytes_roundtrip_works() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    let instance = instantiate_stateful(&mut store);
+    call(&mut store, instance, "bump");
+    call(&mut store, instance, "grow");
+    let bytes = store.snapshot().to_bytes();
+    let expected = observe(&store, instance);
+    // Restore the snapshot into a fresh store with the same module.
+    let mut other = Store::new(&engine, ());
+    let other_instance = instantiate_stateful(&mut other);
+    let snapshot = Snapshot::from_bytes(&bytes).unwrap();
+    other.restore(&snapshot).unwrap();
+    assert_eq!(observe(&other, other_instance), expected);
+    assert_eq!(call(&mut other, other_instance, "bump"), 2);
+}
+
+#[test]
+fn snapshot_restore_fails() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    instantiate_stateful(&mut store);
+    let snapshot = store.snapshot();
+    let mut bytes = snapshot.to_bytes();
+    assert_eq!(
+        Snapshot::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err(),
+        SnapshotError::InvalidEncoding
+    );
+    bytes[8] = 42;
+    assert_eq!(
+        Snapshot::from_bytes(&bytes).unwrap_err(),
+        SnapshotError::UnsupportedVersion { version: 42 }
+    );
+    let mut empty = Store::new(&engine, ());
+    assert_eq!(
+        empty.restore(&snapshot).unwrap_err(),
+        SnapshotError::MissingEntity
+    );
+}
+
+#[test]
+fn snapshot_restore_validates_limits() {
+    let engine = Engine::default();
+    let mut small = Store::new(&engine, ());
+    Memory::new(&mut small, MemoryType::new(1, None)).unwrap();
+    Table::new(&mut small, TableType::new(1, None)).unwrap();
+    let small_snapshot = small.snapshot();
+    let mut large = Store::new(&engine, ());
+    Memory::new(&mut large, MemoryType::new(1, None)).unwrap();
+    Table::new(&mut large, TableType::new(3, None)).unwrap();
+    let large_snapshot = large.snapshot();
+    // The memory snapshot is below the minimum pages of the linear memory.
+    let mut store = Store::new(&engine, ());
+    let memory = Memory::new(&mut store, MemoryType::new(2, None)).unwrap();
+    let table = Table::new(&mut store, TableType::new(1, Some(2))).unwrap();
+    assert_eq!(
+        store.restore(&small_snapshot).unwrap_err(),
+        SnapshotError::MismatchingEntity
+    );
+    assert_eq!(memory.current_pages(&store), Pages(2));
+    // The table snapshot is above the maximum elements of the table.
+    memory.grow(&mut store, Pages(1)).unwrap();
+    assert_eq!(
+        store.restore(&large_snapshot).unwrap_err(),
+        SnapshotError::MismatchingEntity
+    );
+    assert_eq!(memory.current_pages(&store), Pages(3));
+    assert_eq!(table.len(&store), 1);
+}
+
+/// A [`LinearMemory`] backed by a [`Vec`] that fails to resize while `fail` is set.
+struct FlakyMemory {
+    bytes: Vec<u8>,
+    fail: Arc<AtomicBool>,
+}
+
+impl LinearMemory for FlakyMemory {
+    fn data(&self) -> &[u8] {
+        &self.bytes[..]
+    }
+
+    fn data_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes[..]
+    }
+
+    fn resize(&mut self, new_len: usize) -> Result<(), MemoryError> {
+        if self.fail.load(Ordering::SeqCst) {
+            return Err(MemoryError::OutOfBoundsGrowth);
+        }
+        self.bytes.resize(new_len, 0x00);
+        Ok(())
+    }
+}
+
+/// Creates a default linear memory followed by a [`FlakyMemory`] with a single page each.
+fn create_memories(store: &mut Store<()>, fail: &Arc<AtomicBool>) -> (Memory, Memory) {
+    let memory_type = MemoryType::new(1, None);
+    let memory = Memory::new(&mut *store, memory_type).unwrap();
+    let flaky = FlakyMemory {
+        bytes: vec![0x00; 65536],
+        fail: fail.clone(),
+    };
+    let flaky = Memory::new_with_backend(&mut *store, memory_type, flaky).unwrap();
+    (memory, flaky)
+}
+
+#[test]
+fn snapshot_restore_is_atomic() {
+    let engine = Engine::default();
+    let fail = Arc::new(AtomicBool::new(false));
+    let mut other = Store::new(&engine, ());
+    create_memories(&mut other, &fail);
+    let snapshot = other.snapshot();
+    let mut store = Store::new(&engine, ());
+    let (memory, flaky) = create_memories(&mut store, &fail);
+    memory.grow(&mut store, Pages(1)).unwrap();
+    flaky.grow(&mut store, Pages(1)).unwrap();
+    memory.data_mut(&mut store)[65536] = 42;
+    flaky.data_mut(&mut store)[0] = 42;
+    // Shrinking the first linear memory succeeds but shrinking the second one fails.
+    fail.store(true, Ordering::SeqCst);
+    assert_eq!(
+        store.restore(&snapshot).unwrap_err(),
+        SnapshotError::MismatchingEntity
+    );
+    assert_eq!(memory.current_pages(&store), Pages(2));
+    assert_eq!(memory.data(&store)[65536], 42);
+    assert_eq!(flaky.current_pages(&store), Pages(2));
+    assert_eq!(flaky.data(&store)[0], 42);
+    fail.store(false, Ordering::SeqCst);
+    store.restore(&snapshot).unwrap();
+    assert_eq!(memory.current_pages(&store), Pages(1));
+    assert_eq!(flaky.current_pages(&store), Pages(1));
+    assert_eq!(flaky.data(&store)[0], 0);
+}
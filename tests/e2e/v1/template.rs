@@ -0,0 +1,181 @@
+//! Tests for instantiating Wasm modules via `InstanceTemplate` in `wasmi_v1`.
+
+use wasmi_core::{memory_units::Pages, Value};
+use wasmi_v1::{
+    Engine,
+    Extern,
+    Global,
+    Instance,
+    Linker,
+    Memory,
+    MemoryType,
+    Module,
+    Mutability,
+    Store,
+    StoreLimits,
+};
+
+/// A Wasm module with data and element segments and a global variable
+/// whose initial value is taken from an imported global variable.
+const WAT: &str = r#"
+    (module
+        (import "env" "base" (global $base i32))
+        (memory (export "memory") 1)
+        (global $counter (export "counter") (mut i32) (global.get $base))
+        (table (export "table") 2 funcref)
+        (elem (i32.const 1) $bump)
+        (data (i32.const 10) "hello")
+        (data (i32.const 12) "LL")
+        (func $bump (export "bump") (result i32)
+            (global.set $counter (i32.add (global.get $counter) (i32.const 1)))
+            (i32.store8 (i32.const 0) (global.get $counter))
+            (global.get $counter)
+        )
+    )
+"#;
+
+/// Creates a [`Store`] and a [`Linker`] that defines the imports of [`WAT`].
+fn setup(limits: StoreLimits) -> (Store<StoreLimits>, Linker<StoreLimits>, Module) {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, limits);
+    store.limiter(|limits| limits);
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let base = Global::new(&mut store, Value::I32(41), Mutability::Const);
+    let mut linker = <Linker<StoreLimits>>::new();
+    linker.define("env", "base", base).unwrap();
+    (store, linker, module)
+}
+
+/// Returns the exported item `name` of the `instance`.
+fn export<T>(store: &Store<T>, instance: Instance, name: &str) -> Extern {
+    instance.get_export(store, name).unwrap()
+}
+
+/// Calls the exported `bump` function of the `instance`.
+fn bump<T>(store: &mut Store<T>, instance: Instance) -> i32 {
+    export(store, instance, "bump")
+        .into_func()
+        .unwrap()
+        .typed::<(), i32, _>(&*store)
+        .unwrap()
+        .call(&mut *store, ())
+        .unwrap()
+}
+
+/// Asserts that the `instance` is in the state right after instantiation.
+fn assert_initial<T>(store: &mut Store<T>, instance: Instance) {
+    let memory = export(store, instance, "memory").into_memory().unwrap();
+    assert_eq!(memory.current_pages(&*store).0, 1);
+    assert_eq!(memory.data(&*store)[0], 0);
+    assert_eq!(&memory.data(&*store)[10..15], b"heLLo");
+    assert!(memory.data(&*store)[15..].iter().all(|byte| *byte == 0));
+    let table = export(store, instance, "table").into_table().unwrap();
+    assert!(table.get(&*store, 0).unwrap().is_none());
+    assert!(table.get(&*store, 1).unwrap().is_some());
+    assert_eq!(bump(store, instance), 42);
+}
+
+#[test]
+fn template_instantiate_works() {
+    let (mut store, mut linker, module) = setup(StoreLimits::new());
+    let mut template = linker.instantiate_template(&mut store, &module).unwrap();
+    let a = template
+        .instantiate(&mut store)
+        .unwrap()
+        .ensure_no_start(&mut store)
+        .unwrap();
+    let b = template
+        .instantiate(&mut store)
+        .unwrap()
+        .ensure_no_start(&mut store)
+        .unwrap();
+    assert_initial(&mut store, a);
+    // Instances created from the same template do not share owned state.
+    assert_initial(&mut store, b);
+    assert_eq!(bump(&mut store, a), 43);
+}
+
+#[test]
+fn template_reclaim_reuses_zeroed_memory() {
+    // Only a single linear memory may exist at a time.
+    let (mut store, mut linker, module) = setup(StoreLimits::new().limit_memories(1));
+    let mut template = linker.instantiate_template(&mut store, &module).unwrap();
+    for _ in 0..3 {
+        let instance = template
+            .instantiate(&mut store)
+            .unwrap()
+            .ensure_no_start(&mut store)
+            .unwrap();
+        assert_initial(&mut store, instance);
+        // Dirty and grow the linear memory before reclaiming it.
+        let memory = export(&store, instance, "memory").into_memory().unwrap();
+        memory.data_mut(&mut store)[100] = 1;
+        memory.grow(&mut store, Pages(1)).unwrap();
+        template.reclaim(&mut store, instance).unwrap();
+    }
+}
+
+#[test]
+fn template_pool_is_limited() {
+    let (mut store, mut linker, module) = setup(StoreLimits::new());
+    let mut template = linker.instantiate_template(&mut store, &module).unwrap();
+    template.set_pool_limit(1);
+    let instances = (0..3)
+        .map(|_| {
+            template
+                .instantiate(&mut store)
+                .unwrap()
+                .ensure_no_start(&mut store)
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    for instance in instances {
+        template.reclaim(&mut store, instance).unwrap();
+    }
+    assert_eq!(template.pool_len(), 1);
+    template.clear_pool();
+    assert_eq!(template.pool_len(), 0);
+}
+
+#[test]
+fn template_imported_memory_works() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (import "env" "memory" (memory 1))
+            (data (i32.const 0) "wasmi")
+        )
+        "#,
+    )
+    .unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let memory = Memory::new(&mut store, MemoryType::new(1, None)).unwrap();
+    let mut linker = <Linker<()>>::new();
+    linker.define("env", "memory", memory).unwrap();
+    let mut template = linker.instantiate_template(&mut store, &module).unwrap();
+    for _ in 0..2 {
+        memory.data_mut(&mut store)[..5].fill(0);
+        let instance = template
+            .instantiate(&mut store)
+            .unwrap()
+            .ensure_no_start(&mut store)
+            .unwrap();
+        // The data segment is written to the shared linear memory upon every instantiation.
+        assert_eq!(&memory.data(&store)[..5], b"wasmi");
+        template.reclaim(&mut store, instance).unwrap();
+    }
+}
+
+#[test]
+fn template_data_segment_out_of_bounds_fails() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    let wasm = wat::parse_str(r#"(module (memory 1) (data (i32.const 65535) "ab"))"#).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    assert!(<Linker<()>>::new()
+        .instantiate_template(&mut store, &module)
+        .is_err());
+}
@@ -5,7 +5,7 @@ use core::ops;
 /// A deduplicating arena allocator with a given index and entity type.
 ///
 /// For performance reasons the arena cannot deallocate single entities.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DedupArena<Idx, T> {
     entity2idx: BTreeMap<T, Idx>,
     entities: Arena<Idx, T>,
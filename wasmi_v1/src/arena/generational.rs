@@ -0,0 +1,169 @@
+use super::Index;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+/// The generation of a slot of a [`GenerationalArena`].
+///
+/// # Note
+///
+/// The generation of a slot is bumped every time its entity is deallocated
+/// so that indices to deallocated entities can be detected.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Generation(u32);
+
+/// An index into a [`GenerationalArena`] that is checked against the generation of its slot.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GenerationalIdx<Idx> {
+    index: Idx,
+    generation: Generation,
+}
+
+impl<Idx> GenerationalIdx<Idx>
+where
+    Idx: Index,
+{
+    /// Creates a [`GenerationalIdx`] from its raw slot index and generation.
+    pub fn from_raw(index: u32, generation: u32) -> Self {
+        Self {
+            index: Idx::from_usize(index as usize),
+            generation: Generation(generation),
+        }
+    }
+
+    /// Returns the raw slot index and generation of the [`GenerationalIdx`].
+    pub fn into_raw(self) -> (u32, u32) {
+        (self.index.into_usize() as u32, self.generation.0)
+    }
+}
+
+/// A slot of a [`GenerationalArena`].
+#[derive(Debug)]
+struct Slot<T> {
+    /// The current generation of the slot.
+    generation: Generation,
+    /// The entity of the slot if it is occupied.
+    entity: Option<T>,
+}
+
+/// An arena allocator that can deallocate single entities.
+///
+/// # Note
+///
+/// Deallocated slots are reused by later allocations.
+/// Every index carries the generation of its slot at the time of allocation
+/// so that indices to deallocated entities never resolve to another entity
+/// that reuses the same slot.
+#[derive(Debug)]
+pub struct GenerationalArena<Idx, T> {
+    /// The slots of the arena.
+    slots: Vec<Slot<T>>,
+    /// The indices of the vacant slots that can be reused.
+    free: Vec<usize>,
+    /// The number of occupied slots.
+    len: usize,
+    __marker: PhantomData<fn() -> Idx>,
+}
+
+impl<Idx, T> Default for GenerationalArena<Idx, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Idx, T> GenerationalArena<Idx, T> {
+    /// Creates a new empty generational arena.
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+            __marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of allocated entities.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<Idx, T> GenerationalArena<Idx, T>
+where
+    Idx: Index,
+{
+    /// Allocates a new entity and returns its index.
+    ///
+    /// Reuses a previously deallocated slot if possible.
+    pub fn alloc(&mut self, entity: T) -> GenerationalIdx<Idx> {
+        self.len += 1;
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index];
+            debug_assert!(slot.entity.is_none());
+            slot.entity = Some(entity);
+            return GenerationalIdx {
+                index: Idx::from_usize(index),
+                generation: slot.generation,
+            };
+        }
+        let index = self.slots.len();
+        let generation = Generation(0);
+        self.slots.push(Slot {
+            generation,
+            entity: Some(entity),
+        });
+        GenerationalIdx {
+            index: Idx::from_usize(index),
+            generation,
+        }
+    }
+
+    /// Deallocates the entity at the given index and returns it.
+    ///
+    /// Returns `None` if there is no entity at the index
+    /// or if the entity has already been deallocated.
+    pub fn dealloc(&mut self, index: GenerationalIdx<Idx>) -> Option<T> {
+        let raw_index = index.index.into_usize();
+        let slot = self.slots.get_mut(raw_index)?;
+        if slot.generation != index.generation {
+            return None;
+        }
+        let entity = slot.entity.take()?;
+        self.len -= 1;
+        // Slots that ran out of generations are retired so that
+        // their indices can never be confused with older ones.
+        if let Some(next) = slot.generation.0.checked_add(1) {
+            slot.generation = Generation(next);
+            self.free.push(raw_index);
+        }
+        Some(entity)
+    }
+
+    /// Returns a shared reference to the entity at the given index if any.
+    pub fn get(&self, index: GenerationalIdx<Idx>) -> Option<&T> {
+        self.slots
+            .get(index.index.into_usize())
+            .filter(|slot| slot.generation == index.generation)
+            .and_then(|slot| slot.entity.as_ref())
+    }
+
+    /// Returns an iterator over the allocated entities and their indices.
+    pub fn iter(&self) -> impl Iterator<Item = (GenerationalIdx<Idx>, &T)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.entity.as_ref().map(|entity| {
+                let index = GenerationalIdx {
+                    index: Idx::from_usize(index),
+                    generation: slot.generation,
+                };
+                (index, entity)
+            })
+        })
+    }
+
+    /// Returns an exclusive reference to the entity at the given index if any.
+    pub fn get_mut(&mut self, index: GenerationalIdx<Idx>) -> Option<&mut T> {
+        self.slots
+            .get_mut(index.index.into_usize())
+            .filter(|slot| slot.generation == index.generation)
+            .and_then(|slot| slot.entity.as_mut())
+    }
+}
@@ -1,16 +1,22 @@
 //! Fast arena allocators for different usage purposes.
 //!
-//! They cannot deallocate single allocated entities for extra efficiency.
+//! Except for the [`GenerationalArena`] they cannot deallocate single allocated
+//! entities for extra efficiency.
 //! These allocators mainly serve as the backbone for an efficient Wasm store
 //! implementation.
 
 mod dedup;
+mod generational;
 mod guarded;
 
 #[cfg(test)]
 mod tests;
 
-pub use self::{dedup::DedupArena, guarded::GuardedEntity};
+pub use self::{
+    dedup::DedupArena,
+    generational::{GenerationalArena, GenerationalIdx},
+    guarded::GuardedEntity,
+};
 use alloc::vec::Vec;
 use core::{
     iter,
@@ -31,7 +37,7 @@ pub trait Index: Copy {
 /// An arena allocator with a given index and entity type.
 ///
 /// For performance reasons the arena cannot deallocate single entities.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Arena<Idx, T> {
     entities: Vec<T>,
     __marker: PhantomData<fn() -> Idx>,
@@ -159,3 +159,65 @@ mod dedup_arena {
         assert_eq!(arena.len(), TEST_ENTITIES.len());
     }
 }
+
+mod generational_arena {
+    use super::*;
+
+    fn alloc_generational_arena(
+        entities: &[&'static str],
+    ) -> (
+        GenerationalArena<usize, &'static str>,
+        Vec<GenerationalIdx<usize>>,
+    ) {
+        let mut arena = <GenerationalArena<usize, &'static str>>::new();
+        // Check that the given arena is actually empty.
+        assert_eq!(arena.len(), 0);
+        // Fill arena and check invariants while doing so.
+        let indices = entities
+            .iter()
+            .map(|str| arena.alloc(str))
+            .collect::<Vec<_>>();
+        // Check state of filled arena.
+        assert_eq!(arena.len(), entities.len());
+        for (idx, str) in indices.iter().zip(entities) {
+            assert_eq!(arena.get(*idx), Some(str));
+        }
+        // Return filled arena.
+        (arena, indices)
+    }
+
+    #[test]
+    fn alloc_works() {
+        alloc_generational_arena(TEST_ENTITIES);
+    }
+
+    #[test]
+    fn dealloc_works() {
+        let (mut arena, indices) = alloc_generational_arena(TEST_ENTITIES);
+        assert_eq!(arena.dealloc(indices[1]), Some(TEST_ENTITIES[1]));
+        assert_eq!(arena.len(), TEST_ENTITIES.len() - 1);
+        assert_eq!(arena.get(indices[1]), None);
+        assert_eq!(arena.get_mut(indices[1]), None);
+        // Deallocating twice is detected.
+        assert_eq!(arena.dealloc(indices[1]), None);
+        assert_eq!(arena.len(), TEST_ENTITIES.len() - 1);
+        // Other entities are unaffected.
+        assert_eq!(arena.get(indices[0]), Some(&TEST_ENTITIES[0]));
+        assert_eq!(arena.get(indices[2]), Some(&TEST_ENTITIES[2]));
+    }
+
+    #[test]
+    fn reuse_works() {
+        let (mut arena, indices) = alloc_generational_arena(TEST_ENTITIES);
+        arena.dealloc(indices[2]).unwrap();
+        // The vacant slot is reused by the next allocation.
+        let reused = arena.alloc("e");
+        assert_eq!(arena.len(), TEST_ENTITIES.len());
+        assert_eq!(arena.get(reused), Some(&"e"));
+        // The stale index does not resolve to the entity reusing its slot.
+        assert_ne!(reused, indices[2]);
+        assert_eq!(arena.get(indices[2]), None);
+        assert_eq!(arena.dealloc(indices[2]), None);
+        assert_eq!(arena.get(reused), Some(&"e"));
+    }
+}
@@ -0,0 +1,487 @@
+//! Generates typed Rust bindings for the imports and exports of a Wasm module.
+//!
+//! This is meant to be used from build scripts:
+//!
+//! ```ignore
+//! // build.rs
+//! let wasm = std::fs::read("guest.wasm")?;
+//! let bindings = wasmi_v1::bindgen::generate("Guest", &wasm)?;
+//! let out_dir = std::env::var("OUT_DIR")?;
+//! std::fs::write(format!("{}/guest.rs", out_dir), bindings)?;
+//!
+//! // lib.rs
+//! include!(concat!(env!("OUT_DIR"), "/guest.rs"));
+//! ```
+//!
+//! For a module with the name `Guest` this generates:
+//!
+//! - A `Guest` struct with one typed method per exported function and
+//!   accessors for all exported linear memories, tables and global variables.
+//! - A `GuestImports` trait with one method per imported function which
+//!   provides an `add_to_linker` method to define them in a [`Linker`].
+//!
+//! # Note
+//!
+//! Imported linear memories, tables and global variables are not part of
+//! the generated imports trait and must be defined in the [`Linker`] manually.
+//!
+//! [`Linker`]: crate::Linker
+
+use crate::{core::ValueType, module::ModuleImportType, Engine, Error, FuncType, Module};
+use alloc::{
+    collections::BTreeSet,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Write;
+
+/// Generates Rust bindings for the Wasm module given by its `wasm` bytes.
+///
+/// The generated bindings struct is called `name` and the trait for the
+/// required function imports is called `{name}Imports`.
+/// The `wasm` bytes are parsed and validated using the default [`Config`].
+///
+/// # Errors
+///
+/// If the `wasm` bytes do not represent a valid Wasm module.
+///
+/// [`Config`]: crate::Config
+pub fn generate(name: &str, wasm: &[u8]) -> Result<String, Error> {
+    let engine = Engine::default();
+    let module = Module::new(&engine, wasm)?;
+    let mut exports = Vec::new();
+    let mut export_idents = <BTreeSet<String>>::new();
+    // Reserve the identifiers of the generated non-export methods.
+    export_idents.insert(String::from("new"));
+    export_idents.insert(String::from("instance"));
+    for export in module.exports() {
+        let ident = unique_ident(&mut export_idents, export.field());
+        let item = match export.item_type() {
+            ModuleImportType::Func(func_type) => {
+                ExportItem::Func(engine.resolve_func_type(*func_type, Clone::clone))
+            }
+            ModuleImportType::Table(_) => ExportItem::Extern("Table", "into_table"),
+            ModuleImportType::Memory(_) => ExportItem::Extern("Memory", "into_memory"),
+            ModuleImportType::Global(_) => ExportItem::Extern("Global", "into_global"),
+        };
+        exports.push((export.field(), ident, item));
+    }
+    let mut imports = Vec::new();
+    let mut import_idents = <BTreeSet<String>>::new();
+    import_idents.insert(String::from("add_to_linker"));
+    for import in module.imports() {
+        if let ModuleImportType::Func(func_type) = import.item_type() {
+            let field = import.field().unwrap_or_default();
+            let ident = unique_ident(
+                &mut import_idents,
+                &format!("{}_{}", import.module(), field),
+            );
+            let func_type = engine.resolve_func_type(*func_type, Clone::clone);
+            imports.push((import.module(), field, ident, func_type));
+        }
+    }
+    let mut out = String::new();
+    write_bindings(&mut out, name, &exports).expect("writing to a string cannot fail");
+    write_imports(&mut out, name, &imports).expect("writing to a string cannot fail");
+    Ok(out)
+}
+
+/// An exported item of the Wasm module.
+enum ExportItem {
+    /// An exported function with its function type.
+    Func(FuncType),
+    /// Any other exported item with its type name and `Extern` conversion method.
+    Extern(&'static str, &'static str),
+}
+
+/// Returns a valid Rust identifier for `name` that is unique within `idents`.
+fn unique_ident(idents: &mut BTreeSet<String>, name: &str) -> String {
+    let mut ident = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | '0'..='9' | '_' => c,
+            'A'..='Z' => c.to_ascii_lowercase(),
+            _ => '_',
+        })
+        .collect::<String>();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    if is_keyword(&ident) {
+        ident.push('_');
+    }
+    let mut candidate = ident.clone();
+    let mut n = 1;
+    while idents.contains(&candidate) {
+        candidate = format!("{}_{}", ident, n);
+        n += 1;
+    }
+    idents.insert(candidate.clone());
+    candidate
+}
+
+/// Returns `true` if `ident` is a strict or reserved Rust keyword.
+fn is_keyword(ident: &str) -> bool {
+    matches!(
+        ident,
+        "_" | "abstract"
+            | "as"
+            | "async"
+            | "await"
+            | "become"
+            | "box"
+            | "break"
+            | "const"
+            | "continue"
+            | "crate"
+            | "do"
+            | "dyn"
+            | "else"
+            | "enum"
+            | "extern"
+            | "false"
+            | "final"
+            | "fn"
+            | "for"
+            | "gen"
+            | "if"
+            | "impl"
+            | "in"
+            | "let"
+            | "loop"
+            | "macro"
+            | "match"
+            | "mod"
+            | "move"
+            | "mut"
+            | "override"
+            | "priv"
+            | "pub"
+            | "ref"
+            | "return"
+            | "self"
+            | "static"
+            | "struct"
+            | "super"
+            | "trait"
+            | "true"
+            | "try"
+            | "type"
+            | "typeof"
+            | "unsafe"
+            | "unsized"
+            | "use"
+            | "virtual"
+            | "where"
+            | "while"
+            | "yield"
+    )
+}
+
+/// Returns the Rust type for the Wasm `value_type`.
+fn rust_type(value_type: ValueType) -> &'static str {
+    match value_type {
+        ValueType::I32 => "i32",
+        ValueType::I64 => "i64",
+        ValueType::F32 => "::wasmi_v1::core::F32",
+        ValueType::F64 => "::wasmi_v1::core::F64",
+    }
+}
+
+/// Returns the Rust tuple type for the sequence of Wasm `value_types`.
+///
+/// Single types are not wrapped into a tuple.
+fn rust_tuple(value_types: &[ValueType]) -> String {
+    match value_types {
+        [value_type] => rust_type(*value_type).to_string(),
+        value_types => format!(
+            "({})",
+            value_types
+                .iter()
+                .map(|value_type| format!("{},", rust_type(*value_type)))
+                .collect::<String>()
+        ),
+    }
+}
+
+/// Returns the parameter declarations and names for the Wasm `params`.
+fn rust_params(params: &[ValueType]) -> (String, String) {
+    let decls = params
+        .iter()
+        .enumerate()
+        .map(|(n, param)| format!(", param{}: {}", n, rust_type(*param)))
+        .collect();
+    let names = params
+        .iter()
+        .enumerate()
+        .map(|(n, _)| format!("param{},", n))
+        .collect();
+    (decls, names)
+}
+
+/// Writes the bindings struct for the module `exports` to `out`.
+fn write_bindings(
+    out: &mut String,
+    name: &str,
+    exports: &[(&str, String, ExportItem)],
+) -> core::fmt::Result {
+    writeln!(
+        out,
+        "/// Typed bindings to the exports of a `{}` instance.",
+        name
+    )?;
+    writeln!(out, "#[derive(Debug, Copy, Clone)]")?;
+    writeln!(out, "pub struct {} {{", name)?;
+    writeln!(out, "    instance: ::wasmi_v1::Instance,")?;
+    for (_, ident, item) in exports {
+        match item {
+            ExportItem::Func(func_type) => writeln!(
+                out,
+                "    {}: ::wasmi_v1::TypedFunc<{}, {}>,",
+                ident,
+                rust_tuple(func_type.params()),
+                rust_tuple(func_type.results()),
+            )?,
+            ExportItem::Extern(ty, _) => writeln!(out, "    {}: ::wasmi_v1::{},", ident, ty)?,
+        }
+    }
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    writeln!(out, "impl {} {{", name)?;
+    writeln!(
+        out,
+        "    /// Creates the bindings for the exports of the `instance`."
+    )?;
+    writeln!(out, "    ///")?;
+    writeln!(out, "    /// # Errors")?;
+    writeln!(out, "    ///")?;
+    writeln!(
+        out,
+        "    /// If the `instance` is missing an export or its type does not match."
+    )?;
+    writeln!(out, "    pub fn new(")?;
+    writeln!(out, "        ctx: impl ::wasmi_v1::AsContext,")?;
+    writeln!(out, "        instance: ::wasmi_v1::Instance,")?;
+    writeln!(
+        out,
+        "    ) -> ::core::result::Result<Self, ::wasmi_v1::Error> {{"
+    )?;
+    if !exports.is_empty() {
+        writeln!(out, "        let missing = |name: &str| {{")?;
+        writeln!(
+            out,
+            "            ::wasmi_v1::Error::from(::wasmi_v1::errors::InstantiationError::MissingExport {{"
+        )?;
+        writeln!(out, "                name: name.into(),")?;
+        writeln!(out, "            }})")?;
+        writeln!(out, "        }};")?;
+    }
+    for (field, ident, item) in exports {
+        let conversion = match item {
+            ExportItem::Func(_) => "into_func",
+            ExportItem::Extern(_, conversion) => conversion,
+        };
+        writeln!(out, "        let {} = instance", ident)?;
+        writeln!(out, "            .get_export(&ctx, {:?})", field)?;
+        writeln!(
+            out,
+            "            .and_then(::wasmi_v1::Extern::{})",
+            conversion
+        )?;
+        writeln!(out, "            .ok_or_else(|| missing({:?}))?;", field)?;
+        if let ExportItem::Func(_) = item {
+            writeln!(out, "        let {} = {}.typed(&ctx)?;", ident, ident)?;
+        }
+    }
+    writeln!(out, "        ::core::result::Result::Ok(Self {{")?;
+    writeln!(out, "            instance,")?;
+    for (_, ident, _) in exports {
+        writeln!(out, "            {},", ident)?;
+    }
+    writeln!(out, "        }})")?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+    writeln!(out, "    /// Returns the underlying instance.")?;
+    writeln!(out, "    pub fn instance(&self) -> ::wasmi_v1::Instance {{")?;
+    writeln!(out, "        self.instance")?;
+    writeln!(out, "    }}")?;
+    for (field, ident, item) in exports {
+        writeln!(out)?;
+        match item {
+            ExportItem::Func(func_type) => {
+                let (decls, names) = rust_params(func_type.params());
+                let params = match func_type.params().len() {
+                    1 => names.trim_end_matches(',').to_string(),
+                    _ => format!("({})", names),
+                };
+                writeln!(out, "    /// Calls the exported function {:?}.", field)?;
+                writeln!(out, "    pub fn {}(", ident)?;
+                writeln!(out, "        &self,")?;
+                writeln!(out, "        ctx: impl ::wasmi_v1::AsContextMut{},", decls)?;
+                writeln!(
+                    out,
+                    "    ) -> ::core::result::Result<{}, ::wasmi_v1::core::Trap> {{",
+                    rust_tuple(func_type.results())
+                )?;
+                writeln!(out, "        self.{}.call(ctx, {})", ident, params)?;
+                writeln!(out, "    }}")?;
+            }
+            ExportItem::Extern(ty, _) => {
+                writeln!(out, "    /// Returns the exported {} {:?}.", ty, field)?;
+                writeln!(out, "    pub fn {}(&self) -> ::wasmi_v1::{} {{", ident, ty)?;
+                writeln!(out, "        self.{}", ident)?;
+                writeln!(out, "    }}")?;
+            }
+        }
+    }
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+/// Writes the trait for the module's function `imports` to `out`.
+fn write_imports(
+    out: &mut String,
+    name: &str,
+    imports: &[(&str, &str, String, FuncType)],
+) -> core::fmt::Result {
+    writeln!(out)?;
+    writeln!(
+        out,
+        "/// The host functions required by a `{}` instance.",
+        name
+    )?;
+    writeln!(out, "pub trait {}Imports: Sized + 'static {{", name)?;
+    for (module, field, ident, func_type) in imports {
+        let (decls, _) = rust_params(func_type.params());
+        writeln!(
+            out,
+            "    /// Implements the imported function {:?} of module {:?}.",
+            field, module
+        )?;
+        writeln!(out, "    fn {}(", ident)?;
+        writeln!(
+            out,
+            "        caller: ::wasmi_v1::Caller<'_, Self>{},",
+            decls
+        )?;
+        writeln!(
+            out,
+            "    ) -> ::core::result::Result<{}, ::wasmi_v1::core::Trap>;",
+            rust_tuple(func_type.results())
+        )?;
+        writeln!(out)?;
+    }
+    writeln!(
+        out,
+        "    /// Defines all imported functions in the `linker`."
+    )?;
+    writeln!(out, "    ///")?;
+    writeln!(out, "    /// # Errors")?;
+    writeln!(out, "    ///")?;
+    writeln!(
+        out,
+        "    /// If any of the functions is already defined in the `linker`."
+    )?;
+    writeln!(out, "    fn add_to_linker(")?;
+    writeln!(
+        out,
+        "        mut ctx: impl ::wasmi_v1::AsContextMut<UserState = Self>,"
+    )?;
+    writeln!(out, "        linker: &mut ::wasmi_v1::Linker<Self>,")?;
+    writeln!(
+        out,
+        "    ) -> ::core::result::Result<(), ::wasmi_v1::errors::LinkerError> {{"
+    )?;
+    for (module, field, ident, func_type) in imports {
+        let (decls, names) = rust_params(func_type.params());
+        writeln!(
+            out,
+            "        let func = ::wasmi_v1::Func::wrap(&mut ctx, |caller: ::wasmi_v1::Caller<'_, Self>{}| {{",
+            decls
+        )?;
+        writeln!(
+            out,
+            "            <Self as {}Imports>::{}(caller, {})",
+            name, ident, names
+        )?;
+        writeln!(out, "        }});")?;
+        writeln!(
+            out,
+            "        linker.define({:?}, {:?}, func)?;",
+            module, field
+        )?;
+    }
+    writeln!(out, "        ::core::result::Result::Ok(())")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate_wat(name: &str, wat: &str) -> String {
+        let wasm = wat::parse_str(wat).unwrap();
+        generate(name, &wasm).unwrap()
+    }
+
+    #[test]
+    fn generates_exports() {
+        let bindings = generate_wat(
+            "Guest",
+            r#"
+            (module
+                (memory (export "memory") 1)
+                (global (export "Counter") (mut i32) (i32.const 0))
+                (func (export "run") (param i32) (result i32) (local.get 0))
+                (func (export "new") (param i64 i64) (result i64) (local.get 0))
+            )
+            "#,
+        );
+        assert!(bindings.contains("pub struct Guest {"));
+        assert!(bindings.contains("memory: ::wasmi_v1::Memory,"));
+        assert!(bindings.contains("counter: ::wasmi_v1::Global,"));
+        assert!(bindings.contains("run: ::wasmi_v1::TypedFunc<i32, i32>,"));
+        // The `new` export must not clash with the `Guest::new` constructor.
+        assert!(bindings.contains("new_1: ::wasmi_v1::TypedFunc<(i64,i64,), i64>,"));
+        assert!(bindings.contains(".get_export(&ctx, \"new\")"));
+    }
+
+    #[test]
+    fn generates_imports() {
+        let bindings = generate_wat(
+            "Guest",
+            r#"
+            (module
+                (import "env" "log" (func (param i32 i32)))
+                (import "env" "add-one" (func (param i64) (result i64)))
+                (import "env" "memory" (memory 1))
+            )
+            "#,
+        );
+        assert!(bindings.contains("pub trait GuestImports: Sized + 'static {"));
+        assert!(bindings.contains("fn env_log("));
+        assert!(bindings.contains("fn env_add_one("));
+        assert!(bindings.contains("linker.define(\"env\", \"add-one\""));
+        assert!(!bindings.contains("env_memory"));
+    }
+
+    #[test]
+    fn sanitizes_identifiers() {
+        let mut idents = BTreeSet::new();
+        assert_eq!(unique_ident(&mut idents, "Foo-Bar"), "foo_bar");
+        assert_eq!(unique_ident(&mut idents, "foo_bar"), "foo_bar_1");
+        assert_eq!(unique_ident(&mut idents, "type"), "type_");
+        assert_eq!(unique_ident(&mut idents, "override"), "override_");
+        assert_eq!(unique_ident(&mut idents, "gen"), "gen_");
+        assert_eq!(unique_ident(&mut idents, "0x"), "_0x");
+        assert_eq!(unique_ident(&mut idents, ""), "__");
+    }
+
+    #[test]
+    fn invalid_wasm_fails() {
+        assert!(generate("Guest", &[0x00, 0x61, 0x73]).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+//! Little-endian binary encoding shared by snapshots and serialized modules.
+
+use alloc::{string::String, vec::Vec};
+
+/// An error that may occur when decoding bytes that have been cut short or are malformed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DecodeError;
+
+/// Encodes values into little-endian bytes.
+#[derive(Debug, Default)]
+pub struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    /// Returns the encoded bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn bytes(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub fn u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub fn u32(&mut self, value: u32) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn u64(&mut self, value: u64) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn len(&mut self, len: usize) {
+        self.u32(len as u32);
+    }
+
+    /// Encodes the `bytes` prefixed with their length.
+    pub fn slice(&mut self, bytes: &[u8]) {
+        self.len(bytes.len());
+        self.bytes(bytes);
+    }
+
+    /// Encodes the `value` prefixed with its length.
+    pub fn str(&mut self, value: &str) {
+        self.slice(value.as_bytes());
+    }
+
+    /// Encodes the `items` using `f` prefixed with their count.
+    pub fn vec<T>(&mut self, items: &[T], mut f: impl FnMut(&mut Self, &T)) {
+        self.len(items.len());
+        for item in items {
+            f(self, item);
+        }
+    }
+}
+
+/// Decodes values from little-endian bytes.
+#[derive(Debug)]
+pub struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    /// Creates a new [`Decoder`] for the given `bytes`.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    /// Returns `Ok` if all bytes have been decoded.
+    pub fn finish(self) -> Result<(), DecodeError> {
+        if !self.bytes.is_empty() {
+            return Err(DecodeError);
+        }
+        Ok(())
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.bytes.len() < len {
+            return Err(DecodeError);
+        }
+        let (bytes, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(bytes)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn u32(&mut self) -> Result<u32, DecodeError> {
+        let mut bytes = [0x00; 4];
+        bytes.copy_from_slice(self.bytes(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, DecodeError> {
+        let mut bytes = [0x00; 8];
+        bytes.copy_from_slice(self.bytes(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    pub fn len(&mut self) -> Result<usize, DecodeError> {
+        self.u32().map(|len| len as usize)
+    }
+
+    /// Decodes bytes prefixed with their length.
+    pub fn slice(&mut self) -> Result<&'a [u8], DecodeError> {
+        let len = self.len()?;
+        self.bytes(len)
+    }
+
+    /// Decodes a UTF-8 string prefixed with its length.
+    pub fn str(&mut self) -> Result<String, DecodeError> {
+        let bytes = self.slice()?;
+        core::str::from_utf8(bytes)
+            .map(Into::into)
+            .map_err(|_| DecodeError)
+    }
+
+    /// Decodes elements using `f` prefixed with their count.
+    ///
+    /// # Note
+    ///
+    /// The capacity is bounded by the remaining bytes so that
+    /// corrupted counts cannot trigger huge allocations.
+    pub fn vec<T>(
+        &mut self,
+        mut f: impl FnMut(&mut Self) -> Result<T, DecodeError>,
+    ) -> Result<Vec<T>, DecodeError> {
+        let len = self.len()?;
+        let mut items = Vec::with_capacity(len.min(self.bytes.len()));
+        for _ in 0..len {
+            items.push(f(self)?);
+        }
+        Ok(items)
+    }
+}
@@ -0,0 +1,362 @@
+//! Binary encoding of `wasmi` bytecode for serialized modules.
+//!
+//! # Note
+//!
+//! Every [`Instruction`] is encoded as a stable opcode byte followed by its
+//! payload. The opcodes must never be reassigned without bumping the version
+//! of the serialized module format.
+
+use super::{
+    BinaryOp,
+    BranchCmpOp,
+    FuncIdx,
+    GlobalIdx,
+    ImmOp,
+    Instruction,
+    LoadOp,
+    Offset,
+    Register,
+    RegisterSpan,
+    SignatureIdx,
+    StoreOp,
+    Target,
+    UnaryOp,
+};
+use crate::{
+    codec::{DecodeError, Decoder, Encoder},
+    engine::InstructionIdx,
+};
+
+/// The payload of an [`Instruction`] variant.
+trait Payload: Sized {
+    fn encode(self, encoder: &mut Encoder);
+    fn decode(decoder: &mut Decoder) -> Result<Self, DecodeError>;
+}
+
+impl Payload for u32 {
+    fn encode(self, encoder: &mut Encoder) {
+        encoder.u32(self);
+    }
+
+    fn decode(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+        decoder.u32()
+    }
+}
+
+impl Payload for i32 {
+    fn encode(self, encoder: &mut Encoder) {
+        encoder.u32(self as u32);
+    }
+
+    fn decode(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+        decoder.u32().map(|value| value as i32)
+    }
+}
+
+impl Payload for Target {
+    fn encode(self, encoder: &mut Encoder) {
+        encoder.len(self.destination_pc().into_usize());
+    }
+
+    fn decode(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+        let dst_pc = InstructionIdx::from_usize(decoder.len()?);
+        Ok(Self::new(dst_pc))
+    }
+}
+
+impl Payload for RegisterSpan {
+    fn encode(self, encoder: &mut Encoder) {
+        self.start().encode(encoder);
+        encoder.len(self.len_registers());
+    }
+
+    fn decode(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+        Ok(Self::new(Register::decode(decoder)?, decoder.u32()?))
+    }
+}
+
+macro_rules! impl_index_payload {
+    ( $( $index:ty ),* $(,)? ) => {
+        $(
+            impl Payload for $index {
+                fn encode(self, encoder: &mut Encoder) {
+                    encoder.u32(self.into_inner());
+                }
+
+                fn decode(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+                    decoder.u32().map(Self::from)
+                }
+            }
+        )*
+    };
+}
+impl_index_payload!(FuncIdx, SignatureIdx, GlobalIdx, Offset, Register);
+
+macro_rules! impl_operands_payload {
+    ( $( $operands:ident { $( $field:ident ),* } ),* $(,)? ) => {
+        $(
+            impl Payload for $operands {
+                fn encode(self, encoder: &mut Encoder) {
+                    $( self.$field.encode(encoder); )*
+                }
+
+                fn decode(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+                    Ok(Self {
+                        $( $field: Payload::decode(decoder)?, )*
+                    })
+                }
+            }
+        )*
+    };
+}
+impl_operands_payload! {
+    UnaryOp { result, input },
+    BinaryOp { result, lhs, rhs },
+    LoadOp { result, ptr, offset },
+    StoreOp { ptr, offset, value },
+    ImmOp { result, input, imm },
+    BranchCmpOp { lhs, rhs, target },
+}
+
+macro_rules! impl_instruction_codec {
+    (
+        $(
+            $op:literal => $name:ident
+                $( ( $payload:ty ) )?
+                $( { $( $field:ident: $field_ty:ty ),* } )?
+            ,
+        )*
+    ) => {
+        impl Instruction {
+            /// Encodes the [`Instruction`] into the `encoder`.
+            pub(crate) fn encode(&self, encoder: &mut Encoder) {
+                match *self {
+                    $(
+                        impl_instruction_codec!(
+                            @pattern $name $( ( payload: $payload ) )? $( { $( $field ),* } )?
+                        ) => {
+                            encoder.u8($op);
+                            $( <$payload as Payload>::encode(payload, encoder); )?
+                            $( $( <$field_ty as Payload>::encode($field, encoder); )* )?
+                        }
+                    )*
+                }
+            }
+
+            /// Decodes an [`Instruction`] encoded by [`Instruction::encode`].
+            ///
+            /// # Errors
+            ///
+            /// If the `decoder` does not start with a valid encoded [`Instruction`].
+            pub(crate) fn decode(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+                let inst = match decoder.u8()? {
+                    $(
+                        $op => Self::$name
+                            $( ( <$payload as Payload>::decode(decoder)? ) )?
+                            $( { $( $field: <$field_ty as Payload>::decode(decoder)?, )* } )?,
+                    )*
+                    _ => return Err(DecodeError),
+                };
+                Ok(inst)
+            }
+        }
+    };
+    ( @pattern $name:ident ( $binding:ident: $payload:ty ) ) => { Self::$name($binding) };
+    ( @pattern $name:ident { $( $field:ident ),* } ) => { Self::$name { $( $field ),* } };
+    ( @pattern $name:ident ) => { Self::$name };
+}
+
+impl_instruction_codec! {
+    0x00 => Copy(UnaryOp),
+    0x03 => BrTable { index: Register, offset: u32, len_targets: u32 },
+    0x04 => Br(Target),
+    0x05 => BrIfEqz { condition: Register, target: Target },
+    0x06 => BrIfNez { condition: Register, target: Target },
+    0x07 => ReturnIfNez { condition: Register, results: RegisterSpan },
+    0x08 => Unreachable,
+    0x09 => Return(RegisterSpan),
+    0x0A => Call { func: FuncIdx, params: Register },
+    0x0B => CallIndirect { signature: SignatureIdx, index: Register, params: Register },
+    0x0D => Select { result: Register, condition: Register, if_false: Register },
+    0x0E => GetGlobal { result: Register, global: GlobalIdx },
+    0x0F => SetGlobal { global: GlobalIdx, input: Register },
+    0x10 => I32Load(LoadOp),
+    0x11 => I64Load(LoadOp),
+    0x12 => F32Load(LoadOp),
+    0x13 => F64Load(LoadOp),
+    0x14 => I32Load8S(LoadOp),
+    0x15 => I32Load8U(LoadOp),
+    0x16 => I32Load16S(LoadOp),
+    0x17 => I32Load16U(LoadOp),
+    0x18 => I64Load8S(LoadOp),
+    0x19 => I64Load8U(LoadOp),
+    0x1A => I64Load16S(LoadOp),
+    0x1B => I64Load16U(LoadOp),
+    0x1C => I64Load32S(LoadOp),
+    0x1D => I64Load32U(LoadOp),
+    0x1E => I32Store(StoreOp),
+    0x1F => I64Store(StoreOp),
+    0x20 => F32Store(StoreOp),
+    0x21 => F64Store(StoreOp),
+    0x22 => I32Store8(StoreOp),
+    0x23 => I32Store16(StoreOp),
+    0x24 => I64Store8(StoreOp),
+    0x25 => I64Store16(StoreOp),
+    0x26 => I64Store32(StoreOp),
+    0x27 => CurrentMemory { result: Register },
+    0x28 => GrowMemory(UnaryOp),
+    0x2A => I32Eqz(UnaryOp),
+    0x2B => I32Eq(BinaryOp),
+    0x2C => I32Ne(BinaryOp),
+    0x2D => I32LtS(BinaryOp),
+    0x2E => I32LtU(BinaryOp),
+    0x2F => I32GtS(BinaryOp),
+    0x30 => I32GtU(BinaryOp),
+    0x31 => I32LeS(BinaryOp),
+    0x32 => I32LeU(BinaryOp),
+    0x33 => I32GeS(BinaryOp),
+    0x34 => I32GeU(BinaryOp),
+    0x35 => I64Eqz(UnaryOp),
+    0x36 => I64Eq(BinaryOp),
+    0x37 => I64Ne(BinaryOp),
+    0x38 => I64LtS(BinaryOp),
+    0x39 => I64LtU(BinaryOp),
+    0x3A => I64GtS(BinaryOp),
+    0x3B => I64GtU(BinaryOp),
+    0x3C => I64LeS(BinaryOp),
+    0x3D => I64LeU(BinaryOp),
+    0x3E => I64GeS(BinaryOp),
+    0x3F => I64GeU(BinaryOp),
+    0x40 => F32Eq(BinaryOp),
+    0x41 => F32Ne(BinaryOp),
+    0x42 => F32Lt(BinaryOp),
+    0x43 => F32Gt(BinaryOp),
+    0x44 => F32Le(BinaryOp),
+    0x45 => F32Ge(BinaryOp),
+    0x46 => F64Eq(BinaryOp),
+    0x47 => F64Ne(BinaryOp),
+    0x48 => F64Lt(BinaryOp),
+    0x49 => F64Gt(BinaryOp),
+    0x4A => F64Le(BinaryOp),
+    0x4B => F64Ge(BinaryOp),
+    0x4C => I32Clz(UnaryOp),
+    0x4D => I32Ctz(UnaryOp),
+    0x4E => I32Popcnt(UnaryOp),
+    0x4F => I32Add(BinaryOp),
+    0x50 => I32Sub(BinaryOp),
+    0x51 => I32Mul(BinaryOp),
+    0x52 => I32DivS(BinaryOp),
+    0x53 => I32DivU(BinaryOp),
+    0x54 => I32RemS(BinaryOp),
+    0x55 => I32RemU(BinaryOp),
+    0x56 => I32And(BinaryOp),
+    0x57 => I32Or(BinaryOp),
+    0x58 => I32Xor(BinaryOp),
+    0x59 => I32Shl(BinaryOp),
+    0x5A => I32ShrS(BinaryOp),
+    0x5B => I32ShrU(BinaryOp),
+    0x5C => I32Rotl(BinaryOp),
+    0x5D => I32Rotr(BinaryOp),
+    0x5E => I64Clz(UnaryOp),
+    0x5F => I64Ctz(UnaryOp),
+    0x60 => I64Popcnt(UnaryOp),
+    0x61 => I64Add(BinaryOp),
+    0x62 => I64Sub(BinaryOp),
+    0x63 => I64Mul(BinaryOp),
+    0x64 => I64DivS(BinaryOp),
+    0x65 => I64DivU(BinaryOp),
+    0x66 => I64RemS(BinaryOp),
+    0x67 => I64RemU(BinaryOp),
+    0x68 => I64And(BinaryOp),
+    0x69 => I64Or(BinaryOp),
+    0x6A => I64Xor(BinaryOp),
+    0x6B => I64Shl(BinaryOp),
+    0x6C => I64ShrS(BinaryOp),
+    0x6D => I64ShrU(BinaryOp),
+    0x6E => I64Rotl(BinaryOp),
+    0x6F => I64Rotr(BinaryOp),
+    0x70 => F32Abs(UnaryOp),
+    0x71 => F32Neg(UnaryOp),
+    0x72 => F32Ceil(UnaryOp),
+    0x73 => F32Floor(UnaryOp),
+    0x74 => F32Trunc(UnaryOp),
+    0x75 => F32Nearest(UnaryOp),
+    0x76 => F32Sqrt(UnaryOp),
+    0x77 => F32Add(BinaryOp),
+    0x78 => F32Sub(BinaryOp),
+    0x79 => F32Mul(BinaryOp),
+    0x7A => F32Div(BinaryOp),
+    0x7B => F32Min(BinaryOp),
+    0x7C => F32Max(BinaryOp),
+    0x7D => F32Copysign(BinaryOp),
+    0x7E => F64Abs(UnaryOp),
+    0x7F => F64Neg(UnaryOp),
+    0x80 => F64Ceil(UnaryOp),
+    0x81 => F64Floor(UnaryOp),
+    0x82 => F64Trunc(UnaryOp),
+    0x83 => F64Nearest(UnaryOp),
+    0x84 => F64Sqrt(UnaryOp),
+    0x85 => F64Add(BinaryOp),
+    0x86 => F64Sub(BinaryOp),
+    0x87 => F64Mul(BinaryOp),
+    0x88 => F64Div(BinaryOp),
+    0x89 => F64Min(BinaryOp),
+    0x8A => F64Max(BinaryOp),
+    0x8B => F64Copysign(BinaryOp),
+    0x8C => I32WrapI64(UnaryOp),
+    0x8D => I32TruncSF32(UnaryOp),
+    0x8E => I32TruncUF32(UnaryOp),
+    0x8F => I32TruncSF64(UnaryOp),
+    0x90 => I32TruncUF64(UnaryOp),
+    0x91 => I64ExtendSI32(UnaryOp),
+    0x92 => I64ExtendUI32(UnaryOp),
+    0x93 => I64TruncSF32(UnaryOp),
+    0x94 => I64TruncUF32(UnaryOp),
+    0x95 => I64TruncSF64(UnaryOp),
+    0x96 => I64TruncUF64(UnaryOp),
+    0x97 => F32ConvertSI32(UnaryOp),
+    0x98 => F32ConvertUI32(UnaryOp),
+    0x99 => F32ConvertSI64(UnaryOp),
+    0x9A => F32ConvertUI64(UnaryOp),
+    0x9B => F32DemoteF64(UnaryOp),
+    0x9C => F64ConvertSI32(UnaryOp),
+    0x9D => F64ConvertUI32(UnaryOp),
+    0x9E => F64ConvertSI64(UnaryOp),
+    0x9F => F64ConvertUI64(UnaryOp),
+    0xA0 => F64PromoteF32(UnaryOp),
+    0xA5 => I32Extend8S(UnaryOp),
+    0xA6 => I32Extend16S(UnaryOp),
+    0xA7 => I64Extend8S(UnaryOp),
+    0xA8 => I64Extend16S(UnaryOp),
+    0xA9 => I64Extend32S(UnaryOp),
+    0xAA => I32TruncSatF32S(UnaryOp),
+    0xAB => I32TruncSatF32U(UnaryOp),
+    0xAC => I32TruncSatF64S(UnaryOp),
+    0xAD => I32TruncSatF64U(UnaryOp),
+    0xAE => I64TruncSatF32S(UnaryOp),
+    0xAF => I64TruncSatF32U(UnaryOp),
+    0xB0 => I64TruncSatF64S(UnaryOp),
+    0xB1 => I64TruncSatF64U(UnaryOp),
+    0xB2 => BrIfI32Eq(BranchCmpOp),
+    0xB3 => BrIfI32Ne(BranchCmpOp),
+    0xB4 => BrIfI32LtS(BranchCmpOp),
+    0xB5 => BrIfI32LtU(BranchCmpOp),
+    0xB6 => BrIfI32GtS(BranchCmpOp),
+    0xB7 => BrIfI32GtU(BranchCmpOp),
+    0xB8 => BrIfI32LeS(BranchCmpOp),
+    0xB9 => BrIfI32LeU(BranchCmpOp),
+    0xBA => BrIfI32GeS(BranchCmpOp),
+    0xBB => BrIfI32GeU(BranchCmpOp),
+    0xBC => BrIfI64Eq(BranchCmpOp),
+    0xBD => BrIfI64Ne(BranchCmpOp),
+    0xBE => BrIfI64LtS(BranchCmpOp),
+    0xBF => BrIfI64LtU(BranchCmpOp),
+    0xC0 => BrIfI64GtS(BranchCmpOp),
+    0xC1 => BrIfI64GtU(BranchCmpOp),
+    0xC2 => BrIfI64LeS(BranchCmpOp),
+    0xC3 => BrIfI64LeU(BranchCmpOp),
+    0xC4 => BrIfI64GeS(BranchCmpOp),
+    0xC5 => BrIfI64GeU(BranchCmpOp),
+    0xC6 => I32AddImm(ImmOp),
+    0xC7 => I64AddImm(ImmOp),
+}
@@ -1,5 +1,6 @@
 //! The instruction architecture of the `wasmi` interpreter.
 
+mod codec;
 mod utils;
 mod visitor;
 
@@ -7,10 +8,24 @@ mod visitor;
 mod tests;
 
 pub use self::{
-    utils::{BrTable, DropKeep, FuncIdx, GlobalIdx, LocalIdx, Offset, SignatureIdx, Target},
+    utils::{
+        BinaryOp,
+        BrTable,
+        BranchCmpOp,
+        FuncIdx,
+        GlobalIdx,
+        ImmOp,
+        LoadOp,
+        Offset,
+        Register,
+        RegisterSpan,
+        SignatureIdx,
+        StoreOp,
+        Target,
+        UnaryOp,
+    },
     visitor::VisitInstruction,
 };
-use wasmi_core::UntypedValue;
 
 /// The internal `wasmi` bytecode that is stored for Wasm functions.
 ///
@@ -18,271 +33,669 @@ use wasmi_core::UntypedValue;
 ///
 /// This representation slightly differs from WebAssembly instructions.
 ///
-/// For example the `BrTable` instruciton is unrolled into separate instructions
-/// each representing either the `BrTable` head or one of its branching targets.
+/// - Instead of operating on an implicit value stack all operands and results of
+///   the `wasmi` bytecode refer to the [`Register`] of the function frame that
+///   holds them. Therefore Wasm `local.get`, `drop` and constant instructions
+///   do not require any `wasmi` bytecode instructions.
+/// - The branching targets of a `BrTable` instruction are stored in a separate
+///   pool of the function body so that they do not inflate the size of every
+///   instruction. The `BrTable` instruction only refers to its slice of the pool.
+/// - Common sequences of Wasm instructions are fused into superinstructions,
+///   e.g. a comparison followed by a conditional branch on its result.
 #[derive(Copy, Debug, Clone, PartialEq)]
 pub enum Instruction {
-    GetLocal {
-        local_depth: LocalIdx,
+    Copy(UnaryOp),
+    Br(Target),
+    BrIfEqz {
+        condition: Register,
+        target: Target,
     },
-    SetLocal {
-        local_depth: LocalIdx,
+    BrIfNez {
+        condition: Register,
+        target: Target,
     },
-    TeeLocal {
-        local_depth: LocalIdx,
+    ReturnIfNez {
+        condition: Register,
+        results: RegisterSpan,
     },
-    Br(Target),
-    BrIfEqz(Target),
-    BrIfNez(Target),
-    ReturnIfNez(DropKeep),
+    BrIfI32Eq(BranchCmpOp),
+    BrIfI32Ne(BranchCmpOp),
+    BrIfI32LtS(BranchCmpOp),
+    BrIfI32LtU(BranchCmpOp),
+    BrIfI32GtS(BranchCmpOp),
+    BrIfI32GtU(BranchCmpOp),
+    BrIfI32LeS(BranchCmpOp),
+    BrIfI32LeU(BranchCmpOp),
+    BrIfI32GeS(BranchCmpOp),
+    BrIfI32GeU(BranchCmpOp),
+    BrIfI64Eq(BranchCmpOp),
+    BrIfI64Ne(BranchCmpOp),
+    BrIfI64LtS(BranchCmpOp),
+    BrIfI64LtU(BranchCmpOp),
+    BrIfI64GtS(BranchCmpOp),
+    BrIfI64GtU(BranchCmpOp),
+    BrIfI64LeS(BranchCmpOp),
+    BrIfI64LeU(BranchCmpOp),
+    BrIfI64GeS(BranchCmpOp),
+    BrIfI64GeU(BranchCmpOp),
     BrTable {
-        len_targets: usize,
+        index: Register,
+        /// The index of the first branching target in the `br_table` pool of the function body.
+        offset: u32,
+        /// The amount of branching targets including the default target.
+        len_targets: u32,
     },
     Unreachable,
-    Return(DropKeep),
-    Call(FuncIdx),
-    CallIndirect(SignatureIdx),
-    Drop,
-    Select,
-    GetGlobal(GlobalIdx),
-    SetGlobal(GlobalIdx),
-    I32Load(Offset),
-    I64Load(Offset),
-    F32Load(Offset),
-    F64Load(Offset),
-    I32Load8S(Offset),
-    I32Load8U(Offset),
-    I32Load16S(Offset),
-    I32Load16U(Offset),
-    I64Load8S(Offset),
-    I64Load8U(Offset),
-    I64Load16S(Offset),
-    I64Load16U(Offset),
-    I64Load32S(Offset),
-    I64Load32U(Offset),
-    I32Store(Offset),
-    I64Store(Offset),
-    F32Store(Offset),
-    F64Store(Offset),
-    I32Store8(Offset),
-    I32Store16(Offset),
-    I64Store8(Offset),
-    I64Store16(Offset),
-    I64Store32(Offset),
-    CurrentMemory,
-    GrowMemory,
-    Const(UntypedValue),
-    I32Eqz,
-    I32Eq,
-    I32Ne,
-    I32LtS,
-    I32LtU,
-    I32GtS,
-    I32GtU,
-    I32LeS,
-    I32LeU,
-    I32GeS,
-    I32GeU,
-    I64Eqz,
-    I64Eq,
-    I64Ne,
-    I64LtS,
-    I64LtU,
-    I64GtS,
-    I64GtU,
-    I64LeS,
-    I64LeU,
-    I64GeS,
-    I64GeU,
-    F32Eq,
-    F32Ne,
-    F32Lt,
-    F32Gt,
-    F32Le,
-    F32Ge,
-    F64Eq,
-    F64Ne,
-    F64Lt,
-    F64Gt,
-    F64Le,
-    F64Ge,
-    I32Clz,
-    I32Ctz,
-    I32Popcnt,
-    I32Add,
-    I32Sub,
-    I32Mul,
-    I32DivS,
-    I32DivU,
-    I32RemS,
-    I32RemU,
-    I32And,
-    I32Or,
-    I32Xor,
-    I32Shl,
-    I32ShrS,
-    I32ShrU,
-    I32Rotl,
-    I32Rotr,
-    I64Clz,
-    I64Ctz,
-    I64Popcnt,
-    I64Add,
-    I64Sub,
-    I64Mul,
-    I64DivS,
-    I64DivU,
-    I64RemS,
-    I64RemU,
-    I64And,
-    I64Or,
-    I64Xor,
-    I64Shl,
-    I64ShrS,
-    I64ShrU,
-    I64Rotl,
-    I64Rotr,
-    F32Abs,
-    F32Neg,
-    F32Ceil,
-    F32Floor,
-    F32Trunc,
-    F32Nearest,
-    F32Sqrt,
-    F32Add,
-    F32Sub,
-    F32Mul,
-    F32Div,
-    F32Min,
-    F32Max,
-    F32Copysign,
-    F64Abs,
-    F64Neg,
-    F64Ceil,
-    F64Floor,
-    F64Trunc,
-    F64Nearest,
-    F64Sqrt,
-    F64Add,
-    F64Sub,
-    F64Mul,
-    F64Div,
-    F64Min,
-    F64Max,
-    F64Copysign,
-    I32WrapI64,
-    I32TruncSF32,
-    I32TruncUF32,
-    I32TruncSF64,
-    I32TruncUF64,
-    I64ExtendSI32,
-    I64ExtendUI32,
-    I64TruncSF32,
-    I64TruncUF32,
-    I64TruncSF64,
-    I64TruncUF64,
-    F32ConvertSI32,
-    F32ConvertUI32,
-    F32ConvertSI64,
-    F32ConvertUI64,
-    F32DemoteF64,
-    F64ConvertSI32,
-    F64ConvertUI32,
-    F64ConvertSI64,
-    F64ConvertUI64,
-    F64PromoteF32,
-    I32ReinterpretF32,
-    I64ReinterpretF64,
-    F32ReinterpretI32,
-    F64ReinterpretI64,
-    I32Extend8S,
-    I32Extend16S,
-    I64Extend8S,
-    I64Extend16S,
-    I64Extend32S,
-    I32TruncSatF32S,
-    I32TruncSatF32U,
-    I32TruncSatF64S,
-    I32TruncSatF64U,
-    I64TruncSatF32S,
-    I64TruncSatF32U,
-    I64TruncSatF64S,
-    I64TruncSatF64U,
-
-    /// The start of a Wasm function body.
-    ///
-    /// - This stores the `wasmi` bytecode length of the function body as well
-    ///   as the amount of local variables.
-    /// - Note that the length of the `wasmi` bytecode might differ from the length
-    ///   of the original WebAssembly bytecode.
-    /// - The types of the local variables do not matter since all stack values
-    ///   are equally sized with 64-bits per value. Storing the amount of local
-    ///   variables eliminates one indirection when calling a Wasm function.
-    ///
-    /// # Note
-    ///
-    /// This is a non-WebAssembly instruction that is specific to how the `wasmi`
-    /// interpreter organizes its internal bytecode.
-    FuncBodyStart {
-        /// This field represents the amount of instruction of the function body.
-        ///
-        /// Note: This does not include any meta instructions such as
-        /// [`Instruction::FuncBodyStart`] or [`Instruction::FuncBodyEnd`].
-        len_instructions: u32,
-        /// Represents the number of local variables of the function body.
-        ///
-        /// Note: The types of the locals do not matter since all stack values
-        ///       use 64-bit encoding in the `wasmi` bytecode interpreter.
-        /// Note: Storing the amount of locals inline with the rest of the
-        ///       function body eliminates one indirection when calling a function.
-        len_locals: u32,
-        max_stack_height: u32,
+    Return(RegisterSpan),
+    Call {
+        func: FuncIdx,
+        params: Register,
     },
-    /// The end of a Wasm function body.
-    ///
-    /// # Note
-    ///
-    /// This is a non-WebAssembly instruction that is specific to how the `wasmi`
-    /// interpreter organizes its internal bytecode.
-    FuncBodyEnd,
+    CallIndirect {
+        signature: SignatureIdx,
+        index: Register,
+        params: Register,
+    },
+    Select {
+        result: Register,
+        condition: Register,
+        if_false: Register,
+    },
+    GetGlobal {
+        result: Register,
+        global: GlobalIdx,
+    },
+    SetGlobal {
+        global: GlobalIdx,
+        input: Register,
+    },
+    I32Load(LoadOp),
+    I64Load(LoadOp),
+    F32Load(LoadOp),
+    F64Load(LoadOp),
+    I32Load8S(LoadOp),
+    I32Load8U(LoadOp),
+    I32Load16S(LoadOp),
+    I32Load16U(LoadOp),
+    I64Load8S(LoadOp),
+    I64Load8U(LoadOp),
+    I64Load16S(LoadOp),
+    I64Load16U(LoadOp),
+    I64Load32S(LoadOp),
+    I64Load32U(LoadOp),
+    I32Store(StoreOp),
+    I64Store(StoreOp),
+    F32Store(StoreOp),
+    F64Store(StoreOp),
+    I32Store8(StoreOp),
+    I32Store16(StoreOp),
+    I64Store8(StoreOp),
+    I64Store16(StoreOp),
+    I64Store32(StoreOp),
+    CurrentMemory {
+        result: Register,
+    },
+    GrowMemory(UnaryOp),
+    I32Eqz(UnaryOp),
+    I32Eq(BinaryOp),
+    I32Ne(BinaryOp),
+    I32LtS(BinaryOp),
+    I32LtU(BinaryOp),
+    I32GtS(BinaryOp),
+    I32GtU(BinaryOp),
+    I32LeS(BinaryOp),
+    I32LeU(BinaryOp),
+    I32GeS(BinaryOp),
+    I32GeU(BinaryOp),
+    I64Eqz(UnaryOp),
+    I64Eq(BinaryOp),
+    I64Ne(BinaryOp),
+    I64LtS(BinaryOp),
+    I64LtU(BinaryOp),
+    I64GtS(BinaryOp),
+    I64GtU(BinaryOp),
+    I64LeS(BinaryOp),
+    I64LeU(BinaryOp),
+    I64GeS(BinaryOp),
+    I64GeU(BinaryOp),
+    F32Eq(BinaryOp),
+    F32Ne(BinaryOp),
+    F32Lt(BinaryOp),
+    F32Gt(BinaryOp),
+    F32Le(BinaryOp),
+    F32Ge(BinaryOp),
+    F64Eq(BinaryOp),
+    F64Ne(BinaryOp),
+    F64Lt(BinaryOp),
+    F64Gt(BinaryOp),
+    F64Le(BinaryOp),
+    F64Ge(BinaryOp),
+    I32Clz(UnaryOp),
+    I32Ctz(UnaryOp),
+    I32Popcnt(UnaryOp),
+    I32Add(BinaryOp),
+    I32AddImm(ImmOp),
+    I32Sub(BinaryOp),
+    I32Mul(BinaryOp),
+    I32DivS(BinaryOp),
+    I32DivU(BinaryOp),
+    I32RemS(BinaryOp),
+    I32RemU(BinaryOp),
+    I32And(BinaryOp),
+    I32Or(BinaryOp),
+    I32Xor(BinaryOp),
+    I32Shl(BinaryOp),
+    I32ShrS(BinaryOp),
+    I32ShrU(BinaryOp),
+    I32Rotl(BinaryOp),
+    I32Rotr(BinaryOp),
+    I64Clz(UnaryOp),
+    I64Ctz(UnaryOp),
+    I64Popcnt(UnaryOp),
+    I64Add(BinaryOp),
+    I64AddImm(ImmOp),
+    I64Sub(BinaryOp),
+    I64Mul(BinaryOp),
+    I64DivS(BinaryOp),
+    I64DivU(BinaryOp),
+    I64RemS(BinaryOp),
+    I64RemU(BinaryOp),
+    I64And(BinaryOp),
+    I64Or(BinaryOp),
+    I64Xor(BinaryOp),
+    I64Shl(BinaryOp),
+    I64ShrS(BinaryOp),
+    I64ShrU(BinaryOp),
+    I64Rotl(BinaryOp),
+    I64Rotr(BinaryOp),
+    F32Abs(UnaryOp),
+    F32Neg(UnaryOp),
+    F32Ceil(UnaryOp),
+    F32Floor(UnaryOp),
+    F32Trunc(UnaryOp),
+    F32Nearest(UnaryOp),
+    F32Sqrt(UnaryOp),
+    F32Add(BinaryOp),
+    F32Sub(BinaryOp),
+    F32Mul(BinaryOp),
+    F32Div(BinaryOp),
+    F32Min(BinaryOp),
+    F32Max(BinaryOp),
+    F32Copysign(BinaryOp),
+    F64Abs(UnaryOp),
+    F64Neg(UnaryOp),
+    F64Ceil(UnaryOp),
+    F64Floor(UnaryOp),
+    F64Trunc(UnaryOp),
+    F64Nearest(UnaryOp),
+    F64Sqrt(UnaryOp),
+    F64Add(BinaryOp),
+    F64Sub(BinaryOp),
+    F64Mul(BinaryOp),
+    F64Div(BinaryOp),
+    F64Min(BinaryOp),
+    F64Max(BinaryOp),
+    F64Copysign(BinaryOp),
+    I32WrapI64(UnaryOp),
+    I32TruncSF32(UnaryOp),
+    I32TruncUF32(UnaryOp),
+    I32TruncSF64(UnaryOp),
+    I32TruncUF64(UnaryOp),
+    I64ExtendSI32(UnaryOp),
+    I64ExtendUI32(UnaryOp),
+    I64TruncSF32(UnaryOp),
+    I64TruncUF32(UnaryOp),
+    I64TruncSF64(UnaryOp),
+    I64TruncUF64(UnaryOp),
+    F32ConvertSI32(UnaryOp),
+    F32ConvertUI32(UnaryOp),
+    F32ConvertSI64(UnaryOp),
+    F32ConvertUI64(UnaryOp),
+    F32DemoteF64(UnaryOp),
+    F64ConvertSI32(UnaryOp),
+    F64ConvertUI32(UnaryOp),
+    F64ConvertSI64(UnaryOp),
+    F64ConvertUI64(UnaryOp),
+    F64PromoteF32(UnaryOp),
+    I32Extend8S(UnaryOp),
+    I32Extend16S(UnaryOp),
+    I64Extend8S(UnaryOp),
+    I64Extend16S(UnaryOp),
+    I64Extend32S(UnaryOp),
+    I32TruncSatF32S(UnaryOp),
+    I32TruncSatF32U(UnaryOp),
+    I32TruncSatF64S(UnaryOp),
+    I32TruncSatF64U(UnaryOp),
+    I64TruncSatF32S(UnaryOp),
+    I64TruncSatF32U(UnaryOp),
+    I64TruncSatF64S(UnaryOp),
+    I64TruncSatF64U(UnaryOp),
 }
 
 impl Instruction {
-    /// Creates a new `Const` instruction from the given value.
-    pub fn constant<T>(value: T) -> Self
-    where
-        T: Into<UntypedValue>,
-    {
-        Self::Const(value.into())
-    }
-
-    /// Creates a new `local.get` instruction from the given local depth.
-    pub fn local_get<T>(local_depth: T) -> Self
+    /// Calls `f` for every [`Register`] operand or result of the [`Instruction`].
+    pub fn for_each_register_mut<F>(&mut self, mut f: F)
     where
-        T: Into<LocalIdx>,
+        F: FnMut(&mut Register),
     {
-        Self::GetLocal {
-            local_depth: local_depth.into(),
+        match self {
+            Self::Br(_) | Self::Unreachable => {}
+            Self::BrIfEqz { condition, .. } | Self::BrIfNez { condition, .. } => f(condition),
+            Self::ReturnIfNez { condition, results } => {
+                f(condition);
+                f(results.start_mut());
+            }
+            Self::BrTable { index, .. } => f(index),
+            Self::BrIfI32Eq(op)
+            | Self::BrIfI32Ne(op)
+            | Self::BrIfI32LtS(op)
+            | Self::BrIfI32LtU(op)
+            | Self::BrIfI32GtS(op)
+            | Self::BrIfI32GtU(op)
+            | Self::BrIfI32LeS(op)
+            | Self::BrIfI32LeU(op)
+            | Self::BrIfI32GeS(op)
+            | Self::BrIfI32GeU(op)
+            | Self::BrIfI64Eq(op)
+            | Self::BrIfI64Ne(op)
+            | Self::BrIfI64LtS(op)
+            | Self::BrIfI64LtU(op)
+            | Self::BrIfI64GtS(op)
+            | Self::BrIfI64GtU(op)
+            | Self::BrIfI64LeS(op)
+            | Self::BrIfI64LeU(op)
+            | Self::BrIfI64GeS(op)
+            | Self::BrIfI64GeU(op) => {
+                f(&mut op.lhs);
+                f(&mut op.rhs);
+            }
+            Self::I32AddImm(op) | Self::I64AddImm(op) => {
+                f(&mut op.result);
+                f(&mut op.input);
+            }
+            Self::Return(results) => f(results.start_mut()),
+            Self::Call { params, .. } => f(params),
+            Self::CallIndirect { index, params, .. } => {
+                f(index);
+                f(params);
+            }
+            Self::Select {
+                result,
+                condition,
+                if_false,
+            } => {
+                f(result);
+                f(condition);
+                f(if_false);
+            }
+            Self::GetGlobal { result, .. } | Self::CurrentMemory { result } => f(result),
+            Self::SetGlobal { input, .. } => f(input),
+            Self::I32Load(op)
+            | Self::I64Load(op)
+            | Self::F32Load(op)
+            | Self::F64Load(op)
+            | Self::I32Load8S(op)
+            | Self::I32Load8U(op)
+            | Self::I32Load16S(op)
+            | Self::I32Load16U(op)
+            | Self::I64Load8S(op)
+            | Self::I64Load8U(op)
+            | Self::I64Load16S(op)
+            | Self::I64Load16U(op)
+            | Self::I64Load32S(op)
+            | Self::I64Load32U(op) => {
+                f(&mut op.result);
+                f(&mut op.ptr);
+            }
+            Self::I32Store(op)
+            | Self::I64Store(op)
+            | Self::F32Store(op)
+            | Self::F64Store(op)
+            | Self::I32Store8(op)
+            | Self::I32Store16(op)
+            | Self::I64Store8(op)
+            | Self::I64Store16(op)
+            | Self::I64Store32(op) => {
+                f(&mut op.ptr);
+                f(&mut op.value);
+            }
+            Self::Copy(op)
+            | Self::GrowMemory(op)
+            | Self::I32Eqz(op)
+            | Self::I64Eqz(op)
+            | Self::I32Clz(op)
+            | Self::I32Ctz(op)
+            | Self::I32Popcnt(op)
+            | Self::I64Clz(op)
+            | Self::I64Ctz(op)
+            | Self::I64Popcnt(op)
+            | Self::F32Abs(op)
+            | Self::F32Neg(op)
+            | Self::F32Ceil(op)
+            | Self::F32Floor(op)
+            | Self::F32Trunc(op)
+            | Self::F32Nearest(op)
+            | Self::F32Sqrt(op)
+            | Self::F64Abs(op)
+            | Self::F64Neg(op)
+            | Self::F64Ceil(op)
+            | Self::F64Floor(op)
+            | Self::F64Trunc(op)
+            | Self::F64Nearest(op)
+            | Self::F64Sqrt(op)
+            | Self::I32WrapI64(op)
+            | Self::I32TruncSF32(op)
+            | Self::I32TruncUF32(op)
+            | Self::I32TruncSF64(op)
+            | Self::I32TruncUF64(op)
+            | Self::I64ExtendSI32(op)
+            | Self::I64ExtendUI32(op)
+            | Self::I64TruncSF32(op)
+            | Self::I64TruncUF32(op)
+            | Self::I64TruncSF64(op)
+            | Self::I64TruncUF64(op)
+            | Self::F32ConvertSI32(op)
+            | Self::F32ConvertUI32(op)
+            | Self::F32ConvertSI64(op)
+            | Self::F32ConvertUI64(op)
+            | Self::F32DemoteF64(op)
+            | Self::F64ConvertSI32(op)
+            | Self::F64ConvertUI32(op)
+            | Self::F64ConvertSI64(op)
+            | Self::F64ConvertUI64(op)
+            | Self::F64PromoteF32(op)
+            | Self::I32Extend8S(op)
+            | Self::I32Extend16S(op)
+            | Self::I64Extend8S(op)
+            | Self::I64Extend16S(op)
+            | Self::I64Extend32S(op)
+            | Self::I32TruncSatF32S(op)
+            | Self::I32TruncSatF32U(op)
+            | Self::I32TruncSatF64S(op)
+            | Self::I32TruncSatF64U(op)
+            | Self::I64TruncSatF32S(op)
+            | Self::I64TruncSatF32U(op)
+            | Self::I64TruncSatF64S(op)
+            | Self::I64TruncSatF64U(op) => {
+                f(&mut op.result);
+                f(&mut op.input);
+            }
+            Self::I32Eq(op)
+            | Self::I32Ne(op)
+            | Self::I32LtS(op)
+            | Self::I32LtU(op)
+            | Self::I32GtS(op)
+            | Self::I32GtU(op)
+            | Self::I32LeS(op)
+            | Self::I32LeU(op)
+            | Self::I32GeS(op)
+            | Self::I32GeU(op)
+            | Self::I64Eq(op)
+            | Self::I64Ne(op)
+            | Self::I64LtS(op)
+            | Self::I64LtU(op)
+            | Self::I64GtS(op)
+            | Self::I64GtU(op)
+            | Self::I64LeS(op)
+            | Self::I64LeU(op)
+            | Self::I64GeS(op)
+            | Self::I64GeU(op)
+            | Self::F32Eq(op)
+            | Self::F32Ne(op)
+            | Self::F32Lt(op)
+            | Self::F32Gt(op)
+            | Self::F32Le(op)
+            | Self::F32Ge(op)
+            | Self::F64Eq(op)
+            | Self::F64Ne(op)
+            | Self::F64Lt(op)
+            | Self::F64Gt(op)
+            | Self::F64Le(op)
+            | Self::F64Ge(op)
+            | Self::I32Add(op)
+            | Self::I32Sub(op)
+            | Self::I32Mul(op)
+            | Self::I32DivS(op)
+            | Self::I32DivU(op)
+            | Self::I32RemS(op)
+            | Self::I32RemU(op)
+            | Self::I32And(op)
+            | Self::I32Or(op)
+            | Self::I32Xor(op)
+            | Self::I32Shl(op)
+            | Self::I32ShrS(op)
+            | Self::I32ShrU(op)
+            | Self::I32Rotl(op)
+            | Self::I32Rotr(op)
+            | Self::I64Add(op)
+            | Self::I64Sub(op)
+            | Self::I64Mul(op)
+            | Self::I64DivS(op)
+            | Self::I64DivU(op)
+            | Self::I64RemS(op)
+            | Self::I64RemU(op)
+            | Self::I64And(op)
+            | Self::I64Or(op)
+            | Self::I64Xor(op)
+            | Self::I64Shl(op)
+            | Self::I64ShrS(op)
+            | Self::I64ShrU(op)
+            | Self::I64Rotl(op)
+            | Self::I64Rotr(op)
+            | Self::F32Add(op)
+            | Self::F32Sub(op)
+            | Self::F32Mul(op)
+            | Self::F32Div(op)
+            | Self::F32Min(op)
+            | Self::F32Max(op)
+            | Self::F32Copysign(op)
+            | Self::F64Add(op)
+            | Self::F64Sub(op)
+            | Self::F64Mul(op)
+            | Self::F64Div(op)
+            | Self::F64Min(op)
+            | Self::F64Max(op)
+            | Self::F64Copysign(op) => {
+                f(&mut op.result);
+                f(&mut op.lhs);
+                f(&mut op.rhs);
+            }
         }
     }
 
-    /// Creates a new `local.set` instruction from the given local depth.
-    pub fn local_set<T>(local_depth: T) -> Self
-    where
-        T: Into<LocalIdx>,
-    {
-        Self::SetLocal {
-            local_depth: local_depth.into(),
+    /// Returns the [`Register`] that stores the result of the [`Instruction`] if any.
+    ///
+    /// # Note
+    ///
+    /// Only returns the result [`Register`] of instructions that read all of their
+    /// inputs before they write their result so that it can be safely replaced.
+    pub fn result_mut(&mut self) -> Option<&mut Register> {
+        match self {
+            Self::GetGlobal { result, .. } | Self::CurrentMemory { result } => Some(result),
+            Self::I32AddImm(op) | Self::I64AddImm(op) => Some(&mut op.result),
+            Self::I32Load(op)
+            | Self::I64Load(op)
+            | Self::F32Load(op)
+            | Self::F64Load(op)
+            | Self::I32Load8S(op)
+            | Self::I32Load8U(op)
+            | Self::I32Load16S(op)
+            | Self::I32Load16U(op)
+            | Self::I64Load8S(op)
+            | Self::I64Load8U(op)
+            | Self::I64Load16S(op)
+            | Self::I64Load16U(op)
+            | Self::I64Load32S(op)
+            | Self::I64Load32U(op) => Some(&mut op.result),
+            Self::Copy(op)
+            | Self::GrowMemory(op)
+            | Self::I32Eqz(op)
+            | Self::I64Eqz(op)
+            | Self::I32Clz(op)
+            | Self::I32Ctz(op)
+            | Self::I32Popcnt(op)
+            | Self::I64Clz(op)
+            | Self::I64Ctz(op)
+            | Self::I64Popcnt(op)
+            | Self::F32Abs(op)
+            | Self::F32Neg(op)
+            | Self::F32Ceil(op)
+            | Self::F32Floor(op)
+            | Self::F32Trunc(op)
+            | Self::F32Nearest(op)
+            | Self::F32Sqrt(op)
+            | Self::F64Abs(op)
+            | Self::F64Neg(op)
+            | Self::F64Ceil(op)
+            | Self::F64Floor(op)
+            | Self::F64Trunc(op)
+            | Self::F64Nearest(op)
+            | Self::F64Sqrt(op)
+            | Self::I32WrapI64(op)
+            | Self::I32TruncSF32(op)
+            | Self::I32TruncUF32(op)
+            | Self::I32TruncSF64(op)
+            | Self::I32TruncUF64(op)
+            | Self::I64ExtendSI32(op)
+            | Self::I64ExtendUI32(op)
+            | Self::I64TruncSF32(op)
+            | Self::I64TruncUF32(op)
+            | Self::I64TruncSF64(op)
+            | Self::I64TruncUF64(op)
+            | Self::F32ConvertSI32(op)
+            | Self::F32ConvertUI32(op)
+            | Self::F32ConvertSI64(op)
+            | Self::F32ConvertUI64(op)
+            | Self::F32DemoteF64(op)
+            | Self::F64ConvertSI32(op)
+            | Self::F64ConvertUI32(op)
+            | Self::F64ConvertSI64(op)
+            | Self::F64ConvertUI64(op)
+            | Self::F64PromoteF32(op)
+            | Self::I32Extend8S(op)
+            | Self::I32Extend16S(op)
+            | Self::I64Extend8S(op)
+            | Self::I64Extend16S(op)
+            | Self::I64Extend32S(op)
+            | Self::I32TruncSatF32S(op)
+            | Self::I32TruncSatF32U(op)
+            | Self::I32TruncSatF64S(op)
+            | Self::I32TruncSatF64U(op)
+            | Self::I64TruncSatF32S(op)
+            | Self::I64TruncSatF32U(op)
+            | Self::I64TruncSatF64S(op)
+            | Self::I64TruncSatF64U(op) => Some(&mut op.result),
+            Self::I32Eq(op)
+            | Self::I32Ne(op)
+            | Self::I32LtS(op)
+            | Self::I32LtU(op)
+            | Self::I32GtS(op)
+            | Self::I32GtU(op)
+            | Self::I32LeS(op)
+            | Self::I32LeU(op)
+            | Self::I32GeS(op)
+            | Self::I32GeU(op)
+            | Self::I64Eq(op)
+            | Self::I64Ne(op)
+            | Self::I64LtS(op)
+            | Self::I64LtU(op)
+            | Self::I64GtS(op)
+            | Self::I64GtU(op)
+            | Self::I64LeS(op)
+            | Self::I64LeU(op)
+            | Self::I64GeS(op)
+            | Self::I64GeU(op)
+            | Self::F32Eq(op)
+            | Self::F32Ne(op)
+            | Self::F32Lt(op)
+            | Self::F32Gt(op)
+            | Self::F32Le(op)
+            | Self::F32Ge(op)
+            | Self::F64Eq(op)
+            | Self::F64Ne(op)
+            | Self::F64Lt(op)
+            | Self::F64Gt(op)
+            | Self::F64Le(op)
+            | Self::F64Ge(op)
+            | Self::I32Add(op)
+            | Self::I32Sub(op)
+            | Self::I32Mul(op)
+            | Self::I32DivS(op)
+            | Self::I32DivU(op)
+            | Self::I32RemS(op)
+            | Self::I32RemU(op)
+            | Self::I32And(op)
+            | Self::I32Or(op)
+            | Self::I32Xor(op)
+            | Self::I32Shl(op)
+            | Self::I32ShrS(op)
+            | Self::I32ShrU(op)
+            | Self::I32Rotl(op)
+            | Self::I32Rotr(op)
+            | Self::I64Add(op)
+            | Self::I64Sub(op)
+            | Self::I64Mul(op)
+            | Self::I64DivS(op)
+            | Self::I64DivU(op)
+            | Self::I64RemS(op)
+            | Self::I64RemU(op)
+            | Self::I64And(op)
+            | Self::I64Or(op)
+            | Self::I64Xor(op)
+            | Self::I64Shl(op)
+            | Self::I64ShrS(op)
+            | Self::I64ShrU(op)
+            | Self::I64Rotl(op)
+            | Self::I64Rotr(op)
+            | Self::F32Add(op)
+            | Self::F32Sub(op)
+            | Self::F32Mul(op)
+            | Self::F32Div(op)
+            | Self::F32Min(op)
+            | Self::F32Max(op)
+            | Self::F32Copysign(op)
+            | Self::F64Add(op)
+            | Self::F64Sub(op)
+            | Self::F64Mul(op)
+            | Self::F64Div(op)
+            | Self::F64Min(op)
+            | Self::F64Max(op)
+            | Self::F64Copysign(op) => Some(&mut op.result),
+            _ => None,
         }
     }
 
-    /// Creates a new `local.tee` instruction from the given local depth.
-    pub fn local_tee<T>(local_depth: T) -> Self
-    where
-        T: Into<LocalIdx>,
-    {
-        Self::TeeLocal {
-            local_depth: local_depth.into(),
+    /// Returns the branch [`Target`] of the [`Instruction`] if any.
+    ///
+    /// # Note
+    ///
+    /// Only returns the [`Target`] of `br`, `br_if` and fused compare and branch instructions.
+    pub fn branch_target_mut(&mut self) -> Option<&mut Target> {
+        match self {
+            Self::Br(target)
+            | Self::BrIfEqz { target, .. }
+            | Self::BrIfNez { target, .. }
+            | Self::BrIfI32Eq(BranchCmpOp { target, .. })
+            | Self::BrIfI32Ne(BranchCmpOp { target, .. })
+            | Self::BrIfI32LtS(BranchCmpOp { target, .. })
+            | Self::BrIfI32LtU(BranchCmpOp { target, .. })
+            | Self::BrIfI32GtS(BranchCmpOp { target, .. })
+            | Self::BrIfI32GtU(BranchCmpOp { target, .. })
+            | Self::BrIfI32LeS(BranchCmpOp { target, .. })
+            | Self::BrIfI32LeU(BranchCmpOp { target, .. })
+            | Self::BrIfI32GeS(BranchCmpOp { target, .. })
+            | Self::BrIfI32GeU(BranchCmpOp { target, .. })
+            | Self::BrIfI64Eq(BranchCmpOp { target, .. })
+            | Self::BrIfI64Ne(BranchCmpOp { target, .. })
+            | Self::BrIfI64LtS(BranchCmpOp { target, .. })
+            | Self::BrIfI64LtU(BranchCmpOp { target, .. })
+            | Self::BrIfI64GtS(BranchCmpOp { target, .. })
+            | Self::BrIfI64GtU(BranchCmpOp { target, .. })
+            | Self::BrIfI64LeS(BranchCmpOp { target, .. })
+            | Self::BrIfI64LeU(BranchCmpOp { target, .. })
+            | Self::BrIfI64GeS(BranchCmpOp { target, .. })
+            | Self::BrIfI64GeU(BranchCmpOp { target, .. }) => Some(target),
+            _ => None,
         }
     }
 }
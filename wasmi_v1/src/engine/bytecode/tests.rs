@@ -3,6 +3,95 @@ use super::*;
 #[test]
 fn size_of_instruction() {
     assert_eq!(core::mem::size_of::<Instruction>(), 16);
-    assert_eq!(core::mem::size_of::<DropKeep>(), 8);
-    assert_eq!(core::mem::size_of::<Target>(), 12);
+    assert_eq!(core::mem::size_of::<Register>(), 4);
+    assert_eq!(core::mem::size_of::<Target>(), 4);
+}
+
+#[test]
+fn encode_decode_instructions() {
+    use crate::{
+        codec::{Decoder, Encoder},
+        engine::InstructionIdx,
+    };
+    let r = Register::from;
+    let insts = [
+        Instruction::Copy(UnaryOp {
+            result: r(3),
+            input: r(0),
+        }),
+        Instruction::BrTable {
+            index: r(1),
+            offset: 3,
+            len_targets: 2,
+        },
+        Instruction::Br(Target::new(InstructionIdx::from_usize(7))),
+        Instruction::BrIfNez {
+            condition: r(2),
+            target: Target::new(InstructionIdx::from_usize(3)),
+        },
+        Instruction::Return(RegisterSpan::new(r(4), 2)),
+        Instruction::CallIndirect {
+            signature: SignatureIdx::from(5),
+            index: r(6),
+            params: r(4),
+        },
+        Instruction::I64Store16(StoreOp {
+            ptr: r(1),
+            offset: Offset::from(42),
+            value: r(2),
+        }),
+        Instruction::I32Add(BinaryOp {
+            result: r(5),
+            lhs: r(0),
+            rhs: r(u32::MAX),
+        }),
+        Instruction::I64TruncSatF64U(UnaryOp {
+            result: r(0),
+            input: r(0),
+        }),
+        Instruction::BrIfI64GeU(BranchCmpOp {
+            lhs: r(1),
+            rhs: r(2),
+            target: Target::new(InstructionIdx::from_usize(9)),
+        }),
+        Instruction::I32AddImm(ImmOp {
+            result: r(3),
+            input: r(1),
+            imm: -1,
+        }),
+    ];
+    let mut encoder = Encoder::default();
+    for inst in &insts {
+        inst.encode(&mut encoder);
+    }
+    let bytes = encoder.into_bytes();
+    let mut decoder = Decoder::new(&bytes);
+    for inst in &insts {
+        assert_eq!(Instruction::decode(&mut decoder).unwrap(), *inst);
+    }
+    decoder.finish().unwrap();
+}
+
+#[test]
+fn for_each_register_mut_visits_all_registers() {
+    let r = Register::from;
+    let mut inst = Instruction::Select {
+        result: r(1),
+        condition: r(2),
+        if_false: r(3),
+    };
+    let mut visited = Vec::new();
+    inst.for_each_register_mut(|register| {
+        visited.push(*register);
+        *register = Register::from(register.into_inner() + 10);
+    });
+    assert_eq!(visited, [r(1), r(2), r(3)]);
+    assert_eq!(
+        inst,
+        Instruction::Select {
+            result: r(11),
+            condition: r(12),
+            if_false: r(13),
+        }
+    );
 }
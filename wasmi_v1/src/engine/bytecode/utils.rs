@@ -1,64 +1,145 @@
-use super::{super::super::engine::InstructionIdx, Instruction};
+use super::super::super::engine::InstructionIdx;
 use core::cmp;
 
-/// Defines how many stack values are going to be dropped and kept after branching.
+/// A register of the currently executed function frame.
+///
+/// # Note
+///
+/// Refers to a stack value relative to the start of the function frame.
+/// A function frame stores the function parameters, local variables,
+/// constant values and temporary values in this order.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct Register(u32);
+
+impl From<u32> for Register {
+    fn from(index: u32) -> Self {
+        Self(index)
+    }
+}
+
+impl Register {
+    /// Returns the inner `u32` index.
+    pub fn into_inner(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the index of the [`Register`] as `usize`.
+    pub fn into_usize(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A contiguous span of registers.
+///
+/// # Note
+///
+/// Used to refer to multiple values at once, e.g. the results of a `return`.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub struct DropKeep {
-    /// The amount of stack values dropped.
-    drop: u32,
-    /// The amount of stack values kept.
-    keep: u32,
+pub struct RegisterSpan {
+    /// The first register of the span.
+    start: Register,
+    /// The amount of registers in the span.
+    len: u32,
 }
 
-impl DropKeep {
-    /// Creates a new [`DropKeep`] with the given amounts to drop and keep.
-    ///
-    /// # Panics
-    ///
-    /// - If `drop` or `keep` values do not respect their limitations.
-    pub fn new(drop: usize, keep: usize) -> Self {
-        let drop = drop.try_into().unwrap_or_else(|error| {
-            panic!("encountered invalid `drop` amount of {}: {}", drop, error)
-        });
-        let keep = keep.try_into().unwrap_or_else(|error| {
-            panic!("encountered invalid `keep` amount of {}: {}", keep, error)
-        });
-        Self { drop, keep }
+impl RegisterSpan {
+    /// Creates a new [`RegisterSpan`] of `len` registers beginning at `start`.
+    pub fn new(start: Register, len: u32) -> Self {
+        Self { start, len }
     }
 
-    /// Creates a new [`DropKeep`] from the given amounts to drop and keep.
-    pub fn new32(drop: u32, keep: u32) -> Self {
-        Self { drop, keep }
+    /// Returns the first register of the [`RegisterSpan`].
+    pub fn start(self) -> Register {
+        self.start
     }
 
-    /// Returns the amount of stack values to drop.
-    pub fn drop(self) -> usize {
-        self.drop as usize
+    /// Returns a mutable reference to the first register of the [`RegisterSpan`].
+    pub fn start_mut(&mut self) -> &mut Register {
+        &mut self.start
     }
 
-    /// Returns the amount of stack values to keep.
-    pub fn keep(self) -> usize {
-        self.keep as usize
+    /// Returns the amount of registers in the [`RegisterSpan`].
+    pub fn len_registers(self) -> usize {
+        self.len as usize
     }
 }
 
+/// The operands of an instruction computing its result from a single input.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UnaryOp {
+    /// The register that stores the result.
+    pub result: Register,
+    /// The register that holds the input.
+    pub input: Register,
+}
+
+/// The operands of an instruction computing its result from two inputs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BinaryOp {
+    /// The register that stores the result.
+    pub result: Register,
+    /// The register that holds the left-hand side input.
+    pub lhs: Register,
+    /// The register that holds the right-hand side input.
+    pub rhs: Register,
+}
+
+/// The operands of an instruction computing its result from an input and an immediate value.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ImmOp {
+    /// The register that stores the result.
+    pub result: Register,
+    /// The register that holds the input.
+    pub input: Register,
+    /// The immediate value.
+    pub imm: i32,
+}
+
+/// The operands of a fused compare and branch instruction.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BranchCmpOp {
+    /// The register that holds the left-hand side of the comparison.
+    pub lhs: Register,
+    /// The register that holds the right-hand side of the comparison.
+    pub rhs: Register,
+    /// The branch target taken if the comparison holds.
+    pub target: Target,
+}
+
+/// The operands of a linear memory load instruction.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LoadOp {
+    /// The register that stores the loaded value.
+    pub result: Register,
+    /// The register that holds the address of the access.
+    pub ptr: Register,
+    /// The offset added to the address of the access.
+    pub offset: Offset,
+}
+
+/// The operands of a linear memory store instruction.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StoreOp {
+    /// The register that holds the address of the access.
+    pub ptr: Register,
+    /// The offset added to the address of the access.
+    pub offset: Offset,
+    /// The register that holds the stored value.
+    pub value: Register,
+}
+
 /// A branching target.
-///
-/// This also specifies how many values on the stack
-/// need to be dropped and kept in order to maintain
-/// value stack integrity.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Target {
     /// The destination program counter.
     dst_pc: InstructionIdx,
-    /// How many values on the stack need to be dropped and kept.
-    drop_keep: DropKeep,
 }
 
 impl Target {
     /// Creates a new `wasmi` branching target.
-    pub fn new(dst_pc: InstructionIdx, drop_keep: DropKeep) -> Self {
-        Self { dst_pc, drop_keep }
+    pub fn new(dst_pc: InstructionIdx) -> Self {
+        Self { dst_pc }
     }
 
     /// Returns the destination program counter (as index).
@@ -81,11 +162,6 @@ impl Target {
         );
         self.dst_pc = new_destination_pc;
     }
-
-    /// Returns the amount of stack values to drop and keep upon taking the branch.
-    pub fn drop_keep(self) -> DropKeep {
-        self.drop_keep
-    }
 }
 
 /// A function index.
@@ -124,28 +200,6 @@ impl SignatureIdx {
     }
 }
 
-/// A local variable index.
-///
-/// # Note
-///
-/// Refers to a local variable of the currently executed function.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-#[repr(transparent)]
-pub struct LocalIdx(u32);
-
-impl From<u32> for LocalIdx {
-    fn from(index: u32) -> Self {
-        Self(index)
-    }
-}
-
-impl LocalIdx {
-    /// Returns the inner `u32` index.
-    pub fn into_inner(self) -> u32 {
-        self.0
-    }
-}
-
 /// A global variable index.
 ///
 /// # Note
@@ -195,12 +249,8 @@ impl Offset {
 /// A reference to a `wasmi` bytecode `br_table`.
 #[derive(Debug)]
 pub struct BrTable<'a> {
-    /// The branches of the `wasmi` bytecode `br_table` including the default target.
-    ///
-    /// # Note
-    ///
-    /// All elements of this slice are of variant [`Instruction::Br`] or [`Instruction::Return`].
-    branches: &'a [Instruction],
+    /// The branching targets of the `wasmi` bytecode `br_table` including the default target.
+    targets: &'a [Target],
 }
 
 impl<'a> BrTable<'a> {
@@ -210,31 +260,24 @@ impl<'a> BrTable<'a> {
     ///
     /// The `targets` slice must contain the default target at its end.
     ///
-    /// # Panics (Debug Mode)
+    /// # Panics
     ///
-    /// If the `targets` slice does not represent a `wasmi` bytecode `br_table`.
-    pub fn new(branches: &'a [Instruction]) -> Self {
+    /// If the `targets` slice is empty.
+    pub fn new(targets: &'a [Target]) -> Self {
         assert!(
-            !branches.is_empty(),
+            !targets.is_empty(),
             "the targets slice must not be empty since the \
             default target must be included at least",
         );
-        debug_assert!(
-            branches
-                .iter()
-                .all(|inst| matches!(inst, Instruction::Br(_) | Instruction::Return(_))),
-            "the branches slice contains non `br` or `return` instructions: {:?}",
-            branches,
-        );
-        Self { branches }
+        Self { targets }
     }
 
-    /// Returns the branch at the given `index` if any or the default target.
-    pub fn branch_or_default(&self, index: usize) -> &Instruction {
+    /// Returns the target at the given `index` if any or the default target.
+    pub fn target_or_default(&self, index: usize) -> Target {
         // The index of the default target which is the last target of the slice.
-        let max_index = self.branches.len() - 1;
+        let max_index = self.targets.len() - 1;
         // A normalized index will always yield a target without panicking.
         let normalized_index = cmp::min(index, max_index);
-        &self.branches[normalized_index]
+        self.targets[normalized_index]
     }
 }
@@ -0,0 +1,629 @@
+//! Caches the entities of the instance of the executed function frames.
+
+use super::{
+    bytecode::{FuncIdx, GlobalIdx},
+    FuncBody,
+};
+use crate::{
+    core::{TrapCode, UntypedValue},
+    func::FuncEntityInternal,
+    memory::guard,
+    module::{DEFAULT_MEMORY_INDEX, DEFAULT_TABLE_INDEX},
+    AsContext,
+    AsContextMut,
+    Func,
+    Instance,
+    Memory,
+    Table,
+};
+use alloc::vec::Vec;
+use core::ptr::NonNull;
+
+/// A function called by the executed Wasm code.
+///
+/// # Note
+///
+/// A Wasm callee carries everything required to set up its function frame
+/// so that calling it does not need to resolve its entity in the [`Store`].
+///
+/// [`Store`]: crate::Store
+#[derive(Debug, Copy, Clone)]
+pub enum Callee {
+    /// A Wasm function with its function body and instance.
+    Wasm {
+        /// The function body of the Wasm function.
+        func_body: FuncBody,
+        /// The instance of the Wasm function.
+        instance: Instance,
+    },
+    /// A host function.
+    Host(Func),
+}
+
+impl Callee {
+    /// Resolves the [`Callee`] of the `func`.
+    pub fn new(ctx: impl AsContext, func: Func) -> Self {
+        match func.as_internal(ctx.as_context()) {
+            FuncEntityInternal::Wasm(wasm_func) => Self::Wasm {
+                func_body: wasm_func.func_body(),
+                instance: wasm_func.instance(),
+            },
+            FuncEntityInternal::Host(_) => Self::Host(func),
+        }
+    }
+}
+
+/// The bytes of a linear memory cached by the [`InstanceCache`].
+///
+/// # Note
+///
+/// Caching the raw bytes allows loads and stores to bypass the
+/// look-up of the linear memory entity in the [`Store`].
+///
+/// [`Store`]: crate::Store
+#[derive(Debug, Copy, Clone)]
+pub struct CachedMemoryBytes {
+    /// The pointer to the first byte of the linear memory.
+    data: NonNull<u8>,
+    /// The length of the linear memory in bytes.
+    len: usize,
+    /// Is `true` if writes need to mark pages dirty.
+    ///
+    /// # Note
+    ///
+    /// In this case writes must go through the linear memory entity.
+    tracks_dirty: bool,
+    /// Is `true` if the bytes of the linear memory are not cached.
+    ///
+    /// # Note
+    ///
+    /// A custom [`LinearMemory`] backend may move its bytes upon any access.
+    /// In this case all loads and stores must go through the linear memory entity.
+    ///
+    /// [`LinearMemory`]: crate::LinearMemory
+    uncached: bool,
+    /// The length of the guard region starting at `data` or 0 if the linear memory is not guarded.
+    ///
+    /// # Note
+    ///
+    /// Out of bounds accesses of a guarded linear memory trap via its guard
+    /// pages so that loads and stores skip their bounds checks.
+    guard_len: usize,
+}
+
+impl CachedMemoryBytes {
+    /// Creates new [`CachedMemoryBytes`] for the given linear memory.
+    ///
+    /// # Note
+    ///
+    /// Registers the guard region of the linear memory for the current thread
+    /// if it is guarded. Since the [`InstanceCache`] is reset after calling host
+    /// functions this also restores the guard region after host functions
+    /// executed Wasm code that accesses other linear memories.
+    fn new(mut ctx: impl AsContextMut, memory: Memory) -> Self {
+        let entity = ctx.as_context_mut().store.resolve_memory_mut(memory);
+        let tracks_dirty = entity.is_tracking_dirty();
+        if entity.has_custom_backend() {
+            return Self {
+                data: NonNull::dangling(),
+                len: 0,
+                tracks_dirty,
+                uncached: true,
+                guard_len: 0,
+            };
+        }
+        let guard_len = entity.guard_region().map_or(0, |(_, len)| len);
+        let (data, len) = entity.data_ptr();
+        let bytes = Self {
+            data,
+            len,
+            tracks_dirty,
+            uncached: false,
+            guard_len,
+        };
+        bytes.set_guard_region();
+        bytes
+    }
+
+    /// Returns `true` if out of bounds accesses trap via guard pages.
+    #[inline(always)]
+    fn is_guarded(&self) -> bool {
+        self.guard_len != 0
+    }
+
+    /// Registers the guard region of the linear memory for the current thread if any.
+    #[inline]
+    fn set_guard_region(&self) {
+        if self.is_guarded() {
+            guard::set_guard_region(self.data.as_ptr(), self.guard_len);
+        }
+    }
+
+    /// Returns `true` if loads need to go through the linear memory entity.
+    #[inline(always)]
+    pub fn reads_through_entity(&self) -> bool {
+        self.uncached
+    }
+
+    /// Returns `true` if stores need to go through the linear memory entity.
+    #[inline(always)]
+    pub fn writes_through_entity(&self) -> bool {
+        self.uncached || self.tracks_dirty
+    }
+
+    /// Returns the pointer to the `len` bytes at `offset` if they are in bounds.
+    #[inline(always)]
+    fn get(&self, offset: usize, len: usize) -> Result<NonNull<u8>, TrapCode> {
+        match offset.checked_add(len) {
+            Some(end) if end <= self.len => {
+                // # Safety
+                //
+                // The bytes at `offset..offset+len` are within the bounds of
+                // the linear memory as checked above.
+                Ok(unsafe { NonNull::new_unchecked(self.data.as_ptr().add(offset)) })
+            }
+            _ => Err(TrapCode::MemoryAccessOutOfBounds),
+        }
+    }
+
+    /// Reads `n` bytes from `memory[offset..offset+n]` into `buffer`
+    /// where `n` is the length of `buffer`.
+    ///
+    /// # Note
+    ///
+    /// - This must not be used if [`CachedMemoryBytes::reads_through_entity`] is `true`.
+    /// - Skips the bounds check if the linear memory is guarded. In this case
+    ///   this must only be called within [`guard::catch_traps`] with an
+    ///   `offset` that fits into 32 bits.
+    ///
+    /// # Errors
+    ///
+    /// If this operation accesses out of bounds linear memory.
+    #[inline(always)]
+    pub fn read(&self, offset: usize, buffer: &mut [u8]) -> Result<(), TrapCode> {
+        debug_assert!(!self.uncached);
+        if self.is_guarded() {
+            debug_assert!(offset <= u32::MAX as usize);
+            // # Safety
+            //
+            // The guard region of the linear memory covers all addresses up
+            // to `offset + n` and has been registered for the current thread
+            // so that out of bounds accesses are turned into traps.
+            unsafe {
+                let src = self.data.as_ptr().wrapping_add(offset);
+                match buffer.len() {
+                    1 => buffer.copy_from_slice(&guard::load::<1>(src)),
+                    2 => buffer.copy_from_slice(&guard::load::<2>(src)),
+                    4 => buffer.copy_from_slice(&guard::load::<4>(src)),
+                    8 => buffer.copy_from_slice(&guard::load::<8>(src)),
+                    _ => {
+                        for (n, byte) in buffer.iter_mut().enumerate() {
+                            *byte = guard::load::<1>(src.wrapping_add(n))[0];
+                        }
+                    }
+                }
+            }
+            return Ok(());
+        }
+        let src = self.get(offset, buffer.len())?;
+        // # Safety
+        //
+        // - The source bytes are in bounds of the linear memory.
+        // - The [`InstanceCache`] resets the cached bytes whenever the
+        //   linear memory could have been resized or reallocated.
+        unsafe {
+            core::ptr::copy_nonoverlapping(src.as_ptr(), buffer.as_mut_ptr(), buffer.len());
+        }
+        Ok(())
+    }
+
+    /// Writes `n` bytes to `memory[offset..offset+n]` from `buffer`
+    /// where `n` is the length of `buffer`.
+    ///
+    /// # Note
+    ///
+    /// - This must not be used if [`CachedMemoryBytes::writes_through_entity`] is `true`.
+    /// - Skips the bounds check if the linear memory is guarded.
+    ///   See [`CachedMemoryBytes::read`] for the requirements in this case.
+    ///
+    /// # Errors
+    ///
+    /// If this operation accesses out of bounds linear memory.
+    #[inline(always)]
+    pub fn write(&mut self, offset: usize, buffer: &[u8]) -> Result<(), TrapCode> {
+        debug_assert!(!self.writes_through_entity());
+        if self.is_guarded() {
+            debug_assert!(offset <= u32::MAX as usize);
+            // # Safety
+            //
+            // See [`CachedMemoryBytes::read`].
+            unsafe {
+                let dst = self.data.as_ptr().wrapping_add(offset);
+                match *buffer {
+                    [b0] => guard::store::<1>(dst, [b0]),
+                    [b0, b1] => guard::store::<2>(dst, [b0, b1]),
+                    [b0, b1, b2, b3] => guard::store::<4>(dst, [b0, b1, b2, b3]),
+                    [b0, b1, b2, b3, b4, b5, b6, b7] => {
+                        guard::store::<8>(dst, [b0, b1, b2, b3, b4, b5, b6, b7])
+                    }
+                    _ => {
+                        for (n, byte) in buffer.iter().enumerate() {
+                            guard::store::<1>(dst.wrapping_add(n), [*byte]);
+                        }
+                    }
+                }
+            }
+            return Ok(());
+        }
+        let dst = self.get(offset, buffer.len())?;
+        // # Safety
+        //
+        // See [`CachedMemoryBytes::read`].
+        unsafe {
+            core::ptr::copy_nonoverlapping(buffer.as_ptr(), dst.as_ptr(), buffer.len());
+        }
+        Ok(())
+    }
+}
+
+/// Caches the entities of the [`Instance`] of the executed function frames.
+///
+/// # Note
+///
+/// - Resolving the default linear memory, global variables and called
+///   functions through the [`Instance`] requires arena look-ups in the
+///   [`Store`] which are too costly for the interpreter's hot path.
+///   Therefore these entities are lazily resolved once and cached.
+/// - The cache must be reset via [`InstanceCache::reset`] whenever the
+///   [`Store`] could have been manipulated from outside of the executed
+///   Wasm code, e.g. after calling a host function.
+///
+/// [`Store`]: crate::Store
+#[derive(Debug)]
+pub struct InstanceCache {
+    /// The instance of the cached entities.
+    instance: Instance,
+    /// The default linear memory (index 0) of the `instance`.
+    default_memory: Option<Memory>,
+    /// The bytes of the default linear memory of the `instance`.
+    ///
+    /// # Note
+    ///
+    /// This is reset whenever the default linear memory grows.
+    default_memory_bytes: Option<CachedMemoryBytes>,
+    /// The default table (index 0) of the `instance`.
+    default_table: Option<Table>,
+    /// The value cells of the global variables of the `instance`.
+    globals: Vec<Option<NonNull<UntypedValue>>>,
+    /// The resolved functions of the `instance` that are called directly.
+    callees: Vec<Option<Callee>>,
+}
+
+impl From<Instance> for InstanceCache {
+    fn from(instance: Instance) -> Self {
+        Self {
+            instance,
+            default_memory: None,
+            default_memory_bytes: None,
+            default_table: None,
+            globals: Vec::new(),
+            callees: Vec::new(),
+        }
+    }
+}
+
+impl InstanceCache {
+    /// Updates the cached [`Instance`] and resets the cache if it changed.
+    #[inline]
+    pub fn update_instance(&mut self, instance: Instance) {
+        if instance.into_inner() == self.instance.into_inner() {
+            return;
+        }
+        self.instance = instance;
+        self.reset();
+    }
+
+    /// Resets all cached entities so that they are lazily reloaded upon their next use.
+    pub fn reset(&mut self) {
+        self.default_memory = None;
+        self.default_memory_bytes = None;
+        self.default_table = None;
+        self.globals.clear();
+        self.callees.clear();
+    }
+
+    /// Resets the cached bytes of the default linear memory.
+    ///
+    /// # Note
+    ///
+    /// This must be called whenever the default linear memory has been resized.
+    pub fn reset_default_memory_bytes(&mut self) {
+        self.default_memory_bytes = None;
+    }
+
+    /// Returns the default linear memory of the cached instance.
+    ///
+    /// # Panics
+    ///
+    /// If there is no default linear memory.
+    #[inline]
+    pub fn default_memory(&mut self, ctx: impl AsContext) -> Memory {
+        match self.default_memory {
+            Some(default_memory) => default_memory,
+            None => self.load_default_memory(ctx),
+        }
+    }
+
+    /// Loads and caches the default linear memory of the cached instance.
+    ///
+    /// # Panics
+    ///
+    /// If there is no default linear memory.
+    #[cold]
+    fn load_default_memory(&mut self, ctx: impl AsContext) -> Memory {
+        let default_memory = self
+            .instance
+            .get_memory(ctx.as_context(), DEFAULT_MEMORY_INDEX)
+            .unwrap_or_else(|| {
+                panic!(
+                    "instance does not have default linear memory: {:?}",
+                    self.instance
+                )
+            });
+        self.default_memory = Some(default_memory);
+        default_memory
+    }
+
+    /// Returns the bytes of the default linear memory of the cached instance.
+    ///
+    /// # Panics
+    ///
+    /// If there is no default linear memory.
+    #[inline]
+    pub fn default_memory_bytes(&mut self, ctx: impl AsContextMut) -> &mut CachedMemoryBytes {
+        match self.default_memory_bytes {
+            Some(ref mut bytes) => bytes,
+            None => self.load_default_memory_bytes(ctx),
+        }
+    }
+
+    /// Loads and caches the bytes of the default linear memory of the cached instance.
+    ///
+    /// # Panics
+    ///
+    /// If there is no default linear memory.
+    #[cold]
+    fn load_default_memory_bytes(&mut self, ctx: impl AsContextMut) -> &mut CachedMemoryBytes {
+        let memory = self.default_memory(ctx.as_context());
+        let bytes = CachedMemoryBytes::new(ctx, memory);
+        self.default_memory_bytes.insert(bytes)
+    }
+
+    /// Returns the default table of the cached instance.
+    ///
+    /// # Panics
+    ///
+    /// If there is no default table.
+    #[inline]
+    pub fn default_table(&mut self, ctx: impl AsContext) -> Table {
+        match self.default_table {
+            Some(default_table) => default_table,
+            None => self.load_default_table(ctx),
+        }
+    }
+
+    /// Loads and caches the default table of the cached instance.
+    ///
+    /// # Panics
+    ///
+    /// If there is no default table.
+    #[cold]
+    fn load_default_table(&mut self, ctx: impl AsContext) -> Table {
+        let default_table = self
+            .instance
+            .get_table(ctx.as_context(), DEFAULT_TABLE_INDEX)
+            .unwrap_or_else(|| panic!("instance does not have default table: {:?}", self.instance));
+        self.default_table = Some(default_table);
+        default_table
+    }
+
+    /// Returns the value cell of the global variable at `index` of the cached instance.
+    ///
+    /// # Note
+    ///
+    /// The returned pointer stays valid until the [`InstanceCache`] is reset.
+    ///
+    /// # Panics
+    ///
+    /// If there is no global variable at `index`.
+    #[inline]
+    pub fn global(&mut self, ctx: impl AsContextMut, index: GlobalIdx) -> NonNull<UntypedValue> {
+        match self.globals.get(index.into_inner() as usize) {
+            Some(Some(global)) => *global,
+            _ => self.load_global(ctx, index),
+        }
+    }
+
+    /// Loads and caches the value cell of the global variable at `index` of the cached instance.
+    ///
+    /// # Panics
+    ///
+    /// If there is no global variable at `index`.
+    #[cold]
+    fn load_global(
+        &mut self,
+        mut ctx: impl AsContextMut,
+        index: GlobalIdx,
+    ) -> NonNull<UntypedValue> {
+        let global = self
+            .instance
+            .get_global(ctx.as_context(), index.into_inner())
+            .unwrap_or_else(|| panic!("missing global at index {:?}", index));
+        let cell = ctx
+            .as_context_mut()
+            .store
+            .resolve_global_mut(global)
+            .get_untyped_ptr();
+        let position = index.into_inner() as usize;
+        if position >= self.globals.len() {
+            self.globals.resize(position + 1, None);
+        }
+        self.globals[position] = Some(cell);
+        cell
+    }
+
+    /// Returns the resolved function at `index` of the cached instance.
+    ///
+    /// # Panics
+    ///
+    /// If there is no function at `index`.
+    #[inline]
+    pub fn callee(&mut self, ctx: impl AsContext, index: FuncIdx) -> Callee {
+        match self.callees.get(index.into_inner() as usize) {
+            Some(Some(callee)) => *callee,
+            _ => self.load_callee(ctx, index),
+        }
+    }
+
+    /// Loads, resolves and caches the function at `index` of the cached instance.
+    ///
+    /// # Panics
+    ///
+    /// If there is no function at `index`.
+    #[cold]
+    fn load_callee(&mut self, ctx: impl AsContext, index: FuncIdx) -> Callee {
+        let ctx = ctx.as_context();
+        let func = self
+            .instance
+            .get_func(&ctx, index.into_inner())
+            .unwrap_or_else(|| panic!("missing function at index {:?}", index));
+        let callee = Callee::new(&ctx, func);
+        let position = index.into_inner() as usize;
+        if position >= self.callees.len() {
+            self.callees.resize(position + 1, None);
+        }
+        self.callees[position] = Some(callee);
+        callee
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::Value,
+        Caller,
+        Config,
+        Engine,
+        Extern,
+        Func,
+        Global,
+        Instance,
+        Linker,
+        Memory,
+        MemoryType,
+        Module,
+        Mutability,
+        Store,
+    };
+    use wasmi_core::memory_units::Pages;
+
+    /// Instantiates the `wat` module with `memory` and `grow` imported from `env`.
+    fn instantiate(engine: &Engine, wat: &str) -> (Store<()>, Memory, Global, Instance) {
+        let wasm = wat::parse_str(wat).unwrap();
+        let module = Module::new(engine, &wasm[..]).unwrap();
+        let mut store = Store::new(engine, ());
+        let memory = Memory::new(&mut store, MemoryType::new(1, None)).unwrap();
+        let global = Global::new(&mut store, Value::I32(0), Mutability::Mutable);
+        let grow = Func::wrap(&mut store, move |mut caller: Caller<()>| {
+            memory.grow(&mut caller, Pages(1)).unwrap();
+        });
+        let mut linker = <Linker<()>>::default();
+        linker.define("env", "memory", memory).unwrap();
+        linker.define("env", "global", global).unwrap();
+        linker.define("env", "grow", grow).unwrap();
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .unwrap()
+            .ensure_no_start(&mut store)
+            .unwrap();
+        (store, memory, global, instance)
+    }
+
+    /// Calls the exported function `name` of `instance` with a single `i32` parameter.
+    fn call(store: &mut Store<()>, instance: Instance, name: &str, param: i32) -> Value {
+        let func = instance
+            .get_export(&*store, name)
+            .and_then(Extern::into_func)
+            .unwrap();
+        let mut results = [Value::I32(0)];
+        func.call(&mut *store, &[Value::I32(param)], &mut results)
+            .unwrap();
+        results[0]
+    }
+
+    const WAT: &str = r#"
+        (module
+            (import "env" "memory" (memory 1))
+            (import "env" "global" (global (mut i32)))
+            (import "env" "grow" (func $grow))
+            (func (export "grow_and_load") (param i32) (result i32)
+                (i32.store (i32.const 0) (local.get 0))
+                (drop (memory.grow (i32.const 1)))
+                (i32.store (i32.const 65536) (i32.load (i32.const 0)))
+                (i32.load (i32.const 65536))
+            )
+            (func (export "host_grow_and_load") (param i32) (result i32)
+                (i32.store (i32.const 0) (local.get 0))
+                (call $grow)
+                (i32.store (i32.const 131072) (i32.load (i32.const 0)))
+                (i32.load (i32.const 131072))
+            )
+            (func (export "add_global") (param i32) (result i32)
+                (global.set 0 (i32.add (global.get 0) (local.get 0)))
+                (global.get 0)
+            )
+            (func (export "store") (param i32) (result i32)
+                (i32.store (local.get 0) (i32.const 1))
+                (i32.const 0)
+            )
+        )
+    "#;
+
+    #[test]
+    fn memory_bytes_are_refreshed_on_grow() {
+        let engine = Engine::default();
+        let (mut store, memory, _, instance) = instantiate(&engine, WAT);
+        assert_eq!(
+            call(&mut store, instance, "grow_and_load", 42),
+            Value::I32(42)
+        );
+        assert_eq!(memory.current_pages(&store), Pages(2));
+        assert_eq!(
+            call(&mut store, instance, "host_grow_and_load", 7),
+            Value::I32(7)
+        );
+        assert_eq!(memory.current_pages(&store), Pages(3));
+        assert_eq!(&memory.data(&store)[131072..131076], &7_i32.to_le_bytes());
+    }
+
+    #[test]
+    fn global_cells_are_shared_with_host() {
+        let engine = Engine::default();
+        let (mut store, _, global, instance) = instantiate(&engine, WAT);
+        assert_eq!(call(&mut store, instance, "add_global", 2), Value::I32(2));
+        assert_eq!(global.get(&store), Value::I32(2));
+        global.set(&mut store, Value::I32(10)).unwrap();
+        assert_eq!(call(&mut store, instance, "add_global", 3), Value::I32(13));
+        assert_eq!(global.get(&store), Value::I32(13));
+    }
+
+    #[test]
+    fn stores_mark_dirty_pages() {
+        let engine = Engine::new(&Config::default().enable_dirty_page_tracking(true));
+        let (mut store, memory, _, instance) = instantiate(&engine, WAT);
+        call(&mut store, instance, "grow_and_load", 0);
+        memory.clear_dirty(&mut store);
+        call(&mut store, instance, "store", 65536 + 10);
+        assert_eq!(memory.dirty_pages(&store), [1]);
+    }
+}
@@ -1,24 +1,12 @@
 //! Data structures to represent the Wasm call stack during execution.
 
 use super::{
-    super::{
-        func::WasmFuncEntity,
-        AsContext,
-        Func,
-        FuncBody,
-        FuncEntityInternal,
-        Instance,
-        Memory,
-        Table,
-    },
+    super::{AsContext, Func, FuncBody, FuncEntityInternal, Instance},
     ResolvedFuncBody,
     ValueStack,
     DEFAULT_CALL_STACK_LIMIT,
 };
-use crate::{
-    core::TrapCode,
-    module::{DEFAULT_MEMORY_INDEX, DEFAULT_TABLE_INDEX},
-};
+use crate::core::TrapCode;
 use alloc::vec::Vec;
 
 /// A function frame of a function in the call stack.
@@ -31,8 +19,6 @@ pub struct FunctionFrame {
     /// Function frame instantiation puts function inputs and locals on
     /// the function stack and prepares for its immediate execution.
     pub instantiated: bool,
-    /// The function that is being executed.
-    pub func: Func,
     /// The function body of the function that is being executed.
     ///
     /// # Note
@@ -48,22 +34,14 @@ pub struct FunctionFrame {
     /// non-local to the function such as linear memories, global variables
     /// and tables.
     pub instance: Instance,
-    /// The default linear memory (index 0) of the `instance`.
+    /// The base pointer of the function frame on the value stack.
     ///
     /// # Note
     ///
-    /// This is just an optimization for the common case of manipulating
-    /// the default linear memory and avoids one indirection to look-up
-    /// the linear memory in the `Instance`.
-    default_memory: Option<Memory>,
-    /// The default table (index 0) of the `instance`.
-    ///
-    /// # Note
+    /// All [`Register`] of the executed function are relative to the base pointer.
     ///
-    /// This is just an optimization for the common case of indirectly
-    /// calling functions using the default table and avoids one indirection
-    /// to look-up the table in the `Instance`.
-    default_table: Option<Table>,
+    /// [`Register`]: super::bytecode::Register
+    pub bp: usize,
     /// The current value of the instruction pointer.
     ///
     /// # Note
@@ -76,12 +54,19 @@ pub struct FunctionFrame {
 impl FunctionFrame {
     /// Creates a new [`FunctionFrame`] from the given `func`.
     ///
+    /// # Note
+    ///
+    /// The function frame begins at the bottom of the value stack
+    /// where the parameters of the root function call are stored.
+    ///
     /// # Panics
     ///
     /// If the `func` has no instance handle, i.e. is not a Wasm function.
     pub fn new(ctx: impl AsContext, func: Func) -> Self {
         match func.as_internal(ctx.as_context()) {
-            FuncEntityInternal::Wasm(wasm_func) => Self::new_wasm(func, wasm_func),
+            FuncEntityInternal::Wasm(wasm_func) => {
+                Self::new_wasm(wasm_func.func_body(), wasm_func.instance(), 0)
+            }
             FuncEntityInternal::Host(host_func) => panic!(
                 "cannot execute host functions using Wasm interpreter: {:?}",
                 host_func
@@ -89,93 +74,43 @@ impl FunctionFrame {
         }
     }
 
-    /// Creates a new [`FunctionFrame`] from the given Wasm function entity.
-    pub(super) fn new_wasm(func: Func, wasm_func: &WasmFuncEntity) -> Self {
-        let instance = wasm_func.instance();
-        let func_body = wasm_func.func_body();
+    /// Creates a new [`FunctionFrame`] for the `func_body` of a Wasm function of `instance`.
+    ///
+    /// The function frame begins at `bp` where its parameters are stored.
+    pub(super) fn new_wasm(func_body: FuncBody, instance: Instance, bp: usize) -> Self {
         Self {
             instantiated: false,
-            func,
             func_body,
             instance,
-            default_memory: None,
-            default_table: None,
+            bp,
             inst_ptr: 0,
         }
     }
 
-    /// Returns the default linear memory of the function frame if any.
-    ///
-    /// # Note
-    ///
-    /// This API allows to lazily and efficiently load the default linear memory if available.
-    ///
-    /// # Panics
-    ///
-    /// If there is no default linear memory.
-    pub fn default_memory(&mut self, ctx: impl AsContext) -> Memory {
-        match self.default_memory {
-            Some(default_memory) => default_memory,
-            None => {
-                // Try to lazily load the default memory.
-                let default_memory = self
-                    .instance
-                    .get_memory(ctx.as_context(), DEFAULT_MEMORY_INDEX)
-                    .unwrap_or_else(|| {
-                        panic!("func does not have default linear memory: {:?}", self.func)
-                    });
-                self.default_memory = Some(default_memory);
-                default_memory
-            }
-        }
-    }
-
-    /// Returns the default table of the function frame if any.
-    ///
-    /// # Note
-    ///
-    /// This API allows to lazily and efficiently load the default table if available.
-    ///
-    /// # Panics
-    ///
-    /// If there is no default table.
-    pub fn default_table(&mut self, ctx: impl AsContext) -> Table {
-        match self.default_table {
-            Some(default_table) => default_table,
-            None => {
-                // Try to lazily load the default memory.
-                let default_table = self
-                    .instance
-                    .get_table(ctx.as_context(), DEFAULT_TABLE_INDEX)
-                    .unwrap_or_else(|| panic!("func does not have default table: {:?}", self.func));
-                self.default_table = Some(default_table);
-                default_table
-            }
-        }
-    }
-
     /// Initializes the function frame.
     ///
     /// # Note
     ///
-    /// Does nothing if the function frame has already been initialized.
+    /// Only restores the stack pointer to the end of the function frame
+    /// if the function frame has already been initialized, e.g. when
+    /// resuming its execution after a nested call has returned.
     pub fn initialize(
         &mut self,
         resolved_func_body: ResolvedFuncBody,
         value_stack: &mut ValueStack,
     ) -> Result<(), TrapCode> {
+        let len_frame = resolved_func_body.len_frame();
         if self.instantiated {
-            // Nothing to do if the function frame has already been initialized.
+            value_stack.set_len(self.bp + len_frame);
             return Ok(());
         }
-        let max_stack_height = resolved_func_body.max_stack_height();
-        value_stack.reserve(max_stack_height)?;
-        let len_locals = resolved_func_body.len_locals();
-        value_stack
-            .extend_zeros(len_locals)
-            .unwrap_or_else(|error| {
-                panic!("encountered stack overflow while pushing locals: {}", error)
-            });
+        value_stack.init_frame(
+            self.bp,
+            resolved_func_body.len_params(),
+            resolved_func_body.len_locals(),
+            resolved_func_body.consts(),
+            len_frame,
+        )?;
         self.instantiated = true;
         Ok(())
     }
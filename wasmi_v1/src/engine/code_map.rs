@@ -2,16 +2,85 @@
 
 use super::{
     super::Index,
-    bytecode::{BrTable, VisitInstruction},
+    bytecode::{BrTable, Target, VisitInstruction},
     Instruction,
 };
-use alloc::vec::Vec;
-use core::iter;
+use crate::module::UncompiledFuncBody;
+use alloc::{boxed::Box, collections::BTreeMap, vec::Vec};
+use core::mem;
+use wasmi_core::UntypedValue;
 
 /// A reference to a Wasm function body stored in the [`CodeMap`].
 #[derive(Debug, Copy, Clone)]
 pub struct FuncBody(usize);
 
+/// The location and frame layout of a compiled function body in the [`CodeMap`].
+///
+/// # Note
+///
+/// All fields are stored as `u32` or `u16` in order to keep the per function
+/// overhead of the [`CodeMap`] small for modules with many functions.
+/// Parameters and local variables fit into `u16` due to the limits of Wasm validation.
+#[derive(Debug, Copy, Clone)]
+struct CompiledFuncBody {
+    /// The offset of the first instruction of the function body.
+    insts: u32,
+    /// The amount of instructions of the function body.
+    len_insts: u32,
+    /// The offset of the first constant value of the function body.
+    consts: u32,
+    /// The amount of constant values of the function body.
+    len_consts: u32,
+    /// The offset of the first `br_table` target of the function body.
+    br_targets: u32,
+    /// The amount of `br_table` targets of the function body.
+    len_br_targets: u32,
+    /// The amount of stack values required by the function including its local variables.
+    max_stack_height: u32,
+    /// The amount of parameters of the function.
+    len_params: u16,
+    /// The amount of local variables of the function.
+    len_locals: u16,
+}
+
+impl CompiledFuncBody {
+    /// The entry of a function body that has not yet been compiled.
+    ///
+    /// # Note
+    ///
+    /// The uncompiled function body itself is stored in [`CodeMap::uncompiled`].
+    const UNCOMPILED: Self = Self {
+        insts: u32::MAX,
+        len_insts: 0,
+        consts: 0,
+        len_consts: 0,
+        br_targets: 0,
+        len_br_targets: 0,
+        max_stack_height: 0,
+        len_params: 0,
+        len_locals: 0,
+    };
+
+    /// Returns `true` if the function body has not yet been compiled.
+    fn is_uncompiled(&self) -> bool {
+        self.insts == u32::MAX
+    }
+}
+
+/// Converts the `value` into `U` or panics with a message naming `what` overflowed.
+fn to_int<U>(value: usize, what: &str) -> U
+where
+    U: TryFrom<usize>,
+    <U as TryFrom<usize>>::Error: core::fmt::Display,
+{
+    value.try_into().unwrap_or_else(|error| {
+        panic!(
+            "encountered too many {} (= {}) for function: {}",
+            what, value, error
+        )
+    })
+}
+
 impl Index for FuncBody {
     fn into_usize(self) -> usize {
         self.0
@@ -35,12 +104,106 @@ pub struct CodeMap {
     /// Also this improves efficiency of deallocating the [`CodeMap`]
     /// and generally improves data locality.
     insts: Vec<Instruction>,
+    /// The constant values of all allocated function bodies.
+    ///
+    /// # Note
+    ///
+    /// The constant values of a function body are copied into its
+    /// function frame upon a call so that instructions can refer to
+    /// them the same way as they refer to local variables.
+    consts: Vec<UntypedValue>,
+    /// The `br_table` targets of all allocated function bodies.
+    ///
+    /// # Note
+    ///
+    /// Storing the targets outside of the instructions keeps every
+    /// [`Instruction::BrTable`] at the size of a single instruction
+    /// regardless of the amount of its targets.
+    br_targets: Vec<Target>,
+    /// All allocated function bodies indexed by [`FuncBody`].
+    ///
+    /// # Note
+    ///
+    /// Uncompiled function bodies are stored separately in order to
+    /// not inflate the size of the entries of all compiled function bodies.
+    bodies: Vec<CompiledFuncBody>,
+    /// The function bodies that are translated upon their first call indexed by [`FuncBody`].
+    uncompiled: BTreeMap<usize, Box<UncompiledFuncBody>>,
+    /// The number of allocated compiled function bodies.
+    len_func_bodies: usize,
 }
 
 impl CodeMap {
-    /// Returns the next [`FuncBody`] index.
-    fn next_index(&self) -> FuncBody {
-        FuncBody(self.insts.len())
+    /// Returns the number of allocated compiled function bodies.
+    pub fn len_func_bodies(&self) -> usize {
+        self.len_func_bodies
+    }
+
+    /// Returns the number of instructions of all allocated function bodies.
+    pub fn len_instructions(&self) -> usize {
+        self.insts.len()
+    }
+
+    /// Returns the number of bytes allocated by the [`CodeMap`].
+    pub fn allocated_bytes(&self) -> usize {
+        self.insts.capacity() * mem::size_of::<Instruction>()
+            + self.consts.capacity() * mem::size_of::<UntypedValue>()
+            + self.br_targets.capacity() * mem::size_of::<Target>()
+            + self.bodies.capacity() * mem::size_of::<CompiledFuncBody>()
+    }
+
+    /// Pushes the `entry` to the function bodies and returns its [`FuncBody`] reference.
+    fn push_entry(&mut self, entry: CompiledFuncBody) -> FuncBody {
+        let idx = FuncBody(self.bodies.len());
+        self.bodies.push(entry);
+        idx
+    }
+
+    /// Allocates a new function body that is translated upon its first call.
+    ///
+    /// # Note
+    ///
+    /// Use [`CodeMap::take_uncompiled`] and [`CodeMap::redirect`] in
+    /// order to replace it with its compiled function body.
+    pub fn alloc_uncompiled(&mut self, func_body: UncompiledFuncBody) -> FuncBody {
+        let idx = self.push_entry(CompiledFuncBody::UNCOMPILED);
+        self.uncompiled
+            .insert(idx.into_usize(), Box::new(func_body));
+        idx
+    }
+
+    /// Returns `true` if the `func_body` has not yet been compiled.
+    #[inline]
+    pub fn is_uncompiled(&self, func_body: FuncBody) -> bool {
+        self.bodies[func_body.into_usize()].is_uncompiled()
+    }
+
+    /// Takes the uncompiled `func_body` out of the [`CodeMap`] if any.
+    ///
+    /// # Note
+    ///
+    /// The `func_body` must be redirected to its compiled
+    /// function body via [`CodeMap::redirect`] before its next use.
+    pub fn take_uncompiled(&mut self, func_body: FuncBody) -> Option<Box<UncompiledFuncBody>> {
+        self.uncompiled.remove(&func_body.into_usize())
+    }
+
+    /// Redirects the `func_body` to the `compiled` function body.
+    ///
+    /// # Panics
+    ///
+    /// If `compiled` is not the last allocated function body.
+    pub fn redirect(&mut self, func_body: FuncBody, compiled: FuncBody) {
+        assert_eq!(
+            compiled.into_usize() + 1,
+            self.bodies.len(),
+            "can only redirect to the last allocated function body"
+        );
+        let entry = self
+            .bodies
+            .pop()
+            .unwrap_or_else(|| panic!("missing compiled function body"));
+        self.bodies[func_body.into_usize()] = entry;
     }
 
     /// Allocates a new function body to the [`CodeMap`].
@@ -48,89 +211,99 @@ impl CodeMap {
     /// Returns a reference to the allocated function body that can
     /// be used with [`CodeMap::resolve`] in order to resolve its
     /// instructions.
-    pub fn alloc<I>(&mut self, len_locals: usize, max_stack_height: usize, insts: I) -> FuncBody
+    pub fn alloc<C, B, I>(
+        &mut self,
+        len_params: usize,
+        len_locals: usize,
+        max_stack_height: usize,
+        consts: C,
+        br_targets: B,
+        insts: I,
+    ) -> FuncBody
     where
+        C: IntoIterator<Item = UntypedValue>,
+        B: IntoIterator<Item = Target>,
         I: IntoIterator<Item = Instruction>,
-        I::IntoIter: ExactSizeIterator,
     {
-        let idx = self.next_index();
-        // We are inserting an artificial `unreachable` Wasm instruction
-        // in between instructions of different function bodies as a small
-        // safety precaution.
-        let insts = insts.into_iter();
-        let len_instructions = insts.len().try_into().unwrap_or_else(|error| {
-            panic!(
-                "encountered too many instructions (= {}) for function: {}",
-                insts.len(),
-                error
-            )
-        });
-        let max_stack_height = (max_stack_height + len_locals)
-            .try_into()
-            .unwrap_or_else(|error| {
-                panic!(
-                "encountered function that requires too many stack values (= {}) for function: {}",
-                max_stack_height, error
-            )
-            });
-        let len_locals = len_locals.try_into().unwrap_or_else(|error| {
-            panic!(
-                "encountered too many local variables (= {}) for function: {}",
-                len_locals, error
-            )
-        });
-        let start = iter::once(Instruction::FuncBodyStart {
-            len_instructions,
-            len_locals,
-            max_stack_height,
-        });
-        let end = iter::once(Instruction::FuncBodyEnd);
-        self.insts.extend(start.chain(insts).chain(end));
-        idx
+        let insts_offset = self.insts.len();
+        let consts_offset = self.consts.len();
+        let br_targets_offset = self.br_targets.len();
+        self.insts.extend(insts);
+        self.consts.extend(consts);
+        self.br_targets.extend(br_targets);
+        self.len_func_bodies += 1;
+        self.push_entry(CompiledFuncBody {
+            insts: to_int(insts_offset, "instructions in the engine"),
+            len_insts: to_int(self.insts.len() - insts_offset, "instructions"),
+            consts: to_int(consts_offset, "constant values in the engine"),
+            len_consts: to_int(self.consts.len() - consts_offset, "constant values"),
+            br_targets: to_int(br_targets_offset, "`br_table` targets in the engine"),
+            len_br_targets: to_int(
+                self.br_targets.len() - br_targets_offset,
+                "`br_table` targets",
+            ),
+            max_stack_height: to_int(max_stack_height + len_locals, "stack values"),
+            len_params: to_int(len_params, "parameters"),
+            len_locals: to_int(len_locals, "local variables"),
+        })
+    }
+
+    /// Appends all compiled function bodies of `other` to the [`CodeMap`].
+    ///
+    /// Relocates the `func_bodies` references into `other` to their appended function bodies.
+    ///
+    /// # Panics
+    ///
+    /// If `other` contains uncompiled function bodies.
+    #[cfg(feature = "std")]
+    pub fn append(&mut self, other: CodeMap, func_bodies: &mut [FuncBody]) {
+        assert!(
+            other.uncompiled.is_empty(),
+            "tried to append uncompiled function body"
+        );
+        let insts_offset: u32 = to_int(self.insts.len(), "instructions in the engine");
+        let consts_offset: u32 = to_int(self.consts.len(), "constant values in the engine");
+        let br_targets_offset: u32 =
+            to_int(self.br_targets.len(), "`br_table` targets in the engine");
+        let bodies_offset = self.bodies.len();
+        self.insts.extend(other.insts);
+        self.consts.extend(other.consts);
+        self.br_targets.extend(other.br_targets);
+        self.bodies
+            .extend(other.bodies.into_iter().map(|compiled| CompiledFuncBody {
+                insts: insts_offset + compiled.insts,
+                consts: consts_offset + compiled.consts,
+                br_targets: br_targets_offset + compiled.br_targets,
+                ..compiled
+            }));
+        self.len_func_bodies += other.len_func_bodies;
+        for func_body in func_bodies {
+            func_body.0 += bodies_offset;
+        }
     }
 
     /// Resolves the instruction of the function body.
     ///
     /// # Panics
     ///
-    /// If the given `func_body` is invalid for this [`CodeMap`].
+    /// - If the given `func_body` is invalid for this [`CodeMap`].
+    /// - If the given `func_body` has not yet been compiled.
     pub fn resolve(&self, func_body: FuncBody) -> ResolvedFuncBody {
-        let offset = func_body.into_usize();
-        let (len_instructions, len_locals, max_stack_height) = match &self.insts[offset] {
-            Instruction::FuncBodyStart {
-                len_instructions,
-                len_locals,
-                max_stack_height,
-            } => (*len_instructions, *len_locals, *max_stack_height),
-            unexpected => panic!(
-                "expected function start instruction but found: {:?}",
-                unexpected
-            ),
-        };
-        let len_instructions = len_instructions as usize;
-        let len_locals = len_locals as usize;
-        let max_stack_height = max_stack_height as usize;
-        // The index of the first instruction in the function body.
-        let first_inst = offset + 1;
-        {
-            // Assert that the end of the function instructions is
-            // properly guarded with the `FuncBodyEnd` sentinel.
-            //
-            // This check is not needed to validate the integrity of
-            // the resolution procedure and therefore the below assertion
-            // is only performed in debug mode.
-            let end = &self.insts[first_inst + len_instructions];
-            debug_assert!(
-                matches!(end, Instruction::FuncBodyEnd),
-                "expected function end instruction but found: {:?}",
-                end,
-            );
+        let compiled = self.bodies[func_body.into_usize()];
+        if compiled.is_uncompiled() {
+            panic!("tried to resolve uncompiled function body: {:?}", func_body)
         }
-        let insts = &self.insts[first_inst..(first_inst + len_instructions)];
+        let slice = |offset: u32, len: u32| offset as usize..(offset as usize + len as usize);
+        let insts = &self.insts[slice(compiled.insts, compiled.len_insts)];
+        let consts = &self.consts[slice(compiled.consts, compiled.len_consts)];
+        let br_targets = &self.br_targets[slice(compiled.br_targets, compiled.len_br_targets)];
         ResolvedFuncBody {
             insts,
-            len_locals,
-            max_stack_height,
+            consts,
+            br_targets,
+            len_params: compiled.len_params as usize,
+            len_locals: compiled.len_locals as usize,
+            max_stack_height: compiled.max_stack_height as usize,
         }
     }
 }
@@ -139,15 +312,12 @@ impl CodeMap {
 ///
 /// Allows to immutably access the `wasmi` instructions of a Wasm
 /// function stored in the [`CodeMap`].
-///
-/// # Dev. Note
-///
-/// This does not include the [`Instruction::FuncBodyStart`] and
-/// [`Instruction::FuncBodyEnd`] instructions surrounding the instructions
-/// of a function body in the [`CodeMap`].
 #[derive(Debug, Copy, Clone)]
 pub struct ResolvedFuncBody<'a> {
     insts: &'a [Instruction],
+    consts: &'a [UntypedValue],
+    br_targets: &'a [Target],
+    len_params: usize,
     len_locals: usize,
     max_stack_height: usize,
 }
@@ -163,6 +333,34 @@ impl ResolvedFuncBody<'_> {
         self.insts.get(index)
     }
 
+    /// Returns the instructions of the function body.
+    pub fn insts(&self) -> &[Instruction] {
+        self.insts
+    }
+
+    /// Returns the constant values of the function body.
+    ///
+    /// # Note
+    ///
+    /// The constant values are stored in the function frame right after the local variables.
+    pub fn consts(&self) -> &[UntypedValue] {
+        self.consts
+    }
+
+    /// Returns the `br_table` targets of the function body.
+    ///
+    /// # Note
+    ///
+    /// Every [`Instruction::BrTable`] refers to a contiguous slice of these targets.
+    pub fn br_targets(&self) -> &[Target] {
+        self.br_targets
+    }
+
+    /// Returns the amount of parameters of the function.
+    pub fn len_params(&self) -> usize {
+        self.len_params
+    }
+
     /// Returns the amount of local variable of the function.
     pub fn len_locals(&self) -> usize {
         self.len_locals
@@ -177,14 +375,29 @@ impl ResolvedFuncBody<'_> {
     pub fn max_stack_height(&self) -> usize {
         self.max_stack_height
     }
+
+    /// Returns the amount of stack values of the function frame.
+    ///
+    /// # Note
+    ///
+    /// This includes the parameters, local variables, constant
+    /// values and temporary values of the function.
+    pub fn len_frame(&self) -> usize {
+        self.len_params + self.consts.len() + self.max_stack_height
+    }
 }
 
 impl<'a> ResolvedFuncBody<'a> {
     /// Visits the corresponding [`Instruction`] method from the given visitor.
     ///
     /// Returns the visitor's outcome value.
+    ///
+    /// # Note
+    ///
+    /// The visitor is borrowed so that it can be reused across the
+    /// execution of many instructions without being reconstructed.
     #[inline(always)]
-    pub fn visit<T>(&self, index: usize, mut visitor: T) -> T::Outcome
+    pub fn visit<T>(&self, index: usize, visitor: &mut T) -> T::Outcome
     where
         T: VisitInstruction,
     {
@@ -204,190 +417,313 @@ impl<'a> ResolvedFuncBody<'a> {
         // part of the `wasmi` interpreter is part of the interpreter's hot path.
         let inst = unsafe { self.insts.get_unchecked(index) };
         match inst {
-            Instruction::GetLocal { local_depth } => visitor.visit_get_local(*local_depth),
-            Instruction::SetLocal { local_depth } => visitor.visit_set_local(*local_depth),
-            Instruction::TeeLocal { local_depth } => visitor.visit_tee_local(*local_depth),
+            Instruction::Copy(op) => visitor.visit_copy(*op),
             Instruction::Br(target) => visitor.visit_br(*target),
-            Instruction::BrIfEqz(target) => visitor.visit_br_if_eqz(*target),
-            Instruction::BrIfNez(target) => visitor.visit_br_if_nez(*target),
-            Instruction::ReturnIfNez(drop_keep) => visitor.visit_return_if_nez(*drop_keep),
-            Instruction::BrTable { len_targets } => visitor.visit_br_table(BrTable::new(
-                &self.insts[(index + 1)..(index + 1 + len_targets)],
-            )),
+            Instruction::BrIfEqz { condition, target } => {
+                visitor.visit_br_if_eqz(*condition, *target)
+            }
+            Instruction::BrIfNez { condition, target } => {
+                visitor.visit_br_if_nez(*condition, *target)
+            }
+            Instruction::ReturnIfNez { condition, results } => {
+                visitor.visit_return_if_nez(*condition, *results)
+            }
+            Instruction::BrIfI32Eq(op) => visitor.visit_br_if_i32_eq(*op),
+            Instruction::BrIfI32Ne(op) => visitor.visit_br_if_i32_ne(*op),
+            Instruction::BrIfI32LtS(op) => visitor.visit_br_if_i32_lt_s(*op),
+            Instruction::BrIfI32LtU(op) => visitor.visit_br_if_i32_lt_u(*op),
+            Instruction::BrIfI32GtS(op) => visitor.visit_br_if_i32_gt_s(*op),
+            Instruction::BrIfI32GtU(op) => visitor.visit_br_if_i32_gt_u(*op),
+            Instruction::BrIfI32LeS(op) => visitor.visit_br_if_i32_le_s(*op),
+            Instruction::BrIfI32LeU(op) => visitor.visit_br_if_i32_le_u(*op),
+            Instruction::BrIfI32GeS(op) => visitor.visit_br_if_i32_ge_s(*op),
+            Instruction::BrIfI32GeU(op) => visitor.visit_br_if_i32_ge_u(*op),
+            Instruction::BrIfI64Eq(op) => visitor.visit_br_if_i64_eq(*op),
+            Instruction::BrIfI64Ne(op) => visitor.visit_br_if_i64_ne(*op),
+            Instruction::BrIfI64LtS(op) => visitor.visit_br_if_i64_lt_s(*op),
+            Instruction::BrIfI64LtU(op) => visitor.visit_br_if_i64_lt_u(*op),
+            Instruction::BrIfI64GtS(op) => visitor.visit_br_if_i64_gt_s(*op),
+            Instruction::BrIfI64GtU(op) => visitor.visit_br_if_i64_gt_u(*op),
+            Instruction::BrIfI64LeS(op) => visitor.visit_br_if_i64_le_s(*op),
+            Instruction::BrIfI64LeU(op) => visitor.visit_br_if_i64_le_u(*op),
+            Instruction::BrIfI64GeS(op) => visitor.visit_br_if_i64_ge_s(*op),
+            Instruction::BrIfI64GeU(op) => visitor.visit_br_if_i64_ge_u(*op),
+            Instruction::BrTable {
+                index,
+                offset,
+                len_targets,
+            } => {
+                let offset = *offset as usize;
+                let len_targets = *len_targets as usize;
+                visitor.visit_br_table(
+                    *index,
+                    BrTable::new(&self.br_targets[offset..(offset + len_targets)]),
+                )
+            }
             Instruction::Unreachable => visitor.visit_unreachable(),
-            Instruction::Return(drop_keep) => visitor.visit_ret(*drop_keep),
-            Instruction::Call(func) => visitor.visit_call(*func),
-            Instruction::CallIndirect(signature) => visitor.visit_call_indirect(*signature),
-            Instruction::Drop => visitor.visit_drop(),
-            Instruction::Select => visitor.visit_select(),
-            Instruction::GetGlobal(global_idx) => visitor.visit_get_global(*global_idx),
-            Instruction::SetGlobal(global_idx) => visitor.visit_set_global(*global_idx),
-            Instruction::I32Load(offset) => visitor.visit_i32_load(*offset),
-            Instruction::I64Load(offset) => visitor.visit_i64_load(*offset),
-            Instruction::F32Load(offset) => visitor.visit_f32_load(*offset),
-            Instruction::F64Load(offset) => visitor.visit_f64_load(*offset),
-            Instruction::I32Load8S(offset) => visitor.visit_i32_load_i8(*offset),
-            Instruction::I32Load8U(offset) => visitor.visit_i32_load_u8(*offset),
-            Instruction::I32Load16S(offset) => visitor.visit_i32_load_i16(*offset),
-            Instruction::I32Load16U(offset) => visitor.visit_i32_load_u16(*offset),
-            Instruction::I64Load8S(offset) => visitor.visit_i64_load_i8(*offset),
-            Instruction::I64Load8U(offset) => visitor.visit_i64_load_u8(*offset),
-            Instruction::I64Load16S(offset) => visitor.visit_i64_load_i16(*offset),
-            Instruction::I64Load16U(offset) => visitor.visit_i64_load_u16(*offset),
-            Instruction::I64Load32S(offset) => visitor.visit_i64_load_i32(*offset),
-            Instruction::I64Load32U(offset) => visitor.visit_i64_load_u32(*offset),
-            Instruction::I32Store(offset) => visitor.visit_i32_store(*offset),
-            Instruction::I64Store(offset) => visitor.visit_i64_store(*offset),
-            Instruction::F32Store(offset) => visitor.visit_f32_store(*offset),
-            Instruction::F64Store(offset) => visitor.visit_f64_store(*offset),
-            Instruction::I32Store8(offset) => visitor.visit_i32_store_8(*offset),
-            Instruction::I32Store16(offset) => visitor.visit_i32_store_16(*offset),
-            Instruction::I64Store8(offset) => visitor.visit_i64_store_8(*offset),
-            Instruction::I64Store16(offset) => visitor.visit_i64_store_16(*offset),
-            Instruction::I64Store32(offset) => visitor.visit_i64_store_32(*offset),
-            Instruction::CurrentMemory => visitor.visit_current_memory(),
-            Instruction::GrowMemory => visitor.visit_grow_memory(),
-            Instruction::Const(bytes) => visitor.visit_const(*bytes),
-            Instruction::I32Eqz => visitor.visit_i32_eqz(),
-            Instruction::I32Eq => visitor.visit_i32_eq(),
-            Instruction::I32Ne => visitor.visit_i32_ne(),
-            Instruction::I32LtS => visitor.visit_i32_lt_s(),
-            Instruction::I32LtU => visitor.visit_i32_lt_u(),
-            Instruction::I32GtS => visitor.visit_i32_gt_s(),
-            Instruction::I32GtU => visitor.visit_i32_gt_u(),
-            Instruction::I32LeS => visitor.visit_i32_le_s(),
-            Instruction::I32LeU => visitor.visit_i32_le_u(),
-            Instruction::I32GeS => visitor.visit_i32_ge_s(),
-            Instruction::I32GeU => visitor.visit_i32_ge_u(),
-            Instruction::I64Eqz => visitor.visit_i64_eqz(),
-            Instruction::I64Eq => visitor.visit_i64_eq(),
-            Instruction::I64Ne => visitor.visit_i64_ne(),
-            Instruction::I64LtS => visitor.visit_i64_lt_s(),
-            Instruction::I64LtU => visitor.visit_i64_lt_u(),
-            Instruction::I64GtS => visitor.visit_i64_gt_s(),
-            Instruction::I64GtU => visitor.visit_i64_gt_u(),
-            Instruction::I64LeS => visitor.visit_i64_le_s(),
-            Instruction::I64LeU => visitor.visit_i64_le_u(),
-            Instruction::I64GeS => visitor.visit_i64_ge_s(),
-            Instruction::I64GeU => visitor.visit_i64_ge_u(),
-            Instruction::F32Eq => visitor.visit_f32_eq(),
-            Instruction::F32Ne => visitor.visit_f32_ne(),
-            Instruction::F32Lt => visitor.visit_f32_lt(),
-            Instruction::F32Gt => visitor.visit_f32_gt(),
-            Instruction::F32Le => visitor.visit_f32_le(),
-            Instruction::F32Ge => visitor.visit_f32_ge(),
-            Instruction::F64Eq => visitor.visit_f64_eq(),
-            Instruction::F64Ne => visitor.visit_f64_ne(),
-            Instruction::F64Lt => visitor.visit_f64_lt(),
-            Instruction::F64Gt => visitor.visit_f64_gt(),
-            Instruction::F64Le => visitor.visit_f64_le(),
-            Instruction::F64Ge => visitor.visit_f64_ge(),
-            Instruction::I32Clz => visitor.visit_i32_clz(),
-            Instruction::I32Ctz => visitor.visit_i32_ctz(),
-            Instruction::I32Popcnt => visitor.visit_i32_popcnt(),
-            Instruction::I32Add => visitor.visit_i32_add(),
-            Instruction::I32Sub => visitor.visit_i32_sub(),
-            Instruction::I32Mul => visitor.visit_i32_mul(),
-            Instruction::I32DivS => visitor.visit_i32_div_s(),
-            Instruction::I32DivU => visitor.visit_i32_div_u(),
-            Instruction::I32RemS => visitor.visit_i32_rem_s(),
-            Instruction::I32RemU => visitor.visit_i32_rem_u(),
-            Instruction::I32And => visitor.visit_i32_and(),
-            Instruction::I32Or => visitor.visit_i32_or(),
-            Instruction::I32Xor => visitor.visit_i32_xor(),
-            Instruction::I32Shl => visitor.visit_i32_shl(),
-            Instruction::I32ShrS => visitor.visit_i32_shr_s(),
-            Instruction::I32ShrU => visitor.visit_i32_shr_u(),
-            Instruction::I32Rotl => visitor.visit_i32_rotl(),
-            Instruction::I32Rotr => visitor.visit_i32_rotr(),
-            Instruction::I64Clz => visitor.visit_i64_clz(),
-            Instruction::I64Ctz => visitor.visit_i64_ctz(),
-            Instruction::I64Popcnt => visitor.visit_i64_popcnt(),
-            Instruction::I64Add => visitor.visit_i64_add(),
-            Instruction::I64Sub => visitor.visit_i64_sub(),
-            Instruction::I64Mul => visitor.visit_i64_mul(),
-            Instruction::I64DivS => visitor.visit_i64_div_s(),
-            Instruction::I64DivU => visitor.visit_i64_div_u(),
-            Instruction::I64RemS => visitor.visit_i64_rem_s(),
-            Instruction::I64RemU => visitor.visit_i64_rem_u(),
-            Instruction::I64And => visitor.visit_i64_and(),
-            Instruction::I64Or => visitor.visit_i64_or(),
-            Instruction::I64Xor => visitor.visit_i64_xor(),
-            Instruction::I64Shl => visitor.visit_i64_shl(),
-            Instruction::I64ShrS => visitor.visit_i64_shr_s(),
-            Instruction::I64ShrU => visitor.visit_i64_shr_u(),
-            Instruction::I64Rotl => visitor.visit_i64_rotl(),
-            Instruction::I64Rotr => visitor.visit_i64_rotr(),
-            Instruction::F32Abs => visitor.visit_f32_abs(),
-            Instruction::F32Neg => visitor.visit_f32_neg(),
-            Instruction::F32Ceil => visitor.visit_f32_ceil(),
-            Instruction::F32Floor => visitor.visit_f32_floor(),
-            Instruction::F32Trunc => visitor.visit_f32_trunc(),
-            Instruction::F32Nearest => visitor.visit_f32_nearest(),
-            Instruction::F32Sqrt => visitor.visit_f32_sqrt(),
-            Instruction::F32Add => visitor.visit_f32_add(),
-            Instruction::F32Sub => visitor.visit_f32_sub(),
-            Instruction::F32Mul => visitor.visit_f32_mul(),
-            Instruction::F32Div => visitor.visit_f32_div(),
-            Instruction::F32Min => visitor.visit_f32_min(),
-            Instruction::F32Max => visitor.visit_f32_max(),
-            Instruction::F32Copysign => visitor.visit_f32_copysign(),
-            Instruction::F64Abs => visitor.visit_f64_abs(),
-            Instruction::F64Neg => visitor.visit_f64_neg(),
-            Instruction::F64Ceil => visitor.visit_f64_ceil(),
-            Instruction::F64Floor => visitor.visit_f64_floor(),
-            Instruction::F64Trunc => visitor.visit_f64_trunc(),
-            Instruction::F64Nearest => visitor.visit_f64_nearest(),
-            Instruction::F64Sqrt => visitor.visit_f64_sqrt(),
-            Instruction::F64Add => visitor.visit_f64_add(),
-            Instruction::F64Sub => visitor.visit_f64_sub(),
-            Instruction::F64Mul => visitor.visit_f64_mul(),
-            Instruction::F64Div => visitor.visit_f64_div(),
-            Instruction::F64Min => visitor.visit_f64_min(),
-            Instruction::F64Max => visitor.visit_f64_max(),
-            Instruction::F64Copysign => visitor.visit_f64_copysign(),
-            Instruction::I32WrapI64 => visitor.visit_i32_wrap_i64(),
-            Instruction::I32TruncSF32 => visitor.visit_i32_trunc_f32(),
-            Instruction::I32TruncUF32 => visitor.visit_u32_trunc_f32(),
-            Instruction::I32TruncSF64 => visitor.visit_i32_trunc_f64(),
-            Instruction::I32TruncUF64 => visitor.visit_u32_trunc_f64(),
-            Instruction::I64ExtendSI32 => visitor.visit_i64_extend_i32(),
-            Instruction::I64ExtendUI32 => visitor.visit_i64_extend_u32(),
-            Instruction::I64TruncSF32 => visitor.visit_i64_trunc_f32(),
-            Instruction::I64TruncUF32 => visitor.visit_u64_trunc_f32(),
-            Instruction::I64TruncSF64 => visitor.visit_i64_trunc_f64(),
-            Instruction::I64TruncUF64 => visitor.visit_u64_trunc_f64(),
-            Instruction::F32ConvertSI32 => visitor.visit_f32_convert_i32(),
-            Instruction::F32ConvertUI32 => visitor.visit_f32_convert_u32(),
-            Instruction::F32ConvertSI64 => visitor.visit_f32_convert_i64(),
-            Instruction::F32ConvertUI64 => visitor.visit_f32_convert_u64(),
-            Instruction::F32DemoteF64 => visitor.visit_f32_demote_f64(),
-            Instruction::F64ConvertSI32 => visitor.visit_f64_convert_i32(),
-            Instruction::F64ConvertUI32 => visitor.visit_f64_convert_u32(),
-            Instruction::F64ConvertSI64 => visitor.visit_f64_convert_i64(),
-            Instruction::F64ConvertUI64 => visitor.visit_f64_convert_u64(),
-            Instruction::F64PromoteF32 => visitor.visit_f64_promote_f32(),
-            Instruction::I32ReinterpretF32 => visitor.visit_i32_reinterpret_f32(),
-            Instruction::I64ReinterpretF64 => visitor.visit_i64_reinterpret_f64(),
-            Instruction::F32ReinterpretI32 => visitor.visit_f32_reinterpret_i32(),
-            Instruction::F64ReinterpretI64 => visitor.visit_f64_reinterpret_i64(),
-            Instruction::I32TruncSatF32S => visitor.visit_i32_trunc_sat_f32(),
-            Instruction::I32TruncSatF32U => visitor.visit_u32_trunc_sat_f32(),
-            Instruction::I32TruncSatF64S => visitor.visit_i32_trunc_sat_f64(),
-            Instruction::I32TruncSatF64U => visitor.visit_u32_trunc_sat_f64(),
-            Instruction::I64TruncSatF32S => visitor.visit_i64_trunc_sat_f32(),
-            Instruction::I64TruncSatF32U => visitor.visit_u64_trunc_sat_f32(),
-            Instruction::I64TruncSatF64S => visitor.visit_i64_trunc_sat_f64(),
-            Instruction::I64TruncSatF64U => visitor.visit_u64_trunc_sat_f64(),
-            Instruction::I32Extend8S => visitor.visit_i32_sign_extend8(),
-            Instruction::I32Extend16S => visitor.visit_i32_sign_extend16(),
-            Instruction::I64Extend8S => visitor.visit_i64_sign_extend8(),
-            Instruction::I64Extend16S => visitor.visit_i64_sign_extend16(),
-            Instruction::I64Extend32S => visitor.visit_i64_sign_extend32(),
-            Instruction::FuncBodyStart { .. } | Instruction::FuncBodyEnd => panic!(
-                "expected start of a new instruction at index {} but found: {:?}",
-                index, inst
-            ),
+            Instruction::Return(results) => visitor.visit_ret(*results),
+            Instruction::Call { func, params } => visitor.visit_call(*func, *params),
+            Instruction::CallIndirect {
+                signature,
+                index,
+                params,
+            } => visitor.visit_call_indirect(*signature, *index, *params),
+            Instruction::Select {
+                result,
+                condition,
+                if_false,
+            } => visitor.visit_select(*result, *condition, *if_false),
+            Instruction::GetGlobal { result, global } => visitor.visit_get_global(*result, *global),
+            Instruction::SetGlobal { global, input } => visitor.visit_set_global(*global, *input),
+            Instruction::I32Load(op) => visitor.visit_i32_load(*op),
+            Instruction::I64Load(op) => visitor.visit_i64_load(*op),
+            Instruction::F32Load(op) => visitor.visit_f32_load(*op),
+            Instruction::F64Load(op) => visitor.visit_f64_load(*op),
+            Instruction::I32Load8S(op) => visitor.visit_i32_load_i8(*op),
+            Instruction::I32Load8U(op) => visitor.visit_i32_load_u8(*op),
+            Instruction::I32Load16S(op) => visitor.visit_i32_load_i16(*op),
+            Instruction::I32Load16U(op) => visitor.visit_i32_load_u16(*op),
+            Instruction::I64Load8S(op) => visitor.visit_i64_load_i8(*op),
+            Instruction::I64Load8U(op) => visitor.visit_i64_load_u8(*op),
+            Instruction::I64Load16S(op) => visitor.visit_i64_load_i16(*op),
+            Instruction::I64Load16U(op) => visitor.visit_i64_load_u16(*op),
+            Instruction::I64Load32S(op) => visitor.visit_i64_load_i32(*op),
+            Instruction::I64Load32U(op) => visitor.visit_i64_load_u32(*op),
+            Instruction::I32Store(op) => visitor.visit_i32_store(*op),
+            Instruction::I64Store(op) => visitor.visit_i64_store(*op),
+            Instruction::F32Store(op) => visitor.visit_f32_store(*op),
+            Instruction::F64Store(op) => visitor.visit_f64_store(*op),
+            Instruction::I32Store8(op) => visitor.visit_i32_store_8(*op),
+            Instruction::I32Store16(op) => visitor.visit_i32_store_16(*op),
+            Instruction::I64Store8(op) => visitor.visit_i64_store_8(*op),
+            Instruction::I64Store16(op) => visitor.visit_i64_store_16(*op),
+            Instruction::I64Store32(op) => visitor.visit_i64_store_32(*op),
+            Instruction::CurrentMemory { result } => visitor.visit_current_memory(*result),
+            Instruction::GrowMemory(op) => visitor.visit_grow_memory(*op),
+            Instruction::I32Eqz(op) => visitor.visit_i32_eqz(*op),
+            Instruction::I32Eq(op) => visitor.visit_i32_eq(*op),
+            Instruction::I32Ne(op) => visitor.visit_i32_ne(*op),
+            Instruction::I32LtS(op) => visitor.visit_i32_lt_s(*op),
+            Instruction::I32LtU(op) => visitor.visit_i32_lt_u(*op),
+            Instruction::I32GtS(op) => visitor.visit_i32_gt_s(*op),
+            Instruction::I32GtU(op) => visitor.visit_i32_gt_u(*op),
+            Instruction::I32LeS(op) => visitor.visit_i32_le_s(*op),
+            Instruction::I32LeU(op) => visitor.visit_i32_le_u(*op),
+            Instruction::I32GeS(op) => visitor.visit_i32_ge_s(*op),
+            Instruction::I32GeU(op) => visitor.visit_i32_ge_u(*op),
+            Instruction::I64Eqz(op) => visitor.visit_i64_eqz(*op),
+            Instruction::I64Eq(op) => visitor.visit_i64_eq(*op),
+            Instruction::I64Ne(op) => visitor.visit_i64_ne(*op),
+            Instruction::I64LtS(op) => visitor.visit_i64_lt_s(*op),
+            Instruction::I64LtU(op) => visitor.visit_i64_lt_u(*op),
+            Instruction::I64GtS(op) => visitor.visit_i64_gt_s(*op),
+            Instruction::I64GtU(op) => visitor.visit_i64_gt_u(*op),
+            Instruction::I64LeS(op) => visitor.visit_i64_le_s(*op),
+            Instruction::I64LeU(op) => visitor.visit_i64_le_u(*op),
+            Instruction::I64GeS(op) => visitor.visit_i64_ge_s(*op),
+            Instruction::I64GeU(op) => visitor.visit_i64_ge_u(*op),
+            Instruction::F32Eq(op) => visitor.visit_f32_eq(*op),
+            Instruction::F32Ne(op) => visitor.visit_f32_ne(*op),
+            Instruction::F32Lt(op) => visitor.visit_f32_lt(*op),
+            Instruction::F32Gt(op) => visitor.visit_f32_gt(*op),
+            Instruction::F32Le(op) => visitor.visit_f32_le(*op),
+            Instruction::F32Ge(op) => visitor.visit_f32_ge(*op),
+            Instruction::F64Eq(op) => visitor.visit_f64_eq(*op),
+            Instruction::F64Ne(op) => visitor.visit_f64_ne(*op),
+            Instruction::F64Lt(op) => visitor.visit_f64_lt(*op),
+            Instruction::F64Gt(op) => visitor.visit_f64_gt(*op),
+            Instruction::F64Le(op) => visitor.visit_f64_le(*op),
+            Instruction::F64Ge(op) => visitor.visit_f64_ge(*op),
+            Instruction::I32Clz(op) => visitor.visit_i32_clz(*op),
+            Instruction::I32Ctz(op) => visitor.visit_i32_ctz(*op),
+            Instruction::I32Popcnt(op) => visitor.visit_i32_popcnt(*op),
+            Instruction::I32Add(op) => visitor.visit_i32_add(*op),
+            Instruction::I32AddImm(op) => visitor.visit_i32_add_imm(*op),
+            Instruction::I32Sub(op) => visitor.visit_i32_sub(*op),
+            Instruction::I32Mul(op) => visitor.visit_i32_mul(*op),
+            Instruction::I32DivS(op) => visitor.visit_i32_div_s(*op),
+            Instruction::I32DivU(op) => visitor.visit_i32_div_u(*op),
+            Instruction::I32RemS(op) => visitor.visit_i32_rem_s(*op),
+            Instruction::I32RemU(op) => visitor.visit_i32_rem_u(*op),
+            Instruction::I32And(op) => visitor.visit_i32_and(*op),
+            Instruction::I32Or(op) => visitor.visit_i32_or(*op),
+            Instruction::I32Xor(op) => visitor.visit_i32_xor(*op),
+            Instruction::I32Shl(op) => visitor.visit_i32_shl(*op),
+            Instruction::I32ShrS(op) => visitor.visit_i32_shr_s(*op),
+            Instruction::I32ShrU(op) => visitor.visit_i32_shr_u(*op),
+            Instruction::I32Rotl(op) => visitor.visit_i32_rotl(*op),
+            Instruction::I32Rotr(op) => visitor.visit_i32_rotr(*op),
+            Instruction::I64Clz(op) => visitor.visit_i64_clz(*op),
+            Instruction::I64Ctz(op) => visitor.visit_i64_ctz(*op),
+            Instruction::I64Popcnt(op) => visitor.visit_i64_popcnt(*op),
+            Instruction::I64Add(op) => visitor.visit_i64_add(*op),
+            Instruction::I64AddImm(op) => visitor.visit_i64_add_imm(*op),
+            Instruction::I64Sub(op) => visitor.visit_i64_sub(*op),
+            Instruction::I64Mul(op) => visitor.visit_i64_mul(*op),
+            Instruction::I64DivS(op) => visitor.visit_i64_div_s(*op),
+            Instruction::I64DivU(op) => visitor.visit_i64_div_u(*op),
+            Instruction::I64RemS(op) => visitor.visit_i64_rem_s(*op),
+            Instruction::I64RemU(op) => visitor.visit_i64_rem_u(*op),
+            Instruction::I64And(op) => visitor.visit_i64_and(*op),
+            Instruction::I64Or(op) => visitor.visit_i64_or(*op),
+            Instruction::I64Xor(op) => visitor.visit_i64_xor(*op),
+            Instruction::I64Shl(op) => visitor.visit_i64_shl(*op),
+            Instruction::I64ShrS(op) => visitor.visit_i64_shr_s(*op),
+            Instruction::I64ShrU(op) => visitor.visit_i64_shr_u(*op),
+            Instruction::I64Rotl(op) => visitor.visit_i64_rotl(*op),
+            Instruction::I64Rotr(op) => visitor.visit_i64_rotr(*op),
+            Instruction::F32Abs(op) => visitor.visit_f32_abs(*op),
+            Instruction::F32Neg(op) => visitor.visit_f32_neg(*op),
+            Instruction::F32Ceil(op) => visitor.visit_f32_ceil(*op),
+            Instruction::F32Floor(op) => visitor.visit_f32_floor(*op),
+            Instruction::F32Trunc(op) => visitor.visit_f32_trunc(*op),
+            Instruction::F32Nearest(op) => visitor.visit_f32_nearest(*op),
+            Instruction::F32Sqrt(op) => visitor.visit_f32_sqrt(*op),
+            Instruction::F32Add(op) => visitor.visit_f32_add(*op),
+            Instruction::F32Sub(op) => visitor.visit_f32_sub(*op),
+            Instruction::F32Mul(op) => visitor.visit_f32_mul(*op),
+            Instruction::F32Div(op) => visitor.visit_f32_div(*op),
+            Instruction::F32Min(op) => visitor.visit_f32_min(*op),
+            Instruction::F32Max(op) => visitor.visit_f32_max(*op),
+            Instruction::F32Copysign(op) => visitor.visit_f32_copysign(*op),
+            Instruction::F64Abs(op) => visitor.visit_f64_abs(*op),
+            Instruction::F64Neg(op) => visitor.visit_f64_neg(*op),
+            Instruction::F64Ceil(op) => visitor.visit_f64_ceil(*op),
+            Instruction::F64Floor(op) => visitor.visit_f64_floor(*op),
+            Instruction::F64Trunc(op) => visitor.visit_f64_trunc(*op),
+            Instruction::F64Nearest(op) => visitor.visit_f64_nearest(*op),
+            Instruction::F64Sqrt(op) => visitor.visit_f64_sqrt(*op),
+            Instruction::F64Add(op) => visitor.visit_f64_add(*op),
+            Instruction::F64Sub(op) => visitor.visit_f64_sub(*op),
+            Instruction::F64Mul(op) => visitor.visit_f64_mul(*op),
+            Instruction::F64Div(op) => visitor.visit_f64_div(*op),
+            Instruction::F64Min(op) => visitor.visit_f64_min(*op),
+            Instruction::F64Max(op) => visitor.visit_f64_max(*op),
+            Instruction::F64Copysign(op) => visitor.visit_f64_copysign(*op),
+            Instruction::I32WrapI64(op) => visitor.visit_i32_wrap_i64(*op),
+            Instruction::I32TruncSF32(op) => visitor.visit_i32_trunc_f32(*op),
+            Instruction::I32TruncUF32(op) => visitor.visit_u32_trunc_f32(*op),
+            Instruction::I32TruncSF64(op) => visitor.visit_i32_trunc_f64(*op),
+            Instruction::I32TruncUF64(op) => visitor.visit_u32_trunc_f64(*op),
+            Instruction::I64ExtendSI32(op) => visitor.visit_i64_extend_i32(*op),
+            Instruction::I64ExtendUI32(op) => visitor.visit_i64_extend_u32(*op),
+            Instruction::I64TruncSF32(op) => visitor.visit_i64_trunc_f32(*op),
+            Instruction::I64TruncUF32(op) => visitor.visit_u64_trunc_f32(*op),
+            Instruction::I64TruncSF64(op) => visitor.visit_i64_trunc_f64(*op),
+            Instruction::I64TruncUF64(op) => visitor.visit_u64_trunc_f64(*op),
+            Instruction::F32ConvertSI32(op) => visitor.visit_f32_convert_i32(*op),
+            Instruction::F32ConvertUI32(op) => visitor.visit_f32_convert_u32(*op),
+            Instruction::F32ConvertSI64(op) => visitor.visit_f32_convert_i64(*op),
+            Instruction::F32ConvertUI64(op) => visitor.visit_f32_convert_u64(*op),
+            Instruction::F32DemoteF64(op) => visitor.visit_f32_demote_f64(*op),
+            Instruction::F64ConvertSI32(op) => visitor.visit_f64_convert_i32(*op),
+            Instruction::F64ConvertUI32(op) => visitor.visit_f64_convert_u32(*op),
+            Instruction::F64ConvertSI64(op) => visitor.visit_f64_convert_i64(*op),
+            Instruction::F64ConvertUI64(op) => visitor.visit_f64_convert_u64(*op),
+            Instruction::F64PromoteF32(op) => visitor.visit_f64_promote_f32(*op),
+            Instruction::I32Extend8S(op) => visitor.visit_i32_sign_extend8(*op),
+            Instruction::I32Extend16S(op) => visitor.visit_i32_sign_extend16(*op),
+            Instruction::I64Extend8S(op) => visitor.visit_i64_sign_extend8(*op),
+            Instruction::I64Extend16S(op) => visitor.visit_i64_sign_extend16(*op),
+            Instruction::I64Extend32S(op) => visitor.visit_i64_sign_extend32(*op),
+            Instruction::I32TruncSatF32S(op) => visitor.visit_i32_trunc_sat_f32(*op),
+            Instruction::I32TruncSatF32U(op) => visitor.visit_u32_trunc_sat_f32(*op),
+            Instruction::I32TruncSatF64S(op) => visitor.visit_i32_trunc_sat_f64(*op),
+            Instruction::I32TruncSatF64U(op) => visitor.visit_u32_trunc_sat_f64(*op),
+            Instruction::I64TruncSatF32S(op) => visitor.visit_i64_trunc_sat_f32(*op),
+            Instruction::I64TruncSatF32U(op) => visitor.visit_u64_trunc_sat_f32(*op),
+            Instruction::I64TruncSatF64S(op) => visitor.visit_i64_trunc_sat_f64(*op),
+            Instruction::I64TruncSatF64U(op) => visitor.visit_u64_trunc_sat_f64(*op),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Engine, Module};
+    use alloc::{format, string::String};
+
+    #[test]
+    fn size_of_compiled_func_body() {
+        assert_eq!(mem::size_of::<CompiledFuncBody>(), 32);
+    }
+
+    /// Returns the number of bytes used by all function bodies of the [`CodeMap`].
+    fn used_bytes(code_map: &CodeMap) -> usize {
+        code_map.insts.len() * mem::size_of::<Instruction>()
+            + code_map.consts.len() * mem::size_of::<UntypedValue>()
+            + code_map.br_targets.len() * mem::size_of::<Target>()
+            + code_map.bodies.len() * mem::size_of::<CompiledFuncBody>()
+    }
+
+    /// Compiles the `wat` module and returns the number of bytes used by its function bodies.
+    fn module_footprint(wat: &str) -> usize {
+        let wasm = wat::parse_str(wat).unwrap();
+        let engine = Engine::default();
+        let _module = Module::new(&engine, &wasm[..]).unwrap();
+        let inner = engine.inner.lock();
+        used_bytes(&inner.code_map)
+    }
+
+    /// Returns a module with many small function bodies.
+    fn many_funcs_wat() -> String {
+        let mut wat = String::from("(module\n");
+        for n in 0..100 {
+            wat.push_str(&format!(
+                r#"
+                (func (export "func_{n}") (param $n i64) (result i64) (local $acc i64)
+                    (block $exit
+                        (loop $continue
+                            (br_if $exit (i64.eqz (local.get $n)))
+                            (local.set $acc (i64.add (local.get $acc) (i64.const {n})))
+                            (local.set $n (i64.sub (local.get $n) (i64.const 1)))
+                            (br $continue)
+                        )
+                    )
+                    (local.get $acc)
+                )
+                "#
+            ));
         }
+        wat.push(')');
+        wat
+    }
+
+    /// Returns a module with a function dispatching over a `br_table` with many targets.
+    fn br_table_wat() -> String {
+        let mut wat = String::from(
+            r#"(module (func (export "dispatch") (param $n i32) (result i32) (local $acc i32)"#,
+        );
+        for n in 0..32 {
+            wat.push_str(&format!("(block $case{n}"));
+        }
+        wat.push_str("(br_table");
+        for n in 0..32 {
+            wat.push_str(&format!(" $case{n}"));
+        }
+        wat.push_str(" $case0 (local.get $n))");
+        for n in 0..32 {
+            wat.push_str(&format!(
+                ") (local.set $acc (i32.add (local.get $acc) (i32.const {n})))"
+            ));
+        }
+        wat.push_str("(local.get $acc)))");
+        wat
+    }
+
+    #[test]
+    fn code_map_footprint() {
+        // The bytes used by the function bodies of the modules back when the
+        // instruction stream held the function headers and `br_table` targets
+        // and every function body entry occupied 40 bytes.
+        const MANY_FUNCS_BEFORE: usize = 15_200;
+        const BR_TABLE_BEFORE: usize = 1_144;
+        let many_funcs = module_footprint(&many_funcs_wat());
+        let br_table = module_footprint(&br_table_wat());
+        assert_eq!(many_funcs, 11_200);
+        assert_eq!(br_table, 708);
+        assert!(many_funcs * 4 <= MANY_FUNCS_BEFORE * 3);
+        assert!(br_table * 3 <= BR_TABLE_BEFORE * 2);
     }
 }
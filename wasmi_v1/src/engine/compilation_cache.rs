@@ -0,0 +1,128 @@
+//! Cache for the translated contents of [`Module`]s created from identical Wasm bytes.
+//!
+//! [`Module`]: crate::Module
+
+use crate::{module::ModuleInner, Config};
+use alloc::{boxed::Box, collections::BTreeMap, sync::Arc, vec::Vec};
+use core::hash::{Hash, Hasher};
+
+/// The translated contents of a [`Module`] and the Wasm bytes it was created from.
+///
+/// [`Module`]: crate::Module
+#[derive(Debug)]
+struct CacheEntry {
+    /// The Wasm bytes of the cached [`Module`].
+    ///
+    /// [`Module`]: crate::Module
+    bytes: Box<[u8]>,
+    /// The translated contents of the cached [`Module`].
+    ///
+    /// [`Module`]: crate::Module
+    module: Arc<ModuleInner>,
+}
+
+/// A cache for the translated contents of [`Module`]s keyed by their Wasm bytes.
+///
+/// # Note
+///
+/// - Entries are looked up by a hash of the Wasm bytes and the [`Config`] and are
+///   then compared byte for byte so that a hash collision never yields the wrong
+///   [`Module`].
+/// - Entries are only removed via [`CompilationCache::clear`] since the function
+///   bodies they refer to stay allocated in the [`Engine`] either way.
+///
+/// [`Module`]: crate::Module
+/// [`Engine`]: crate::Engine
+#[derive(Debug, Default)]
+pub struct CompilationCache {
+    /// The cached entries grouped by the hash of their key.
+    entries: BTreeMap<u64, Vec<CacheEntry>>,
+    /// The total number of cached entries.
+    len_entries: usize,
+}
+
+impl CompilationCache {
+    /// Returns the number of cached [`Module`]s.
+    ///
+    /// [`Module`]: crate::Module
+    pub fn len(&self) -> usize {
+        self.len_entries
+    }
+
+    /// Returns the cached contents of the [`Module`] created from `bytes` if any.
+    ///
+    /// [`Module`]: crate::Module
+    pub fn get(&self, config: &Config, bytes: &[u8]) -> Option<Arc<ModuleInner>> {
+        self.entries
+            .get(&hash_key(config, bytes))?
+            .iter()
+            .find(|entry| &entry.bytes[..] == bytes)
+            .map(|entry| entry.module.clone())
+    }
+
+    /// Caches the contents of the [`Module`] created from `bytes`.
+    ///
+    /// Returns the cached contents which are the already cached ones if another
+    /// [`Module`] has been created from the same `bytes` in the meantime.
+    ///
+    /// [`Module`]: crate::Module
+    pub fn insert(
+        &mut self,
+        config: &Config,
+        bytes: Box<[u8]>,
+        module: Arc<ModuleInner>,
+    ) -> Arc<ModuleInner> {
+        let bucket = self.entries.entry(hash_key(config, &bytes)).or_default();
+        if let Some(entry) = bucket.iter().find(|entry| entry.bytes == bytes) {
+            return entry.module.clone();
+        }
+        bucket.push(CacheEntry {
+            bytes,
+            module: module.clone(),
+        });
+        self.len_entries += 1;
+        module
+    }
+
+    /// Removes all cached entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.len_entries = 0;
+    }
+}
+
+/// Returns the hash of the cache key made up of the [`Config`] and the Wasm `bytes`.
+fn hash_key(config: &Config, bytes: &[u8]) -> u64 {
+    let mut hasher = Fnv1aHasher::default();
+    config.hash(&mut hasher);
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The 64-bit FNV-1a hash function.
+///
+/// # Note
+///
+/// Not resistant against crafted collisions which is fine
+/// since cache entries are compared byte for byte anyways.
+#[derive(Debug)]
+struct Fnv1aHasher(u64);
+
+impl Default for Fnv1aHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1aHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
@@ -1,11 +1,26 @@
 use super::{
-    super::{Global, Memory, Table},
-    bytecode::{BrTable, FuncIdx, GlobalIdx, Instruction, LocalIdx, Offset, SignatureIdx},
+    super::{Memory, Table},
+    bytecode::{
+        BinaryOp,
+        BrTable,
+        BranchCmpOp,
+        FuncIdx,
+        GlobalIdx,
+        ImmOp,
+        LoadOp,
+        Offset,
+        Register,
+        RegisterSpan,
+        SignatureIdx,
+        StoreOp,
+        UnaryOp,
+    },
+    cache::{CachedMemoryBytes, Callee},
     AsContextMut,
-    DropKeep,
     EngineInner,
     FunctionExecutionOutcome,
     FunctionFrame,
+    InstanceCache,
     ResolvedFuncBody,
     Target,
     ValueStack,
@@ -15,6 +30,7 @@ use crate::{
     core::{Trap, TrapCode, F32, F64},
     Func,
 };
+use core::{mem, ptr::NonNull};
 use wasmi_core::{memory_units::Pages, ExtendInto, LittleEndianConvert, UntypedValue, WrapInto};
 
 /// The outcome of a `wasmi` instruction execution.
@@ -29,12 +45,27 @@ pub enum ExecutionOutcome {
     Continue,
     /// Branch to an instruction at the given position.
     Branch(Target),
-    /// Execute function call.
-    ExecuteCall(Func),
-    /// Return from current function block.
-    Return(DropKeep),
+    /// Execute a call of the function at the [`FuncIdx`] of the instance
+    /// with the parameters starting at the given [`Register`].
+    ExecuteCall(FuncIdx, Register),
+    /// Execute an indirect call of the [`Func`] with the parameters
+    /// starting at the given [`Register`].
+    ExecuteIndirectCall(Func, Register),
+    /// Return the values of the [`RegisterSpan`] from the current function block.
+    Return(RegisterSpan),
 }
 
+// The interpreter frames executed within `guard::catch_traps` are discarded
+// without being dropped upon traps on guard pages, so none of their values
+// may need to be dropped.
+const _: () = {
+    assert!(!mem::needs_drop::<FunctionFrame>());
+    assert!(!mem::needs_drop::<Callee>());
+    assert!(!mem::needs_drop::<ExecutionOutcome>());
+    assert!(!mem::needs_drop::<FunctionExecutionOutcome>());
+    assert!(!mem::needs_drop::<ExecutionContext>());
+};
+
 /// State that is used during Wasm function execution.
 #[derive(Debug)]
 pub struct ExecutionContext<'engine, 'func> {
@@ -42,6 +73,8 @@ pub struct ExecutionContext<'engine, 'func> {
     value_stack: &'engine mut ValueStack,
     /// The function frame that is being executed.
     frame: &'func mut FunctionFrame,
+    /// The cached entities of the instance of the executed function frame.
+    cache: &'func mut InstanceCache,
     /// The resolved function body of the executed function frame.
     func_body: ResolvedFuncBody<'engine>,
 }
@@ -51,12 +84,14 @@ impl<'engine, 'func> ExecutionContext<'engine, 'func> {
     pub fn new(
         engine: &'engine mut EngineInner,
         frame: &'func mut FunctionFrame,
+        cache: &'func mut InstanceCache,
     ) -> Result<Self, Trap> {
         let resolved = engine.code_map.resolve(frame.func_body);
         frame.initialize(resolved, &mut engine.value_stack)?;
         Ok(Self {
             value_stack: &mut engine.value_stack,
             frame,
+            cache,
             func_body: resolved,
         })
     }
@@ -67,47 +102,79 @@ impl<'engine, 'func> ExecutionContext<'engine, 'func> {
     ///
     /// This executes instructions sequentially until either the function
     /// calls into another function or the function returns to its caller.
+    ///
+    /// The program counter is kept in a local variable and the per instruction
+    /// execution context is constructed only once so that the hot loop does not
+    /// need to reload them from memory for every executed instruction.
+    /// The program counter is written back to the function frame only
+    /// before calling into another function.
     #[inline(always)]
     pub fn execute_frame(
         self,
         mut ctx: impl AsContextMut,
     ) -> Result<FunctionExecutionOutcome, Trap> {
-        'outer: loop {
-            let pc = self.frame.inst_ptr;
-            let inst_context =
-                InstructionExecutionContext::new(self.value_stack, self.frame, &mut ctx);
-            match self.func_body.visit(pc, inst_context)? {
+        let bp = self.frame.bp;
+        let func_body = self.func_body;
+        let mut pc = self.frame.inst_ptr;
+        let mut inst_context = InstructionExecutionContext::new(
+            self.value_stack.frame_mut(bp),
+            self.frame,
+            self.cache,
+            &mut ctx,
+        );
+        let exit = loop {
+            match func_body.visit(pc, &mut inst_context)? {
                 ExecutionOutcome::Continue => {
                     // Advance instruction pointer.
-                    self.frame.inst_ptr += 1;
+                    pc += 1;
                 }
                 ExecutionOutcome::Branch(target) => {
-                    self.value_stack.drop_keep(target.drop_keep());
                     // Set instruction pointer to the branch target.
-                    self.frame.inst_ptr = target.destination_pc().into_usize();
-                }
-                ExecutionOutcome::ExecuteCall(func) => {
-                    // Advance instruction pointer.
-                    self.frame.inst_ptr += 1;
-                    return Ok(FunctionExecutionOutcome::NestedCall(func));
-                }
-                ExecutionOutcome::Return(drop_keep) => {
-                    self.value_stack.drop_keep(drop_keep);
-                    break 'outer;
+                    pc = target.destination_pc().into_usize();
                 }
+                exit => break exit,
             }
-        }
-        Ok(FunctionExecutionOutcome::Return)
+        };
+        let (callee, params) = match exit {
+            ExecutionOutcome::ExecuteCall(func_index, params) => {
+                let callee = inst_context
+                    .cache
+                    .callee(inst_context.ctx.as_context(), func_index);
+                (callee, params)
+            }
+            ExecutionOutcome::ExecuteIndirectCall(func, params) => {
+                (Callee::new(inst_context.ctx.as_context(), func), params)
+            }
+            ExecutionOutcome::Return(results) => {
+                self.value_stack.return_values(
+                    bp,
+                    results.start().into_usize(),
+                    results.len_registers(),
+                );
+                return Ok(FunctionExecutionOutcome::Return);
+            }
+            ExecutionOutcome::Continue | ExecutionOutcome::Branch(_) => {
+                unreachable!("encountered non-exiting execution outcome: {:?}", exit)
+            }
+        };
+        // Advance instruction pointer so that execution resumes
+        // after the call once the called function returns.
+        inst_context.frame.inst_ptr = pc + 1;
+        // The called function frame begins at its parameters.
+        self.value_stack.set_len(bp + params.into_usize());
+        Ok(FunctionExecutionOutcome::NestedCall(callee))
     }
 }
 
 /// An execution context for executing a single `wasmi` bytecode instruction.
 #[derive(Debug)]
 struct InstructionExecutionContext<'engine, 'func, Ctx> {
-    /// Stores the value stack of live values on the Wasm stack.
-    value_stack: &'engine mut ValueStack,
+    /// The registers of the function frame that is being executed.
+    regs: &'engine mut [UntypedValue],
     /// The function frame that is being executed.
     frame: &'func mut FunctionFrame,
+    /// The cached entities of the instance of the executed function frame.
+    cache: &'func mut InstanceCache,
     /// A mutable [`Store`] context.
     ///
     /// [`Store`]: [`crate::v1::Store`]
@@ -120,24 +187,73 @@ where
 {
     /// Creates a new [`InstructionExecutionContext`] for executing a single `wasmi` bytecode instruction.
     pub fn new(
-        value_stack: &'engine mut ValueStack,
+        regs: &'engine mut [UntypedValue],
         frame: &'func mut FunctionFrame,
+        cache: &'func mut InstanceCache,
         ctx: Ctx,
     ) -> Self {
+        const { assert!(!mem::needs_drop::<Self>()) };
         Self {
-            value_stack,
+            regs,
             frame,
+            cache,
             ctx,
         }
     }
 
+    /// Returns the value of the `register`.
+    #[inline(always)]
+    fn get(&self, register: Register) -> UntypedValue {
+        let index = register.into_usize();
+        debug_assert!(
+            index < self.regs.len(),
+            "expect register {} to be within the function frame due to validation",
+            index,
+        );
+        // # Safety
+        //
+        // The `wasmi` bytecode translation only produces registers that are
+        // within the function frame and the function frame has been reserved
+        // on the value stack upon its initialization. Therefore this bounds
+        // check can be safely eliminated which is valuable since registers
+        // are accessed in the interpreter's hot path.
+        unsafe { *self.regs.get_unchecked(index) }
+    }
+
+    /// Returns the value of the `register` as `T`.
+    #[inline(always)]
+    fn get_as<T>(&self, register: Register) -> T
+    where
+        T: From<UntypedValue>,
+    {
+        T::from(self.get(register))
+    }
+
+    /// Sets the value of the `register` to `value`.
+    #[inline(always)]
+    fn set<T>(&mut self, register: Register, value: T)
+    where
+        T: Into<UntypedValue>,
+    {
+        let index = register.into_usize();
+        debug_assert!(
+            index < self.regs.len(),
+            "expect register {} to be within the function frame due to validation",
+            index,
+        );
+        // # Safety
+        //
+        // See [`InstructionExecutionContext::get`].
+        unsafe { *self.regs.get_unchecked_mut(index) = value.into() }
+    }
+
     /// Returns the default linear memory.
     ///
     /// # Panics
     ///
     /// If there is no default linear memory.
     fn default_memory(&mut self) -> Memory {
-        self.frame.default_memory(self.ctx.as_context())
+        self.cache.default_memory(self.ctx.as_context())
     }
 
     /// Returns the default linear memory.
@@ -146,25 +262,27 @@ where
     ///
     /// If there is no default linear memory.
     fn default_table(&mut self) -> Table {
-        self.frame.default_table(self.ctx.as_context())
+        self.cache.default_table(self.ctx.as_context())
     }
 
-    /// Returns the global variable at the given index.
+    /// Returns the bytes of the default linear memory.
     ///
     /// # Panics
     ///
-    /// If there is no global variable at the given index.
-    fn global(&self, global_index: GlobalIdx) -> Global {
-        self.frame
-            .instance
-            .get_global(self.ctx.as_context(), global_index.into_inner())
-            .unwrap_or_else(|| panic!("missing global at index {:?}", global_index))
+    /// If there is no default linear memory.
+    #[inline(always)]
+    fn default_memory_bytes(&mut self) -> &mut CachedMemoryBytes {
+        self.cache.default_memory_bytes(self.ctx.as_context_mut())
     }
 
-    /// Returns the local depth as `usize`.
-    fn convert_local_depth(local_depth: LocalIdx) -> usize {
-        // TODO: calculate the -1 offset at module compilation time.
-        (local_depth.into_inner() - 1) as usize
+    /// Returns the value cell of the global variable at the given index.
+    ///
+    /// # Panics
+    ///
+    /// If there is no global variable at the given index.
+    #[inline(always)]
+    fn global(&mut self, global_index: GlobalIdx) -> NonNull<UntypedValue> {
+        self.cache.global(self.ctx.as_context_mut(), global_index)
     }
 
     /// Calculates the effective address of a linear memory access.
@@ -181,6 +299,49 @@ where
             .map_err(Into::into)
     }
 
+    /// Reads the `buffer` from the default memory at the given `address`.
+    ///
+    /// # Note
+    ///
+    /// Reads go through the linear memory entity if it has a custom backend.
+    ///
+    /// # Errors
+    ///
+    /// If this operation accesses out of bounds linear memory.
+    #[inline(always)]
+    fn read_default_memory(&mut self, address: usize, buffer: &mut [u8]) -> Result<(), TrapCode> {
+        let cached = self.default_memory_bytes();
+        if !cached.reads_through_entity() {
+            return cached.read(address, buffer);
+        }
+        let memory = self.default_memory();
+        memory
+            .read(self.ctx.as_context(), address, buffer)
+            .map_err(|_| TrapCode::MemoryAccessOutOfBounds)
+    }
+
+    /// Writes the `bytes` to the default memory at the given `address`.
+    ///
+    /// # Note
+    ///
+    /// Writes go through the linear memory entity if it tracks dirty
+    /// pages or has a custom backend.
+    ///
+    /// # Errors
+    ///
+    /// If this operation accesses out of bounds linear memory.
+    #[inline(always)]
+    fn write_default_memory(&mut self, address: usize, bytes: &[u8]) -> Result<(), TrapCode> {
+        let cached = self.default_memory_bytes();
+        if !cached.writes_through_entity() {
+            return cached.write(address, bytes);
+        }
+        let memory = self.default_memory();
+        memory
+            .write(self.ctx.as_context_mut(), address, bytes)
+            .map_err(|_| TrapCode::MemoryAccessOutOfBounds)
+    }
+
     /// Loads a value of type `T` from the default memory at the given address offset.
     ///
     /// # Note
@@ -191,21 +352,17 @@ where
     /// - `i64.load`
     /// - `f32.load`
     /// - `f64.load`
-    fn execute_load<T>(&mut self, offset: Offset) -> Result<ExecutionOutcome, Trap>
+    fn execute_load<T>(&mut self, op: LoadOp) -> Result<ExecutionOutcome, Trap>
     where
         UntypedValue: From<T>,
         T: LittleEndianConvert,
     {
-        let memory = self.default_memory();
-        let entry = self.value_stack.last_mut();
-        let raw_address = u32::from(*entry);
-        let address = Self::effective_address(offset, raw_address)?;
+        let raw_address = self.get_as::<u32>(op.ptr);
+        let address = Self::effective_address(op.offset, raw_address)?;
         let mut bytes = <<T as LittleEndianConvert>::Bytes as Default>::default();
-        memory
-            .read(self.ctx.as_context(), address, bytes.as_mut())
-            .map_err(|_| TrapCode::MemoryAccessOutOfBounds)?;
+        self.read_default_memory(address, bytes.as_mut())?;
         let value = <T as LittleEndianConvert>::from_le_bytes(bytes);
-        *entry = value.into();
+        self.set(op.result, value);
         Ok(ExecutionOutcome::Continue)
     }
 
@@ -225,21 +382,17 @@ where
     /// - `i64.load_16u`
     /// - `i64.load_32s`
     /// - `i64.load_32u`
-    fn execute_load_extend<T, U>(&mut self, offset: Offset) -> Result<ExecutionOutcome, Trap>
+    fn execute_load_extend<T, U>(&mut self, op: LoadOp) -> Result<ExecutionOutcome, Trap>
     where
         T: ExtendInto<U> + LittleEndianConvert,
         UntypedValue: From<U>,
     {
-        let memory = self.default_memory();
-        let entry = self.value_stack.last_mut();
-        let raw_address = u32::from(*entry);
-        let address = Self::effective_address(offset, raw_address)?;
+        let raw_address = self.get_as::<u32>(op.ptr);
+        let address = Self::effective_address(op.offset, raw_address)?;
         let mut bytes = <<T as LittleEndianConvert>::Bytes as Default>::default();
-        memory
-            .read(self.ctx.as_context(), address, bytes.as_mut())
-            .map_err(|_| TrapCode::MemoryAccessOutOfBounds)?;
+        self.read_default_memory(address, bytes.as_mut())?;
         let extended = <T as LittleEndianConvert>::from_le_bytes(bytes).extend_into();
-        *entry = extended.into();
+        self.set(op.result, extended);
         Ok(ExecutionOutcome::Continue)
     }
 
@@ -253,18 +406,15 @@ where
     /// - `i64.store`
     /// - `f32.store`
     /// - `f64.store`
-    fn execute_store<T>(&mut self, offset: Offset) -> Result<ExecutionOutcome, Trap>
+    fn execute_store<T>(&mut self, op: StoreOp) -> Result<ExecutionOutcome, Trap>
     where
         T: LittleEndianConvert + From<UntypedValue>,
     {
-        let stack_value = self.value_stack.pop_as::<T>();
-        let raw_address = self.value_stack.pop_as::<u32>();
-        let address = Self::effective_address(offset, raw_address)?;
-        let memory = self.default_memory();
-        let bytes = <T as LittleEndianConvert>::into_le_bytes(stack_value);
-        memory
-            .write(self.ctx.as_context_mut(), address, bytes.as_ref())
-            .map_err(|_| TrapCode::MemoryAccessOutOfBounds)?;
+        let stored_value = self.get_as::<T>(op.value);
+        let raw_address = self.get_as::<u32>(op.ptr);
+        let address = Self::effective_address(op.offset, raw_address)?;
+        let bytes = <T as LittleEndianConvert>::into_le_bytes(stored_value);
+        self.write_default_memory(address, bytes.as_ref())?;
         Ok(ExecutionOutcome::Continue)
     }
 
@@ -279,70 +429,90 @@ where
     /// - `i64.store8`
     /// - `i64.store16`
     /// - `i64.store32`
-    fn execute_store_wrap<T, U>(&mut self, offset: Offset) -> Result<ExecutionOutcome, Trap>
+    fn execute_store_wrap<T, U>(&mut self, op: StoreOp) -> Result<ExecutionOutcome, Trap>
     where
         T: WrapInto<U> + From<UntypedValue>,
         U: LittleEndianConvert,
     {
-        let wrapped_value = self.value_stack.pop_as::<T>().wrap_into();
-        let raw_address = self.value_stack.pop_as::<u32>();
-        let address = Self::effective_address(offset, raw_address)?;
-        let memory = self.default_memory();
+        let wrapped_value = self.get_as::<T>(op.value).wrap_into();
+        let raw_address = self.get_as::<u32>(op.ptr);
+        let address = Self::effective_address(op.offset, raw_address)?;
         let bytes = <U as LittleEndianConvert>::into_le_bytes(wrapped_value);
-        memory
-            .write(self.ctx.as_context_mut(), address, bytes.as_ref())
-            .map_err(|_| TrapCode::MemoryAccessOutOfBounds)?;
+        self.write_default_memory(address, bytes.as_ref())?;
         Ok(ExecutionOutcome::Continue)
     }
 
+    #[inline(always)]
     fn execute_unary(
         &mut self,
+        op: UnaryOp,
         f: fn(UntypedValue) -> UntypedValue,
     ) -> Result<ExecutionOutcome, Trap> {
-        let entry = self.value_stack.last_mut();
-        *entry = f(*entry);
+        let input = self.get(op.input);
+        self.set(op.result, f(input));
         Ok(ExecutionOutcome::Continue)
     }
 
+    #[inline(always)]
     fn try_execute_unary(
         &mut self,
+        op: UnaryOp,
         f: fn(UntypedValue) -> Result<UntypedValue, TrapCode>,
     ) -> Result<ExecutionOutcome, Trap> {
-        let entry = self.value_stack.last_mut();
-        *entry = f(*entry)?;
+        let input = self.get(op.input);
+        self.set(op.result, f(input)?);
         Ok(ExecutionOutcome::Continue)
     }
 
+    #[inline(always)]
     fn execute_binary(
         &mut self,
+        op: BinaryOp,
         f: fn(UntypedValue, UntypedValue) -> UntypedValue,
     ) -> Result<ExecutionOutcome, Trap> {
-        let right = self.value_stack.pop();
-        let entry = self.value_stack.last_mut();
-        let left = *entry;
-        *entry = f(left, right);
+        let lhs = self.get(op.lhs);
+        let rhs = self.get(op.rhs);
+        self.set(op.result, f(lhs, rhs));
         Ok(ExecutionOutcome::Continue)
     }
 
+    #[inline(always)]
     fn try_execute_binary(
         &mut self,
+        op: BinaryOp,
         f: fn(UntypedValue, UntypedValue) -> Result<UntypedValue, TrapCode>,
     ) -> Result<ExecutionOutcome, Trap> {
-        let right = self.value_stack.pop();
-        let entry = self.value_stack.last_mut();
-        let left = *entry;
-        *entry = f(left, right)?;
+        let lhs = self.get(op.lhs);
+        let rhs = self.get(op.rhs);
+        self.set(op.result, f(lhs, rhs)?);
         Ok(ExecutionOutcome::Continue)
     }
 
-    fn execute_reinterpret<T, U>(&mut self) -> Result<ExecutionOutcome, Trap>
-    where
-        UntypedValue: From<U>,
-        T: From<UntypedValue>,
-    {
-        // Nothing to do for `wasmi` bytecode.
+    #[inline(always)]
+    fn execute_imm(
+        &mut self,
+        op: ImmOp,
+        f: fn(UntypedValue, i32) -> UntypedValue,
+    ) -> Result<ExecutionOutcome, Trap> {
+        let input = self.get(op.input);
+        self.set(op.result, f(input, op.imm));
         Ok(ExecutionOutcome::Continue)
     }
+
+    #[inline(always)]
+    fn execute_br_cmp(
+        &mut self,
+        op: BranchCmpOp,
+        f: fn(UntypedValue, UntypedValue) -> UntypedValue,
+    ) -> Result<ExecutionOutcome, Trap> {
+        let lhs = self.get(op.lhs);
+        let rhs = self.get(op.rhs);
+        if bool::from(f(lhs, rhs)) {
+            Ok(ExecutionOutcome::Branch(op.target))
+        } else {
+            Ok(ExecutionOutcome::Continue)
+        }
+    }
 }
 
 impl<'engine, 'func, Ctx> VisitInstruction for InstructionExecutionContext<'engine, 'func, Ctx>
@@ -359,99 +529,161 @@ where
         Ok(ExecutionOutcome::Branch(target))
     }
 
-    fn visit_br_if_eqz(&mut self, target: Target) -> Self::Outcome {
-        let condition = self.value_stack.pop_as();
-        if condition {
+    fn visit_br_if_eqz(&mut self, condition: Register, target: Target) -> Self::Outcome {
+        if self.get_as::<bool>(condition) {
             Ok(ExecutionOutcome::Continue)
         } else {
             Ok(ExecutionOutcome::Branch(target))
         }
     }
 
-    fn visit_br_if_nez(&mut self, target: Target) -> Self::Outcome {
-        let condition = self.value_stack.pop_as();
-        if condition {
+    fn visit_br_if_nez(&mut self, condition: Register, target: Target) -> Self::Outcome {
+        if self.get_as::<bool>(condition) {
             Ok(ExecutionOutcome::Branch(target))
         } else {
             Ok(ExecutionOutcome::Continue)
         }
     }
 
-    fn visit_return_if_nez(&mut self, drop_keep: DropKeep) -> Self::Outcome {
-        let condition = self.value_stack.pop_as();
-        if condition {
-            Ok(ExecutionOutcome::Return(drop_keep))
+    fn visit_return_if_nez(&mut self, condition: Register, results: RegisterSpan) -> Self::Outcome {
+        if self.get_as::<bool>(condition) {
+            Ok(ExecutionOutcome::Return(results))
         } else {
             Ok(ExecutionOutcome::Continue)
         }
     }
 
-    fn visit_br_table(&mut self, br_table: BrTable) -> Self::Outcome {
-        let index: u32 = self.value_stack.pop_as();
-        match br_table.branch_or_default(index as usize) {
-            Instruction::Br(target) => Ok(ExecutionOutcome::Branch(*target)),
-            Instruction::Return(drop_keep) => Ok(ExecutionOutcome::Return(*drop_keep)),
-            unexpected => panic!(
-                "encountered unexpected `br_table` branch arm: {:?}",
-                unexpected
-            ),
-        }
+    fn visit_br_if_i32_eq(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i32_eq)
     }
 
-    fn visit_ret(&mut self, drop_keep: DropKeep) -> Self::Outcome {
-        Ok(ExecutionOutcome::Return(drop_keep))
+    fn visit_br_if_i32_ne(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i32_ne)
     }
 
-    fn visit_get_local(&mut self, local_depth: LocalIdx) -> Self::Outcome {
-        let local_depth = Self::convert_local_depth(local_depth);
-        let value = self.value_stack.peek(local_depth);
-        self.value_stack.push(value);
-        Ok(ExecutionOutcome::Continue)
+    fn visit_br_if_i32_lt_s(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i32_lt_s)
     }
 
-    fn visit_set_local(&mut self, local_depth: LocalIdx) -> Self::Outcome {
-        let local_depth = Self::convert_local_depth(local_depth);
-        let new_value = self.value_stack.pop();
-        *self.value_stack.peek_mut(local_depth) = new_value;
-        Ok(ExecutionOutcome::Continue)
+    fn visit_br_if_i32_lt_u(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i32_lt_u)
+    }
+
+    fn visit_br_if_i32_gt_s(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i32_gt_s)
+    }
+
+    fn visit_br_if_i32_gt_u(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i32_gt_u)
+    }
+
+    fn visit_br_if_i32_le_s(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i32_le_s)
+    }
+
+    fn visit_br_if_i32_le_u(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i32_le_u)
     }
 
-    fn visit_tee_local(&mut self, local_depth: LocalIdx) -> Self::Outcome {
-        let local_depth = Self::convert_local_depth(local_depth);
-        let new_value = self.value_stack.last();
-        *self.value_stack.peek_mut(local_depth) = new_value;
+    fn visit_br_if_i32_ge_s(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i32_ge_s)
+    }
+
+    fn visit_br_if_i32_ge_u(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i32_ge_u)
+    }
+
+    fn visit_br_if_i64_eq(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i64_eq)
+    }
+
+    fn visit_br_if_i64_ne(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i64_ne)
+    }
+
+    fn visit_br_if_i64_lt_s(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i64_lt_s)
+    }
+
+    fn visit_br_if_i64_lt_u(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i64_lt_u)
+    }
+
+    fn visit_br_if_i64_gt_s(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i64_gt_s)
+    }
+
+    fn visit_br_if_i64_gt_u(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i64_gt_u)
+    }
+
+    fn visit_br_if_i64_le_s(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i64_le_s)
+    }
+
+    fn visit_br_if_i64_le_u(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i64_le_u)
+    }
+
+    fn visit_br_if_i64_ge_s(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i64_ge_s)
+    }
+
+    fn visit_br_if_i64_ge_u(&mut self, op: BranchCmpOp) -> Self::Outcome {
+        self.execute_br_cmp(op, UntypedValue::i64_ge_u)
+    }
+
+    fn visit_br_table(&mut self, index: Register, br_table: BrTable) -> Self::Outcome {
+        let index = self.get_as::<u32>(index);
+        Ok(ExecutionOutcome::Branch(
+            br_table.target_or_default(index as usize),
+        ))
+    }
+
+    fn visit_ret(&mut self, results: RegisterSpan) -> Self::Outcome {
+        Ok(ExecutionOutcome::Return(results))
+    }
+
+    fn visit_copy(&mut self, op: UnaryOp) -> Self::Outcome {
+        let value = self.get(op.input);
+        self.set(op.result, value);
         Ok(ExecutionOutcome::Continue)
     }
 
-    fn visit_get_global(&mut self, global_index: GlobalIdx) -> Self::Outcome {
-        let global_value = self.global(global_index).get(self.ctx.as_context());
-        self.value_stack.push(global_value);
+    fn visit_get_global(&mut self, result: Register, global_index: GlobalIdx) -> Self::Outcome {
+        let cell = self.global(global_index);
+        // # Safety
+        //
+        // The [`InstanceCache`] resets its cached value cells whenever
+        // the global variables of the store could have been reallocated.
+        let global_value = unsafe { *cell.as_ptr() };
+        self.set(result, global_value);
         Ok(ExecutionOutcome::Continue)
     }
 
-    fn visit_set_global(&mut self, global_index: GlobalIdx) -> Self::Outcome {
-        let global = self.global(global_index);
-        let new_value = self
-            .value_stack
-            .pop()
-            .with_type(global.value_type(self.ctx.as_context()));
-        global
-            .set(self.ctx.as_context_mut(), new_value)
-            .unwrap_or_else(|error| panic!("encountered type mismatch upon global_set: {}", error));
+    fn visit_set_global(&mut self, global_index: GlobalIdx, input: Register) -> Self::Outcome {
+        let cell = self.global(global_index);
+        let new_value = self.get(input);
+        // # Safety
+        //
+        // - See [`InstructionExecutionContext::visit_get_global`].
+        // - Wasm validation guarantees that the global variable
+        //   is mutable and that the new value is of its type.
+        unsafe { *cell.as_ptr() = new_value };
         Ok(ExecutionOutcome::Continue)
     }
 
-    fn visit_call(&mut self, func_index: FuncIdx) -> Self::Outcome {
-        let func = self
-            .frame
-            .instance
-            .get_func(self.ctx.as_context_mut(), func_index.into_inner())
-            .unwrap_or_else(|| panic!("missing function at index {:?}", func_index));
-        Ok(ExecutionOutcome::ExecuteCall(func))
+    fn visit_call(&mut self, func_index: FuncIdx, params: Register) -> Self::Outcome {
+        Ok(ExecutionOutcome::ExecuteCall(func_index, params))
     }
 
-    fn visit_call_indirect(&mut self, signature_index: SignatureIdx) -> Self::Outcome {
-        let func_index: u32 = self.value_stack.pop_as();
+    fn visit_call_indirect(
+        &mut self,
+        signature_index: SignatureIdx,
+        index: Register,
+        params: Register,
+    ) -> Self::Outcome {
+        let func_index = self.get_as::<u32>(index);
         let table = self.default_table();
         let func = table
             .get(self.ctx.as_context(), func_index as usize)
@@ -471,683 +703,674 @@ where
         if actual_signature != expected_signature {
             return Err(TrapCode::UnexpectedSignature).map_err(Into::into);
         }
-        Ok(ExecutionOutcome::ExecuteCall(func))
+        Ok(ExecutionOutcome::ExecuteIndirectCall(func, params))
     }
 
-    fn visit_const(&mut self, bytes: UntypedValue) -> Self::Outcome {
-        self.value_stack.push(bytes);
-        Ok(ExecutionOutcome::Continue)
-    }
-
-    fn visit_drop(&mut self) -> Self::Outcome {
-        let _ = self.value_stack.pop();
-        Ok(ExecutionOutcome::Continue)
-    }
-
-    fn visit_select(&mut self) -> Self::Outcome {
-        self.value_stack.pop2_eval(|e1, e2, e3| {
-            let condition = <bool as From<UntypedValue>>::from(e3);
-            let result = if condition { *e1 } else { e2 };
-            *e1 = result;
-        });
+    fn visit_select(
+        &mut self,
+        result: Register,
+        condition: Register,
+        if_false: Register,
+    ) -> Self::Outcome {
+        // Note: The `result` register already holds the value selected upon a true `condition`.
+        if !self.get_as::<bool>(condition) {
+            let value = self.get(if_false);
+            self.set(result, value);
+        }
         Ok(ExecutionOutcome::Continue)
     }
 
-    fn visit_current_memory(&mut self) -> Self::Outcome {
+    fn visit_current_memory(&mut self, result: Register) -> Self::Outcome {
         let memory = self.default_memory();
-        let result = memory.current_pages(self.ctx.as_context()).0 as u32;
-        self.value_stack.push(result);
+        let size = memory.current_pages(self.ctx.as_context()).0 as u32;
+        self.set(result, size);
         Ok(ExecutionOutcome::Continue)
     }
 
-    fn visit_grow_memory(&mut self) -> Self::Outcome {
-        let pages: u32 = self.value_stack.pop_as();
+    fn visit_grow_memory(&mut self, op: UnaryOp) -> Self::Outcome {
+        let pages = self.get_as::<u32>(op.input);
         let memory = self.default_memory();
         let new_size = match memory.grow(self.ctx.as_context_mut(), Pages(pages as usize)) {
-            Ok(Pages(old_size)) => old_size as u32,
+            Ok(Pages(old_size)) => {
+                // Note: Growing the linear memory may have reallocated its bytes.
+                self.cache.reset_default_memory_bytes();
+                old_size as u32
+            }
             Err(_) => {
                 // Note: The WebAssembly spec demands to return `0xFFFF_FFFF`
                 //       in case of failure for this instruction.
                 u32::MAX
             }
         };
-        self.value_stack.push(new_size);
+        self.set(op.result, new_size);
         Ok(ExecutionOutcome::Continue)
     }
 
-    fn visit_i32_load(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_load::<i32>(offset)
-    }
-
-    fn visit_i64_load(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_load::<i64>(offset)
-    }
-
-    fn visit_f32_load(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_load::<F32>(offset)
+    fn visit_i32_load(&mut self, op: LoadOp) -> Self::Outcome {
+        self.execute_load::<i32>(op)
     }
 
-    fn visit_f64_load(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_load::<F64>(offset)
+    fn visit_i64_load(&mut self, op: LoadOp) -> Self::Outcome {
+        self.execute_load::<i64>(op)
     }
 
-    fn visit_i32_load_i8(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_load_extend::<i8, i32>(offset)
+    fn visit_f32_load(&mut self, op: LoadOp) -> Self::Outcome {
+        self.execute_load::<F32>(op)
     }
 
-    fn visit_i32_load_u8(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_load_extend::<u8, i32>(offset)
+    fn visit_f64_load(&mut self, op: LoadOp) -> Self::Outcome {
+        self.execute_load::<F64>(op)
     }
 
-    fn visit_i32_load_i16(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_load_extend::<i16, i32>(offset)
+    fn visit_i32_load_i8(&mut self, op: LoadOp) -> Self::Outcome {
+        self.execute_load_extend::<i8, i32>(op)
     }
 
-    fn visit_i32_load_u16(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_load_extend::<u16, i32>(offset)
+    fn visit_i32_load_u8(&mut self, op: LoadOp) -> Self::Outcome {
+        self.execute_load_extend::<u8, i32>(op)
     }
 
-    fn visit_i64_load_i8(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_load_extend::<i8, i64>(offset)
+    fn visit_i32_load_i16(&mut self, op: LoadOp) -> Self::Outcome {
+        self.execute_load_extend::<i16, i32>(op)
     }
 
-    fn visit_i64_load_u8(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_load_extend::<u8, i64>(offset)
+    fn visit_i32_load_u16(&mut self, op: LoadOp) -> Self::Outcome {
+        self.execute_load_extend::<u16, i32>(op)
     }
 
-    fn visit_i64_load_i16(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_load_extend::<i16, i64>(offset)
+    fn visit_i64_load_i8(&mut self, op: LoadOp) -> Self::Outcome {
+        self.execute_load_extend::<i8, i64>(op)
     }
 
-    fn visit_i64_load_u16(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_load_extend::<u16, i64>(offset)
+    fn visit_i64_load_u8(&mut self, op: LoadOp) -> Self::Outcome {
+        self.execute_load_extend::<u8, i64>(op)
     }
 
-    fn visit_i64_load_i32(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_load_extend::<i32, i64>(offset)
+    fn visit_i64_load_i16(&mut self, op: LoadOp) -> Self::Outcome {
+        self.execute_load_extend::<i16, i64>(op)
     }
 
-    fn visit_i64_load_u32(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_load_extend::<u32, i64>(offset)
+    fn visit_i64_load_u16(&mut self, op: LoadOp) -> Self::Outcome {
+        self.execute_load_extend::<u16, i64>(op)
     }
 
-    fn visit_i32_store(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_store::<i32>(offset)
+    fn visit_i64_load_i32(&mut self, op: LoadOp) -> Self::Outcome {
+        self.execute_load_extend::<i32, i64>(op)
     }
 
-    fn visit_i64_store(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_store::<i64>(offset)
+    fn visit_i64_load_u32(&mut self, op: LoadOp) -> Self::Outcome {
+        self.execute_load_extend::<u32, i64>(op)
     }
 
-    fn visit_f32_store(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_store::<F32>(offset)
+    fn visit_i32_store(&mut self, op: StoreOp) -> Self::Outcome {
+        self.execute_store::<i32>(op)
     }
 
-    fn visit_f64_store(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_store::<F64>(offset)
+    fn visit_i64_store(&mut self, op: StoreOp) -> Self::Outcome {
+        self.execute_store::<i64>(op)
     }
 
-    fn visit_i32_store_8(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_store_wrap::<i32, i8>(offset)
+    fn visit_f32_store(&mut self, op: StoreOp) -> Self::Outcome {
+        self.execute_store::<F32>(op)
     }
 
-    fn visit_i32_store_16(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_store_wrap::<i32, i16>(offset)
+    fn visit_f64_store(&mut self, op: StoreOp) -> Self::Outcome {
+        self.execute_store::<F64>(op)
     }
 
-    fn visit_i64_store_8(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_store_wrap::<i64, i8>(offset)
+    fn visit_i32_store_8(&mut self, op: StoreOp) -> Self::Outcome {
+        self.execute_store_wrap::<i32, i8>(op)
     }
 
-    fn visit_i64_store_16(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_store_wrap::<i64, i16>(offset)
+    fn visit_i32_store_16(&mut self, op: StoreOp) -> Self::Outcome {
+        self.execute_store_wrap::<i32, i16>(op)
     }
 
-    fn visit_i64_store_32(&mut self, offset: Offset) -> Self::Outcome {
-        self.execute_store_wrap::<i64, i32>(offset)
+    fn visit_i64_store_8(&mut self, op: StoreOp) -> Self::Outcome {
+        self.execute_store_wrap::<i64, i8>(op)
     }
 
-    fn visit_i32_eqz(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i32_eqz)
+    fn visit_i64_store_16(&mut self, op: StoreOp) -> Self::Outcome {
+        self.execute_store_wrap::<i64, i16>(op)
     }
 
-    fn visit_i32_eq(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_eq)
+    fn visit_i64_store_32(&mut self, op: StoreOp) -> Self::Outcome {
+        self.execute_store_wrap::<i64, i32>(op)
     }
 
-    fn visit_i32_ne(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_ne)
+    fn visit_i32_eqz(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i32_eqz)
     }
 
-    fn visit_i32_lt_s(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_lt_s)
+    fn visit_i32_eq(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_eq)
     }
 
-    fn visit_i32_lt_u(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_lt_u)
+    fn visit_i32_ne(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_ne)
     }
 
-    fn visit_i32_gt_s(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_gt_s)
+    fn visit_i32_lt_s(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_lt_s)
     }
 
-    fn visit_i32_gt_u(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_gt_u)
+    fn visit_i32_lt_u(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_lt_u)
     }
 
-    fn visit_i32_le_s(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_le_s)
+    fn visit_i32_gt_s(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_gt_s)
     }
 
-    fn visit_i32_le_u(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_le_u)
+    fn visit_i32_gt_u(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_gt_u)
     }
 
-    fn visit_i32_ge_s(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_ge_s)
+    fn visit_i32_le_s(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_le_s)
     }
 
-    fn visit_i32_ge_u(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_ge_u)
+    fn visit_i32_le_u(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_le_u)
     }
 
-    fn visit_i64_eqz(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i64_eqz)
+    fn visit_i32_ge_s(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_ge_s)
     }
 
-    fn visit_i64_eq(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_eq)
+    fn visit_i32_ge_u(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_ge_u)
     }
 
-    fn visit_i64_ne(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_ne)
+    fn visit_i64_eqz(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i64_eqz)
     }
 
-    fn visit_i64_lt_s(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_lt_s)
+    fn visit_i64_eq(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_eq)
     }
 
-    fn visit_i64_lt_u(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_lt_u)
+    fn visit_i64_ne(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_ne)
     }
 
-    fn visit_i64_gt_s(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_gt_s)
+    fn visit_i64_lt_s(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_lt_s)
     }
 
-    fn visit_i64_gt_u(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_gt_u)
+    fn visit_i64_lt_u(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_lt_u)
     }
 
-    fn visit_i64_le_s(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_le_s)
+    fn visit_i64_gt_s(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_gt_s)
     }
 
-    fn visit_i64_le_u(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_le_u)
+    fn visit_i64_gt_u(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_gt_u)
     }
 
-    fn visit_i64_ge_s(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_ge_s)
+    fn visit_i64_le_s(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_le_s)
     }
 
-    fn visit_i64_ge_u(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_ge_u)
+    fn visit_i64_le_u(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_le_u)
     }
 
-    fn visit_f32_eq(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f32_eq)
+    fn visit_i64_ge_s(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_ge_s)
     }
 
-    fn visit_f32_ne(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f32_ne)
+    fn visit_i64_ge_u(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_ge_u)
     }
 
-    fn visit_f32_lt(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f32_lt)
+    fn visit_f32_eq(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f32_eq)
     }
 
-    fn visit_f32_gt(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f32_gt)
+    fn visit_f32_ne(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f32_ne)
     }
 
-    fn visit_f32_le(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f32_le)
+    fn visit_f32_lt(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f32_lt)
     }
 
-    fn visit_f32_ge(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f32_ge)
+    fn visit_f32_gt(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f32_gt)
     }
 
-    fn visit_f64_eq(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f64_eq)
+    fn visit_f32_le(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f32_le)
     }
 
-    fn visit_f64_ne(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f64_ne)
+    fn visit_f32_ge(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f32_ge)
     }
 
-    fn visit_f64_lt(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f64_lt)
+    fn visit_f64_eq(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f64_eq)
     }
 
-    fn visit_f64_gt(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f64_gt)
+    fn visit_f64_ne(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f64_ne)
     }
 
-    fn visit_f64_le(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f64_le)
+    fn visit_f64_lt(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f64_lt)
     }
 
-    fn visit_f64_ge(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f64_ge)
+    fn visit_f64_gt(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f64_gt)
     }
 
-    fn visit_i32_clz(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i32_clz)
+    fn visit_f64_le(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f64_le)
     }
 
-    fn visit_i32_ctz(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i32_ctz)
+    fn visit_f64_ge(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f64_ge)
     }
 
-    fn visit_i32_popcnt(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i32_popcnt)
+    fn visit_i32_clz(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i32_clz)
     }
 
-    fn visit_i32_add(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_add)
+    fn visit_i32_ctz(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i32_ctz)
     }
 
-    fn visit_i32_sub(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_sub)
+    fn visit_i32_popcnt(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i32_popcnt)
     }
 
-    fn visit_i32_mul(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_mul)
+    fn visit_i32_add(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_add)
     }
 
-    fn visit_i32_div_s(&mut self) -> Self::Outcome {
-        self.try_execute_binary(UntypedValue::i32_div_s)
+    fn visit_i32_add_imm(&mut self, op: ImmOp) -> Self::Outcome {
+        self.execute_imm(op, |input, imm| input.i32_add(imm.into()))
     }
 
-    fn visit_i32_div_u(&mut self) -> Self::Outcome {
-        self.try_execute_binary(UntypedValue::i32_div_u)
+    fn visit_i32_sub(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_sub)
     }
 
-    fn visit_i32_rem_s(&mut self) -> Self::Outcome {
-        self.try_execute_binary(UntypedValue::i32_rem_s)
+    fn visit_i32_mul(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_mul)
     }
 
-    fn visit_i32_rem_u(&mut self) -> Self::Outcome {
-        self.try_execute_binary(UntypedValue::i32_rem_u)
+    fn visit_i32_div_s(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.try_execute_binary(op, UntypedValue::i32_div_s)
     }
 
-    fn visit_i32_and(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_and)
+    fn visit_i32_div_u(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.try_execute_binary(op, UntypedValue::i32_div_u)
     }
 
-    fn visit_i32_or(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_or)
+    fn visit_i32_rem_s(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.try_execute_binary(op, UntypedValue::i32_rem_s)
     }
 
-    fn visit_i32_xor(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_xor)
+    fn visit_i32_rem_u(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.try_execute_binary(op, UntypedValue::i32_rem_u)
     }
 
-    fn visit_i32_shl(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_shl)
+    fn visit_i32_and(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_and)
     }
 
-    fn visit_i32_shr_s(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_shr_s)
+    fn visit_i32_or(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_or)
     }
 
-    fn visit_i32_shr_u(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_shr_u)
+    fn visit_i32_xor(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_xor)
     }
 
-    fn visit_i32_rotl(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_rotl)
+    fn visit_i32_shl(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_shl)
     }
 
-    fn visit_i32_rotr(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i32_rotr)
+    fn visit_i32_shr_s(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_shr_s)
     }
 
-    fn visit_i64_clz(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i64_clz)
+    fn visit_i32_shr_u(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_shr_u)
     }
 
-    fn visit_i64_ctz(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i64_ctz)
+    fn visit_i32_rotl(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_rotl)
     }
 
-    fn visit_i64_popcnt(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i64_popcnt)
+    fn visit_i32_rotr(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i32_rotr)
     }
 
-    fn visit_i64_add(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_add)
+    fn visit_i64_clz(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i64_clz)
     }
 
-    fn visit_i64_sub(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_sub)
+    fn visit_i64_ctz(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i64_ctz)
     }
 
-    fn visit_i64_mul(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_mul)
+    fn visit_i64_popcnt(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i64_popcnt)
     }
 
-    fn visit_i64_div_s(&mut self) -> Self::Outcome {
-        self.try_execute_binary(UntypedValue::i64_div_s)
+    fn visit_i64_add(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_add)
     }
 
-    fn visit_i64_div_u(&mut self) -> Self::Outcome {
-        self.try_execute_binary(UntypedValue::i64_div_u)
+    fn visit_i64_add_imm(&mut self, op: ImmOp) -> Self::Outcome {
+        self.execute_imm(op, |input, imm| input.i64_add(i64::from(imm).into()))
     }
 
-    fn visit_i64_rem_s(&mut self) -> Self::Outcome {
-        self.try_execute_binary(UntypedValue::i64_rem_s)
+    fn visit_i64_sub(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_sub)
     }
 
-    fn visit_i64_rem_u(&mut self) -> Self::Outcome {
-        self.try_execute_binary(UntypedValue::i64_rem_u)
+    fn visit_i64_mul(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_mul)
     }
 
-    fn visit_i64_and(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_and)
+    fn visit_i64_div_s(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.try_execute_binary(op, UntypedValue::i64_div_s)
     }
 
-    fn visit_i64_or(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_or)
+    fn visit_i64_div_u(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.try_execute_binary(op, UntypedValue::i64_div_u)
     }
 
-    fn visit_i64_xor(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_xor)
+    fn visit_i64_rem_s(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.try_execute_binary(op, UntypedValue::i64_rem_s)
     }
 
-    fn visit_i64_shl(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_shl)
+    fn visit_i64_rem_u(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.try_execute_binary(op, UntypedValue::i64_rem_u)
     }
 
-    fn visit_i64_shr_s(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_shr_s)
+    fn visit_i64_and(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_and)
     }
 
-    fn visit_i64_shr_u(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_shr_u)
+    fn visit_i64_or(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_or)
     }
 
-    fn visit_i64_rotl(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_rotl)
+    fn visit_i64_xor(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_xor)
     }
 
-    fn visit_i64_rotr(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::i64_rotr)
+    fn visit_i64_shl(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_shl)
     }
 
-    fn visit_f32_abs(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f32_abs)
+    fn visit_i64_shr_s(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_shr_s)
     }
 
-    fn visit_f32_neg(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f32_neg)
+    fn visit_i64_shr_u(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_shr_u)
     }
 
-    fn visit_f32_ceil(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f32_ceil)
+    fn visit_i64_rotl(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_rotl)
     }
 
-    fn visit_f32_floor(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f32_floor)
+    fn visit_i64_rotr(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::i64_rotr)
     }
 
-    fn visit_f32_trunc(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f32_trunc)
+    fn visit_f32_abs(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f32_abs)
     }
 
-    fn visit_f32_nearest(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f32_nearest)
+    fn visit_f32_neg(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f32_neg)
     }
 
-    fn visit_f32_sqrt(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f32_sqrt)
+    fn visit_f32_ceil(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f32_ceil)
     }
 
-    fn visit_f32_add(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f32_add)
+    fn visit_f32_floor(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f32_floor)
     }
 
-    fn visit_f32_sub(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f32_sub)
+    fn visit_f32_trunc(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f32_trunc)
     }
 
-    fn visit_f32_mul(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f32_mul)
+    fn visit_f32_nearest(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f32_nearest)
     }
 
-    fn visit_f32_div(&mut self) -> Self::Outcome {
-        self.try_execute_binary(UntypedValue::f32_div)
+    fn visit_f32_sqrt(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f32_sqrt)
     }
 
-    fn visit_f32_min(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f32_min)
+    fn visit_f32_add(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f32_add)
     }
 
-    fn visit_f32_max(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f32_max)
+    fn visit_f32_sub(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f32_sub)
     }
 
-    fn visit_f32_copysign(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f32_copysign)
+    fn visit_f32_mul(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f32_mul)
     }
 
-    fn visit_f64_abs(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f64_abs)
+    fn visit_f32_div(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.try_execute_binary(op, UntypedValue::f32_div)
     }
 
-    fn visit_f64_neg(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f64_neg)
+    fn visit_f32_min(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f32_min)
     }
 
-    fn visit_f64_ceil(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f64_ceil)
+    fn visit_f32_max(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f32_max)
     }
 
-    fn visit_f64_floor(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f64_floor)
+    fn visit_f32_copysign(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f32_copysign)
     }
 
-    fn visit_f64_trunc(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f64_trunc)
+    fn visit_f64_abs(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f64_abs)
     }
 
-    fn visit_f64_nearest(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f64_nearest)
+    fn visit_f64_neg(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f64_neg)
     }
 
-    fn visit_f64_sqrt(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f64_sqrt)
+    fn visit_f64_ceil(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f64_ceil)
     }
 
-    fn visit_f64_add(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f64_add)
+    fn visit_f64_floor(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f64_floor)
     }
 
-    fn visit_f64_sub(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f64_sub)
+    fn visit_f64_trunc(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f64_trunc)
     }
 
-    fn visit_f64_mul(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f64_mul)
+    fn visit_f64_nearest(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f64_nearest)
     }
 
-    fn visit_f64_div(&mut self) -> Self::Outcome {
-        self.try_execute_binary(UntypedValue::f64_div)
+    fn visit_f64_sqrt(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f64_sqrt)
     }
 
-    fn visit_f64_min(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f64_min)
+    fn visit_f64_add(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f64_add)
     }
 
-    fn visit_f64_max(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f64_max)
+    fn visit_f64_sub(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f64_sub)
     }
 
-    fn visit_f64_copysign(&mut self) -> Self::Outcome {
-        self.execute_binary(UntypedValue::f64_copysign)
+    fn visit_f64_mul(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f64_mul)
     }
 
-    fn visit_i32_wrap_i64(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i32_wrap_i64)
+    fn visit_f64_div(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.try_execute_binary(op, UntypedValue::f64_div)
     }
 
-    fn visit_i32_trunc_f32(&mut self) -> Self::Outcome {
-        self.try_execute_unary(UntypedValue::i32_trunc_f32_s)
+    fn visit_f64_min(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f64_min)
     }
 
-    fn visit_u32_trunc_f32(&mut self) -> Self::Outcome {
-        self.try_execute_unary(UntypedValue::i32_trunc_f32_u)
+    fn visit_f64_max(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f64_max)
     }
 
-    fn visit_i32_trunc_f64(&mut self) -> Self::Outcome {
-        self.try_execute_unary(UntypedValue::i32_trunc_f64_s)
+    fn visit_f64_copysign(&mut self, op: BinaryOp) -> Self::Outcome {
+        self.execute_binary(op, UntypedValue::f64_copysign)
     }
 
-    fn visit_u32_trunc_f64(&mut self) -> Self::Outcome {
-        self.try_execute_unary(UntypedValue::i32_trunc_f64_u)
+    fn visit_i32_wrap_i64(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i32_wrap_i64)
     }
 
-    fn visit_i64_extend_i32(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i64_extend_i32_s)
+    fn visit_i32_trunc_f32(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.try_execute_unary(op, UntypedValue::i32_trunc_f32_s)
     }
 
-    fn visit_i64_extend_u32(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i64_extend_i32_u)
+    fn visit_u32_trunc_f32(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.try_execute_unary(op, UntypedValue::i32_trunc_f32_u)
     }
 
-    fn visit_i64_trunc_f32(&mut self) -> Self::Outcome {
-        self.try_execute_unary(UntypedValue::i64_trunc_f32_s)
+    fn visit_i32_trunc_f64(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.try_execute_unary(op, UntypedValue::i32_trunc_f64_s)
     }
 
-    fn visit_u64_trunc_f32(&mut self) -> Self::Outcome {
-        self.try_execute_unary(UntypedValue::i64_trunc_f32_u)
+    fn visit_u32_trunc_f64(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.try_execute_unary(op, UntypedValue::i32_trunc_f64_u)
     }
 
-    fn visit_i64_trunc_f64(&mut self) -> Self::Outcome {
-        self.try_execute_unary(UntypedValue::i64_trunc_f64_s)
+    fn visit_i64_extend_i32(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i64_extend_i32_s)
     }
 
-    fn visit_u64_trunc_f64(&mut self) -> Self::Outcome {
-        self.try_execute_unary(UntypedValue::i64_trunc_f64_u)
+    fn visit_i64_extend_u32(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i64_extend_i32_u)
     }
 
-    fn visit_f32_convert_i32(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f32_convert_i32_s)
+    fn visit_i64_trunc_f32(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.try_execute_unary(op, UntypedValue::i64_trunc_f32_s)
     }
 
-    fn visit_f32_convert_u32(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f32_convert_i32_u)
+    fn visit_u64_trunc_f32(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.try_execute_unary(op, UntypedValue::i64_trunc_f32_u)
     }
 
-    fn visit_f32_convert_i64(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f32_convert_i64_s)
+    fn visit_i64_trunc_f64(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.try_execute_unary(op, UntypedValue::i64_trunc_f64_s)
     }
 
-    fn visit_f32_convert_u64(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f32_convert_i64_u)
+    fn visit_u64_trunc_f64(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.try_execute_unary(op, UntypedValue::i64_trunc_f64_u)
     }
 
-    fn visit_f32_demote_f64(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f32_demote_f64)
+    fn visit_f32_convert_i32(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f32_convert_i32_s)
     }
 
-    fn visit_f64_convert_i32(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f64_convert_i32_s)
+    fn visit_f32_convert_u32(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f32_convert_i32_u)
     }
 
-    fn visit_f64_convert_u32(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f64_convert_i32_u)
+    fn visit_f32_convert_i64(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f32_convert_i64_s)
     }
 
-    fn visit_f64_convert_i64(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f64_convert_i64_s)
+    fn visit_f32_convert_u64(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f32_convert_i64_u)
     }
 
-    fn visit_f64_convert_u64(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f64_convert_i64_u)
+    fn visit_f32_demote_f64(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f32_demote_f64)
     }
 
-    fn visit_f64_promote_f32(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::f64_promote_f32)
+    fn visit_f64_convert_i32(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f64_convert_i32_s)
     }
 
-    fn visit_i32_reinterpret_f32(&mut self) -> Self::Outcome {
-        self.execute_reinterpret::<F32, i32>()
+    fn visit_f64_convert_u32(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f64_convert_i32_u)
     }
 
-    fn visit_i64_reinterpret_f64(&mut self) -> Self::Outcome {
-        self.execute_reinterpret::<F64, i64>()
+    fn visit_f64_convert_i64(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f64_convert_i64_s)
     }
 
-    fn visit_f32_reinterpret_i32(&mut self) -> Self::Outcome {
-        self.execute_reinterpret::<i32, F32>()
+    fn visit_f64_convert_u64(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f64_convert_i64_u)
     }
 
-    fn visit_f64_reinterpret_i64(&mut self) -> Self::Outcome {
-        self.execute_reinterpret::<i64, F64>()
+    fn visit_f64_promote_f32(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::f64_promote_f32)
     }
 
-    fn visit_i32_sign_extend8(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i32_extend8_s)
+    fn visit_i32_sign_extend8(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i32_extend8_s)
     }
 
-    fn visit_i32_sign_extend16(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i32_extend16_s)
+    fn visit_i32_sign_extend16(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i32_extend16_s)
     }
 
-    fn visit_i64_sign_extend8(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i64_extend8_s)
+    fn visit_i64_sign_extend8(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i64_extend8_s)
     }
 
-    fn visit_i64_sign_extend16(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i64_extend16_s)
+    fn visit_i64_sign_extend16(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i64_extend16_s)
     }
 
-    fn visit_i64_sign_extend32(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i64_extend32_s)
+    fn visit_i64_sign_extend32(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i64_extend32_s)
     }
 
-    fn visit_i32_trunc_sat_f32(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i32_trunc_sat_f32_s)
+    fn visit_i32_trunc_sat_f32(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i32_trunc_sat_f32_s)
     }
 
-    fn visit_u32_trunc_sat_f32(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i32_trunc_sat_f32_u)
+    fn visit_u32_trunc_sat_f32(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i32_trunc_sat_f32_u)
     }
 
-    fn visit_i32_trunc_sat_f64(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i32_trunc_sat_f64_s)
+    fn visit_i32_trunc_sat_f64(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i32_trunc_sat_f64_s)
     }
 
-    fn visit_u32_trunc_sat_f64(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i32_trunc_sat_f64_u)
+    fn visit_u32_trunc_sat_f64(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i32_trunc_sat_f64_u)
     }
 
-    fn visit_i64_trunc_sat_f32(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i64_trunc_sat_f32_s)
+    fn visit_i64_trunc_sat_f32(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i64_trunc_sat_f32_s)
     }
 
-    fn visit_u64_trunc_sat_f32(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i64_trunc_sat_f32_u)
+    fn visit_u64_trunc_sat_f32(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i64_trunc_sat_f32_u)
     }
 
-    fn visit_i64_trunc_sat_f64(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i64_trunc_sat_f64_s)
+    fn visit_i64_trunc_sat_f64(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i64_trunc_sat_f64_s)
     }
 
-    fn visit_u64_trunc_sat_f64(&mut self) -> Self::Outcome {
-        self.execute_unary(UntypedValue::i64_trunc_sat_f64_u)
+    fn visit_u64_trunc_sat_f64(&mut self, op: UnaryOp) -> Self::Outcome {
+        self.execute_unary(op, UntypedValue::i64_trunc_sat_f64_u)
     }
 }
@@ -1,4 +1,4 @@
-use core::cmp;
+use core::{cmp, ops::Range};
 use wasmi_core::{DecodeUntypedSlice, EncodeUntypedSlice, UntypedValue};
 
 #[derive(Debug)]
@@ -56,6 +56,25 @@ impl<'a> FuncParams<'a> {
             .unwrap_or_else(|error| panic!("encountered unexpected invalid tuple length: {error}"))
     }
 
+    /// Returns the host function parameters within `range` as `T`.
+    ///
+    /// # Note
+    ///
+    /// This is useful for host functions that decode their parameters piecewise.
+    ///
+    /// # Panics
+    ///
+    /// - If `range` is out of bounds for the function parameters.
+    /// - If the number of function parameters dictated by `T` does not match.
+    pub fn read_params_at<T>(&self, range: Range<usize>) -> T
+    where
+        T: DecodeUntypedSlice,
+    {
+        let params_buffer = &self.params_results[..self.len_params][range];
+        UntypedValue::decode_slice::<T>(params_buffer)
+            .unwrap_or_else(|error| panic!("encountered unexpected invalid tuple length: {error}"))
+    }
+
     /// Sets the results of the function invocation.
     ///
     /// # Panics
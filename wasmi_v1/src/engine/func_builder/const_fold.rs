@@ -0,0 +1,299 @@
+//! Constant folding of instructions during translation.
+//!
+//! # Note
+//!
+//! The evaluation uses the same [`UntypedValue`] operations as the `wasmi`
+//! interpreter so that folded results are equal to the results computed
+//! at runtime. Evaluations that trap are never folded so that the trap
+//! still happens when the instruction is executed.
+
+use crate::engine::Instruction;
+use wasmi_core::UntypedValue;
+
+/// Evaluates the unary operation `inst` on the constant `input`.
+///
+/// Returns `None` if `inst` is not a unary operation or if its evaluation traps.
+pub fn fold_unary(inst: &Instruction, input: UntypedValue) -> Option<UntypedValue> {
+    use Instruction as I;
+    let result = match inst {
+        I::I32Eqz(_) => input.i32_eqz(),
+        I::I64Eqz(_) => input.i64_eqz(),
+        I::I32Clz(_) => input.i32_clz(),
+        I::I32Ctz(_) => input.i32_ctz(),
+        I::I32Popcnt(_) => input.i32_popcnt(),
+        I::I64Clz(_) => input.i64_clz(),
+        I::I64Ctz(_) => input.i64_ctz(),
+        I::I64Popcnt(_) => input.i64_popcnt(),
+        I::F32Abs(_) => input.f32_abs(),
+        I::F32Neg(_) => input.f32_neg(),
+        I::F32Ceil(_) => input.f32_ceil(),
+        I::F32Floor(_) => input.f32_floor(),
+        I::F32Trunc(_) => input.f32_trunc(),
+        I::F32Nearest(_) => input.f32_nearest(),
+        I::F32Sqrt(_) => input.f32_sqrt(),
+        I::F64Abs(_) => input.f64_abs(),
+        I::F64Neg(_) => input.f64_neg(),
+        I::F64Ceil(_) => input.f64_ceil(),
+        I::F64Floor(_) => input.f64_floor(),
+        I::F64Trunc(_) => input.f64_trunc(),
+        I::F64Nearest(_) => input.f64_nearest(),
+        I::F64Sqrt(_) => input.f64_sqrt(),
+        I::I32WrapI64(_) => input.i32_wrap_i64(),
+        I::I64ExtendSI32(_) => input.i64_extend_i32_s(),
+        I::I64ExtendUI32(_) => input.i64_extend_i32_u(),
+        I::F32ConvertSI32(_) => input.f32_convert_i32_s(),
+        I::F32ConvertUI32(_) => input.f32_convert_i32_u(),
+        I::F32ConvertSI64(_) => input.f32_convert_i64_s(),
+        I::F32ConvertUI64(_) => input.f32_convert_i64_u(),
+        I::F32DemoteF64(_) => input.f32_demote_f64(),
+        I::F64ConvertSI32(_) => input.f64_convert_i32_s(),
+        I::F64ConvertUI32(_) => input.f64_convert_i32_u(),
+        I::F64ConvertSI64(_) => input.f64_convert_i64_s(),
+        I::F64ConvertUI64(_) => input.f64_convert_i64_u(),
+        I::F64PromoteF32(_) => input.f64_promote_f32(),
+        I::I32Extend8S(_) => input.i32_extend8_s(),
+        I::I32Extend16S(_) => input.i32_extend16_s(),
+        I::I64Extend8S(_) => input.i64_extend8_s(),
+        I::I64Extend16S(_) => input.i64_extend16_s(),
+        I::I64Extend32S(_) => input.i64_extend32_s(),
+        I::I32TruncSatF32S(_) => input.i32_trunc_sat_f32_s(),
+        I::I32TruncSatF32U(_) => input.i32_trunc_sat_f32_u(),
+        I::I32TruncSatF64S(_) => input.i32_trunc_sat_f64_s(),
+        I::I32TruncSatF64U(_) => input.i32_trunc_sat_f64_u(),
+        I::I64TruncSatF32S(_) => input.i64_trunc_sat_f32_s(),
+        I::I64TruncSatF32U(_) => input.i64_trunc_sat_f32_u(),
+        I::I64TruncSatF64S(_) => input.i64_trunc_sat_f64_s(),
+        I::I64TruncSatF64U(_) => input.i64_trunc_sat_f64_u(),
+        I::I32TruncSF32(_) => input.i32_trunc_f32_s().ok()?,
+        I::I32TruncUF32(_) => input.i32_trunc_f32_u().ok()?,
+        I::I32TruncSF64(_) => input.i32_trunc_f64_s().ok()?,
+        I::I32TruncUF64(_) => input.i32_trunc_f64_u().ok()?,
+        I::I64TruncSF32(_) => input.i64_trunc_f32_s().ok()?,
+        I::I64TruncUF32(_) => input.i64_trunc_f32_u().ok()?,
+        I::I64TruncSF64(_) => input.i64_trunc_f64_s().ok()?,
+        I::I64TruncUF64(_) => input.i64_trunc_f64_u().ok()?,
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Evaluates the binary operation `inst` on the constants `lhs` and `rhs`.
+///
+/// Returns `None` if `inst` is not a binary operation or if its evaluation traps.
+pub fn fold_binary(
+    inst: &Instruction,
+    lhs: UntypedValue,
+    rhs: UntypedValue,
+) -> Option<UntypedValue> {
+    use Instruction as I;
+    let result = match inst {
+        I::I32Eq(_) => lhs.i32_eq(rhs),
+        I::I32Ne(_) => lhs.i32_ne(rhs),
+        I::I32LtS(_) => lhs.i32_lt_s(rhs),
+        I::I32LtU(_) => lhs.i32_lt_u(rhs),
+        I::I32GtS(_) => lhs.i32_gt_s(rhs),
+        I::I32GtU(_) => lhs.i32_gt_u(rhs),
+        I::I32LeS(_) => lhs.i32_le_s(rhs),
+        I::I32LeU(_) => lhs.i32_le_u(rhs),
+        I::I32GeS(_) => lhs.i32_ge_s(rhs),
+        I::I32GeU(_) => lhs.i32_ge_u(rhs),
+        I::I64Eq(_) => lhs.i64_eq(rhs),
+        I::I64Ne(_) => lhs.i64_ne(rhs),
+        I::I64LtS(_) => lhs.i64_lt_s(rhs),
+        I::I64LtU(_) => lhs.i64_lt_u(rhs),
+        I::I64GtS(_) => lhs.i64_gt_s(rhs),
+        I::I64GtU(_) => lhs.i64_gt_u(rhs),
+        I::I64LeS(_) => lhs.i64_le_s(rhs),
+        I::I64LeU(_) => lhs.i64_le_u(rhs),
+        I::I64GeS(_) => lhs.i64_ge_s(rhs),
+        I::I64GeU(_) => lhs.i64_ge_u(rhs),
+        I::F32Eq(_) => lhs.f32_eq(rhs),
+        I::F32Ne(_) => lhs.f32_ne(rhs),
+        I::F32Lt(_) => lhs.f32_lt(rhs),
+        I::F32Gt(_) => lhs.f32_gt(rhs),
+        I::F32Le(_) => lhs.f32_le(rhs),
+        I::F32Ge(_) => lhs.f32_ge(rhs),
+        I::F64Eq(_) => lhs.f64_eq(rhs),
+        I::F64Ne(_) => lhs.f64_ne(rhs),
+        I::F64Lt(_) => lhs.f64_lt(rhs),
+        I::F64Gt(_) => lhs.f64_gt(rhs),
+        I::F64Le(_) => lhs.f64_le(rhs),
+        I::F64Ge(_) => lhs.f64_ge(rhs),
+        I::I32Add(_) => lhs.i32_add(rhs),
+        I::I32Sub(_) => lhs.i32_sub(rhs),
+        I::I32Mul(_) => lhs.i32_mul(rhs),
+        I::I32And(_) => lhs.i32_and(rhs),
+        I::I32Or(_) => lhs.i32_or(rhs),
+        I::I32Xor(_) => lhs.i32_xor(rhs),
+        I::I32Shl(_) => lhs.i32_shl(rhs),
+        I::I32ShrS(_) => lhs.i32_shr_s(rhs),
+        I::I32ShrU(_) => lhs.i32_shr_u(rhs),
+        I::I32Rotl(_) => lhs.i32_rotl(rhs),
+        I::I32Rotr(_) => lhs.i32_rotr(rhs),
+        I::I64Add(_) => lhs.i64_add(rhs),
+        I::I64Sub(_) => lhs.i64_sub(rhs),
+        I::I64Mul(_) => lhs.i64_mul(rhs),
+        I::I64And(_) => lhs.i64_and(rhs),
+        I::I64Or(_) => lhs.i64_or(rhs),
+        I::I64Xor(_) => lhs.i64_xor(rhs),
+        I::I64Shl(_) => lhs.i64_shl(rhs),
+        I::I64ShrS(_) => lhs.i64_shr_s(rhs),
+        I::I64ShrU(_) => lhs.i64_shr_u(rhs),
+        I::I64Rotl(_) => lhs.i64_rotl(rhs),
+        I::I64Rotr(_) => lhs.i64_rotr(rhs),
+        I::F32Add(_) => lhs.f32_add(rhs),
+        I::F32Sub(_) => lhs.f32_sub(rhs),
+        I::F32Mul(_) => lhs.f32_mul(rhs),
+        I::F32Min(_) => lhs.f32_min(rhs),
+        I::F32Max(_) => lhs.f32_max(rhs),
+        I::F32Copysign(_) => lhs.f32_copysign(rhs),
+        I::F64Add(_) => lhs.f64_add(rhs),
+        I::F64Sub(_) => lhs.f64_sub(rhs),
+        I::F64Mul(_) => lhs.f64_mul(rhs),
+        I::F64Min(_) => lhs.f64_min(rhs),
+        I::F64Max(_) => lhs.f64_max(rhs),
+        I::F64Copysign(_) => lhs.f64_copysign(rhs),
+        I::I32DivS(_) => lhs.i32_div_s(rhs).ok()?,
+        I::I32DivU(_) => lhs.i32_div_u(rhs).ok()?,
+        I::I32RemS(_) => lhs.i32_rem_s(rhs).ok()?,
+        I::I32RemU(_) => lhs.i32_rem_u(rhs).ok()?,
+        I::I64DivS(_) => lhs.i64_div_s(rhs).ok()?,
+        I::I64DivU(_) => lhs.i64_div_u(rhs).ok()?,
+        I::I64RemS(_) => lhs.i64_rem_s(rhs).ok()?,
+        I::I64RemU(_) => lhs.i64_rem_u(rhs).ok()?,
+        I::F32Div(_) => lhs.f32_div(rhs).ok()?,
+        I::F64Div(_) => lhs.f64_div(rhs).ok()?,
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Returns `true` if `inst` neither traps nor has side effects besides writing its result.
+///
+/// # Note
+///
+/// Such instructions may be removed if their result is never used.
+pub fn is_pure(inst: &Instruction) -> bool {
+    use Instruction as I;
+    matches!(
+        inst,
+        I::Copy(_)
+            | I::Select { .. }
+            | I::I32AddImm(_)
+            | I::I64AddImm(_)
+            | I::I32Eqz(_)
+            | I::I64Eqz(_)
+            | I::I32Clz(_)
+            | I::I32Ctz(_)
+            | I::I32Popcnt(_)
+            | I::I64Clz(_)
+            | I::I64Ctz(_)
+            | I::I64Popcnt(_)
+            | I::F32Abs(_)
+            | I::F32Neg(_)
+            | I::F32Ceil(_)
+            | I::F32Floor(_)
+            | I::F32Trunc(_)
+            | I::F32Nearest(_)
+            | I::F32Sqrt(_)
+            | I::F64Abs(_)
+            | I::F64Neg(_)
+            | I::F64Ceil(_)
+            | I::F64Floor(_)
+            | I::F64Trunc(_)
+            | I::F64Nearest(_)
+            | I::F64Sqrt(_)
+            | I::I32WrapI64(_)
+            | I::I64ExtendSI32(_)
+            | I::I64ExtendUI32(_)
+            | I::F32ConvertSI32(_)
+            | I::F32ConvertUI32(_)
+            | I::F32ConvertSI64(_)
+            | I::F32ConvertUI64(_)
+            | I::F32DemoteF64(_)
+            | I::F64ConvertSI32(_)
+            | I::F64ConvertUI32(_)
+            | I::F64ConvertSI64(_)
+            | I::F64ConvertUI64(_)
+            | I::F64PromoteF32(_)
+            | I::I32Extend8S(_)
+            | I::I32Extend16S(_)
+            | I::I64Extend8S(_)
+            | I::I64Extend16S(_)
+            | I::I64Extend32S(_)
+            | I::I32TruncSatF32S(_)
+            | I::I32TruncSatF32U(_)
+            | I::I32TruncSatF64S(_)
+            | I::I32TruncSatF64U(_)
+            | I::I64TruncSatF32S(_)
+            | I::I64TruncSatF32U(_)
+            | I::I64TruncSatF64S(_)
+            | I::I64TruncSatF64U(_)
+            | I::I32Eq(_)
+            | I::I32Ne(_)
+            | I::I32LtS(_)
+            | I::I32LtU(_)
+            | I::I32GtS(_)
+            | I::I32GtU(_)
+            | I::I32LeS(_)
+            | I::I32LeU(_)
+            | I::I32GeS(_)
+            | I::I32GeU(_)
+            | I::I64Eq(_)
+            | I::I64Ne(_)
+            | I::I64LtS(_)
+            | I::I64LtU(_)
+            | I::I64GtS(_)
+            | I::I64GtU(_)
+            | I::I64LeS(_)
+            | I::I64LeU(_)
+            | I::I64GeS(_)
+            | I::I64GeU(_)
+            | I::F32Eq(_)
+            | I::F32Ne(_)
+            | I::F32Lt(_)
+            | I::F32Gt(_)
+            | I::F32Le(_)
+            | I::F32Ge(_)
+            | I::F64Eq(_)
+            | I::F64Ne(_)
+            | I::F64Lt(_)
+            | I::F64Gt(_)
+            | I::F64Le(_)
+            | I::F64Ge(_)
+            | I::I32Add(_)
+            | I::I32Sub(_)
+            | I::I32Mul(_)
+            | I::I32And(_)
+            | I::I32Or(_)
+            | I::I32Xor(_)
+            | I::I32Shl(_)
+            | I::I32ShrS(_)
+            | I::I32ShrU(_)
+            | I::I32Rotl(_)
+            | I::I32Rotr(_)
+            | I::I64Add(_)
+            | I::I64Sub(_)
+            | I::I64Mul(_)
+            | I::I64And(_)
+            | I::I64Or(_)
+            | I::I64Xor(_)
+            | I::I64Shl(_)
+            | I::I64ShrS(_)
+            | I::I64ShrU(_)
+            | I::I64Rotl(_)
+            | I::I64Rotr(_)
+            | I::F32Add(_)
+            | I::F32Sub(_)
+            | I::F32Mul(_)
+            | I::F32Min(_)
+            | I::F32Max(_)
+            | I::F32Copysign(_)
+            | I::F64Add(_)
+            | I::F64Sub(_)
+            | I::F64Mul(_)
+            | I::F64Min(_)
+            | I::F64Max(_)
+            | I::F64Copysign(_)
+    )
+}
@@ -0,0 +1,61 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+use wasmi_core::UntypedValue;
+
+/// A registry where the constant values of a function are deduplicated.
+///
+/// # Note
+///
+/// Constant values are stored in the function frame after the local variables
+/// so that instructions may refer to them as registers. Constant values with
+/// equal bit patterns share a register regardless of their types.
+#[derive(Debug, Default)]
+pub struct ConstsRegistry {
+    /// The indices of the registered constant values by their bits.
+    indices: BTreeMap<u64, u32>,
+    /// The registered constant values in the order of their registration.
+    values: Vec<UntypedValue>,
+}
+
+impl ConstsRegistry {
+    /// Registers the constant `value` and returns its index.
+    ///
+    /// Returns the index of an equal constant value if it has already been registered.
+    pub fn alloc(&mut self, value: UntypedValue) -> u32 {
+        let values = &mut self.values;
+        *self.indices.entry(value.to_bits()).or_insert_with(|| {
+            let index = values.len() as u32;
+            values.push(value);
+            index
+        })
+    }
+
+    /// Returns the number of registered constant values.
+    pub fn len_registered(&self) -> u32 {
+        self.values.len() as u32
+    }
+
+    /// Returns the registered constant values and resets the [`ConstsRegistry`].
+    pub fn drain(&mut self) -> impl ExactSizeIterator<Item = UntypedValue> + '_ {
+        self.indices.clear();
+        self.values.drain(..)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alloc_deduplicates() {
+        let mut registry = ConstsRegistry::default();
+        assert_eq!(registry.alloc(UntypedValue::from(1_i32)), 0);
+        assert_eq!(registry.alloc(UntypedValue::from(2_i64)), 1);
+        assert_eq!(registry.alloc(UntypedValue::from(1_i64)), 0);
+        assert_eq!(registry.len_registered(), 2);
+        assert_eq!(
+            registry.drain().collect::<Vec<_>>(),
+            [UntypedValue::from(1_i32), UntypedValue::from(2_i64)]
+        );
+        assert_eq!(registry.len_registered(), 0);
+    }
+}
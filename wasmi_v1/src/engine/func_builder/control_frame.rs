@@ -110,6 +110,13 @@ pub struct IfControlFrame {
     /// - An `end_of_else_is_reachable` field is not needed since it will
     ///   be easily computed once the translation reaches the end of the `if`.
     end_of_then_is_reachable: Option<bool>,
+    /// The optional `else` branch is reachable.
+    ///
+    /// # Note
+    ///
+    /// This is `false` if the condition of the `if` is known to be
+    /// non-zero during translation so that the `then` branch is always taken.
+    else_is_reachable: bool,
 }
 
 impl IfControlFrame {
@@ -130,6 +137,7 @@ impl IfControlFrame {
             else_label,
             stack_height,
             end_of_then_is_reachable: None,
+            else_is_reachable: true,
         }
     }
 
@@ -162,6 +170,16 @@ impl IfControlFrame {
         self.block_type
     }
 
+    /// Returns `true` if the optional `else` branch of the [`IfControlFrame`] is reachable.
+    pub fn is_else_reachable(&self) -> bool {
+        self.else_is_reachable
+    }
+
+    /// Marks the optional `else` branch of the [`IfControlFrame`] as unreachable.
+    pub fn make_else_unreachable(&mut self) {
+        self.else_is_reachable = false;
+    }
+
     /// Updates the reachability of the end of the `then` branch.
     ///
     /// # Panics
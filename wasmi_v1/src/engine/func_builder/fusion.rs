@@ -0,0 +1,115 @@
+//! Fusion of conditional branches with the instructions computing their conditions.
+
+use crate::engine::{
+    bytecode::{BinaryOp, BranchCmpOp, Register, UnaryOp},
+    Instruction,
+    Target,
+};
+
+/// The condition of a conditional branch.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BranchCondition {
+    /// The branch depends on the value stored in the register.
+    Register(Register),
+    /// The branch depends on the outcome of the comparison instruction.
+    ///
+    /// # Note
+    ///
+    /// The comparison instruction has been removed from the instruction
+    /// sequence and is fused into the conditional branch instead.
+    Fused(Instruction),
+}
+
+/// Creates a fused compare and branch instruction from its operands.
+type MakeBranchCmp = fn(BranchCmpOp) -> Instruction;
+
+impl BranchCondition {
+    /// Returns `true` if `inst` computes a condition that can be fused into a conditional branch.
+    ///
+    /// # Note
+    ///
+    /// Floating point comparisons are not fused since their negation
+    /// does not yield the inverse comparison in the presence of NaN.
+    pub fn is_fusable(inst: &Instruction) -> bool {
+        matches!(inst, Instruction::I32Eqz(_) | Instruction::I64Eqz(_))
+            || Self::compare_and_branch(inst).is_some()
+    }
+
+    /// Returns the operands of the comparison `inst` and the fused compare and
+    /// branch instructions taken if the comparison holds or does not hold respectively.
+    fn compare_and_branch(inst: &Instruction) -> Option<(BinaryOp, MakeBranchCmp, MakeBranchCmp)> {
+        use Instruction as I;
+        let fused: (BinaryOp, MakeBranchCmp, MakeBranchCmp) = match *inst {
+            I::I32Eq(op) => (op, I::BrIfI32Eq, I::BrIfI32Ne),
+            I::I32Ne(op) => (op, I::BrIfI32Ne, I::BrIfI32Eq),
+            I::I32LtS(op) => (op, I::BrIfI32LtS, I::BrIfI32GeS),
+            I::I32LtU(op) => (op, I::BrIfI32LtU, I::BrIfI32GeU),
+            I::I32GtS(op) => (op, I::BrIfI32GtS, I::BrIfI32LeS),
+            I::I32GtU(op) => (op, I::BrIfI32GtU, I::BrIfI32LeU),
+            I::I32LeS(op) => (op, I::BrIfI32LeS, I::BrIfI32GtS),
+            I::I32LeU(op) => (op, I::BrIfI32LeU, I::BrIfI32GtU),
+            I::I32GeS(op) => (op, I::BrIfI32GeS, I::BrIfI32LtS),
+            I::I32GeU(op) => (op, I::BrIfI32GeU, I::BrIfI32LtU),
+            I::I64Eq(op) => (op, I::BrIfI64Eq, I::BrIfI64Ne),
+            I::I64Ne(op) => (op, I::BrIfI64Ne, I::BrIfI64Eq),
+            I::I64LtS(op) => (op, I::BrIfI64LtS, I::BrIfI64GeS),
+            I::I64LtU(op) => (op, I::BrIfI64LtU, I::BrIfI64GeU),
+            I::I64GtS(op) => (op, I::BrIfI64GtS, I::BrIfI64LeS),
+            I::I64GtU(op) => (op, I::BrIfI64GtU, I::BrIfI64LeU),
+            I::I64LeS(op) => (op, I::BrIfI64LeS, I::BrIfI64GtS),
+            I::I64LeU(op) => (op, I::BrIfI64LeU, I::BrIfI64GtU),
+            I::I64GeS(op) => (op, I::BrIfI64GeS, I::BrIfI64LtS),
+            I::I64GeU(op) => (op, I::BrIfI64GeU, I::BrIfI64LtU),
+            _ => return None,
+        };
+        Some(fused)
+    }
+
+    /// Returns the conditional branch to `target` taken if the condition is non-zero.
+    pub fn branch_if_nez(self, target: Target) -> Instruction {
+        self.branch_if(true, target)
+    }
+
+    /// Returns the conditional branch to `target` taken if the condition is zero.
+    pub fn branch_if_eqz(self, target: Target) -> Instruction {
+        self.branch_if(false, target)
+    }
+
+    /// Returns the conditional branch to `target` taken if the condition equals `nez`.
+    ///
+    /// # Panics
+    ///
+    /// If a fused condition is not fusable as determined by [`BranchCondition::is_fusable`].
+    fn branch_if(self, nez: bool, target: Target) -> Instruction {
+        let inst = match self {
+            Self::Register(condition) => {
+                return Self::branch_on_register(condition, nez, target);
+            }
+            Self::Fused(inst) => inst,
+        };
+        match inst {
+            // Branching if `x == 0` is non-zero is branching if `x` is zero and vice versa.
+            Instruction::I32Eqz(UnaryOp { input, .. })
+            | Instruction::I64Eqz(UnaryOp { input, .. }) => {
+                Self::branch_on_register(input, !nez, target)
+            }
+            _ => {
+                let (BinaryOp { lhs, rhs, .. }, if_holds, if_not_holds) =
+                    Self::compare_and_branch(&inst).unwrap_or_else(|| {
+                        panic!("encountered non-fusable branch condition: {:?}", inst)
+                    });
+                let make_inst = if nez { if_holds } else { if_not_holds };
+                make_inst(BranchCmpOp { lhs, rhs, target })
+            }
+        }
+    }
+
+    /// Returns the `br_nez` or `br_eqz` instruction to `target` on the `condition` register.
+    fn branch_on_register(condition: Register, nez: bool, target: Target) -> Instruction {
+        if nez {
+            Instruction::BrIfNez { condition, target }
+        } else {
+            Instruction::BrIfEqz { condition, target }
+        }
+    }
+}
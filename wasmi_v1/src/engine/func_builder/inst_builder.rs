@@ -1,8 +1,14 @@
 //! Abstractions to build up instructions forming Wasm function bodies.
 
-use crate::engine::{Engine, FuncBody, Instruction};
+use super::TranslationTarget;
+use crate::engine::{
+    bytecode::{Register, Target},
+    FuncBody,
+    Instruction,
+};
 use alloc::vec::Vec;
 use core::mem;
+use wasmi_core::UntypedValue;
 
 /// A reference to an instruction of the partially
 /// constructed function body of the [`InstructionsBuilder`].
@@ -74,13 +80,10 @@ pub struct LabelIdx(pub(crate) usize);
 /// A relocation entry that specifies.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Reloc {
-    /// Patch the target of the `br`, `br_eqz` or `br_nez` instruction.
+    /// Patch the target of the `br`, `br_eqz`, `br_nez` or fused compare and branch instruction.
     Br { inst_idx: InstructionIdx },
-    /// Patch the specified target index inside of a Wasm `br_table` instruction.
-    BrTable {
-        inst_idx: InstructionIdx,
-        target_idx: usize,
-    },
+    /// Patch the specified target in the `br_table` target pool of the function body.
+    BrTable { target_idx: usize },
 }
 
 /// The relative depth of a Wasm branching target.
@@ -108,8 +111,17 @@ impl RelativeDepth {
 pub struct InstructionsBuilder {
     /// The instructions of the partially constructed function body.
     insts: Vec<Instruction>,
+    /// The `br_table` targets of the partially constructed function body.
+    br_targets: Vec<Target>,
     /// All labels and their uses.
     labels: Vec<Label>,
+    /// The instruction index of the most recently resolved label.
+    ///
+    /// # Note
+    ///
+    /// Instructions before this index must not be altered anymore since
+    /// multiple control flow paths might join at the resolved label.
+    fence: Option<InstructionIdx>,
 }
 
 impl InstructionsBuilder {
@@ -163,6 +175,7 @@ impl InstructionsBuilder {
     /// If the label has already been resolved.
     pub fn resolve_label(&mut self, label: LabelIdx) {
         let dst_pc = self.current_pc();
+        self.fence = Some(dst_pc);
         let old_label = mem::replace(&mut self.labels[label.0], Label::Resolved(dst_pc));
         match old_label {
             Label::Resolved(idx) => panic!(
@@ -204,32 +217,65 @@ impl InstructionsBuilder {
         idx
     }
 
+    /// Returns the result [`Register`] of the last pushed instruction if any.
+    ///
+    /// # Note
+    ///
+    /// Returns `None` if a label has been resolved at the current instruction
+    /// position since the last instruction is no longer the sole predecessor.
+    pub fn last_result_mut(&mut self) -> Option<&mut Register> {
+        if self.fence == Some(self.current_pc()) {
+            return None;
+        }
+        self.insts.last_mut().and_then(Instruction::result_mut)
+    }
+
+    /// Removes and returns the last pushed instruction if it satisfies `predicate`.
+    ///
+    /// # Note
+    ///
+    /// Returns `None` if a label has been resolved at the current instruction
+    /// position since the last instruction is no longer the sole predecessor.
+    pub fn pop_last_if<P>(&mut self, predicate: P) -> Option<Instruction>
+    where
+        P: FnOnce(&mut Instruction) -> bool,
+    {
+        if self.fence == Some(self.current_pc()) {
+            return None;
+        }
+        if !predicate(self.insts.last_mut()?) {
+            return None;
+        }
+        self.insts.pop()
+    }
+
+    /// Pushes the `br_table` branching targets to the [`InstructionsBuilder`].
+    ///
+    /// Returns the index of the first pushed target in the `br_table` target pool.
+    pub fn push_br_targets<T>(&mut self, targets: T) -> usize
+    where
+        T: IntoIterator<Item = Target>,
+    {
+        let offset = self.br_targets.len();
+        self.br_targets.extend(targets);
+        offset
+    }
+
     /// Allows to patch the branch target of branch instructions.
     pub fn patch_relocation(&mut self, reloc: Reloc, dst_pc: InstructionIdx) {
         match reloc {
-            Reloc::Br { inst_idx } => match &mut self.insts[inst_idx.into_usize()] {
-                Instruction::Br(target)
-                | Instruction::BrIfEqz(target)
-                | Instruction::BrIfNez(target) => {
+            Reloc::Br { inst_idx } => match self.insts[inst_idx.into_usize()].branch_target_mut() {
+                Some(target) => {
                     target.update_destination_pc(dst_pc);
                 }
-                _ => panic!(
+                None => panic!(
                     "branch relocation points to a non-branch instruction: {:?}",
                     reloc
                 ),
             },
-            Reloc::BrTable {
-                inst_idx,
-                target_idx,
-            } => match &mut self.insts[inst_idx.into_usize() + target_idx + 1] {
-                Instruction::Br(target) => {
-                    target.update_destination_pc(dst_pc);
-                }
-                _ => panic!(
-                    "`br_table` relocation points to a non-`br_table` instruction: {:?}",
-                    reloc
-                ),
-            },
+            Reloc::BrTable { target_idx } => {
+                self.br_targets[target_idx].update_destination_pc(dst_pc);
+            }
         }
     }
 
@@ -238,16 +284,51 @@ impl InstructionsBuilder {
     /// # Note
     ///
     /// This feeds the built-up instructions of the function body
-    /// into the [`Engine`] so that the [`Engine`] is
+    /// into the [`TranslationTarget`] so that the engine is
     /// aware of the Wasm function existance. Returns a `FuncBody`
     /// reference that allows to retrieve the instructions.
+    ///
+    /// During translation the registers of constant values are encoded as
+    /// `u32::MAX - index` since the number of constants is not yet known.
+    /// This relocates all registers to the final function frame layout of
+    /// parameters, local variables, constants and temporaries.
     #[must_use]
-    pub fn finish(
+    pub fn finish<C, T>(
         &mut self,
-        engine: &Engine,
+        target: &mut T,
+        len_params: usize,
         len_locals: usize,
         max_stack_height: usize,
-    ) -> FuncBody {
-        engine.alloc_func_body(len_locals, max_stack_height, self.insts.drain(..))
+        consts: C,
+    ) -> FuncBody
+    where
+        C: ExactSizeIterator<Item = UntypedValue>,
+        T: TranslationTarget,
+    {
+        let len_params_locals = (len_params + len_locals) as u32;
+        let len_consts = consts.len() as u32;
+        let min_const = u32::MAX - len_consts;
+        for inst in &mut self.insts {
+            inst.for_each_register_mut(|register| {
+                let index = register.into_inner();
+                let relocated = if index > min_const {
+                    len_params_locals + (u32::MAX - index)
+                } else if index >= len_params_locals {
+                    index + len_consts
+                } else {
+                    index
+                };
+                *register = Register::from(relocated);
+            });
+        }
+        self.fence = None;
+        target.alloc_func_body(
+            len_params,
+            len_locals,
+            max_stack_height,
+            consts,
+            self.br_targets.drain(..),
+            self.insts.drain(..),
+        )
     }
 }
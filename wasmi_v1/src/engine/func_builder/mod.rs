@@ -1,13 +1,17 @@
 #![allow(dead_code, unused_variables)] // TODO: remove annotation once done
 
+mod const_fold;
+mod consts_registry;
 mod control_frame;
 mod control_stack;
+mod fusion;
 mod inst_builder;
 mod locals_registry;
+mod target;
 mod value_stack;
 
-pub use self::inst_builder::{InstructionIdx, InstructionsBuilder, LabelIdx, RelativeDepth, Reloc};
 use self::{
+    consts_registry::ConstsRegistry,
     control_frame::{
         BlockControlFrame,
         ControlFrame,
@@ -17,12 +21,17 @@ use self::{
         UnreachableControlFrame,
     },
     control_stack::ControlFlowStack,
+    fusion::BranchCondition,
     locals_registry::LocalsRegistry,
-    value_stack::ValueStack,
+    value_stack::{Provider, ValueStack},
 };
-use super::{DropKeep, FuncBody, Instruction, Target};
+pub use self::{
+    inst_builder::{InstructionIdx, InstructionsBuilder, LabelIdx, RelativeDepth, Reloc},
+    target::{EngineCode, TranslationTarget},
+};
+use super::{FuncBody, Instruction, Target};
 use crate::{
-    engine::bytecode::Offset,
+    engine::bytecode::{BinaryOp, ImmOp, LoadOp, Offset, Register, RegisterSpan, StoreOp, UnaryOp},
     module::{
         BlockType,
         FuncIdx,
@@ -33,18 +42,26 @@ use crate::{
         TableIdx,
         DEFAULT_MEMORY_INDEX,
     },
-    Engine,
     FuncType,
     ModuleError,
     Mutability,
 };
-use wasmi_core::{Value, ValueType, F32, F64};
+use wasmi_core::{UntypedValue, Value, ValueType, F32, F64};
 
 /// The interface to translate a `wasmi` bytecode function using Wasm bytecode.
+///
+/// # Note
+///
+/// The translated function frame consists of the function parameters, its
+/// local variables, its constant values and its temporaries in this order.
+/// Every position of the emulated value stack owns a temporary register.
+/// Values of local variables and constants are referred to by their registers
+/// directly and only copied into temporaries when required, e.g. at control
+/// flow boundaries.
 #[derive(Debug)]
-pub struct FunctionBuilder<'engine, 'parser> {
-    /// The [`Engine`] for which the function is translated.
-    engine: &'engine Engine,
+pub struct FunctionBuilder<'parser, T> {
+    /// The [`TranslationTarget`] for which the function is translated.
+    target: T,
     /// The function under construction.
     func: FuncIdx,
     /// The immutable `wasmi` module resources.
@@ -61,6 +78,8 @@ pub struct FunctionBuilder<'engine, 'parser> {
     inst_builder: InstructionsBuilder,
     /// Stores and resolves local variable types.
     locals: LocalsRegistry,
+    /// Stores and deduplicates the constant values of the function.
+    consts: ConstsRegistry,
     /// This represents the reachability of the currently translated code.
     ///
     /// - `true`: The currently translated code is reachable.
@@ -73,23 +92,27 @@ pub struct FunctionBuilder<'engine, 'parser> {
     reachable: bool,
 }
 
-impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
+impl<'parser, T> FunctionBuilder<'parser, T>
+where
+    T: TranslationTarget,
+{
     /// Creates a new [`FunctionBuilder`].
-    pub fn new(engine: &'engine Engine, func: FuncIdx, res: ModuleResources<'parser>) -> Self {
+    pub fn new(target: T, func: FuncIdx, res: ModuleResources<'parser>) -> Self {
         let mut inst_builder = InstructionsBuilder::default();
         let mut control_frames = ControlFlowStack::default();
         Self::register_func_body_block(func, res, &mut inst_builder, &mut control_frames);
         let mut value_stack = ValueStack::default();
         let mut locals = LocalsRegistry::default();
-        Self::register_func_params(func, res, &mut value_stack, &mut locals);
+        Self::register_func_params(&target, func, res, &mut value_stack, &mut locals);
         Self {
-            engine,
+            target,
             func,
             res,
             control_frames,
             value_stack,
             inst_builder,
             locals,
+            consts: ConstsRegistry::default(),
             reachable: true,
         }
     }
@@ -97,23 +120,19 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     /// Returns the [`FuncType`] of the function that is currently translated.
     fn func_type(&self) -> FuncType {
         let dedup_func_type = self.res.get_type_of_func(self.func);
-        self.engine.resolve_func_type(dedup_func_type, Clone::clone)
+        self.target.resolve_func_type(dedup_func_type, Clone::clone)
     }
 
     /// Resolves the [`FuncType`] of the given [`FuncTypeIdx`].
     fn func_type_at(&self, func_type_index: FuncTypeIdx) -> FuncType {
         let dedup_func_type = self.res.get_func_type(func_type_index);
-        self.res
-            .engine()
-            .resolve_func_type(dedup_func_type, Clone::clone)
+        self.target.resolve_func_type(dedup_func_type, Clone::clone)
     }
 
     /// Resolves the [`FuncType`] of the given [`FuncIdx`].
     fn func_type_of(&self, func_index: FuncIdx) -> FuncType {
         let dedup_func_type = self.res.get_type_of_func(func_index);
-        self.res
-            .engine()
-            .resolve_func_type(dedup_func_type, Clone::clone)
+        self.target.resolve_func_type(dedup_func_type, Clone::clone)
     }
 
     /// Registers the `block` control frame surrounding the entire function body.
@@ -132,15 +151,14 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
 
     /// Registers the function parameters in the emulated value stack.
     fn register_func_params(
+        target: &T,
         func: FuncIdx,
         res: ModuleResources<'parser>,
         value_stack: &mut ValueStack,
         locals: &mut LocalsRegistry,
     ) -> usize {
         let dedup_func_type = res.get_type_of_func(func);
-        let func_type = res
-            .engine()
-            .resolve_func_type(dedup_func_type, Clone::clone);
+        let func_type = target.resolve_func_type(dedup_func_type, Clone::clone);
         let params = func_type.params();
         for param_type in params {
             locals.register_locals(*param_type, 1);
@@ -180,10 +198,14 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
 
     /// Finishes constructing the function and returns its [`FuncBody`].
     pub fn finish(mut self) -> FuncBody {
+        let len_params = self.func_type().params().len();
+        let len_locals = self.len_locals();
         self.inst_builder.finish(
-            self.engine,
-            self.len_locals(),
+            &mut self.target,
+            len_params,
+            len_locals,
             self.value_stack.max_stack_height() as usize,
+            self.consts.drain(),
         )
     }
 
@@ -207,83 +229,172 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
         Ok(())
     }
 
-    /// Computes how many values should be dropped and kept for the specific branch.
+    /// Returns the temporary [`Register`] of the emulated value stack `position`.
+    fn temp_register(&self, position: u32) -> Register {
+        Register::from(self.locals.len_registered() + position)
+    }
+
+    /// Returns the [`Register`] of the value with `provider` at the emulated value stack `position`.
     ///
-    /// # Panics
+    /// # Note
     ///
-    /// If underflow of the value stack is detected.
-    fn compute_drop_keep(&self, depth: u32) -> DropKeep {
-        debug_assert!(self.is_reachable());
-        let frame = self.control_frames.nth_back(depth);
-        // Find out how many values we need to keep (copy to the new stack location after the drop).
-        let keep = match frame.kind() {
-            ControlFrameKind::Block | ControlFrameKind::If => {
-                frame.block_type().len_results(self.engine)
+    /// Constant values are registered as function local constants upon first use.
+    /// The registers of constant values are encoded as `u32::MAX - index`
+    /// during translation and relocated once the function has been translated.
+    fn provider_register(&mut self, position: u32, provider: Provider) -> Register {
+        match provider {
+            Provider::Temp => self.temp_register(position),
+            Provider::Local(local_idx) => Register::from(local_idx),
+            Provider::Const(value) => Register::from(u32::MAX - self.consts.alloc(value)),
+        }
+    }
+
+    /// Returns the [`Register`] of the value at the emulated value stack `position`.
+    fn register_at(&mut self, position: u32) -> Register {
+        let provider = self.value_stack.get(position).provider;
+        self.provider_register(position, provider)
+    }
+
+    /// Returns the constant value at the emulated value stack `position` if any.
+    fn const_at(&self, position: u32) -> Option<UntypedValue> {
+        match self.value_stack.get(position).provider {
+            Provider::Const(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Pops the top most value from the emulated value stack and returns its [`Register`].
+    fn pop_register(&mut self, expected: ValueType) -> Register {
+        let register = self.register_at(self.value_stack.len() - 1);
+        let actual = self.value_stack.pop1();
+        debug_assert_eq!(actual.value_type, expected);
+        register
+    }
+
+    /// Returns the [`BranchCondition`] for the `condition` that has just been popped from the emulated value stack.
+    ///
+    /// # Note
+    ///
+    /// If the `condition` has been computed by the last instruction into its
+    /// temporary register the instruction is removed so that it can be fused
+    /// into the conditional branch.
+    /// This must be called before any further instructions are pushed.
+    fn take_condition(&mut self, condition: Register) -> BranchCondition {
+        if condition != self.temp_register(self.value_stack.len()) {
+            return BranchCondition::Register(condition);
+        }
+        let fused = self.inst_builder.pop_last_if(|inst| {
+            BranchCondition::is_fusable(inst) && inst.result_mut().copied() == Some(condition)
+        });
+        match fused {
+            Some(inst) => BranchCondition::Fused(inst),
+            None => BranchCondition::Register(condition),
+        }
+    }
+
+    /// Copies the value at the emulated value stack `position` into its temporary [`Register`].
+    ///
+    /// Does nothing if the value already resides in its temporary [`Register`].
+    fn materialize(&mut self, position: u32) {
+        if let Provider::Temp = self.value_stack.get(position).provider {
+            return;
+        }
+        let result = self.temp_register(position);
+        let input = self.register_at(position);
+        self.inst_builder
+            .push_inst(Instruction::Copy(UnaryOp { result, input }));
+        self.value_stack.set_provider(position, Provider::Temp);
+    }
+
+    /// Copies the `len` top most values of the emulated value stack into their temporary [`Register`].
+    fn materialize_top(&mut self, len: u32) {
+        let height = self.value_stack.len();
+        for position in height - len..height {
+            self.materialize(position);
+        }
+    }
+
+    /// Prepares the emulated value stack for entering a control flow frame with `len_params` parameters.
+    ///
+    /// # Note
+    ///
+    /// Labels might join multiple control flow paths which is why values on the
+    /// emulated value stack must not refer to mutable local variables across them.
+    /// The parameters of the control flow frame are copied into their temporary
+    /// registers since branches to the frame expect them there.
+    fn materialize_frame_entry(&mut self, len_params: u32) {
+        let height = self.value_stack.len();
+        for position in 0..height {
+            let is_local = matches!(self.value_stack.get(position).provider, Provider::Local(_));
+            if is_local || position >= height - len_params {
+                self.materialize(position);
             }
-            ControlFrameKind::Loop => frame.block_type().len_params(self.engine),
-        };
-        // Find out how many values we need to drop.
-        let current_height = self.value_stack.len();
-        let origin_height = frame.stack_height();
-        assert!(
-            origin_height <= current_height,
-            "encountered value stack underflow: current height {}, original height {}",
-            current_height,
-            origin_height,
-        );
-        let height_diff = current_height - origin_height;
-        assert!(
-            keep <= height_diff,
-            "tried to keep {} values while having only {} values available on the frame",
-            keep,
-            height_diff,
-        );
-        let drop = height_diff - keep;
-        DropKeep::new32(drop, keep)
-    }
-
-    /// Compute [`DropKeep`] for the return statement.
+        }
+    }
+
+    /// Copies all values referring to the local variable at `local_idx` into their temporary [`Register`].
     ///
-    /// # Panics
+    /// # Note
     ///
-    /// - If the control flow frame stack is empty.
-    /// - If the value stack is underflown.
-    fn drop_keep_return(&self) -> DropKeep {
-        debug_assert!(self.is_reachable());
-        assert!(
-            !self.control_frames.is_empty(),
-            "drop_keep_return cannot be called with the frame stack empty"
-        );
-        let max_depth = self
-            .control_frames
-            .len()
-            .checked_sub(1)
-            .expect("control flow frame stack must not be empty") as u32;
-        let drop_keep = self.compute_drop_keep(max_depth);
-        let len_params_locals = self.locals.len_registered() as usize;
-        DropKeep::new(
-            // Drop all local variables and parameters upon exit.
-            drop_keep.drop() + len_params_locals,
-            drop_keep.keep(),
-        )
+    /// This is required before the local variable is overwritten.
+    fn preserve_local(&mut self, local_idx: u32) {
+        for position in 0..self.value_stack.len() {
+            if self.value_stack.get(position).provider == Provider::Local(local_idx) {
+                self.materialize(position);
+            }
+        }
     }
 
-    /// Returns the relative depth on the stack of the local variable.
+    /// Copies the `len` top most values of the emulated value stack into
+    /// the temporary registers starting at the stack `height`.
     ///
     /// # Note
     ///
-    /// See stack layout definition in `isa.rs`.
-    fn relative_local_depth(&self, local_idx: u32) -> u32 {
-        debug_assert!(self.is_reachable());
-        let stack_height = self.value_stack.len();
-        let len_params_locals = self.locals.len_registered();
-        stack_height
-            .checked_add(len_params_locals)
-            .and_then(|x| x.checked_sub(local_idx))
-            .unwrap_or_else(|| panic!("cannot convert local index into local depth: {}", local_idx))
+    /// This does not alter the emulated value stack since it is used
+    /// right before branching to a control flow frame at `height`.
+    fn copy_branch_values(&mut self, height: u32, len: u32) {
+        let first = self.value_stack.len() - len;
+        // Note: Copying in ascending order is safe since source
+        //       registers are never below their destination registers.
+        for n in 0..len {
+            let result = self.temp_register(height + n);
+            let input = self.register_at(first + n);
+            if result != input {
+                self.inst_builder
+                    .push_inst(Instruction::Copy(UnaryOp { result, input }));
+            }
+        }
     }
 
-    /// Returns the target at the given `depth` together with its [`DropKeep`].
+    /// Returns the [`RegisterSpan`] of the `len` top most values of the emulated value stack.
+    ///
+    /// # Note
+    ///
+    /// Copies the values into their temporary registers if required.
+    fn results_span(&mut self, len: u32) -> RegisterSpan {
+        let height = self.value_stack.len();
+        match len {
+            0 => RegisterSpan::new(Register::from(0), 0),
+            1 => RegisterSpan::new(self.register_at(height - 1), 1),
+            _ => {
+                self.materialize_top(len);
+                RegisterSpan::new(self.temp_register(height - len), len)
+            }
+        }
+    }
+
+    /// Returns the number of values kept by branches to the control flow frame at `depth`.
+    fn len_branch_values(&self, depth: u32) -> u32 {
+        let frame = self.control_frames.nth_back(depth);
+        match frame.kind() {
+            ControlFrameKind::Block | ControlFrameKind::If => {
+                frame.block_type().len_results(&self.target)
+            }
+            ControlFrameKind::Loop => frame.block_type().len_params(&self.target),
+        }
+    }
+
+    /// Returns the target at the given `depth`.
     ///
     /// # Panics
     ///
@@ -291,18 +402,35 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     /// - If the value stack underflowed.
     fn acquire_target(&self, relative_depth: u32) -> AquiredTarget {
         debug_assert!(self.is_reachable());
+        let len = self.len_branch_values(relative_depth);
         if self.control_frames.is_root(relative_depth) {
-            let drop_keep = self.drop_keep_return();
-            AquiredTarget::Return(drop_keep)
+            AquiredTarget::Return { len }
         } else {
-            let label = self
-                .control_frames
-                .nth_back(relative_depth)
-                .branch_destination();
-            let drop_keep = self.compute_drop_keep(relative_depth);
-            AquiredTarget::Branch(label, drop_keep)
+            let frame = self.control_frames.nth_back(relative_depth);
+            let height = frame.stack_height();
+            let current_height = self.value_stack.len();
+            assert!(
+                height + len <= current_height,
+                "encountered value stack underflow: current height {}, branch to height {} with {} values",
+                current_height,
+                height,
+                len,
+            );
+            AquiredTarget::Branch {
+                label: frame.branch_destination(),
+                height,
+                len,
+            }
         }
     }
+
+    /// Pushes a `br` instruction to the `label` carrying `len` values to the stack `height`.
+    fn push_br(&mut self, label: LabelIdx, height: u32, len: u32) {
+        self.copy_branch_values(height, len);
+        let dst_pc = self.try_resolve_label(label, |pc| Reloc::Br { inst_idx: pc });
+        self.inst_builder
+            .push_inst(Instruction::Br(Target::new(dst_pc)));
+    }
 }
 
 /// An aquired target.
@@ -311,7 +439,14 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
 #[derive(Debug)]
 pub enum AquiredTarget {
     /// The branch jumps to the label.
-    Branch(LabelIdx, DropKeep),
+    Branch {
+        /// The label of the branch destination.
+        label: LabelIdx,
+        /// The height of the emulated value stack at the branch destination.
+        height: u32,
+        /// The number of values carried by the branch.
+        len: u32,
+    },
     /// The branch returns to the caller.
     ///
     /// # Note
@@ -319,10 +454,16 @@ pub enum AquiredTarget {
     /// This is returned if the `relative_depth` points to the outmost
     /// function body `block`. WebAssembly defines branches to this control
     /// flow frame as equivalent to returning from the function.
-    Return(DropKeep),
+    Return {
+        /// The number of values returned to the caller.
+        len: u32,
+    },
 }
 
-impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
+impl<'parser, T> FunctionBuilder<'parser, T>
+where
+    T: TranslationTarget,
+{
     /// Translates a Wasm `unreachable` instruction.
     pub fn translate_unreachable(&mut self) -> Result<(), ModuleError> {
         self.translate_if_reachable(|builder| {
@@ -346,7 +487,7 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     /// When the emulated value stack underflows. This should not happen
     /// since we have already validated the input Wasm prior.
     fn frame_stack_height(&self, block_type: BlockType) -> u32 {
-        let len_params = block_type.len_params(self.engine);
+        let len_params = block_type.len_params(&self.target);
         let stack_height = self.value_stack.len();
         stack_height.checked_sub(len_params).unwrap_or_else(|| {
             panic!(
@@ -361,6 +502,7 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     pub fn translate_block(&mut self, block_type: BlockType) -> Result<(), ModuleError> {
         let stack_height = self.frame_stack_height(block_type);
         if self.is_reachable() {
+            self.materialize_frame_entry(block_type.len_params(&self.target));
             let end_label = self.inst_builder.new_label();
             self.control_frames.push_frame(BlockControlFrame::new(
                 block_type,
@@ -381,6 +523,7 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     pub fn translate_loop(&mut self, block_type: BlockType) -> Result<(), ModuleError> {
         let stack_height = self.frame_stack_height(block_type);
         if self.is_reachable() {
+            self.materialize_frame_entry(block_type.len_params(&self.target));
             let header = self.inst_builder.new_label();
             self.inst_builder.resolve_label(header);
             self.control_frames
@@ -398,21 +541,39 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     /// Translates a Wasm `if` control flow operator.
     pub fn translate_if(&mut self, block_type: BlockType) -> Result<(), ModuleError> {
         if self.is_reachable() {
-            let condition = self.value_stack.pop1();
-            debug_assert_eq!(condition, ValueType::I32);
+            let constant = self.const_at(self.value_stack.len() - 1);
+            let condition = match constant {
+                Some(_) => {
+                    self.value_stack.pop1();
+                    None
+                }
+                None => {
+                    let condition = self.pop_register(ValueType::I32);
+                    Some(self.take_condition(condition))
+                }
+            };
             let stack_height = self.frame_stack_height(block_type);
+            self.materialize_frame_entry(block_type.len_params(&self.target));
             let else_label = self.inst_builder.new_label();
             let end_label = self.inst_builder.new_label();
-            self.control_frames.push_frame(IfControlFrame::new(
-                block_type,
-                end_label,
-                else_label,
-                stack_height,
-            ));
-            let dst_pc = self.try_resolve_label(else_label, |pc| Reloc::Br { inst_idx: pc });
-            let branch_target = Target::new(dst_pc, DropKeep::new(0, 0));
-            self.inst_builder
-                .push_inst(Instruction::BrIfEqz(branch_target));
+            let mut if_frame = IfControlFrame::new(block_type, end_label, else_label, stack_height);
+            match (condition, constant.map(bool::from)) {
+                (Some(condition), _) => {
+                    let dst_pc =
+                        self.try_resolve_label(else_label, |pc| Reloc::Br { inst_idx: pc });
+                    self.inst_builder
+                        .push_inst(condition.branch_if_eqz(Target::new(dst_pc)));
+                }
+                (None, Some(true)) => {
+                    // The `then` branch is always taken.
+                    if_frame.make_else_unreachable();
+                }
+                (None, _) => {
+                    // The `else` branch is always taken.
+                    self.reachable = false;
+                }
+            }
+            self.control_frames.push_frame(if_frame);
         } else {
             let stack_height = self.frame_stack_height(block_type);
             self.control_frames.push_frame(UnreachableControlFrame::new(
@@ -451,28 +612,43 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
         // Create the jump from the end of the `then` block to the `if`
         // block's end label in case the end of `then` is reachable.
         if reachable {
-            let dst_pc =
-                self.try_resolve_label(if_frame.end_label(), |pc| Reloc::Br { inst_idx: pc });
-            let target = Target::new(dst_pc, DropKeep::new(0, 0));
-            self.inst_builder.push_inst(Instruction::Br(target));
+            let len_results = if_frame.block_type().len_results(&self.target);
+            if if_frame.is_else_reachable() {
+                self.push_br(if_frame.end_label(), if_frame.stack_height(), len_results);
+            } else {
+                // The `then` block falls through to the end since the `else` block is skipped.
+                self.copy_branch_values(if_frame.stack_height(), len_results);
+            }
         }
         // Now resolve labels for the instructions of the `else` block
         self.inst_builder.resolve_label(if_frame.else_label());
         // We need to reset the value stack to exactly how it has been
         // when entering the `if` in the first place so that the `else`
         // block has the same parameters on top of the stack.
+        //
+        // Note: The parameters reside in their temporary registers.
         self.value_stack.shrink_to(if_frame.stack_height());
-        if_frame.block_type().foreach_param(self.engine, |param| {
-            self.value_stack.push(param);
+        if_frame.block_type().foreach_param(&self.target, |param| {
+            self.value_stack.push_temp(param);
         });
         self.control_frames.push_frame(if_frame);
-        // We can reset reachability now since the parent `if` block was reachable.
-        self.reachable = true;
+        // We can reset reachability now since the parent `if` block was reachable
+        // unless its condition is known to always take the `then` branch.
+        self.reachable = if_frame.is_else_reachable();
         Ok(())
     }
 
     /// Translates a Wasm `end` control flow operator.
     pub fn translate_end(&mut self) -> Result<(), ModuleError> {
+        let frame = self.control_frames.last();
+        let frame_stack_height = frame.stack_height();
+        let is_root = self.control_frames.len() == 1;
+        if self.is_reachable() && !is_root {
+            // The results of the control flow frame are expected
+            // in the temporary registers starting at its stack height.
+            let len_results = frame.block_type().len_results(&self.target);
+            self.copy_branch_values(frame_stack_height, len_results);
+        }
         let frame = self.control_frames.last();
         if let ControlFrame::If(if_frame) = &frame {
             // At this point we can resolve the `Else` label.
@@ -489,8 +665,7 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
         }
         // These bindings are required because of borrowing issues.
         let frame_reachable = frame.is_reachable();
-        let frame_stack_height = frame.stack_height();
-        if self.control_frames.len() == 1 {
+        if is_root {
             // If the control flow frames stack is empty after this point
             // we know that we are endeding the function body `block`
             // frame and therefore we have to return from the function.
@@ -504,7 +679,7 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
         let frame = self.control_frames.pop_frame();
         frame
             .block_type()
-            .foreach_result(self.engine, |result| self.value_stack.push(result));
+            .foreach_result(&self.target, |result| self.value_stack.push_temp(result));
         Ok(())
     }
 
@@ -512,14 +687,10 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     pub fn translate_br(&mut self, relative_depth: u32) -> Result<(), ModuleError> {
         self.translate_if_reachable(|builder| {
             match builder.acquire_target(relative_depth) {
-                AquiredTarget::Branch(end_label, drop_keep) => {
-                    let dst_pc =
-                        builder.try_resolve_label(end_label, |pc| Reloc::Br { inst_idx: pc });
-                    builder
-                        .inst_builder
-                        .push_inst(Instruction::Br(Target::new(dst_pc, drop_keep)));
+                AquiredTarget::Branch { label, height, len } => {
+                    builder.push_br(label, height, len);
                 }
-                AquiredTarget::Return(drop_keep) => {
+                AquiredTarget::Return { .. } => {
                     // In this case the `br` can be directly translated as `return`.
                     builder.translate_return()?;
                 }
@@ -532,20 +703,48 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     /// Translates a Wasm `br_if` control flow operator.
     pub fn translate_br_if(&mut self, relative_depth: u32) -> Result<(), ModuleError> {
         self.translate_if_reachable(|builder| {
-            let condition = builder.value_stack.pop1();
-            debug_assert_eq!(condition, ValueType::I32);
+            if let Some(condition) = builder.const_at(builder.value_stack.len() - 1) {
+                // The branch is either always or never taken.
+                builder.value_stack.pop1();
+                if bool::from(condition) {
+                    builder.translate_br(relative_depth)?;
+                }
+                return Ok(());
+            }
+            let condition = builder.pop_register(ValueType::I32);
             match builder.acquire_target(relative_depth) {
-                AquiredTarget::Branch(end_label, drop_keep) => {
-                    let dst_pc =
-                        builder.try_resolve_label(end_label, |pc| Reloc::Br { inst_idx: pc });
+                AquiredTarget::Branch { label, height, len }
+                    if height + len == builder.value_stack.len() =>
+                {
+                    // The branch does not drop any values so that we can
+                    // branch directly once the values reside in their temporaries.
+                    //
+                    // Note: The condition is taken before materializing since the
+                    //       copies only write to registers below the condition.
+                    let condition = builder.take_condition(condition);
+                    builder.materialize_top(len);
+                    let dst_pc = builder.try_resolve_label(label, |pc| Reloc::Br { inst_idx: pc });
+                    builder
+                        .inst_builder
+                        .push_inst(condition.branch_if_nez(Target::new(dst_pc)));
+                }
+                AquiredTarget::Branch { label, height, len } => {
+                    // The values need to be copied to the branch destination
+                    // which must only happen if the branch is actually taken.
+                    let condition = builder.take_condition(condition);
+                    let skip = builder.inst_builder.new_label();
+                    let dst_pc = builder.try_resolve_label(skip, |pc| Reloc::Br { inst_idx: pc });
                     builder
                         .inst_builder
-                        .push_inst(Instruction::BrIfNez(Target::new(dst_pc, drop_keep)));
+                        .push_inst(condition.branch_if_eqz(Target::new(dst_pc)));
+                    builder.push_br(label, height, len);
+                    builder.inst_builder.resolve_label(skip);
                 }
-                AquiredTarget::Return(drop_keep) => {
+                AquiredTarget::Return { len } => {
+                    let results = builder.results_span(len);
                     builder
                         .inst_builder
-                        .push_inst(Instruction::ReturnIfNez(drop_keep));
+                        .push_inst(Instruction::ReturnIfNez { condition, results });
                 }
             }
             Ok(())
@@ -553,50 +752,74 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     }
 
     /// Translates a Wasm `br_table` control flow operator.
-    pub fn translate_br_table<T>(
+    pub fn translate_br_table<I>(
         &mut self,
         default: RelativeDepth,
-        targets: T,
+        targets: I,
     ) -> Result<(), ModuleError>
     where
-        T: IntoIterator<Item = RelativeDepth>,
+        I: IntoIterator<Item = RelativeDepth>,
     {
         self.translate_if_reachable(|builder| {
-            let case = builder.value_stack.pop1();
-            debug_assert_eq!(case, ValueType::I32);
-
-            fn compute_inst(
-                builder: &mut FunctionBuilder,
-                n: usize,
-                depth: RelativeDepth,
-            ) -> Instruction {
+            let index = builder.pop_register(ValueType::I32);
+            let targets = targets.into_iter().chain(Some(default)).collect::<Vec<_>>();
+            // All branch targets of a `br_table` carry the same number of values.
+            let len = builder.len_branch_values(default.into_u32());
+            builder.materialize_top(len);
+            let results = builder.results_span(len);
+            let height = builder.value_stack.len();
+            let offset = builder
+                .inst_builder
+                .push_br_targets(targets.iter().map(|_| Target::new(InstructionIdx::INVALID)));
+            builder.inst_builder.push_inst(Instruction::BrTable {
+                index,
+                offset: offset as u32,
+                len_targets: targets.len() as u32,
+            });
+            // Branches that drop values are redirected to trampolines
+            // following the `br_table` that copy the values accordingly.
+            // Branches that return from the function share a single trampoline.
+            let mut trampolines = Vec::new();
+            let mut returns = Vec::new();
+            for (n, depth) in targets.into_iter().enumerate() {
+                let target_idx = offset + n;
                 match builder.acquire_target(depth.into_u32()) {
-                    AquiredTarget::Branch(label_idx, drop_keep) => {
-                        let dst_pc = builder.try_resolve_label(label_idx, |pc| Reloc::BrTable {
-                            inst_idx: pc,
-                            target_idx: n,
-                        });
-                        Instruction::Br(Target::new(dst_pc, drop_keep))
+                    AquiredTarget::Branch {
+                        label,
+                        height: dst_height,
+                        len,
+                    } if dst_height + len == height => {
+                        let dst_pc = builder
+                            .inst_builder
+                            .try_resolve_label(label, || Reloc::BrTable { target_idx });
+                        if dst_pc != InstructionIdx::INVALID {
+                            builder
+                                .inst_builder
+                                .patch_relocation(Reloc::BrTable { target_idx }, dst_pc);
+                        }
+                    }
+                    AquiredTarget::Branch { label, height, len } => {
+                        trampolines.push((target_idx, label, height, len));
                     }
-                    AquiredTarget::Return(drop_keep) => Instruction::Return(drop_keep),
+                    AquiredTarget::Return { .. } => returns.push(target_idx),
                 }
             }
-
-            let branches = targets
-                .into_iter()
-                .enumerate()
-                .map(|(n, depth)| compute_inst(builder, n, depth))
-                .collect::<Vec<_>>();
-            // We include the default target in `len_branches`.
-            let len_branches = branches.len();
-            let default_branch = compute_inst(builder, len_branches, default);
-            builder.inst_builder.push_inst(Instruction::BrTable {
-                len_targets: len_branches + 1,
-            });
-            for branch in branches {
-                builder.inst_builder.push_inst(branch);
+            if !returns.is_empty() {
+                let trampoline = builder.inst_builder.current_pc();
+                for target_idx in returns {
+                    builder
+                        .inst_builder
+                        .patch_relocation(Reloc::BrTable { target_idx }, trampoline);
+                }
+                builder.inst_builder.push_inst(Instruction::Return(results));
+            }
+            for (target_idx, label, height, len) in trampolines {
+                let trampoline = builder.inst_builder.current_pc();
+                builder
+                    .inst_builder
+                    .patch_relocation(Reloc::BrTable { target_idx }, trampoline);
+                builder.push_br(label, height, len);
             }
-            builder.inst_builder.push_inst(default_branch);
             builder.reachable = false;
             Ok(())
         })
@@ -605,34 +828,46 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     /// Translates a Wasm `return` control flow operator.
     pub fn translate_return(&mut self) -> Result<(), ModuleError> {
         self.translate_if_reachable(|builder| {
-            let drop_keep = builder.drop_keep_return();
-            builder
-                .inst_builder
-                .push_inst(Instruction::Return(drop_keep));
+            let len_results = builder.func_type().results().len() as u32;
+            let results = builder.results_span(len_results);
+            builder.inst_builder.push_inst(Instruction::Return(results));
             builder.reachable = false;
             Ok(())
         })
     }
 
     /// Adjusts the emulated [`ValueStack`] given the [`FuncType`] of the call.
-    fn adjust_value_stack_for_call(&mut self, func_type: &FuncType) {
+    ///
+    /// Returns the [`Register`] of the first call parameter.
+    ///
+    /// # Note
+    ///
+    /// The call parameters are copied into consecutive temporary registers
+    /// where the called function frame begins and the call results are
+    /// returned to the same temporary registers.
+    fn adjust_value_stack_for_call(&mut self, func_type: &FuncType) -> Register {
         let (params, results) = func_type.params_results();
+        self.materialize_top(params.len() as u32);
         for param in params.iter().rev() {
             let popped = self.value_stack.pop1();
-            debug_assert_eq!(popped, *param);
+            debug_assert_eq!(popped.value_type, *param);
         }
+        let params = self.temp_register(self.value_stack.len());
         for result in results {
-            self.value_stack.push(*result);
+            self.value_stack.push_temp(*result);
         }
+        params
     }
 
     /// Translates a Wasm `call` instruction.
     pub fn translate_call(&mut self, func_idx: FuncIdx) -> Result<(), ModuleError> {
         self.translate_if_reachable(|builder| {
             let func_type = builder.func_type_of(func_idx);
-            builder.adjust_value_stack_for_call(&func_type);
-            let func_idx = func_idx.into_u32().into();
-            builder.inst_builder.push_inst(Instruction::Call(func_idx));
+            let params = builder.adjust_value_stack_for_call(&func_type);
+            let func = func_idx.into_u32().into();
+            builder
+                .inst_builder
+                .push_inst(Instruction::Call { func, params });
             Ok(())
         })
     }
@@ -647,23 +882,33 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
             /// The default Wasm MVP table index.
             const DEFAULT_TABLE_INDEX: u32 = 0;
             assert_eq!(table_idx.into_u32(), DEFAULT_TABLE_INDEX);
-            let func_type_offset = builder.value_stack.pop1();
-            debug_assert_eq!(func_type_offset, ValueType::I32);
+            let index = builder.pop_register(ValueType::I32);
             let func_type = builder.func_type_at(func_type_idx);
-            builder.adjust_value_stack_for_call(&func_type);
-            let func_type_idx = func_type_idx.into_u32().into();
-            builder
-                .inst_builder
-                .push_inst(Instruction::CallIndirect(func_type_idx));
+            let params = builder.adjust_value_stack_for_call(&func_type);
+            let signature = func_type_idx.into_u32().into();
+            builder.inst_builder.push_inst(Instruction::CallIndirect {
+                signature,
+                index,
+                params,
+            });
             Ok(())
         })
     }
 
     /// Translates a Wasm `drop` instruction.
+    ///
+    /// # Note
+    ///
+    /// Removes the last instruction if it is pure and computed the dropped value.
     pub fn translate_drop(&mut self) -> Result<(), ModuleError> {
         self.translate_if_reachable(|builder| {
-            builder.value_stack.pop1();
-            builder.inst_builder.push_inst(Instruction::Drop);
+            let value = builder.value_stack.pop1();
+            if let Provider::Temp = value.provider {
+                let temp = builder.temp_register(builder.value_stack.len());
+                builder.inst_builder.pop_last_if(|inst| {
+                    const_fold::is_pure(inst) && inst.result_mut().copied() == Some(temp)
+                });
+            }
             Ok(())
         })
     }
@@ -671,11 +916,40 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     /// Translates a Wasm `select` instruction.
     pub fn translate_select(&mut self) -> Result<(), ModuleError> {
         self.translate_if_reachable(|builder| {
-            let (v0, v1, selector) = builder.value_stack.pop3();
-            debug_assert_eq!(selector, ValueType::I32);
-            debug_assert_eq!(v0, v1);
-            builder.value_stack.push(v0);
-            builder.inst_builder.push_inst(Instruction::Select);
+            if let Some(condition) = builder.const_at(builder.value_stack.len() - 1) {
+                // The selected value is known during translation.
+                builder.value_stack.pop1();
+                let if_false = builder.value_stack.pop1();
+                if bool::from(condition) {
+                    return Ok(());
+                }
+                let if_true = builder.value_stack.pop1();
+                let position = builder.value_stack.len();
+                builder
+                    .value_stack
+                    .push(if_true.value_type, if_false.provider);
+                if let Provider::Temp = if_false.provider {
+                    let result = builder.temp_register(position);
+                    let input = builder.temp_register(position + 1);
+                    builder
+                        .inst_builder
+                        .push_inst(Instruction::Copy(UnaryOp { result, input }));
+                }
+                return Ok(());
+            }
+            let condition = builder.pop_register(ValueType::I32);
+            let value_type = builder.value_stack.top().value_type;
+            let if_false = builder.pop_register(value_type);
+            // The result is written to the temporary of the first value
+            // which therefore is required to reside there.
+            let position = builder.value_stack.len() - 1;
+            builder.materialize(position);
+            let result = builder.temp_register(position);
+            builder.inst_builder.push_inst(Instruction::Select {
+                result,
+                condition,
+                if_false,
+            });
             Ok(())
         })
     }
@@ -683,32 +957,57 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     /// Translate a Wasm `local.get` instruction.
     pub fn translate_local_get(&mut self, local_idx: u32) -> Result<(), ModuleError> {
         self.translate_if_reachable(|builder| {
-            let local_depth = builder.relative_local_depth(local_idx);
-            builder
-                .inst_builder
-                .push_inst(Instruction::local_get(local_depth));
             let value_type = builder
                 .locals
                 .resolve_local(local_idx)
                 .unwrap_or_else(|| panic!("failed to resolve local {}", local_idx));
-            builder.value_stack.push(value_type);
+            builder
+                .value_stack
+                .push(value_type, Provider::Local(local_idx));
             Ok(())
         })
     }
 
+    /// Writes the top most value of the emulated value stack into the local variable at `local_idx`.
+    ///
+    /// # Note
+    ///
+    /// Retargets the result of the last instruction to the local variable
+    /// if possible instead of copying the value.
+    fn write_local(&mut self, local_idx: u32) {
+        let expected = self
+            .locals
+            .resolve_local(local_idx)
+            .unwrap_or_else(|| panic!("failed to resolve local {}", local_idx));
+        let value = self.value_stack.pop1();
+        debug_assert_eq!(value.value_type, expected);
+        let position = self.value_stack.len();
+        self.preserve_local(local_idx);
+        let local = Register::from(local_idx);
+        let input = match value.provider {
+            Provider::Local(idx) if idx == local_idx => return,
+            provider => self.provider_register(position, provider),
+        };
+        if let (Provider::Temp, Some(result)) =
+            (value.provider, self.inst_builder.last_result_mut())
+        {
+            if *result == input {
+                // The temporary is not used elsewhere so that
+                // the last instruction can write the local directly.
+                *result = local;
+                return;
+            }
+        }
+        self.inst_builder.push_inst(Instruction::Copy(UnaryOp {
+            result: local,
+            input,
+        }));
+    }
+
     /// Translate a Wasm `local.set` instruction.
     pub fn translate_local_set(&mut self, local_idx: u32) -> Result<(), ModuleError> {
         self.translate_if_reachable(|builder| {
-            let actual = builder.value_stack.pop1();
-            let local_depth = builder.relative_local_depth(local_idx);
-            builder
-                .inst_builder
-                .push_inst(Instruction::local_set(local_depth));
-            let expected = builder
-                .locals
-                .resolve_local(local_idx)
-                .unwrap_or_else(|| panic!("failed to resolve local {}", local_idx));
-            debug_assert_eq!(actual, expected);
+            builder.write_local(local_idx);
             Ok(())
         })
     }
@@ -716,16 +1015,11 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     /// Translate a Wasm `local.tee` instruction.
     pub fn translate_local_tee(&mut self, local_idx: u32) -> Result<(), ModuleError> {
         self.translate_if_reachable(|builder| {
-            let local_depth = builder.relative_local_depth(local_idx);
+            let value_type = builder.value_stack.top().value_type;
+            builder.write_local(local_idx);
             builder
-                .inst_builder
-                .push_inst(Instruction::local_tee(local_depth));
-            let expected = builder
-                .locals
-                .resolve_local(local_idx)
-                .unwrap_or_else(|| panic!("failed to resolve local {}", local_idx));
-            let actual = builder.value_stack.top();
-            debug_assert_eq!(actual, expected);
+                .value_stack
+                .push(value_type, Provider::Local(local_idx));
             Ok(())
         })
     }
@@ -734,11 +1028,12 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     pub fn translate_global_get(&mut self, global_idx: GlobalIdx) -> Result<(), ModuleError> {
         self.translate_if_reachable(|builder| {
             let global_type = builder.res.get_type_of_global(global_idx);
-            builder.value_stack.push(global_type.value_type());
-            let global_idx = global_idx.into_u32().into();
+            let result = builder.temp_register(builder.value_stack.len());
+            builder.value_stack.push_temp(global_type.value_type());
+            let global = global_idx.into_u32().into();
             builder
                 .inst_builder
-                .push_inst(Instruction::GetGlobal(global_idx));
+                .push_inst(Instruction::GetGlobal { result, global });
             Ok(())
         })
     }
@@ -748,13 +1043,11 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
         self.translate_if_reachable(|builder| {
             let global_type = builder.res.get_type_of_global(global_idx);
             debug_assert_eq!(global_type.mutability(), Mutability::Mutable);
-            let expected = global_type.value_type();
-            let actual = builder.value_stack.pop1();
-            debug_assert_eq!(actual, expected);
-            let global_idx = global_idx.into_u32().into();
+            let input = builder.pop_register(global_type.value_type());
+            let global = global_idx.into_u32().into();
             builder
                 .inst_builder
-                .push_inst(Instruction::SetGlobal(global_idx));
+                .push_inst(Instruction::SetGlobal { global, input });
             Ok(())
         })
     }
@@ -784,15 +1077,19 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
         memory_idx: MemoryIdx,
         offset: u32,
         loaded_type: ValueType,
-        make_inst: fn(Offset) -> Instruction,
+        make_inst: fn(LoadOp) -> Instruction,
     ) -> Result<(), ModuleError> {
         self.translate_if_reachable(|builder| {
             debug_assert_eq!(memory_idx.into_u32(), DEFAULT_MEMORY_INDEX);
-            let pointer = builder.value_stack.pop1();
-            debug_assert_eq!(pointer, ValueType::I32);
-            builder.value_stack.push(loaded_type);
+            let ptr = builder.pop_register(ValueType::I32);
+            let result = builder.temp_register(builder.value_stack.len());
+            builder.value_stack.push_temp(loaded_type);
             let offset = Offset::from(offset);
-            builder.inst_builder.push_inst(make_inst(offset));
+            builder.inst_builder.push_inst(make_inst(LoadOp {
+                result,
+                ptr,
+                offset,
+            }));
             Ok(())
         })
     }
@@ -943,15 +1240,16 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
         memory_idx: MemoryIdx,
         offset: u32,
         stored_value: ValueType,
-        make_inst: fn(Offset) -> Instruction,
+        make_inst: fn(StoreOp) -> Instruction,
     ) -> Result<(), ModuleError> {
         self.translate_if_reachable(|builder| {
             debug_assert_eq!(memory_idx.into_u32(), DEFAULT_MEMORY_INDEX);
-            let (pointer, stored) = builder.value_stack.pop2();
-            debug_assert_eq!(pointer, ValueType::I32);
-            assert_eq!(stored_value, stored);
+            let value = builder.pop_register(stored_value);
+            let ptr = builder.pop_register(ValueType::I32);
             let offset = Offset::from(offset);
-            builder.inst_builder.push_inst(make_inst(offset));
+            builder
+                .inst_builder
+                .push_inst(make_inst(StoreOp { ptr, offset, value }));
             Ok(())
         })
     }
@@ -1041,20 +1339,19 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     pub fn translate_memory_size(&mut self, memory_idx: MemoryIdx) -> Result<(), ModuleError> {
         self.translate_if_reachable(|builder| {
             debug_assert_eq!(memory_idx.into_u32(), DEFAULT_MEMORY_INDEX);
-            builder.value_stack.push(ValueType::I32);
-            builder.inst_builder.push_inst(Instruction::CurrentMemory);
+            let result = builder.temp_register(builder.value_stack.len());
+            builder.value_stack.push_temp(ValueType::I32);
+            builder
+                .inst_builder
+                .push_inst(Instruction::CurrentMemory { result });
             Ok(())
         })
     }
 
     /// Translate a Wasm `memory.grow` instruction.
     pub fn translate_memory_grow(&mut self, memory_idx: MemoryIdx) -> Result<(), ModuleError> {
-        self.translate_if_reachable(|builder| {
-            debug_assert_eq!(memory_idx.into_u32(), DEFAULT_MEMORY_INDEX);
-            debug_assert_eq!(builder.value_stack.top(), ValueType::I32);
-            builder.inst_builder.push_inst(Instruction::GrowMemory);
-            Ok(())
-        })
+        debug_assert_eq!(memory_idx.into_u32(), DEFAULT_MEMORY_INDEX);
+        self.translate_unary(ValueType::I32, ValueType::I32, Instruction::GrowMemory)
     }
 
     /// Translate a Wasm `<ty>.const` instruction.
@@ -1067,14 +1364,96 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     /// - `i64.const`
     /// - `f32.const`
     /// - `f64.const`
-    fn translate_const<T>(&mut self, value: T) -> Result<(), ModuleError>
+    fn translate_const<V>(&mut self, value: V) -> Result<(), ModuleError>
     where
-        T: Into<Value>,
+        V: Into<Value>,
     {
         self.translate_if_reachable(|builder| {
             let value = value.into();
-            builder.value_stack.push(value.value_type());
-            builder.inst_builder.push_inst(Instruction::constant(value));
+            builder.value_stack.push(
+                value.value_type(),
+                Provider::Const(UntypedValue::from(value)),
+            );
+            Ok(())
+        })
+    }
+
+    /// Translate a unary Wasm instruction producing a value of `output_type`.
+    ///
+    /// # Note
+    ///
+    /// The result is written to the temporary register of the input's stack position.
+    /// The operation is evaluated during translation if its input is a constant value.
+    fn translate_unary(
+        &mut self,
+        input_type: ValueType,
+        output_type: ValueType,
+        make_inst: fn(UnaryOp) -> Instruction,
+    ) -> Result<(), ModuleError> {
+        self.translate_if_reachable(|builder| {
+            let position = builder.value_stack.len() - 1;
+            if let Some(input) = builder.const_at(position) {
+                let placeholder = Register::from(0);
+                let inst = make_inst(UnaryOp {
+                    result: placeholder,
+                    input: placeholder,
+                });
+                if let Some(result) = const_fold::fold_unary(&inst, input) {
+                    builder.value_stack.pop1();
+                    builder
+                        .value_stack
+                        .push(output_type, Provider::Const(result));
+                    return Ok(());
+                }
+            }
+            let input = builder.pop_register(input_type);
+            let result = builder.temp_register(builder.value_stack.len());
+            builder.value_stack.push_temp(output_type);
+            builder
+                .inst_builder
+                .push_inst(make_inst(UnaryOp { result, input }));
+            Ok(())
+        })
+    }
+
+    /// Translate a binary Wasm instruction producing a value of `output_type`.
+    ///
+    /// # Note
+    ///
+    /// The result is written to the temporary register of the left-hand side's stack position.
+    /// The operation is evaluated during translation if both inputs are constant values.
+    fn translate_binary(
+        &mut self,
+        input_type: ValueType,
+        output_type: ValueType,
+        make_inst: fn(BinaryOp) -> Instruction,
+    ) -> Result<(), ModuleError> {
+        self.translate_if_reachable(|builder| {
+            let height = builder.value_stack.len();
+            if let (Some(lhs), Some(rhs)) =
+                (builder.const_at(height - 2), builder.const_at(height - 1))
+            {
+                let placeholder = Register::from(0);
+                let inst = make_inst(BinaryOp {
+                    result: placeholder,
+                    lhs: placeholder,
+                    rhs: placeholder,
+                });
+                if let Some(result) = const_fold::fold_binary(&inst, lhs, rhs) {
+                    builder.value_stack.shrink_to(height - 2);
+                    builder
+                        .value_stack
+                        .push(output_type, Provider::Const(result));
+                    return Ok(());
+                }
+            }
+            let rhs = builder.pop_register(input_type);
+            let lhs = builder.pop_register(input_type);
+            let result = builder.temp_register(builder.value_stack.len());
+            builder.value_stack.push_temp(output_type);
+            builder
+                .inst_builder
+                .push_inst(make_inst(BinaryOp { result, lhs, rhs }));
             Ok(())
         })
     }
@@ -1110,15 +1489,9 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     fn translate_unary_cmp(
         &mut self,
         input_type: ValueType,
-        inst: Instruction,
+        make_inst: fn(UnaryOp) -> Instruction,
     ) -> Result<(), ModuleError> {
-        self.translate_if_reachable(|builder| {
-            let condition = builder.value_stack.pop1();
-            debug_assert_eq!(condition, input_type);
-            builder.value_stack.push(ValueType::I32);
-            builder.inst_builder.push_inst(inst);
-            Ok(())
-        })
+        self.translate_unary(input_type, ValueType::I32, make_inst)
     }
 
     /// Translate a Wasm `i32.eqz` instruction.
@@ -1141,16 +1514,9 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     fn translate_binary_cmp(
         &mut self,
         input_type: ValueType,
-        inst: Instruction,
+        make_inst: fn(BinaryOp) -> Instruction,
     ) -> Result<(), ModuleError> {
-        self.translate_if_reachable(|builder| {
-            let (v0, v1) = builder.value_stack.pop2();
-            debug_assert_eq!(v0, v1);
-            debug_assert_eq!(v0, input_type);
-            builder.value_stack.push(ValueType::I32);
-            builder.inst_builder.push_inst(inst);
-            Ok(())
-        })
+        self.translate_binary(input_type, ValueType::I32, make_inst)
     }
 
     /// Translate a Wasm `i32.eq` instruction.
@@ -1337,14 +1703,9 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     pub fn translate_unary_operation(
         &mut self,
         value_type: ValueType,
-        inst: Instruction,
+        make_inst: fn(UnaryOp) -> Instruction,
     ) -> Result<(), ModuleError> {
-        self.translate_if_reachable(|builder| {
-            let actual_type = builder.value_stack.top();
-            debug_assert_eq!(actual_type, value_type);
-            builder.inst_builder.push_inst(inst);
-            Ok(())
-        })
+        self.translate_unary(value_type, value_type, make_inst)
     }
 
     /// Translate a Wasm `i32.clz` instruction.
@@ -1386,26 +1747,84 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     pub fn translate_binary_operation(
         &mut self,
         value_type: ValueType,
-        inst: Instruction,
+        make_inst: fn(BinaryOp) -> Instruction,
+    ) -> Result<(), ModuleError> {
+        self.translate_binary(value_type, value_type, make_inst)
+    }
+
+    /// Translate a Wasm binary operation that may use an immediate operand.
+    ///
+    /// # Note
+    ///
+    /// This is used to translate the following Wasm instructions:
+    ///
+    /// - `{i32, i64}.add`
+    /// - `{i32, i64}.sub`
+    ///
+    /// If the right-hand side input is a constant value that is converted
+    /// into an immediate value by `make_imm` the operation is translated
+    /// using `make_imm_inst` instead of `make_inst`. The same applies to
+    /// the left-hand side input of `commutative` operations.
+    fn translate_binary_imm(
+        &mut self,
+        value_type: ValueType,
+        commutative: bool,
+        make_inst: fn(BinaryOp) -> Instruction,
+        make_imm_inst: fn(ImmOp) -> Instruction,
+        make_imm: fn(UntypedValue) -> Option<i32>,
     ) -> Result<(), ModuleError> {
         self.translate_if_reachable(|builder| {
-            let (v0, v1) = builder.value_stack.pop2();
-            debug_assert_eq!(v0, v1);
-            debug_assert_eq!(v0, value_type);
-            builder.value_stack.push(value_type);
-            builder.inst_builder.push_inst(inst);
+            let height = builder.value_stack.len();
+            if builder.const_at(height - 2).is_some() && builder.const_at(height - 1).is_some() {
+                // Operations on constant values are folded instead.
+                return builder.translate_binary_operation(value_type, make_inst);
+            }
+            let rhs_imm = builder.const_at(height - 1).and_then(make_imm);
+            let lhs_imm = builder
+                .const_at(height - 2)
+                .filter(|_| commutative)
+                .and_then(make_imm);
+            let (input, imm) = match (lhs_imm, rhs_imm) {
+                (_, Some(imm)) => {
+                    builder.value_stack.pop1();
+                    (builder.pop_register(value_type), imm)
+                }
+                (Some(imm), None) => {
+                    let input = builder.pop_register(value_type);
+                    builder.value_stack.pop1();
+                    (input, imm)
+                }
+                (None, None) => return builder.translate_binary_operation(value_type, make_inst),
+            };
+            let result = builder.temp_register(builder.value_stack.len());
+            builder.value_stack.push_temp(value_type);
+            builder
+                .inst_builder
+                .push_inst(make_imm_inst(ImmOp { result, input, imm }));
             Ok(())
         })
     }
 
     /// Translate a Wasm `i32.add` instruction.
     pub fn translate_i32_add(&mut self) -> Result<(), ModuleError> {
-        self.translate_binary_operation(ValueType::I32, Instruction::I32Add)
+        self.translate_binary_imm(
+            ValueType::I32,
+            true,
+            Instruction::I32Add,
+            Instruction::I32AddImm,
+            |value| Some(i32::from(value)),
+        )
     }
 
     /// Translate a Wasm `i32.sub` instruction.
     pub fn translate_i32_sub(&mut self) -> Result<(), ModuleError> {
-        self.translate_binary_operation(ValueType::I32, Instruction::I32Sub)
+        self.translate_binary_imm(
+            ValueType::I32,
+            false,
+            Instruction::I32Sub,
+            Instruction::I32AddImm,
+            |value| Some(i32::from(value).wrapping_neg()),
+        )
     }
 
     /// Translate a Wasm `i32.mul` instruction.
@@ -1490,12 +1909,28 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
 
     /// Translate a Wasm `i64.add` instruction.
     pub fn translate_i64_add(&mut self) -> Result<(), ModuleError> {
-        self.translate_binary_operation(ValueType::I64, Instruction::I64Add)
+        self.translate_binary_imm(
+            ValueType::I64,
+            true,
+            Instruction::I64Add,
+            Instruction::I64AddImm,
+            |value| i32::try_from(i64::from(value)).ok(),
+        )
     }
 
     /// Translate a Wasm `i64.sub` instruction.
     pub fn translate_i64_sub(&mut self) -> Result<(), ModuleError> {
-        self.translate_binary_operation(ValueType::I64, Instruction::I64Sub)
+        self.translate_binary_imm(
+            ValueType::I64,
+            false,
+            Instruction::I64Sub,
+            Instruction::I64AddImm,
+            |value| {
+                i64::from(value)
+                    .checked_neg()
+                    .and_then(|imm| i32::try_from(imm).ok())
+            },
+        )
     }
 
     /// Translate a Wasm `i64.mul` instruction.
@@ -1715,21 +2150,37 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
     /// - `f32.demote_f64`
     /// - `f64.convert_{i32, u32, i64, u64}`
     /// - `f64.promote_f32`
+    pub fn translate_conversion(
+        &mut self,
+        input_type: ValueType,
+        output_type: ValueType,
+        make_inst: fn(UnaryOp) -> Instruction,
+    ) -> Result<(), ModuleError> {
+        self.translate_unary(input_type, output_type, make_inst)
+    }
+
+    /// Translate a Wasm reinterpret instruction.
+    ///
+    /// # Note
+    ///
+    /// This is used to translate the following Wasm instructions:
+    ///
     /// - `i32.reinterpret_f32`
     /// - `i64.reinterpret_f64`
     /// - `f32.reinterpret_i32`
     /// - `f64.reinterpret_i64`
-    pub fn translate_conversion(
+    ///
+    /// Reinterpretations do not alter the bits of the value and therefore
+    /// only change the type of the value on the emulated value stack.
+    fn translate_reinterpret(
         &mut self,
         input_type: ValueType,
         output_type: ValueType,
-        inst: Instruction,
     ) -> Result<(), ModuleError> {
         self.translate_if_reachable(|builder| {
             let input = builder.value_stack.pop1();
-            debug_assert_eq!(input, input_type);
-            builder.value_stack.push(output_type);
-            builder.inst_builder.push_inst(inst);
+            debug_assert_eq!(input.value_type, input_type);
+            builder.value_stack.push(output_type, input.provider);
             Ok(())
         })
     }
@@ -1841,38 +2292,22 @@ impl<'engine, 'parser> FunctionBuilder<'engine, 'parser> {
 
     /// Translate a Wasm `i32.reinterpret_f32` instruction.
     pub fn translate_i32_reinterpret_f32(&mut self) -> Result<(), ModuleError> {
-        self.translate_conversion(
-            ValueType::F32,
-            ValueType::I32,
-            Instruction::I32ReinterpretF32,
-        )
+        self.translate_reinterpret(ValueType::F32, ValueType::I32)
     }
 
     /// Translate a Wasm `i64.reinterpret_f64` instruction.
     pub fn translate_i64_reinterpret_f64(&mut self) -> Result<(), ModuleError> {
-        self.translate_conversion(
-            ValueType::F64,
-            ValueType::I64,
-            Instruction::I64ReinterpretF64,
-        )
+        self.translate_reinterpret(ValueType::F64, ValueType::I64)
     }
 
     /// Translate a Wasm `f32.reinterpret_i32` instruction.
     pub fn translate_f32_reinterpret_i32(&mut self) -> Result<(), ModuleError> {
-        self.translate_conversion(
-            ValueType::I32,
-            ValueType::F32,
-            Instruction::F32ReinterpretI32,
-        )
+        self.translate_reinterpret(ValueType::I32, ValueType::F32)
     }
 
     /// Translate a Wasm `f64.reinterpret_i64` instruction.
     pub fn translate_f64_reinterpret_i64(&mut self) -> Result<(), ModuleError> {
-        self.translate_conversion(
-            ValueType::I64,
-            ValueType::F64,
-            Instruction::F64ReinterpretI64,
-        )
+        self.translate_reinterpret(ValueType::I64, ValueType::F64)
     }
 
     /// Translate a Wasm `i32.extend_8s` instruction.
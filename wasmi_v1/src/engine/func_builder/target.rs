@@ -0,0 +1,133 @@
+use crate::{
+    engine::{
+        code_map::CodeMap,
+        func_types::FuncTypeRegistry,
+        DedupFuncType,
+        FuncBody,
+        Instruction,
+        Target,
+    },
+    Engine,
+    FuncType,
+};
+use wasmi_core::UntypedValue;
+
+/// The target into which the [`FunctionBuilder`] translates function bodies.
+///
+/// # Note
+///
+/// This is either a shared [`Engine`] or the borrowed code of an [`Engine`]
+/// that is already locked, e.g. while it lazily compiles a function body.
+///
+/// [`FunctionBuilder`]: super::FunctionBuilder
+pub trait TranslationTarget {
+    /// Resolves a deduplicated function type into a [`FuncType`] entity.
+    ///
+    /// # Panics
+    ///
+    /// If the deduplicated function type is not owned by the target.
+    fn resolve_func_type<F, R>(&self, func_type: DedupFuncType, f: F) -> R
+    where
+        F: FnOnce(&FuncType) -> R;
+
+    /// Allocates the instructions of a Wasm function body to the target.
+    ///
+    /// Returns a [`FuncBody`] reference to the allocated function body.
+    fn alloc_func_body<C, B, I>(
+        &mut self,
+        len_params: usize,
+        len_locals: usize,
+        max_stack_height: usize,
+        consts: C,
+        br_targets: B,
+        insts: I,
+    ) -> FuncBody
+    where
+        C: IntoIterator<Item = UntypedValue>,
+        B: IntoIterator<Item = Target>,
+        I: IntoIterator<Item = Instruction>;
+}
+
+impl TranslationTarget for &'_ Engine {
+    fn resolve_func_type<F, R>(&self, func_type: DedupFuncType, f: F) -> R
+    where
+        F: FnOnce(&FuncType) -> R,
+    {
+        Engine::resolve_func_type(self, func_type, f)
+    }
+
+    fn alloc_func_body<C, B, I>(
+        &mut self,
+        len_params: usize,
+        len_locals: usize,
+        max_stack_height: usize,
+        consts: C,
+        br_targets: B,
+        insts: I,
+    ) -> FuncBody
+    where
+        C: IntoIterator<Item = UntypedValue>,
+        B: IntoIterator<Item = Target>,
+        I: IntoIterator<Item = Instruction>,
+    {
+        Engine::alloc_func_body(
+            self,
+            len_params,
+            len_locals,
+            max_stack_height,
+            consts,
+            br_targets,
+            insts,
+        )
+    }
+}
+
+/// The borrowed code map and function types of a locked [`Engine`].
+#[derive(Debug)]
+pub struct EngineCode<'a> {
+    code_map: &'a mut CodeMap,
+    func_types: &'a FuncTypeRegistry,
+}
+
+impl<'a> EngineCode<'a> {
+    /// Creates a new [`EngineCode`] from the borrowed `code_map` and `func_types`.
+    pub fn new(code_map: &'a mut CodeMap, func_types: &'a FuncTypeRegistry) -> Self {
+        Self {
+            code_map,
+            func_types,
+        }
+    }
+}
+
+impl TranslationTarget for EngineCode<'_> {
+    fn resolve_func_type<F, R>(&self, func_type: DedupFuncType, f: F) -> R
+    where
+        F: FnOnce(&FuncType) -> R,
+    {
+        f(self.func_types.resolve_func_type(func_type))
+    }
+
+    fn alloc_func_body<C, B, I>(
+        &mut self,
+        len_params: usize,
+        len_locals: usize,
+        max_stack_height: usize,
+        consts: C,
+        br_targets: B,
+        insts: I,
+    ) -> FuncBody
+    where
+        C: IntoIterator<Item = UntypedValue>,
+        B: IntoIterator<Item = Target>,
+        I: IntoIterator<Item = Instruction>,
+    {
+        self.code_map.alloc(
+            len_params,
+            len_locals,
+            max_stack_height,
+            consts,
+            br_targets,
+            insts,
+        )
+    }
+}
@@ -1,12 +1,43 @@
 use core::cmp;
 
-use wasmi_core::ValueType;
+use wasmi_core::{UntypedValue, ValueType};
+
+/// The provider of a value on the emulated value stack.
+///
+/// # Note
+///
+/// Values of local variables and constants are not copied onto the
+/// value stack during translation. Instead the emulated value stack
+/// refers to their registers directly until they are consumed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Provider {
+    /// The value resides in the temporary register of its stack position.
+    Temp,
+    /// The value is provided by the local variable at the index.
+    Local(u32),
+    /// The value is the constant value.
+    ///
+    /// # Note
+    ///
+    /// Constant values are registered as function local constants
+    /// only once an instruction refers to them.
+    Const(UntypedValue),
+}
+
+/// A value on the emulated value stack.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StackValue {
+    /// The type of the value.
+    pub value_type: ValueType,
+    /// The provider of the value.
+    pub provider: Provider,
+}
 
 /// The value stack that is emulated during Wasm to `wasmi` bytecode translation.
 #[derive(Debug, Default)]
 pub struct ValueStack {
     /// The values of the emulated value stack.
-    values: Vec<ValueType>,
+    values: Vec<StackValue>,
     /// The maximum height of the emulated value stack of the translated function.
     ///
     /// # Note
@@ -28,7 +59,7 @@ impl ValueStack {
         self.max_stack_height = cmp::max(len, max);
     }
 
-    /// Pushes the [`ValueType`] to the emulated [`ValueStack`].
+    /// Pushes a value of type [`ValueType`] with its [`Provider`] to the emulated [`ValueStack`].
     ///
     /// # Note
     ///
@@ -37,51 +68,54 @@ impl ValueStack {
     /// translation from Wasm bytecode to `wasmi` bytecode.
     ///
     /// [`Value`]: [`wasmi_core::Value`]
-    pub fn push(&mut self, value_type: ValueType) {
-        self.values.push(value_type);
+    pub fn push(&mut self, value_type: ValueType, provider: Provider) {
+        self.values.push(StackValue {
+            value_type,
+            provider,
+        });
         self.update_max_height();
     }
 
-    /// Returns the top most [`ValueType`] form the emulated [`ValueStack`].
-    pub fn top(&self) -> ValueType {
+    /// Pushes a value of type [`ValueType`] residing in its temporary register.
+    pub fn push_temp(&mut self, value_type: ValueType) {
+        self.push(value_type, Provider::Temp)
+    }
+
+    /// Returns the top most [`StackValue`] form the emulated [`ValueStack`].
+    pub fn top(&self) -> StackValue {
         *self
             .values
             .last()
             .expect("tried to peek last value from empty emulated value stack")
     }
 
-    /// Pops the top most [`ValueType`] from the emulated [`ValueStack`].
+    /// Returns the [`StackValue`] at the `position` of the emulated [`ValueStack`].
     ///
     /// # Panics
     ///
-    /// If the emulated [`ValueStack`] is empty.
-    pub fn pop1(&mut self) -> ValueType {
-        self.values
-            .pop()
-            .expect("tried to pop value from an empty emulated value stack")
+    /// If the `position` is out of bounds.
+    pub fn get(&self, position: u32) -> StackValue {
+        self.values[position as usize]
     }
 
-    /// Pops the 2 top most [`ValueType`] from the emulated [`ValueStack`].
+    /// Replaces the [`Provider`] of the value at the `position` of the emulated [`ValueStack`].
     ///
     /// # Panics
     ///
-    /// If the emulated [`ValueStack`] is empty.
-    pub fn pop2(&mut self) -> (ValueType, ValueType) {
-        let rhs = self.pop1();
-        let lhs = self.pop1();
-        (lhs, rhs)
+    /// If the `position` is out of bounds.
+    pub fn set_provider(&mut self, position: u32, provider: Provider) {
+        self.values[position as usize].provider = provider;
     }
 
-    /// Pops the 3 top most [`ValueType`] from the emulated [`ValueStack`].
+    /// Pops the top most [`StackValue`] from the emulated [`ValueStack`].
     ///
     /// # Panics
     ///
     /// If the emulated [`ValueStack`] is empty.
-    pub fn pop3(&mut self) -> (ValueType, ValueType, ValueType) {
-        let v2 = self.pop1();
-        let v1 = self.pop1();
-        let v0 = self.pop1();
-        (v0, v1, v2)
+    pub fn pop1(&mut self) -> StackValue {
+        self.values
+            .pop()
+            .expect("tried to pop value from an empty emulated value stack")
     }
 
     /// Returns the current length of the emulated [`ValueStack`].
@@ -115,6 +149,6 @@ impl ValueStack {
                 error
             )
         });
-        self.values.resize(new_height, ValueType::I32);
+        self.values.truncate(new_height);
     }
 }
@@ -72,7 +72,7 @@ impl DedupFuncType {
 /// deduplicated [`FuncType`] instances is as fast as comparing integer values.
 /// Also with respect to `wasmi` bytecode deduplicated [`FuncType`] entities
 /// require a lot less space to be stored.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FuncTypeRegistry {
     /// A unique identifier for the associated engine.
     ///
@@ -115,6 +115,11 @@ impl FuncTypeRegistry {
         })
     }
 
+    /// Returns the number of deduplicated function types.
+    pub(crate) fn len(&self) -> usize {
+        self.func_types.len()
+    }
+
     /// Allocates a new function type to the engine.
     pub(crate) fn alloc_func_type(&mut self, func_type: FuncType) -> DedupFuncType {
         DedupFuncType::from_inner(Guarded::new(
@@ -1,8 +1,10 @@
 //! The `wasmi` interpreter.
 
 pub mod bytecode;
+mod cache;
 pub mod call_stack;
 pub mod code_map;
+mod compilation_cache;
 pub mod exec_context;
 mod func_args;
 mod func_builder;
@@ -12,15 +14,25 @@ pub mod value_stack;
 
 pub(crate) use self::func_args::{FuncParams, FuncResults};
 pub use self::{
-    bytecode::{DropKeep, Target},
+    bytecode::Target,
     code_map::FuncBody,
-    func_builder::{FunctionBuilder, InstructionIdx, LabelIdx, RelativeDepth, Reloc},
+    func_builder::{
+        EngineCode,
+        FunctionBuilder,
+        InstructionIdx,
+        LabelIdx,
+        RelativeDepth,
+        Reloc,
+        TranslationTarget,
+    },
     traits::{CallParams, CallResults},
 };
 use self::{
     bytecode::{Instruction, VisitInstruction},
+    cache::{Callee, InstanceCache},
     call_stack::{CallStack, FunctionFrame},
     code_map::{CodeMap, ResolvedFuncBody},
+    compilation_cache::CompilationCache,
     exec_context::ExecutionContext,
     func_types::FuncTypeRegistry,
     value_stack::ValueStack,
@@ -28,14 +40,18 @@ use self::{
 use super::{func::FuncEntityInternal, AsContext, AsContextMut, Func};
 use crate::{
     arena::{GuardedEntity, Index},
-    core::Trap,
+    core::{Trap, UntypedValue},
     func::HostFuncEntity,
+    memory::guard,
+    module::{ModuleInner, UncompiledFuncBody},
+    EngineStats,
     FuncType,
     Instance,
 };
 use alloc::sync::Arc;
 use core::{
     cmp,
+    mem,
     sync::atomic::{AtomicUsize, Ordering},
 };
 pub use func_types::DedupFuncType;
@@ -53,7 +69,7 @@ pub enum FunctionExecutionOutcome {
     /// The function has returned.
     Return,
     /// The function called another function.
-    NestedCall(Func),
+    NestedCall(Callee),
 }
 
 /// A unique engine index.
@@ -100,7 +116,7 @@ pub struct Engine {
 }
 
 /// Configuration for an [`Engine`].
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Hash)]
 pub struct Config {
     /// The internal value stack limit.
     ///
@@ -148,6 +164,65 @@ pub struct Config {
     ///
     /// [`multi-value`]: https://github.com/WebAssembly/multi-value
     multi_value: bool,
+    /// Is `true` if linear memories are initialized from a precomputed image.
+    ///
+    /// # Note
+    ///
+    /// Disabled by default.
+    copy_on_write_memory_init: bool,
+    /// Is `true` if linear memories track the pages written to them.
+    ///
+    /// # Note
+    ///
+    /// Disabled by default.
+    dirty_page_tracking: bool,
+    /// Is `true` if loads and stores of linear memories rely on guard pages for bounds checks.
+    ///
+    /// # Note
+    ///
+    /// Disabled by default.
+    guard_pages: bool,
+    /// Determines when Wasm function bodies are translated into `wasmi` bytecode.
+    ///
+    /// # Note
+    ///
+    /// Defaults to [`CompilationMode::Eager`].
+    compilation_mode: CompilationMode,
+    /// Is `true` if [`Module`]s created from identical Wasm bytes share their translation.
+    ///
+    /// # Note
+    ///
+    /// Disabled by default.
+    ///
+    /// [`Module`]: crate::Module
+    compilation_cache: bool,
+    /// Is `true` if function bodies are validated and translated on multiple threads.
+    ///
+    /// # Note
+    ///
+    /// Disabled by default.
+    #[cfg(feature = "std")]
+    parallel_compilation: bool,
+}
+
+/// Determines when Wasm function bodies are translated into `wasmi` bytecode.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CompilationMode {
+    /// All function bodies are translated upon creation of the [`Module`].
+    ///
+    /// [`Module`]: crate::Module
+    Eager,
+    /// All function bodies are validated upon creation of the [`Module`]
+    /// but only translated upon their first call.
+    ///
+    /// # Note
+    ///
+    /// This speeds up the creation of [`Module`] instances with many functions
+    /// of which only a few are ever called. Invalid function bodies are still
+    /// reported upon creation of the [`Module`].
+    ///
+    /// [`Module`]: crate::Module
+    Lazy,
 }
 
 impl Default for Config {
@@ -159,6 +234,13 @@ impl Default for Config {
             sign_extension: true,
             saturating_float_to_int: true,
             multi_value: true,
+            copy_on_write_memory_init: false,
+            dirty_page_tracking: false,
+            guard_pages: false,
+            compilation_mode: CompilationMode::Eager,
+            compilation_cache: false,
+            #[cfg(feature = "std")]
+            parallel_compilation: false,
         }
     }
 }
@@ -177,6 +259,13 @@ impl Config {
             sign_extension: false,
             saturating_float_to_int: false,
             multi_value: false,
+            copy_on_write_memory_init: false,
+            dirty_page_tracking: false,
+            guard_pages: false,
+            compilation_mode: CompilationMode::Eager,
+            compilation_cache: false,
+            #[cfg(feature = "std")]
+            parallel_compilation: false,
         }
     }
 
@@ -223,6 +312,131 @@ impl Config {
     pub const fn multi_value(&self) -> bool {
         self.multi_value
     }
+
+    /// Enables initializing linear memories from an image that is built once per [`Module`].
+    ///
+    /// # Note
+    ///
+    /// - The image of the default linear memory is built from the data segments
+    ///   upon creation of the [`Module`] if all data segments have constant offsets.
+    ///   Otherwise the data segments are written upon every instantiation.
+    /// - With the `virtual_memory` crate feature enabled on Linux the image is mapped
+    ///   copy-on-write into new linear memories so that instantiation does not depend
+    ///   on the size of the data segments and unmodified pages are shared.
+    ///   Otherwise the image is copied into new linear memories.
+    ///
+    /// [`Module`]: crate::Module
+    pub const fn enable_copy_on_write_memory_init(mut self, enable: bool) -> Self {
+        self.copy_on_write_memory_init = enable;
+        self
+    }
+
+    /// Returns `true` if linear memories are initialized from an image built once per [`Module`].
+    ///
+    /// [`Module`]: crate::Module
+    pub const fn copy_on_write_memory_init(&self) -> bool {
+        self.copy_on_write_memory_init
+    }
+
+    /// Enables tracking the pages written to linear memories.
+    ///
+    /// # Note
+    ///
+    /// Applies to all linear memories created in a [`Store`] using this configuration.
+    /// The dirty pages are queried via [`Memory::dirty_pages`] and reset via [`Memory::clear_dirty`].
+    ///
+    /// [`Store`]: crate::Store
+    /// [`Memory::dirty_pages`]: crate::Memory::dirty_pages
+    /// [`Memory::clear_dirty`]: crate::Memory::clear_dirty
+    pub const fn enable_dirty_page_tracking(mut self, enable: bool) -> Self {
+        self.dirty_page_tracking = enable;
+        self
+    }
+
+    /// Returns `true` if linear memories track the pages written to them.
+    pub const fn dirty_page_tracking(&self) -> bool {
+        self.dirty_page_tracking
+    }
+
+    /// Enables eliding the bounds checks of loads and stores via guard pages.
+    ///
+    /// # Note
+    ///
+    /// - Only has an effect with the `virtual_memory` crate feature on Linux x86_64.
+    ///   Otherwise all loads and stores of linear memories are bounds checked.
+    /// - Creating the first linear memory with guard pages installs a process wide
+    ///   `SIGSEGV` and `SIGBUS` handler. It turns faults on guard pages during Wasm
+    ///   execution into traps and forwards all other signals to the previous handlers.
+    /// - Applies to all linear memories created in a [`Store`] using this configuration
+    ///   except for those with a user provided [`LinearMemory`] backend.
+    ///
+    /// [`Store`]: crate::Store
+    /// [`LinearMemory`]: crate::LinearMemory
+    pub const fn enable_guard_pages(mut self, enable: bool) -> Self {
+        self.guard_pages = enable;
+        self
+    }
+
+    /// Returns `true` if loads and stores of linear memories rely on guard pages for bounds checks.
+    pub const fn guard_pages(&self) -> bool {
+        self.guard_pages
+    }
+
+    /// Sets the [`CompilationMode`] that determines when Wasm function bodies are translated.
+    pub const fn set_compilation_mode(mut self, mode: CompilationMode) -> Self {
+        self.compilation_mode = mode;
+        self
+    }
+
+    /// Returns the [`CompilationMode`] that determines when Wasm function bodies are translated.
+    pub const fn compilation_mode(&self) -> CompilationMode {
+        self.compilation_mode
+    }
+
+    /// Enables caching the translation of [`Module`]s in the [`Engine`].
+    ///
+    /// # Note
+    ///
+    /// - Creating a [`Module`] from Wasm bytes that have already been used to create
+    ///   a [`Module`] with the same [`Engine`] returns a [`Module`] that shares the
+    ///   translated function bodies and function types instead of translating them again.
+    /// - The cache keeps the Wasm bytes of all cached [`Module`]s alive in order to
+    ///   compare them upon lookup. Use [`Engine::clear_compilation_cache`] to release them.
+    ///
+    /// [`Module`]: crate::Module
+    pub const fn enable_compilation_cache(mut self, enable: bool) -> Self {
+        self.compilation_cache = enable;
+        self
+    }
+
+    /// Returns `true` if the translation of [`Module`]s is cached in the [`Engine`].
+    ///
+    /// [`Module`]: crate::Module
+    pub const fn compilation_cache(&self) -> bool {
+        self.compilation_cache
+    }
+
+    /// Enables validation and translation of function bodies on multiple threads.
+    ///
+    /// # Note
+    ///
+    /// - The function bodies of a [`Module`] are collected while parsing and then split
+    ///   among one thread per available core. Their translations are committed to the
+    ///   [`Engine`] in a single batch.
+    /// - Has no effect with [`CompilationMode::Lazy`].
+    ///
+    /// [`Module`]: crate::Module
+    #[cfg(feature = "std")]
+    pub const fn enable_parallel_compilation(mut self, enable: bool) -> Self {
+        self.parallel_compilation = enable;
+        self
+    }
+
+    /// Returns `true` if function bodies are validated and translated on multiple threads.
+    #[cfg(feature = "std")]
+    pub const fn parallel_compilation(&self) -> bool {
+        self.parallel_compilation
+    }
 }
 
 impl Default for Engine {
@@ -248,6 +462,54 @@ impl Engine {
         *self.inner.lock().config()
     }
 
+    /// Returns statistics about the compiled code of the [`Engine`].
+    pub fn stats(&self) -> EngineStats {
+        let inner = self.inner.lock();
+        EngineStats {
+            func_types: inner.func_types.len(),
+            func_bodies: inner.code_map.len_func_bodies(),
+            instructions: inner.code_map.len_instructions(),
+            code_map_bytes: inner.code_map.allocated_bytes(),
+            cached_modules: inner.compilation_cache.len(),
+        }
+    }
+
+    /// Returns the cached translation of the [`Module`] created from the Wasm `bytes` if any.
+    ///
+    /// [`Module`]: crate::Module
+    pub(crate) fn get_cached_module(&self, bytes: &[u8]) -> Option<Arc<ModuleInner>> {
+        let inner = self.inner.lock();
+        inner.compilation_cache.get(&inner.config, bytes)
+    }
+
+    /// Caches the translation of the [`Module`] created from the Wasm `bytes`.
+    ///
+    /// Returns the cached translation which is the one of another [`Module`]
+    /// if it has been created from the same `bytes` in the meantime.
+    ///
+    /// [`Module`]: crate::Module
+    pub(crate) fn cache_module(
+        &self,
+        bytes: Box<[u8]>,
+        module: Arc<ModuleInner>,
+    ) -> Arc<ModuleInner> {
+        let mut inner = self.inner.lock();
+        let config = inner.config;
+        inner.compilation_cache.insert(&config, bytes, module)
+    }
+
+    /// Removes all cached [`Module`] translations from the [`Engine`].
+    ///
+    /// # Note
+    ///
+    /// Existing [`Module`]s are unaffected. The function bodies of the removed
+    /// translations stay allocated as long as the [`Engine`] exists.
+    ///
+    /// [`Module`]: crate::Module
+    pub fn clear_compilation_cache(&self) {
+        self.inner.lock().compilation_cache.clear();
+    }
+
     /// Allocates a new function type to the engine.
     pub(super) fn alloc_func_type(&self, func_type: FuncType) -> DedupFuncType {
         self.inner.lock().func_types.alloc_func_type(func_type)
@@ -270,19 +532,108 @@ impl Engine {
     /// Allocates the instructions of a Wasm function body to the [`Engine`].
     ///
     /// Returns a [`FuncBody`] reference to the allocated function body.
-    pub(super) fn alloc_func_body<I>(
+    pub(super) fn alloc_func_body<C, B, I>(
         &self,
+        len_params: usize,
         len_locals: usize,
         max_stack_height: usize,
+        consts: C,
+        br_targets: B,
         insts: I,
     ) -> FuncBody
     where
+        C: IntoIterator<Item = UntypedValue>,
+        B: IntoIterator<Item = Target>,
         I: IntoIterator<Item = Instruction>,
-        I::IntoIter: ExactSizeIterator,
     {
-        self.inner
-            .lock()
-            .alloc_func_body(len_locals, max_stack_height, insts)
+        self.inner.lock().alloc_func_body(
+            len_params,
+            len_locals,
+            max_stack_height,
+            consts,
+            br_targets,
+            insts,
+        )
+    }
+
+    /// Allocates a Wasm function body to the [`Engine`] that is translated upon its first call.
+    ///
+    /// Returns a [`FuncBody`] reference to the allocated function body.
+    pub(super) fn alloc_uncompiled_func_body(&self, func_body: UncompiledFuncBody) -> FuncBody {
+        self.inner.lock().code_map.alloc_uncompiled(func_body)
+    }
+
+    /// Creates a new [`Engine`] for the translation of function bodies on another thread.
+    ///
+    /// # Note
+    ///
+    /// The forked [`Engine`] shares the function types of the [`Engine`] but allocates
+    /// function bodies into its own code map so that forks do not contend on the lock.
+    /// Use [`Engine::commit_func_bodies`] in order to move its function bodies back.
+    #[cfg(feature = "std")]
+    pub(super) fn fork(&self) -> Engine {
+        let inner = self.inner.lock();
+        let mut forked = EngineInner::new(&inner.config);
+        forked.func_types = inner.func_types.clone();
+        Self {
+            inner: Arc::new(Mutex::new(forked)),
+        }
+    }
+
+    /// Moves the function bodies of all `forks` into the [`Engine`] in a single batch.
+    ///
+    /// Returns the [`FuncBody`] references of all forks relocated to their moved function bodies.
+    ///
+    /// # Panics
+    ///
+    /// If a fork is still referenced elsewhere.
+    #[cfg(feature = "std")]
+    pub(super) fn commit_func_bodies<I>(&self, forks: I) -> Vec<FuncBody>
+    where
+        I: IntoIterator<Item = (Engine, Vec<FuncBody>)>,
+    {
+        let mut inner = self.inner.lock();
+        let mut committed = Vec::new();
+        for (fork, mut func_bodies) in forks {
+            let fork = Arc::try_unwrap(fork.inner)
+                .unwrap_or_else(|_| panic!("tried to commit function bodies of a shared engine"))
+                .into_inner();
+            inner.code_map.append(fork.code_map, &mut func_bodies);
+            committed.extend(func_bodies);
+        }
+        committed
+    }
+
+    /// Resolves the [`FuncBody`] and calls `f` with its parts.
+    ///
+    /// # Note
+    ///
+    /// - The parts are the amount of parameters and local variables, the maximum stack height,
+    ///   the function local constants, the `br_table` targets and the `wasmi` bytecode
+    ///   instructions as given to [`Engine::alloc_func_body`].
+    /// - Compiles the [`FuncBody`] first if it has not yet been compiled.
+    ///
+    /// # Panics
+    ///
+    /// If the [`FuncBody`] is invalid for the [`Engine`].
+    pub(super) fn resolve_func_body<F, R>(&self, func_body: FuncBody, f: F) -> R
+    where
+        F: FnOnce(usize, usize, usize, &[UntypedValue], &[Target], &[Instruction]) -> R,
+    {
+        let mut inner = self.inner.lock();
+        inner.compile_func_body(func_body);
+        let resolved = inner.code_map.resolve(func_body);
+        let len_locals = resolved.len_locals();
+        // Note: The code map stores the maximum stack height including the local variables.
+        let max_stack_height = resolved.max_stack_height() - len_locals;
+        f(
+            resolved.len_params(),
+            len_locals,
+            max_stack_height,
+            resolved.consts(),
+            resolved.br_targets(),
+            resolved.insts(),
+        )
     }
 
     /// Resolves the [`FuncBody`] to the underlying `wasmi` bytecode instructions.
@@ -306,6 +657,27 @@ impl Engine {
             .map(Clone::clone)
     }
 
+    /// Resolves the [`FuncBody`] to its `br_table` targets.
+    ///
+    /// # Note
+    ///
+    /// This API is mainly intended for unit testing purposes and shall not be used
+    /// outside of this context. The function bodies are intended to be data private
+    /// to the `wasmi` interpreter.
+    ///
+    /// # Panics
+    ///
+    /// If the [`FuncBody`] is invalid for the [`Engine`].
+    #[cfg(test)]
+    pub(crate) fn resolve_br_targets(&self, func_body: FuncBody) -> Vec<Target> {
+        self.inner
+            .lock()
+            .code_map
+            .resolve(func_body)
+            .br_targets()
+            .to_vec()
+    }
+
     /// Executes the given [`Func`] using the given arguments `params` and stores the result into `results`.
     ///
     /// # Note
@@ -356,6 +728,14 @@ pub struct EngineInner {
     /// The engine deduplicates function types to make the equality
     /// comparison very fast. This helps to speed up indirect calls.
     func_types: FuncTypeRegistry,
+    /// The translations of [`Module`]s keyed by their Wasm bytes.
+    ///
+    /// # Note
+    ///
+    /// Only used if enabled by the [`Config`].
+    ///
+    /// [`Module`]: crate::Module
+    compilation_cache: CompilationCache,
 }
 
 impl EngineInner {
@@ -368,6 +748,7 @@ impl EngineInner {
             call_stack: CallStack::new(config.call_stack_limit),
             code_map: CodeMap::default(),
             func_types: FuncTypeRegistry::new(engine_idx),
+            compilation_cache: CompilationCache::default(),
         }
     }
 
@@ -417,17 +798,53 @@ impl EngineInner {
     /// Allocates the instructions of a Wasm function body to the [`Engine`].
     ///
     /// Returns a [`FuncBody`] reference to the allocated function body.
-    pub fn alloc_func_body<I>(
+    pub fn alloc_func_body<C, B, I>(
         &mut self,
+        len_params: usize,
         len_locals: usize,
         max_stack_height: usize,
+        consts: C,
+        br_targets: B,
         insts: I,
     ) -> FuncBody
     where
+        C: IntoIterator<Item = UntypedValue>,
+        B: IntoIterator<Item = Target>,
         I: IntoIterator<Item = Instruction>,
-        I::IntoIter: ExactSizeIterator,
     {
-        self.code_map.alloc(len_locals, max_stack_height, insts)
+        self.code_map.alloc(
+            len_params,
+            len_locals,
+            max_stack_height,
+            consts,
+            br_targets,
+            insts,
+        )
+    }
+
+    /// Compiles the [`FuncBody`] if it has not yet been compiled.
+    #[inline]
+    fn compile_func_body(&mut self, func_body: FuncBody) {
+        if self.code_map.is_uncompiled(func_body) {
+            self.compile_uncompiled_func_body(func_body)
+        }
+    }
+
+    /// Translates the uncompiled [`FuncBody`] and redirects it to its compiled function body.
+    ///
+    /// # Note
+    ///
+    /// The [`EngineInner`] is locked during execution and translation. Therefore
+    /// the translation operates on the borrowed code map and function types.
+    #[cold]
+    #[inline(never)]
+    fn compile_uncompiled_func_body(&mut self, func_body: FuncBody) {
+        let uncompiled = match self.code_map.take_uncompiled(func_body) {
+            Some(uncompiled) => uncompiled,
+            None => return,
+        };
+        let compiled = uncompiled.translate(EngineCode::new(&mut self.code_map, &self.func_types));
+        self.code_map.redirect(func_body, compiled);
     }
 
     /// Executes the given [`Func`] using the given arguments `args` and stores the result into `results`.
@@ -458,7 +875,7 @@ impl EngineInner {
             FuncEntityInternal::Host(host_func) => {
                 let signature = host_func.signature();
                 let host_func = host_func.clone();
-                self.execute_host_func(&mut ctx, host_func, None)?;
+                self.execute_host_func(&mut ctx, host_func, None, 0)?;
                 signature
             }
         };
@@ -526,27 +943,81 @@ impl EngineInner {
     /// - When encountering a Wasm trap during the execution of `func`.
     fn execute_wasm_func(&mut self, mut ctx: impl AsContextMut, func: Func) -> Result<(), Trap> {
         let mut function_frame = FunctionFrame::new(&ctx, func);
-        'outer: loop {
-            match self.execute_frame(&mut ctx, &mut function_frame)? {
+        let mut cache = InstanceCache::from(function_frame.instance());
+        self.compile_func_body(function_frame.func_body);
+        loop {
+            // Note: Out of bounds accesses of guarded linear memories fault on their
+            //       guard pages which is turned into a trap here. Upon such a trap the
+            //       stack frames of the interpreter are discarded without being dropped
+            //       which is why host functions are called outside of `catch_traps`.
+            let host_call = guard::catch_traps(|| {
+                self.execute_frames(&mut ctx, &mut function_frame, &mut cache)
+            })??;
+            let func = match host_call {
+                Some(func) => func,
+                None => return Ok(()),
+            };
+            let host_func = match func.as_internal(&ctx) {
+                FuncEntityInternal::Host(host_func) => host_func.clone(),
+                FuncEntityInternal::Wasm(_) => {
+                    unreachable!("resolved Wasm function as host callee: {:?}", func)
+                }
+            };
+            let instance = function_frame.instance();
+            let base = self.value_stack.len();
+            self.execute_host_func(&mut ctx, host_func, Some(instance), base)?;
+            // Note: The host function may have manipulated the store,
+            //       e.g. grown linear memories or allocated new entities,
+            //       which invalidates the cached entities.
+            cache.reset();
+        }
+    }
+
+    /// Executes the `function_frame` and all Wasm function frames that it calls.
+    ///
+    /// Returns the host function to call on behalf of the `function_frame` if any.
+    /// In this case execution resumes with the `function_frame` after the call.
+    ///
+    /// # Note
+    ///
+    /// All values owned by the interpreter frames executed here must not need to be
+    /// dropped since these frames are discarded upon traps on guard pages.
+    ///
+    /// # Errors
+    ///
+    /// When encountering a Wasm trap during the execution.
+    fn execute_frames<C>(
+        &mut self,
+        mut ctx: C,
+        function_frame: &mut FunctionFrame,
+        cache: &mut InstanceCache,
+    ) -> Result<Option<Func>, Trap>
+    where
+        C: AsContextMut,
+    {
+        const { assert!(!mem::needs_drop::<C>()) };
+        loop {
+            match self.execute_frame(&mut ctx, function_frame, cache)? {
                 FunctionExecutionOutcome::Return => match self.call_stack.pop() {
                     Some(frame) => {
-                        function_frame = frame;
-                        continue 'outer;
-                    }
-                    None => return Ok(()),
-                },
-                FunctionExecutionOutcome::NestedCall(func) => match func.as_internal(&ctx) {
-                    FuncEntityInternal::Wasm(wasm_func) => {
-                        let nested_frame = FunctionFrame::new_wasm(func, wasm_func);
-                        self.call_stack.push(function_frame)?;
-                        function_frame = nested_frame;
-                    }
-                    FuncEntityInternal::Host(host_func) => {
-                        let instance = function_frame.instance();
-                        let host_func = host_func.clone();
-                        self.execute_host_func(&mut ctx, host_func, Some(instance))?;
+                        *function_frame = frame;
+                        cache.update_instance(function_frame.instance());
                     }
+                    None => return Ok(None),
                 },
+                FunctionExecutionOutcome::NestedCall(Callee::Wasm {
+                    func_body,
+                    instance,
+                }) => {
+                    // Note: The nested frame begins at the parameters of the call.
+                    let bp = self.value_stack.len();
+                    let nested_frame = FunctionFrame::new_wasm(func_body, instance, bp);
+                    self.compile_func_body(nested_frame.func_body);
+                    self.call_stack
+                        .push(mem::replace(function_frame, nested_frame))?;
+                    cache.update_instance(function_frame.instance());
+                }
+                FunctionExecutionOutcome::NestedCall(Callee::Host(func)) => return Ok(Some(func)),
             }
         }
     }
@@ -561,11 +1032,16 @@ impl EngineInner {
         &mut self,
         mut ctx: impl AsContextMut,
         frame: &mut FunctionFrame,
+        cache: &mut InstanceCache,
     ) -> Result<FunctionExecutionOutcome, Trap> {
-        ExecutionContext::new(self, frame)?.execute_frame(&mut ctx)
+        ExecutionContext::new(self, frame, cache)?.execute_frame(&mut ctx)
     }
 
-    /// Executes the given host function.
+    /// Executes the given host function with its parameters starting at `base` on the value stack.
+    ///
+    /// # Note
+    ///
+    /// The host function writes its results to the value stack starting at `base`.
     ///
     /// # Errors
     ///
@@ -577,6 +1053,7 @@ impl EngineInner {
         mut ctx: C,
         host_func: HostFuncEntity<<C as AsContext>::UserState>,
         instance: Option<Instance>,
+        base: usize,
     ) -> Result<(), Trap>
     where
         C: AsContextMut,
@@ -592,29 +1069,20 @@ impl EngineInner {
         let len_inputs = input_types.len();
         let len_outputs = output_types.len();
         let max_inout = cmp::max(len_inputs, len_outputs);
+        self.value_stack.set_len(base);
         self.value_stack.reserve(max_inout)?;
-        if len_outputs > len_inputs {
-            let delta = len_outputs - len_inputs;
-            self.value_stack.extend_zeros(delta)?;
+        let params_results = &mut self.value_stack.frame_mut(base)[..max_inout];
+        for output in &mut params_results[len_inputs..] {
+            *output = UntypedValue::default();
         }
-        let params_results = FuncParams::new(
-            self.value_stack.peek_as_slice_mut(max_inout),
-            len_inputs,
-            len_outputs,
-        );
+        let params_results = FuncParams::new(params_results, len_inputs, len_outputs);
         // Now we are ready to perform the host function call.
         // Note: We need to clone the host function due to some borrowing issues.
         //       This should not be a big deal since host functions usually are cheap to clone.
         host_func.call(ctx.as_context_mut(), instance, params_results)?;
-        // If the host functions returns fewer results than it receives parameters
-        // the value stack needs to be shrinked for the delta.
-        if len_outputs < len_inputs {
-            let delta = len_inputs - len_outputs;
-            self.value_stack.drop(delta);
-        }
         // At this point the host function has been called and has directly
-        // written its results into the value stack so that the last entries
-        // in the value stack are the result values of the host function call.
+        // written its results into the value stack starting at `base`.
+        self.value_stack.set_len(base + len_outputs);
         Ok(())
     }
 }
@@ -1,6 +1,6 @@
 //! Data structures to represent the Wasm value stack during execution.
 
-use super::{DropKeep, DEFAULT_VALUE_STACK_LIMIT};
+use super::DEFAULT_VALUE_STACK_LIMIT;
 use crate::core::TrapCode;
 use alloc::vec::Vec;
 use core::{fmt, fmt::Debug, iter, mem};
@@ -95,142 +95,65 @@ impl ValueStack {
         }
     }
 
-    /// Extends the value stack by the `additional` amount of zeros.
-    ///
-    /// # Errors
-    ///
-    /// If the value stack cannot fit `additional` stack values.
-    pub fn extend_zeros(&mut self, additional: usize) -> Result<(), TrapCode> {
-        let cells = self
-            .entries
-            .get_mut(self.stack_ptr..self.stack_ptr + additional)
-            .ok_or(TrapCode::StackOverflow)?;
-        cells.fill(Default::default());
-        self.stack_ptr += additional;
-        Ok(())
-    }
-
-    /// Drops some amount of entries and keeps some amount of them at the new top.
-    ///
-    /// # Note
-    ///
-    /// For an amount of entries to keep `k` and an amount of entries to drop `d`
-    /// this has the following effect on stack `s` and stack pointer `sp`.
-    ///
-    /// 1) Copy `k` elements from indices starting at `sp - k` to `sp - k - d`.
-    /// 2) Adjust stack pointer: `sp -= d`
-    ///
-    /// After this operation the value stack will have `d` fewer entries and the
-    /// top `k` entries are the top `k` entries before this operation.
-    ///
-    /// Note that `k + d` cannot be greater than the stack length.
-    pub fn drop_keep(&mut self, drop_keep: DropKeep) {
-        let drop = drop_keep.drop();
-        if drop == 0 {
-            // Nothing to do in this case.
-            return;
-        }
-        let keep = drop_keep.keep();
-        // Copy kept values over to their new place on the stack.
-        // Note: We cannot use `memcpy` since the slices may overlap.
-        let src = self.stack_ptr - keep;
-        let dst = self.stack_ptr - keep - drop;
-        for i in 0..keep {
-            self.entries[dst + i] = self.entries[src + i];
-        }
-        self.stack_ptr -= drop;
-    }
-
-    /// Returns the last stack entry of the [`ValueStack`].
+    /// Initializes the function frame beginning at `bp` and sets the stack pointer to its end.
     ///
     /// # Note
     ///
-    /// This has the same effect as [`ValueStack::peek`]`(0)`.
-    pub fn last(&self) -> UntypedValue {
-        self.entries[self.stack_ptr - 1]
-    }
-
-    /// Returns the last stack entry of the [`ValueStack`].
-    ///
-    /// # Note
+    /// The `len_params` parameters of the function frame are expected to
+    /// already be in place. The local variables are initialized to zero and
+    /// the `consts` are copied right after them.
     ///
-    /// This has the same effect as [`ValueStack::peek`]`(0)`.
-    pub fn last_mut(&mut self) -> &mut UntypedValue {
-        &mut self.entries[self.stack_ptr - 1]
-    }
-
-    /// Peeks the entry at the given depth from the last entry.
-    ///
-    /// # Note
+    /// # Errors
     ///
-    /// Given a `depth` of 0 has the same effect as [`ValueStack::last`].
-    pub fn peek(&self, depth: usize) -> UntypedValue {
-        self.entries[self.stack_ptr - depth - 1]
+    /// If the value stack cannot fit the `len_frame` values of the function frame.
+    pub fn init_frame(
+        &mut self,
+        bp: usize,
+        len_params: usize,
+        len_locals: usize,
+        consts: &[UntypedValue],
+        len_frame: usize,
+    ) -> Result<(), TrapCode> {
+        self.stack_ptr = bp;
+        self.reserve(len_frame)?;
+        let locals = bp + len_params;
+        let consts_start = locals + len_locals;
+        self.entries[locals..consts_start].fill(Default::default());
+        self.entries[consts_start..(consts_start + consts.len())].copy_from_slice(consts);
+        self.stack_ptr = bp + len_frame;
+        Ok(())
     }
 
-    /// Peeks the `&mut` entry at the given depth from the last entry.
+    /// Returns the values of the function frame beginning at `bp`.
     ///
     /// # Note
     ///
-    /// Given a `depth` of 0 has the same effect as [`ValueStack::last_mut`].
-    pub fn peek_mut(&mut self, depth: usize) -> &mut UntypedValue {
-        &mut self.entries[self.stack_ptr - depth - 1]
+    /// The returned slice extends to the end of the reserved value stack.
+    pub fn frame_mut(&mut self, bp: usize) -> &mut [UntypedValue] {
+        &mut self.entries[bp..]
     }
 
-    /// Pops the last [`UntypedValue`] from the [`ValueStack`].
+    /// Moves the `len` values at offset `results` of the function frame beginning at `bp`
+    /// to the start of the function frame and drops all values after them.
     ///
     /// # Note
     ///
-    /// This operation heavily relies on the prior validation of
-    /// the executed WebAssembly bytecode for correctness.
-    pub fn pop(&mut self) -> UntypedValue {
-        self.stack_ptr -= 1;
-        self.entries[self.stack_ptr]
-    }
-
-    pub fn drop(&mut self, depth: usize) {
-        self.stack_ptr -= depth;
-    }
-
-    /// Pops the last [`UntypedValue`] from the [`ValueStack`] as `T`.
-    pub fn pop_as<T>(&mut self) -> T
-    where
-        T: From<UntypedValue>,
-    {
-        T::from(self.pop())
+    /// This is used to return the results of a function to its caller.
+    pub fn return_values(&mut self, bp: usize, results: usize, len: usize) {
+        let src = bp + results;
+        // Note: We cannot use `memcpy` since the slices may overlap.
+        self.entries.copy_within(src..(src + len), bp);
+        self.stack_ptr = bp + len;
     }
 
-    /// Pops the last pair of [`UntypedValue`] from the [`ValueStack`].
+    /// Sets the stack pointer of the [`ValueStack`] to `len`.
     ///
     /// # Note
     ///
-    /// - This operation is slightly more efficient than using
-    ///   [`ValueStack::pop`] twice.
-    /// - This operation heavily relies on the prior validation of
-    ///   the executed WebAssembly bytecode for correctness.
-    pub fn pop2(&mut self) -> (UntypedValue, UntypedValue) {
-        self.stack_ptr -= 2;
-        (
-            self.entries[self.stack_ptr],
-            self.entries[self.stack_ptr + 1],
-        )
-    }
-
-    /// Evaluates `f` on the top three stack entries.
-    ///
-    /// In summary this procedure does the following:
-    ///
-    /// - Pop entry `e3`.
-    /// - Pop entry `e2`.
-    /// - Peek entry `&mut e1_ptr`.
-    /// - Evaluate `f(e1_ptr, e2, e3)`.
-    pub fn pop2_eval<F>(&mut self, f: F)
-    where
-        F: FnOnce(&mut UntypedValue, UntypedValue, UntypedValue),
-    {
-        let (e2, e3) = self.pop2();
-        let e1 = self.last_mut();
-        f(e1, e2, e3)
+    /// The `len` must not exceed the reserved value stack.
+    pub fn set_len(&mut self, len: usize) {
+        debug_assert!(len <= self.capacity());
+        self.stack_ptr = len;
     }
 
     /// Pushes the [`UntypedValue`] to the end of the [`ValueStack`].
@@ -300,13 +223,6 @@ impl ValueStack {
         &self.entries[0..len]
     }
 
-    /// Returns an exclusive slice to the last `depth` entries in the value stack.
-    pub fn peek_as_slice_mut(&mut self, depth: usize) -> &mut [UntypedValue] {
-        let start = self.stack_ptr - depth;
-        let end = self.stack_ptr;
-        &mut self.entries[start..end]
-    }
-
     /// Clears the [`ValueStack`] entirely.
     ///
     /// # Note
@@ -325,63 +241,44 @@ mod tests {
     use super::*;
 
     #[test]
-    fn drop_keep_works() {
-        fn assert_drop_keep<E>(stack: &ValueStack, drop_keep: DropKeep, expected: E)
+    fn init_frame_works() {
+        let mut stack = [1, 2, 3, 4]
+            .into_iter()
+            .map(UntypedValue::from)
+            .collect::<ValueStack>();
+        let consts = [UntypedValue::from(10), UntypedValue::from(20)];
+        stack.init_frame(2, 2, 3, &consts, 9).unwrap();
+        assert_eq!(stack.len(), 11);
+        assert_eq!(
+            &stack.frame_mut(2)[..9],
+            [3, 4, 0, 0, 0, 10, 20, 0, 0].map(UntypedValue::from),
+        );
+    }
+
+    #[test]
+    fn return_values_works() {
+        fn assert_return_values<E>(stack: &ValueStack, results: usize, len: usize, expected: E)
         where
             E: IntoIterator,
             E::Item: Into<UntypedValue>,
         {
             let mut s = stack.clone();
-            s.drop_keep(drop_keep);
+            s.return_values(1, results, len);
             assert_eq!(
                 s,
                 expected.into_iter().map(Into::into).collect::<ValueStack>()
             );
         }
 
-        let test_inputs = [1, 2, 3, 4, 5, 6];
-        let stack = test_inputs
+        let stack = [1, 2, 3, 4, 5, 6]
             .into_iter()
             .map(UntypedValue::from)
             .collect::<ValueStack>();
-
-        // Drop is always 0 but keep varies:
-        for keep in 0..stack.len() {
-            // Assert that nothing was changed since nothing was dropped.
-            assert_drop_keep(&stack, DropKeep::new(0, keep), test_inputs);
-        }
-
-        // Drop is always 1 but keep varies:
-        assert_drop_keep(&stack, DropKeep::new(1, 0), [1, 2, 3, 4, 5]);
-        assert_drop_keep(&stack, DropKeep::new(1, 1), [1, 2, 3, 4, 6]);
-        assert_drop_keep(&stack, DropKeep::new(1, 2), [1, 2, 3, 5, 6]);
-        assert_drop_keep(&stack, DropKeep::new(1, 3), [1, 2, 4, 5, 6]);
-        assert_drop_keep(&stack, DropKeep::new(1, 4), [1, 3, 4, 5, 6]);
-        assert_drop_keep(&stack, DropKeep::new(1, 5), [2, 3, 4, 5, 6]);
-
-        // Drop is always 2 but keep varies:
-        assert_drop_keep(&stack, DropKeep::new(2, 0), [1, 2, 3, 4]);
-        assert_drop_keep(&stack, DropKeep::new(2, 1), [1, 2, 3, 6]);
-        assert_drop_keep(&stack, DropKeep::new(2, 2), [1, 2, 5, 6]);
-        assert_drop_keep(&stack, DropKeep::new(2, 3), [1, 4, 5, 6]);
-        assert_drop_keep(&stack, DropKeep::new(2, 4), [3, 4, 5, 6]);
-
-        // Drop is always 3 but keep varies:
-        assert_drop_keep(&stack, DropKeep::new(3, 0), [1, 2, 3]);
-        assert_drop_keep(&stack, DropKeep::new(3, 1), [1, 2, 6]);
-        assert_drop_keep(&stack, DropKeep::new(3, 2), [1, 5, 6]);
-        assert_drop_keep(&stack, DropKeep::new(3, 3), [4, 5, 6]);
-
-        // Drop is always 4 but keep varies:
-        assert_drop_keep(&stack, DropKeep::new(4, 0), [1, 2]);
-        assert_drop_keep(&stack, DropKeep::new(4, 1), [1, 6]);
-        assert_drop_keep(&stack, DropKeep::new(4, 2), [5, 6]);
-
-        // Drop is always 5 but keep varies:
-        assert_drop_keep(&stack, DropKeep::new(5, 0), [1]);
-        assert_drop_keep(&stack, DropKeep::new(5, 1), [6]);
-
-        // Drop is always 6.
-        assert_drop_keep(&stack, DropKeep::new(6, 0), iter::repeat(0).take(0));
+        assert_return_values(&stack, 0, 0, [1]);
+        assert_return_values(&stack, 4, 0, [1]);
+        assert_return_values(&stack, 0, 2, [1, 2, 3]);
+        assert_return_values(&stack, 2, 1, [1, 4]);
+        assert_return_values(&stack, 1, 3, [1, 3, 4, 5]);
+        assert_return_values(&stack, 2, 3, [1, 4, 5, 6]);
     }
 }
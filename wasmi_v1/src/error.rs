@@ -1,10 +1,12 @@
 use super::errors::{
     FuncError,
     GlobalError,
+    InstanceError,
     InstantiationError,
     LinkerError,
     MemoryError,
     ModuleError,
+    SnapshotError,
     TableError,
 };
 use crate::core::Trap;
@@ -24,10 +26,14 @@ pub enum Error {
     Linker(LinkerError),
     /// A module instantiation error.
     Instantiation(InstantiationError),
+    /// A module instance error.
+    Instance(InstanceError),
     /// A module compilation, validation and translation error.
     Module(ModuleError),
     /// A function error.
     Func(FuncError),
+    /// A snapshot error.
+    Snapshot(SnapshotError),
     /// A trap as defined by the WebAssembly specification.
     Trap(Trap),
 }
@@ -45,7 +51,9 @@ impl Display for Error {
             Self::Linker(error) => Display::fmt(error, f),
             Self::Func(error) => Display::fmt(error, f),
             Self::Instantiation(error) => Display::fmt(error, f),
+            Self::Instance(error) => Display::fmt(error, f),
             Self::Module(error) => Display::fmt(error, f),
+            Self::Snapshot(error) => Display::fmt(error, f),
         }
     }
 }
@@ -74,6 +82,12 @@ impl From<TableError> for Error {
     }
 }
 
+impl From<SnapshotError> for Error {
+    fn from(error: SnapshotError) -> Self {
+        Self::Snapshot(error)
+    }
+}
+
 impl From<LinkerError> for Error {
     fn from(error: LinkerError) -> Self {
         Self::Linker(error)
@@ -86,6 +100,12 @@ impl From<InstantiationError> for Error {
     }
 }
 
+impl From<InstanceError> for Error {
+    fn from(error: InstanceError) -> Self {
+        Self::Instance(error)
+    }
+}
+
 impl From<ModuleError> for Error {
     fn from(error: ModuleError) -> Self {
         Self::Module(error)
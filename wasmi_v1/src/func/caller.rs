@@ -1,5 +1,5 @@
 use super::super::{AsContext, AsContextMut, StoreContext, StoreContextMut};
-use crate::{Engine, Extern, Instance};
+use crate::{Engine, Extern, Instance, Memory};
 
 /// Represents the caller’s context when creating a host function via [`Func::wrap`].
 ///
@@ -25,6 +25,14 @@ impl<'a, T> Caller<'a, T> {
         }
     }
 
+    /// Returns a [`Caller`] reborrowing the store context of `self`.
+    pub(crate) fn reborrow(&mut self) -> Caller<'_, T> {
+        Caller {
+            store: self.store.as_context_mut(),
+            instance: self.instance,
+        }
+    }
+
     /// Queries the caller for an exported definition identifier by `name`.
     ///
     /// Returns `None` if there is no associated [`Instance`] of the caller
@@ -44,6 +52,16 @@ impl<'a, T> Caller<'a, T> {
         self.store.store.state_mut()
     }
 
+    /// Returns an exclusive slice to the bytes of the linear `memory`
+    /// and an exclusive reference to the host provided data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the store of the caller does not own the `memory`.
+    pub fn data_and_host_data_mut(&mut self, memory: Memory) -> (&mut [u8], &mut T) {
+        memory.data_and_state_mut(&mut self.store)
+    }
+
     /// Returns a shared reference to the used [`Engine`].
     pub fn engine(&self) -> &Engine {
         self.store.store.engine()
@@ -1,4 +1,5 @@
 use super::Func;
+use crate::core::HostError;
 use core::{fmt, fmt::Display};
 
 /// Errors that can occur upon operating with [`Func`] instances.
@@ -14,6 +15,14 @@ pub enum FuncError {
     ///
     /// [`TypedFunc`]: [`super::TypedFunc`]
     MismatchingResults { func: Func },
+    /// Encountered when a host function with borrowed parameters such as `&str`
+    /// is called by an instance that does not export its linear memory as `"memory"`.
+    MissingMemoryExport,
+    /// Encountered when a `&mut [u8]` host function parameter overlaps
+    /// with another borrowed parameter of the same call.
+    OverlappingMemoryBorrows,
+    /// Encountered when a `&str` host function parameter is not valid UTF-8.
+    InvalidUtf8(core::str::Utf8Error),
 }
 
 impl Display for FuncError {
@@ -29,6 +38,21 @@ impl Display for FuncError {
                 "encountered mismatching function result types for TypedFunc: {:?}",
                 func
             ),
+            FuncError::MissingMemoryExport => write!(
+                f,
+                "encountered borrowed host function parameters without an exported linear memory",
+            ),
+            FuncError::OverlappingMemoryBorrows => write!(
+                f,
+                "encountered overlapping exclusive borrows of host function parameters",
+            ),
+            FuncError::InvalidUtf8(error) => write!(
+                f,
+                "encountered invalid UTF-8 in host function string parameter: {}",
+                error
+            ),
         }
     }
 }
+
+impl HostError for FuncError {}
@@ -0,0 +1,378 @@
+use super::{into_func::WasmTypeList, FuncError, WasmType};
+use crate::{
+    core::{Trap, TrapCode},
+    AsContextMut,
+    Caller,
+    Extern,
+    Memory,
+};
+use alloc::vec::Vec;
+use core::{marker::PhantomData, ops::Range, slice};
+
+/// Types that can be used as parameters of host functions.
+///
+/// # Note
+///
+/// Besides all [`WasmType`] types this includes borrowed views into the
+/// linear memory of the calling instance: `&str`, `&[u8]` and `&mut [u8]`.
+/// Each borrowed view is lowered to a pair of `i32` Wasm parameters denoting
+/// the pointer and length of the view in the linear memory that the calling
+/// instance exports under the name `"memory"`.
+///
+/// Host functions that also take a [`Caller`] receive views into copies of the
+/// borrowed regions since the [`Caller`] grants access to the whole store
+/// including the linear memory. The copies of `&mut [u8]` views are written
+/// back to the linear memory after the host function returned successfully.
+pub trait HostParam {
+    /// The Wasm types this parameter is lowered to.
+    #[doc(hidden)]
+    type Lowered: WasmTypeList;
+
+    /// The type of the parameter as seen by the host function.
+    #[doc(hidden)]
+    type View<'a>;
+
+    /// Is `true` if the parameter borrows from the caller's linear memory.
+    #[doc(hidden)]
+    const BORROWS_MEMORY: bool;
+
+    /// Is `true` if the parameter exclusively borrows from the caller's linear memory.
+    #[doc(hidden)]
+    const BORROWS_MEMORY_MUT: bool = false;
+
+    /// Returns the pointer and length of the borrowed region of the caller's linear memory if any.
+    #[doc(hidden)]
+    fn borrowed_region(_lowered: &Self::Lowered) -> Option<(u32, u32)> {
+        None
+    }
+
+    /// Lifts the lowered Wasm values into the view of the parameter.
+    ///
+    /// # Errors
+    ///
+    /// If the parameter cannot be borrowed from the caller's linear memory.
+    #[doc(hidden)]
+    fn lift<'a>(
+        lowered: Self::Lowered,
+        memory: &mut GuestMemory<'a>,
+    ) -> Result<Self::View<'a>, Trap>;
+}
+
+impl<T> HostParam for T
+where
+    T: WasmType,
+{
+    type Lowered = T;
+    type View<'a> = T;
+    const BORROWS_MEMORY: bool = false;
+
+    fn lift<'a>(lowered: Self::Lowered, _memory: &mut GuestMemory<'a>) -> Result<T, Trap> {
+        Ok(lowered)
+    }
+}
+
+impl HostParam for &'_ [u8] {
+    type Lowered = (u32, u32);
+    type View<'a> = &'a [u8];
+    const BORROWS_MEMORY: bool = true;
+
+    fn borrowed_region(lowered: &Self::Lowered) -> Option<(u32, u32)> {
+        Some(*lowered)
+    }
+
+    fn lift<'a>((ptr, len): Self::Lowered, memory: &mut GuestMemory<'a>) -> Result<&'a [u8], Trap> {
+        memory.borrow(ptr, len)
+    }
+}
+
+impl HostParam for &'_ mut [u8] {
+    type Lowered = (u32, u32);
+    type View<'a> = &'a mut [u8];
+    const BORROWS_MEMORY: bool = true;
+    const BORROWS_MEMORY_MUT: bool = true;
+
+    fn borrowed_region(lowered: &Self::Lowered) -> Option<(u32, u32)> {
+        Some(*lowered)
+    }
+
+    fn lift<'a>(
+        (ptr, len): Self::Lowered,
+        memory: &mut GuestMemory<'a>,
+    ) -> Result<&'a mut [u8], Trap> {
+        memory.borrow_mut(ptr, len)
+    }
+}
+
+impl HostParam for &'_ str {
+    type Lowered = (u32, u32);
+    type View<'a> = &'a str;
+    const BORROWS_MEMORY: bool = true;
+
+    fn borrowed_region(lowered: &Self::Lowered) -> Option<(u32, u32)> {
+        Some(*lowered)
+    }
+
+    fn lift<'a>((ptr, len): Self::Lowered, memory: &mut GuestMemory<'a>) -> Result<&'a str, Trap> {
+        let bytes = memory.borrow(ptr, len)?;
+        core::str::from_utf8(bytes).map_err(|error| Trap::host(FuncError::InvalidUtf8(error)))
+    }
+}
+
+/// The linear memory of the caller of a host function with borrowed parameters.
+///
+/// # Note
+///
+/// Hands out shared and exclusive views into the linear memory and makes
+/// sure that no exclusive view overlaps with any other view of the same call.
+/// The views either refer to the linear memory itself or to the copies
+/// of the borrowed regions held by [`GuestCopies`].
+#[derive(Debug)]
+pub struct GuestMemory<'a> {
+    /// The start of the bytes the views refer to.
+    ///
+    /// These are either the linear memory bytes or the copies of the borrowed regions.
+    data: *mut u8,
+    /// The length of the linear memory in bytes.
+    len: usize,
+    /// Is `true` if the views refer to the copies of the borrowed regions.
+    copied: bool,
+    /// Is `true` if exclusive views may be handed out.
+    writable: bool,
+    /// The byte ranges that have been borrowed so far and if they are exclusive.
+    borrows: Vec<(Range<usize>, bool)>,
+    /// The number of bytes of the copies that have been handed out so far.
+    len_copied: usize,
+    /// The lifetime of the exclusive borrow of the linear memory.
+    marker: PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> GuestMemory<'a> {
+    /// Creates a [`GuestMemory`] that does not provide any linear memory.
+    pub(crate) fn empty() -> Self {
+        Self::new(&mut [])
+    }
+
+    /// Creates a [`GuestMemory`] from the exclusively borrowed linear memory bytes.
+    fn new(bytes: &'a mut [u8]) -> Self {
+        Self {
+            data: bytes.as_mut_ptr(),
+            len: bytes.len(),
+            copied: false,
+            writable: true,
+            borrows: Vec::new(),
+            len_copied: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Creates a [`GuestMemory`] from the linear memory exported by the `caller`.
+    ///
+    /// # Note
+    ///
+    /// The linear memory is borrowed exclusively only if `writable` is `true`
+    /// so that shared views do not mark any pages of the linear memory dirty.
+    ///
+    /// # Errors
+    ///
+    /// If the `caller` does not export a linear memory under the name `"memory"`.
+    pub(crate) fn from_caller<T>(caller: &'a mut Caller<T>, writable: bool) -> Result<Self, Trap> {
+        let memory = exported_memory(caller)?;
+        if writable {
+            return Ok(Self::new(memory.data_mut(caller.as_context_mut())));
+        }
+        let caller: &'a Caller<T> = caller;
+        let bytes = memory.data(caller);
+        Ok(Self {
+            // Safety: No exclusive views are handed out since `writable` is `false`.
+            data: bytes.as_ptr() as *mut u8,
+            len: bytes.len(),
+            copied: false,
+            writable: false,
+            borrows: Vec::new(),
+            len_copied: 0,
+            marker: PhantomData,
+        })
+    }
+
+    /// Returns the byte ranges borrowed by all views and if they are exclusive.
+    ///
+    /// # Note
+    ///
+    /// Consumes the [`GuestMemory`] so that no views outlive this call.
+    pub(crate) fn into_borrows(self) -> Vec<(Range<usize>, bool)> {
+        self.borrows
+    }
+
+    /// Registers a borrow of `len` bytes at `ptr` and returns the range of the view into `data`.
+    ///
+    /// # Errors
+    ///
+    /// - If the borrow is out of bounds of the linear memory.
+    /// - If the borrow overlaps with a borrow that conflicts with it.
+    fn register(&mut self, ptr: u32, len: u32, exclusive: bool) -> Result<Range<usize>, Trap> {
+        let start = ptr as usize;
+        let end = start
+            .checked_add(len as usize)
+            .filter(|&end| end <= self.len)
+            .ok_or(TrapCode::MemoryAccessOutOfBounds)?;
+        let range = start..end;
+        let overlaps = |other: &Range<usize>| {
+            !range.is_empty()
+                && !other.is_empty()
+                && range.start < other.end
+                && other.start < range.end
+        };
+        if self
+            .borrows
+            .iter()
+            .any(|(other, other_exclusive)| (exclusive || *other_exclusive) && overlaps(other))
+        {
+            return Err(Trap::host(FuncError::OverlappingMemoryBorrows));
+        }
+        self.borrows.push((range.clone(), exclusive));
+        if !self.copied {
+            return Ok(range);
+        }
+        // The copies of the borrowed regions are laid out in the order of their borrows.
+        let start = self.len_copied;
+        self.len_copied += range.len();
+        Ok(start..self.len_copied)
+    }
+
+    /// Returns a shared view of `len` bytes at `ptr`.
+    ///
+    /// # Errors
+    ///
+    /// - If the view is out of bounds of the linear memory.
+    /// - If the view overlaps with an exclusive view.
+    fn borrow(&mut self, ptr: u32, len: u32) -> Result<&'a [u8], Trap> {
+        let range = self.register(ptr, len, false)?;
+        // Safety: The range is within bounds of the borrowed bytes
+        //         and does not overlap with any exclusive view handed out.
+        Ok(unsafe { slice::from_raw_parts(self.data.add(range.start), range.len()) })
+    }
+
+    /// Returns an exclusive view of `len` bytes at `ptr`.
+    ///
+    /// # Errors
+    ///
+    /// - If the view is out of bounds of the linear memory.
+    /// - If the view overlaps with any other view.
+    ///
+    /// # Panics
+    ///
+    /// If the [`GuestMemory`] only provides shared views.
+    fn borrow_mut(&mut self, ptr: u32, len: u32) -> Result<&'a mut [u8], Trap> {
+        assert!(
+            self.writable,
+            "encountered exclusive view into shared guest memory"
+        );
+        let range = self.register(ptr, len, true)?;
+        // Safety: The range is within bounds of the exclusively borrowed bytes
+        //         and does not overlap with any other view handed out.
+        Ok(unsafe { slice::from_raw_parts_mut(self.data.add(range.start), range.len()) })
+    }
+}
+
+/// Copies of the regions of the caller's linear memory borrowed by a host function taking a [`Caller`].
+///
+/// # Note
+///
+/// Since the [`Caller`] grants access to the whole store the views of borrowed
+/// parameters cannot refer to the linear memory itself. Instead they refer to
+/// copies of the borrowed regions that are written back to the linear memory
+/// for exclusive views after the host function returned.
+#[derive(Debug, Default)]
+pub(crate) struct GuestCopies {
+    /// The linear memory exported by the caller if any region has been copied.
+    memory: Option<Memory>,
+    /// The length of the linear memory in bytes.
+    len: usize,
+    /// The copies of all borrowed regions in the order of their borrows.
+    copies: Vec<u8>,
+}
+
+impl GuestCopies {
+    /// Copies the borrowed `regions` of the linear memory exported by the `caller`.
+    ///
+    /// # Errors
+    ///
+    /// - If the `caller` does not export a linear memory under the name `"memory"`.
+    /// - If a region is out of bounds of the linear memory.
+    pub(crate) fn from_caller<T>(
+        caller: &Caller<T>,
+        regions: impl IntoIterator<Item = (u32, u32)>,
+    ) -> Result<Self, Trap> {
+        let memory = exported_memory(caller)?;
+        let bytes = memory.data(caller);
+        let mut copies = Vec::new();
+        for (ptr, len) in regions {
+            let region = (ptr as usize)
+                .checked_add(len as usize)
+                .and_then(|end| bytes.get(ptr as usize..end))
+                .ok_or(TrapCode::MemoryAccessOutOfBounds)?;
+            copies.extend_from_slice(region);
+        }
+        Ok(Self {
+            memory: Some(memory),
+            len: bytes.len(),
+            copies,
+        })
+    }
+
+    /// Returns a [`GuestMemory`] handing out views into the copies.
+    ///
+    /// # Note
+    ///
+    /// The views must be borrowed in the order of the regions given to [`GuestCopies::from_caller`].
+    pub(crate) fn memory(&mut self) -> GuestMemory<'_> {
+        GuestMemory {
+            data: self.copies.as_mut_ptr(),
+            len: self.len,
+            copied: true,
+            writable: true,
+            borrows: Vec::new(),
+            len_copied: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Writes the copies of all exclusively `borrows` regions back to the linear memory.
+    ///
+    /// # Errors
+    ///
+    /// If a region is out of bounds of the linear memory.
+    pub(crate) fn write_back<T>(
+        &self,
+        caller: &mut Caller<T>,
+        borrows: &[(Range<usize>, bool)],
+    ) -> Result<(), Trap> {
+        let memory = match self.memory {
+            Some(memory) => memory,
+            None => return Ok(()),
+        };
+        let mut offset = 0;
+        for (range, exclusive) in borrows {
+            let copy = &self.copies[offset..offset + range.len()];
+            if *exclusive {
+                memory
+                    .write(&mut *caller, range.start, copy)
+                    .map_err(|_| TrapCode::MemoryAccessOutOfBounds)?;
+            }
+            offset += range.len();
+        }
+        Ok(())
+    }
+}
+
+/// Returns the linear memory exported by the `caller` under the name `"memory"`.
+///
+/// # Errors
+///
+/// If the `caller` does not export a linear memory under the name `"memory"`.
+fn exported_memory<T>(caller: &Caller<T>) -> Result<Memory, Trap> {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| Trap::host(FuncError::MissingMemoryExport))
+}
@@ -1,5 +1,6 @@
 use super::{
     super::engine::{FuncParams, FuncResults},
+    host_param::{GuestCopies, GuestMemory, HostParam},
     HostFuncTrampoline,
 };
 use crate::{
@@ -12,10 +13,17 @@ use core::{array, iter::FusedIterator};
 use wasmi_core::{DecodeUntypedSlice, EncodeUntypedSlice, UntypedValue};
 
 /// Closures and functions that can be used as host functions.
+///
+/// # Note
+///
+/// Host functions may declare any [`HostParam`] as parameter, including the
+/// borrowed views `&str`, `&[u8]` and `&mut [u8]` into the linear memory of the
+/// calling instance.
+/// Since a [`Caller`] already provides exclusive access to the whole store the
+/// views of host functions that take a [`Caller`] refer to copies of the borrowed
+/// regions. The copies of `&mut [u8]` views are written back to the linear memory
+/// after the host function returned successfully.
 pub trait IntoFunc<T, Params, Results>: Send + Sync + 'static {
-    /// The parameters of the host function.
-    #[doc(hidden)]
-    type Params: WasmTypeList;
     /// The results of the host function.
     #[doc(hidden)]
     type Results: WasmTypeList;
@@ -30,53 +38,139 @@ macro_rules! impl_into_func {
         impl<T, F, $($tuple,)* R> IntoFunc<T, ($($tuple,)*), R> for F
         where
             F: Fn($($tuple),*) -> R,
+            F: for<'a> Fn($(<$tuple as HostParam>::View<'a>),*) -> R,
             F: Send + Sync + 'static,
             $(
-                $tuple: WasmType,
+                $tuple: HostParam,
             )*
             R: WasmResults,
         {
-            type Params = ($($tuple,)*);
             type Results = <R as WasmResults>::Ok;
 
-            #[allow(non_snake_case)]
+            #[allow(non_snake_case, unused_mut, unused_variables, unused_assignments)]
             fn into_func(self) -> (FuncType, HostFuncTrampoline<T>) {
-                IntoFunc::into_func(
-                    move |
-                        _: Caller<'_, T>,
+                /// Calls `func` with the lifted host function parameters.
+                ///
+                /// # Note
+                ///
+                /// This exists to resolve the call against the higher-ranked `Fn` bound.
+                #[inline]
+                #[allow(clippy::too_many_arguments, clippy::extra_unused_lifetimes)]
+                fn call<'a, F, $($tuple,)* R>(func: &F, $($tuple: <$tuple as HostParam>::View<'a>),*) -> R
+                where
+                    F: for<'b> Fn($(<$tuple as HostParam>::View<'b>),*) -> R,
+                    $(
+                        $tuple: HostParam,
+                    )*
+                {
+                    func($($tuple),*)
+                }
+
+                let signature = FuncType::new(
+                    core::iter::empty()
                         $(
-                            $tuple: $tuple,
+                            .chain(<<$tuple as HostParam>::Lowered as WasmTypeList>::value_types())
+                        )*,
+                    <Self::Results as WasmTypeList>::value_types(),
+                );
+                let trampoline = HostFuncTrampoline::new(
+                    move |mut caller: Caller<T>, params_results: FuncParams| -> Result<FuncResults, Trap> {
+                        let mut offset = 0;
+                        $(
+                            let len = <<$tuple as HostParam>::Lowered as WasmTypeList>::LEN;
+                            let $tuple: <$tuple as HostParam>::Lowered =
+                                params_results.read_params_at(offset..offset + len);
+                            offset += len;
+                        )*
+                        let borrows_memory = false $( || <$tuple as HostParam>::BORROWS_MEMORY )*;
+                        let borrows_memory_mut = false $( || <$tuple as HostParam>::BORROWS_MEMORY_MUT )*;
+                        let mut memory = match borrows_memory {
+                            true => GuestMemory::from_caller(&mut caller, borrows_memory_mut)?,
+                            false => GuestMemory::empty(),
+                        };
+                        $(
+                            let $tuple = <$tuple as HostParam>::lift($tuple, &mut memory)?;
                         )*
-                    | {
-                        (self)($($tuple),*)
-                    }
-                )
+                        let results: Self::Results =
+                            call::<F, $($tuple,)* R>(&self, $($tuple),*).into_fallible()?;
+                        Ok(params_results.write_results(results))
+                    },
+                );
+                (signature, trampoline)
             }
         }
 
         impl<T, F, $($tuple,)* R> IntoFunc<T, (Caller<'_, T>, $($tuple),*), R> for F
         where
             F: Fn(Caller<T>, $($tuple),*) -> R,
+            F: for<'a> Fn(Caller<T>, $(<$tuple as HostParam>::View<'a>),*) -> R,
             F: Send + Sync + 'static,
             $(
-                $tuple: WasmType,
+                $tuple: HostParam,
             )*
             R: WasmResults,
         {
-            type Params = ($($tuple,)*);
             type Results = <R as WasmResults>::Ok;
 
-            #[allow(non_snake_case)]
+            #[allow(non_snake_case, unused_mut, unused_variables, unused_assignments)]
             fn into_func(self) -> (FuncType, HostFuncTrampoline<T>) {
+                /// Calls `func` with the `caller` and the lifted host function parameters.
+                ///
+                /// # Note
+                ///
+                /// This exists to resolve the call against the higher-ranked `Fn` bound.
+                #[inline]
+                #[allow(clippy::too_many_arguments, clippy::extra_unused_lifetimes)]
+                fn call<'a, F, T, $($tuple,)* R>(
+                    func: &F,
+                    caller: Caller<T>,
+                    $($tuple: <$tuple as HostParam>::View<'a>),*
+                ) -> R
+                where
+                    F: for<'b> Fn(Caller<T>, $(<$tuple as HostParam>::View<'b>),*) -> R,
+                    $(
+                        $tuple: HostParam,
+                    )*
+                {
+                    func(caller, $($tuple),*)
+                }
+
                 let signature = FuncType::new(
-                    <Self::Params as WasmTypeList>::value_types(),
+                    core::iter::empty()
+                        $(
+                            .chain(<<$tuple as HostParam>::Lowered as WasmTypeList>::value_types())
+                        )*,
                     <Self::Results as WasmTypeList>::value_types(),
                 );
                 let trampoline = HostFuncTrampoline::new(
-                    move |caller: Caller<T>, params_results: FuncParams| -> Result<FuncResults, Trap> {
-                        let ($($tuple,)*): Self::Params = params_results.read_params();
+                    move |mut caller: Caller<T>, params_results: FuncParams| -> Result<FuncResults, Trap> {
+                        let mut offset = 0;
+                        $(
+                            let len = <<$tuple as HostParam>::Lowered as WasmTypeList>::LEN;
+                            let $tuple: <$tuple as HostParam>::Lowered =
+                                params_results.read_params_at(offset..offset + len);
+                            offset += len;
+                        )*
+                        let borrows_memory = false $( || <$tuple as HostParam>::BORROWS_MEMORY )*;
+                        let mut copies = match borrows_memory {
+                            true => {
+                                let regions = core::iter::empty()
+                                    $(
+                                        .chain(<$tuple as HostParam>::borrowed_region(&$tuple))
+                                    )*;
+                                GuestCopies::from_caller(&caller, regions)?
+                            }
+                            false => GuestCopies::default(),
+                        };
+                        let mut memory = copies.memory();
+                        $(
+                            let $tuple = <$tuple as HostParam>::lift($tuple, &mut memory)?;
+                        )*
                         let results: Self::Results =
-                            (self)(caller, $($tuple),*).into_fallible()?;
+                            call::<F, T, $($tuple,)* R>(&self, caller.reborrow(), $($tuple),*)
+                                .into_fallible()?;
+                        let borrows = memory.into_borrows();
+                        copies.write_back(&mut caller, &borrows)?;
                         Ok(params_results.write_results(results))
                     },
                 );
@@ -107,6 +201,17 @@ where
     }
 }
 
+impl<T1> WasmResults for Result<T1, Trap>
+where
+    T1: WasmType,
+{
+    type Ok = T1;
+
+    fn into_fallible(self) -> Result<<Self as WasmResults>::Ok, Trap> {
+        self
+    }
+}
+
 macro_rules! impl_wasm_return_type {
     ( $n:literal $( $tuple:ident )* ) => {
         impl<$($tuple),*> WasmResults for ($($tuple,)*)
@@ -138,6 +243,63 @@ macro_rules! impl_wasm_return_type {
 }
 for_each_tuple!(impl_wasm_return_type);
 
+/// Return types of methods exposed as host functions by the `host_module` macro.
+///
+/// # Note
+///
+/// In contrast to [`WasmResults`] this also accepts `Result` types with any
+/// error type that converts into a [`Trap`]. This allows the macro to tell
+/// `Result` return types apart by their actual type instead of their name.
+#[doc(hidden)]
+pub trait HostModuleResults {
+    /// The results of the host function.
+    type Results: WasmResults;
+
+    /// Converts `self` into the results of the host function.
+    fn into_results(self) -> Self::Results;
+}
+
+impl<T1> HostModuleResults for T1
+where
+    T1: WasmType,
+{
+    type Results = T1;
+
+    fn into_results(self) -> Self::Results {
+        self
+    }
+}
+
+impl<R, E> HostModuleResults for Result<R, E>
+where
+    Result<R, Trap>: WasmResults,
+    E: Into<Trap>,
+{
+    type Results = Result<R, Trap>;
+
+    fn into_results(self) -> Self::Results {
+        self.map_err(Into::into)
+    }
+}
+
+macro_rules! impl_host_module_results {
+    ( $n:literal $( $tuple:ident )* ) => {
+        impl<$($tuple),*> HostModuleResults for ($($tuple,)*)
+        where
+            $(
+                $tuple: WasmType
+            ),*
+        {
+            type Results = ($($tuple,)*);
+
+            fn into_results(self) -> Self::Results {
+                self
+            }
+        }
+    };
+}
+for_each_tuple!(impl_host_module_results);
+
 /// Types that can be used as parameters or results of host functions.
 pub trait WasmType: FromValue + Into<Value> + From<UntypedValue> + Into<UntypedValue> {
     /// Returns the value type of the Wasm type.
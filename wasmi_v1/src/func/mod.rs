@@ -1,12 +1,14 @@
 mod caller;
 mod error;
+mod host_param;
 mod into_func;
 mod typed_func;
 
 pub use self::{
     caller::Caller,
     error::FuncError,
-    into_func::IntoFunc,
+    host_param::HostParam,
+    into_func::{HostModuleResults, IntoFunc, WasmType},
     typed_func::{TypedFunc, WasmParams, WasmResults},
 };
 use super::{
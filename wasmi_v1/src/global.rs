@@ -1,6 +1,6 @@
 use super::{AsContext, AsContextMut, Index, Stored};
-use crate::core::{Value, ValueType};
-use core::{fmt, fmt::Display};
+use crate::core::{UntypedValue, Value, ValueType};
+use core::{fmt, fmt::Display, ptr::NonNull};
 
 /// A raw index to a global variable entity.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -89,8 +89,10 @@ impl GlobalType {
 /// A global variable entitiy.
 #[derive(Debug)]
 pub struct GlobalEntity {
-    /// The current value of the global variable.
-    value: Value,
+    /// The current untyped value of the global variable.
+    value: UntypedValue,
+    /// The type of the value of the global variable.
+    value_type: ValueType,
     /// The mutability of the global variable.
     mutability: Mutability,
 }
@@ -99,7 +101,8 @@ impl GlobalEntity {
     /// Creates a new global entity with the given initial value and mutability.
     pub fn new(initial_value: Value, mutability: Mutability) -> Self {
         Self {
-            value: initial_value,
+            value: initial_value.into(),
+            value_type: initial_value.value_type(),
             mutability,
         }
     }
@@ -111,7 +114,7 @@ impl GlobalEntity {
 
     /// Returns the type of the global variable value.
     pub fn value_type(&self) -> ValueType {
-        self.value.value_type()
+        self.value_type
     }
 
     /// Returns the [`GlobalType`] of the global variable.
@@ -135,13 +138,25 @@ impl GlobalEntity {
                 encountered: new_value.value_type(),
             });
         }
-        self.value = new_value;
+        self.value = new_value.into();
         Ok(())
     }
 
     /// Returns the current value of the global variable.
     pub fn get(&self) -> Value {
-        self.value
+        self.value.with_type(self.value_type)
+    }
+
+    /// Returns a pointer to the untyped value of the global variable.
+    ///
+    /// # Note
+    ///
+    /// This is used by the `wasmi` interpreter to cache the value cells
+    /// of global variables for fast accesses during execution.
+    /// Writes through the pointer are required to respect the
+    /// type and mutability of the global variable.
+    pub(crate) fn get_untyped_ptr(&mut self) -> NonNull<UntypedValue> {
+        NonNull::from(&mut self.value)
     }
 }
 
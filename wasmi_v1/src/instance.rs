@@ -15,7 +15,7 @@ use alloc::{
     string::{String, ToString},
     vec::Vec,
 };
-use core::{iter::FusedIterator, ops::Deref};
+use core::{fmt, fmt::Display, iter::FusedIterator, ops::Deref};
 
 /// A raw index to a module instance entity.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -31,8 +31,36 @@ impl Index for InstanceIdx {
     }
 }
 
-/// A module instance entity.
+/// An error that may occur upon operating on module instances.
 #[derive(Debug)]
+#[non_exhaustive]
+pub enum InstanceError {
+    /// Occurs when dropping an instance whose owned entity is still in use.
+    ///
+    /// An entity is in use if it is imported by another instance or, for
+    /// functions, if it is an element of a table not owned by the instance.
+    EntityInUse {
+        /// The owned entity that is still in use.
+        entity: Extern,
+    },
+}
+
+impl Display for InstanceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::EntityInUse { entity } => {
+                write!(
+                    f,
+                    "cannot drop instance since its owned entity is still in use: {:?}",
+                    entity
+                )
+            }
+        }
+    }
+}
+
+/// A module instance entity.
+#[derive(Debug, Clone)]
 pub struct InstanceEntity {
     initialized: bool,
     func_types: Vec<DedupFuncType>,
@@ -41,6 +69,19 @@ pub struct InstanceEntity {
     memories: Vec<Memory>,
     globals: Vec<Global>,
     exports: BTreeMap<String, Extern>,
+    /// The number of imported entities of each kind.
+    ///
+    /// All entities that follow the imported ones are owned by the instance.
+    len_imports: ImportsLen,
+}
+
+/// The number of imported entities of each kind of a module instance.
+#[derive(Debug, Default, Copy, Clone)]
+struct ImportsLen {
+    tables: usize,
+    funcs: usize,
+    memories: usize,
+    globals: usize,
 }
 
 impl InstanceEntity {
@@ -54,6 +95,7 @@ impl InstanceEntity {
             memories: Vec::new(),
             globals: Vec::new(),
             exports: BTreeMap::new(),
+            len_imports: ImportsLen::default(),
         }
     }
 
@@ -68,6 +110,7 @@ impl InstanceEntity {
                 memories: Vec::default(),
                 globals: Vec::default(),
                 exports: BTreeMap::default(),
+                len_imports: ImportsLen::default(),
             },
         }
     }
@@ -107,6 +150,54 @@ impl InstanceEntity {
         self.exports.get(name).copied()
     }
 
+    /// Returns the tables owned by the instance.
+    ///
+    /// These are all tables that are not imported.
+    pub(crate) fn owned_tables(&self) -> &[Table] {
+        &self.tables[self.len_imports.tables..]
+    }
+
+    /// Returns the functions owned by the instance.
+    ///
+    /// These are all functions that are not imported.
+    pub(crate) fn owned_funcs(&self) -> &[Func] {
+        &self.funcs[self.len_imports.funcs..]
+    }
+
+    /// Returns the linear memories owned by the instance.
+    ///
+    /// These are all linear memories that are not imported.
+    pub(crate) fn owned_memories(&self) -> &[Memory] {
+        &self.memories[self.len_imports.memories..]
+    }
+
+    /// Returns the global variables owned by the instance.
+    ///
+    /// These are all global variables that are not imported.
+    pub(crate) fn owned_globals(&self) -> &[Global] {
+        &self.globals[self.len_imports.globals..]
+    }
+
+    /// Returns the tables imported by the instance.
+    pub(crate) fn imported_tables(&self) -> &[Table] {
+        &self.tables[..self.len_imports.tables]
+    }
+
+    /// Returns the functions imported by the instance.
+    pub(crate) fn imported_funcs(&self) -> &[Func] {
+        &self.funcs[..self.len_imports.funcs]
+    }
+
+    /// Returns the linear memories imported by the instance.
+    pub(crate) fn imported_memories(&self) -> &[Memory] {
+        &self.memories[..self.len_imports.memories]
+    }
+
+    /// Returns the global variables imported by the instance.
+    pub(crate) fn imported_globals(&self) -> &[Global] {
+        &self.globals[..self.len_imports.globals]
+    }
+
     /// Returns an iterator over the exports of the [`Instance`].
     ///
     /// The order of the yielded exports is not specified.
@@ -156,7 +247,7 @@ impl ExactSizeIterator for ExportsIter<'_> {
 impl FusedIterator for ExportsIter<'_> {}
 
 /// A module instance entitiy builder.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstanceEntityBuilder {
     /// The [`InstanceEntity`] under construction.
     instance: InstanceEntity,
@@ -206,6 +297,21 @@ impl InstanceEntityBuilder {
         self.instance.exports.insert(name.to_string(), new_value);
     }
 
+    /// Marks all entities pushed so far as imported.
+    ///
+    /// # Note
+    ///
+    /// All entities pushed afterwards are owned by the [`InstanceEntity`]
+    /// under construction and are dropped together with it.
+    pub(crate) fn finish_imports(&mut self) {
+        self.instance.len_imports = ImportsLen {
+            tables: self.instance.tables.len(),
+            funcs: self.instance.funcs.len(),
+            memories: self.instance.memories.len(),
+            globals: self.instance.globals.len(),
+        };
+    }
+
     /// Finishes constructing the [`InstanceEntity`].
     pub(crate) fn finish(mut self) -> InstanceEntity {
         self.instance.initialized = true;
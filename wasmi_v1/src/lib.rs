@@ -14,6 +14,8 @@ extern crate std as alloc;
 mod foreach_tuple;
 
 mod arena;
+pub mod bindgen;
+mod codec;
 mod engine;
 mod error;
 mod external;
@@ -21,12 +23,19 @@ mod func;
 mod func_type;
 mod global;
 mod instance;
+mod limits;
 mod linker;
 mod memory;
 mod module;
+mod snapshot;
+mod stats;
 mod store;
 mod table;
 
+/// Exposes the methods of an `impl` block as host functions of a Wasm host module.
+#[cfg(feature = "macros")]
+pub use wasmi_v1_macros::host_module;
+
 /// Definitions from the `wasmi_core` crate.
 #[doc(inline)]
 pub use wasmi_core as core;
@@ -36,13 +45,19 @@ pub mod errors {
     pub use super::{
         func::FuncError,
         global::GlobalError,
+        instance::InstanceError,
         linker::LinkerError,
         memory::MemoryError,
-        module::{InstantiationError, ModuleError},
+        module::{DeserializeError, InstantiationError, ModuleError},
+        snapshot::SnapshotError,
         table::TableError,
     };
 }
 
+#[doc(hidden)]
+pub use self::func::HostModuleResults;
+#[doc(hidden)]
+pub use self::memory::align_to as memory_align_to;
 use self::{
     arena::{GuardedEntity, Index},
     engine::FuncBody,
@@ -54,16 +69,26 @@ use self::{
     table::{TableEntity, TableIdx},
 };
 pub use self::{
-    engine::{Config, Engine},
+    engine::{CompilationMode, Config, Engine},
     error::Error,
     external::Extern,
-    func::{Caller, Func, TypedFunc, WasmParams, WasmResults},
+    func::{Caller, Func, HostParam, TypedFunc, WasmParams, WasmResults},
     func_type::FuncType,
     global::{Global, GlobalType, Mutability},
     instance::{ExportsIter, Instance},
+    limits::{
+        ResourceLimiter,
+        StoreLimits,
+        DEFAULT_INSTANCE_LIMIT,
+        DEFAULT_MEMORY_LIMIT,
+        DEFAULT_TABLE_LIMIT,
+        TABLE_ELEMENT_BYTES,
+    },
     linker::Linker,
-    memory::{Memory, MemoryType},
-    module::{InstancePre, Module, ModuleError, Read},
+    memory::{LinearMemory, Memory, MemoryCreator, MemoryRepr, MemoryType, WasmPtr, WasmSlice},
+    module::{InstancePre, InstanceTemplate, Module, ModuleError, Read},
+    snapshot::Snapshot,
+    stats::{EngineStats, ResourceUsage},
     store::{AsContext, AsContextMut, Store, StoreContext, StoreContextMut},
     table::{Table, TableType},
 };
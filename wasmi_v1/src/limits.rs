@@ -0,0 +1,205 @@
+use crate::Func;
+use core::mem;
+use wasmi_core::memory_units::Pages;
+
+/// The default limit for the number of instances in a [`Store`].
+///
+/// [`Store`]: crate::Store
+pub const DEFAULT_INSTANCE_LIMIT: usize = 10_000;
+
+/// The default limit for the number of tables in a [`Store`].
+///
+/// [`Store`]: crate::Store
+pub const DEFAULT_TABLE_LIMIT: usize = 10_000;
+
+/// The default limit for the number of linear memories in a [`Store`].
+///
+/// [`Store`]: crate::Store
+pub const DEFAULT_MEMORY_LIMIT: usize = 10_000;
+
+/// The number of bytes every table element accounts for
+/// in the budget of [`ResourceLimiter::total_bytes`].
+pub const TABLE_ELEMENT_BYTES: usize = mem::size_of::<Option<Func>>();
+
+/// Limits the resources that can be allocated by the entities of a [`Store`].
+///
+/// # Note
+///
+/// A [`ResourceLimiter`] is installed on a [`Store`] via [`Store::limiter`].
+/// It is consulted whenever linear memories or tables are created or grown
+/// and whenever a new module instance is created, including the creation of
+/// linear memories and tables with their initial sizes upon instantiation.
+///
+/// [`Store`]: crate::Store
+/// [`Store::limiter`]: crate::Store::limiter
+pub trait ResourceLimiter {
+    /// Returns `true` if a linear memory may grow from `current` to `desired` pages.
+    ///
+    /// # Note
+    ///
+    /// - Upon creation of a linear memory `current` is zero and
+    ///   `desired` is the initial number of pages of the memory.
+    /// - `maximum` is the maximum number of pages declared by the memory type if any.
+    /// - Denying the growth causes `memory.grow` to return `-1` and
+    ///   creation of the linear memory to fail.
+    fn memory_growing(&mut self, current: Pages, desired: Pages, maximum: Option<Pages>) -> bool;
+
+    /// Returns `true` if a table may grow from `current` to `desired` elements.
+    ///
+    /// # Note
+    ///
+    /// - Upon creation of a table `current` is zero and
+    ///   `desired` is the initial number of elements of the table.
+    /// - `maximum` is the maximum number of elements declared by the table type if any.
+    /// - Denying the growth causes growing or creating the table to fail.
+    fn table_growing(&mut self, current: usize, desired: usize, maximum: Option<usize>) -> bool;
+
+    /// Returns the maximum number of module instances in the [`Store`].
+    ///
+    /// [`Store`]: crate::Store
+    fn instances(&self) -> usize {
+        DEFAULT_INSTANCE_LIMIT
+    }
+
+    /// Returns the maximum number of tables in the [`Store`].
+    ///
+    /// [`Store`]: crate::Store
+    fn tables(&self) -> usize {
+        DEFAULT_TABLE_LIMIT
+    }
+
+    /// Returns the maximum number of linear memories in the [`Store`].
+    ///
+    /// [`Store`]: crate::Store
+    fn memories(&self) -> usize {
+        DEFAULT_MEMORY_LIMIT
+    }
+
+    /// Returns the maximum number of bytes of all linear memories and tables
+    /// in the [`Store`] combined.
+    ///
+    /// # Note
+    ///
+    /// - Every table element accounts for [`TABLE_ELEMENT_BYTES`] bytes.
+    /// - Exceeding the budget is treated the same as a denial by
+    ///   [`ResourceLimiter::memory_growing`] or [`ResourceLimiter::table_growing`].
+    /// - Returns `None` by default in which case the combined size is not limited.
+    ///
+    /// [`Store`]: crate::Store
+    fn total_bytes(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// A [`ResourceLimiter`] with static limits.
+///
+/// # Note
+///
+/// By default linear memories and tables may grow up to
+/// their declared maximum and the number of instances, tables
+/// and linear memories is limited by their respective defaults.
+#[derive(Debug, Copy, Clone)]
+pub struct StoreLimits {
+    /// The maximum number of pages of a single linear memory.
+    memory_pages: Option<Pages>,
+    /// The maximum number of elements of a single table.
+    table_elements: Option<usize>,
+    /// The maximum number of module instances.
+    instances: usize,
+    /// The maximum number of tables.
+    tables: usize,
+    /// The maximum number of linear memories.
+    memories: usize,
+    /// The maximum number of bytes of all linear memories and tables combined.
+    total_bytes: Option<usize>,
+}
+
+impl Default for StoreLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StoreLimits {
+    /// Creates the default [`StoreLimits`].
+    pub const fn new() -> Self {
+        Self {
+            memory_pages: None,
+            table_elements: None,
+            instances: DEFAULT_INSTANCE_LIMIT,
+            tables: DEFAULT_TABLE_LIMIT,
+            memories: DEFAULT_MEMORY_LIMIT,
+            total_bytes: None,
+        }
+    }
+
+    /// Limits the number of pages of every single linear memory.
+    pub const fn limit_memory_pages(mut self, limit: Pages) -> Self {
+        self.memory_pages = Some(limit);
+        self
+    }
+
+    /// Limits the number of elements of every single table.
+    pub const fn limit_table_elements(mut self, limit: usize) -> Self {
+        self.table_elements = Some(limit);
+        self
+    }
+
+    /// Limits the number of module instances.
+    pub const fn limit_instances(mut self, limit: usize) -> Self {
+        self.instances = limit;
+        self
+    }
+
+    /// Limits the number of tables.
+    pub const fn limit_tables(mut self, limit: usize) -> Self {
+        self.tables = limit;
+        self
+    }
+
+    /// Limits the number of linear memories.
+    pub const fn limit_memories(mut self, limit: usize) -> Self {
+        self.memories = limit;
+        self
+    }
+
+    /// Limits the number of bytes of all linear memories and tables combined.
+    ///
+    /// See [`ResourceLimiter::total_bytes`] for how table elements are accounted.
+    pub const fn limit_total_bytes(mut self, limit: usize) -> Self {
+        self.total_bytes = Some(limit);
+        self
+    }
+}
+
+impl ResourceLimiter for StoreLimits {
+    fn memory_growing(&mut self, _current: Pages, desired: Pages, _maximum: Option<Pages>) -> bool {
+        match self.memory_pages {
+            Some(limit) => desired <= limit,
+            None => true,
+        }
+    }
+
+    fn table_growing(&mut self, _current: usize, desired: usize, _maximum: Option<usize>) -> bool {
+        match self.table_elements {
+            Some(limit) => desired <= limit,
+            None => true,
+        }
+    }
+
+    fn instances(&self) -> usize {
+        self.instances
+    }
+
+    fn tables(&self) -> usize {
+        self.tables
+    }
+
+    fn memories(&self) -> usize {
+        self.memories
+    }
+
+    fn total_bytes(&self) -> Option<usize> {
+        self.total_bytes
+    }
+}
@@ -1,9 +1,11 @@
 use super::{
     errors::{MemoryError, TableError},
+    AsContext,
     AsContextMut,
     Error,
     Extern,
     InstancePre,
+    InstanceTemplate,
     Module,
 };
 use crate::{
@@ -355,6 +357,33 @@ impl<T> Linker<T> {
         context: impl AsContextMut,
         module: &'a Module,
     ) -> Result<InstancePre<'a>, Error> {
+        self.resolve_imports(&context, module)?;
+        module.instantiate(context, self.externals.drain(..))
+    }
+
+    /// Creates an [`InstanceTemplate`] for the given [`Module`] using the definitions in the [`Linker`].
+    ///
+    /// # Note
+    ///
+    /// Use this instead of [`Linker::instantiate`] in order to efficiently
+    /// instantiate the same [`Module`] many times in the same [`Store`].
+    ///
+    /// [`Store`]: crate::Store
+    pub fn instantiate_template<'a>(
+        &mut self,
+        context: impl AsContextMut,
+        module: &'a Module,
+    ) -> Result<InstanceTemplate<'a>, Error> {
+        self.resolve_imports(&context, module)?;
+        InstanceTemplate::new(context, module, self.externals.drain(..))
+    }
+
+    /// Resolves the imports of the [`Module`] into the cached externals buffer.
+    ///
+    /// # Errors
+    ///
+    /// If an import cannot be resolved or has a mismatching type.
+    fn resolve_imports(&mut self, context: impl AsContext, module: &Module) -> Result<(), Error> {
         // Clear the cached externals buffer.
         self.externals.clear();
 
@@ -421,6 +450,6 @@ impl<T> Linker<T> {
             };
             self.externals.push(external);
         }
-        module.instantiate(context, self.externals.drain(..))
+        Ok(())
     }
 }
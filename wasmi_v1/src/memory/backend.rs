@@ -0,0 +1,208 @@
+use super::{byte_buffer::ByteBuffer, MemoryError, MemoryType};
+use alloc::{boxed::Box, vec::Vec};
+use core::{fmt, fmt::Debug};
+
+/// A user provided storage for the bytes of a linear memory.
+///
+/// # Note
+///
+/// - Use [`Memory::new_with_backend`] to create a linear memory with a
+///   custom backend or install a [`MemoryCreator`] on the [`Store`] via
+///   [`Store::memory_creator`] in order to decide how the linear memories
+///   of all instances are allocated.
+/// - The length of a [`LinearMemory`] is always a multiple of the Wasm page size.
+/// - Backends may move their bytes upon any call to [`LinearMemory::data_mut`] or
+///   [`LinearMemory::resize`] since `wasmi` does not cache pointers into them.
+///
+/// [`Memory::new_with_backend`]: crate::Memory::new_with_backend
+/// [`Store`]: crate::Store
+/// [`Store::memory_creator`]: crate::Store::memory_creator
+pub trait LinearMemory: Send + Sync {
+    /// Returns a shared slice to the bytes of the linear memory.
+    fn data(&self) -> &[u8];
+
+    /// Returns an exclusive slice to the bytes of the linear memory.
+    fn data_mut(&mut self) -> &mut [u8];
+
+    /// Resizes the linear memory to `new_len` bytes.
+    ///
+    /// # Note
+    ///
+    /// - Upon growing all new bytes must be zero.
+    /// - Shrinking is only used to restore linear memories to a previous
+    ///   state, e.g. upon [`Store::restore`].
+    ///
+    /// # Errors
+    ///
+    /// If the linear memory cannot be resized to `new_len` bytes.
+    /// This causes `memory.grow` to return `-1`.
+    ///
+    /// [`Store::restore`]: crate::Store::restore
+    fn resize(&mut self, new_len: usize) -> Result<(), MemoryError>;
+}
+
+impl LinearMemory for Vec<u8> {
+    fn data(&self) -> &[u8] {
+        &self[..]
+    }
+
+    fn data_mut(&mut self) -> &mut [u8] {
+        &mut self[..]
+    }
+
+    fn resize(&mut self, new_len: usize) -> Result<(), MemoryError> {
+        Vec::resize(self, new_len, 0x00_u8);
+        Ok(())
+    }
+}
+
+/// A linear memory backed by a fixed size host buffer.
+///
+/// # Note
+///
+/// The buffer cannot be resized, therefore `memory.grow` always fails.
+impl LinearMemory for &'static mut [u8] {
+    fn data(&self) -> &[u8] {
+        self
+    }
+
+    fn data_mut(&mut self) -> &mut [u8] {
+        self
+    }
+
+    fn resize(&mut self, new_len: usize) -> Result<(), MemoryError> {
+        if new_len != self.len() {
+            return Err(MemoryError::OutOfBoundsGrowth);
+        }
+        Ok(())
+    }
+}
+
+/// Creates the [`LinearMemory`] backends of all linear memories of a [`Store`].
+///
+/// # Note
+///
+/// Installed on a [`Store`] via [`Store::memory_creator`].
+///
+/// [`Store`]: crate::Store
+/// [`Store::memory_creator`]: crate::Store::memory_creator
+pub trait MemoryCreator: Send + Sync {
+    /// Creates a new [`LinearMemory`] backend for a linear memory of the given `memory_type`.
+    ///
+    /// # Note
+    ///
+    /// The returned backend must have exactly as many bytes as
+    /// the initial pages of the `memory_type` require.
+    ///
+    /// # Errors
+    ///
+    /// If the backend cannot be created. This fails the creation of the linear memory.
+    fn new_memory(&self, memory_type: MemoryType) -> Result<Box<dyn LinearMemory>, MemoryError>;
+}
+
+/// The storage of the bytes of a linear memory entity.
+pub(super) enum MemoryBackend {
+    /// The default byte buffer selected by the crate features.
+    Default(ByteBuffer),
+    /// A user provided backend.
+    Custom(Box<dyn LinearMemory>),
+}
+
+impl Debug for MemoryBackend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Default(buffer) => f.debug_tuple("Default").field(buffer).finish(),
+            Self::Custom(backend) => f
+                .debug_struct("Custom")
+                .field("len", &backend.data().len())
+                .finish(),
+        }
+    }
+}
+
+impl MemoryBackend {
+    /// Returns the length of the backend in bytes.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Default(buffer) => buffer.len(),
+            Self::Custom(backend) => backend.data().len(),
+        }
+    }
+
+    /// Grows the backend by the given delta.
+    ///
+    /// # Errors
+    ///
+    /// If the backend cannot grow by the given delta.
+    pub fn grow(&mut self, delta: usize) -> Result<(), MemoryError> {
+        match self {
+            Self::Default(buffer) => buffer.grow(delta),
+            Self::Custom(backend) => {
+                let new_len = backend
+                    .data()
+                    .len()
+                    .checked_add(delta)
+                    .ok_or(MemoryError::OutOfBoundsGrowth)?;
+                backend.resize(new_len)
+            }
+        }
+    }
+
+    /// Shrinks the backend to the given length.
+    ///
+    /// # Errors
+    ///
+    /// If the backend cannot be shrunk.
+    pub fn shrink(&mut self, new_len: usize) -> Result<(), MemoryError> {
+        match self {
+            Self::Default(buffer) => buffer.shrink(new_len),
+            Self::Custom(backend) => backend.resize(new_len),
+        }
+    }
+
+    /// Returns `true` if this is a user provided backend.
+    pub fn is_custom(&self) -> bool {
+        matches!(self, Self::Custom(_))
+    }
+
+    /// Enables eliding bounds checks via guard pages if supported.
+    ///
+    /// # Note
+    ///
+    /// Custom backends are never guarded.
+    pub fn enable_guard_pages(&mut self) {
+        if let Self::Default(buffer) = self {
+            buffer.enable_guard_pages()
+        }
+    }
+
+    /// Returns the start and length of the guard region if out of bounds accesses trap.
+    ///
+    /// # Note
+    ///
+    /// Custom backends are never guarded.
+    pub fn guard_region(&mut self) -> Option<(*const u8, usize)> {
+        match self {
+            Self::Default(buffer) => buffer.guard_region(),
+            Self::Custom(_) => None,
+        }
+    }
+
+    /// Returns a shared slice to the bytes of the backend.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        match self {
+            Self::Default(buffer) => buffer.data(),
+            Self::Custom(backend) => backend.data(),
+        }
+    }
+
+    /// Returns an exclusive slice to the bytes of the backend.
+    #[inline]
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        match self {
+            Self::Default(buffer) => buffer.data_mut(),
+            Self::Custom(backend) => backend.data_mut(),
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use super::{max_memory_len, MemoryError};
-use alloc::{vec, vec::Vec};
+use alloc::{boxed::Box, vec, vec::Vec};
 use core::{fmt, fmt::Display};
 
 /// Dummy error for fallible `Vec`-based virtual memory operations.
@@ -39,6 +39,21 @@ impl ByteBuffer {
         Ok(Self { bytes })
     }
 
+    /// Creates a new byte buffer with the given initial length that starts with the `image`.
+    ///
+    /// # Errors
+    ///
+    /// - If the `image` does not fit into the initial length.
+    /// - Same as [`ByteBuffer::new`].
+    pub fn with_image(initial_len: usize, image: &ByteBufferImage) -> Result<Self, MemoryError> {
+        if image.len() > initial_len {
+            return Err(MemoryError::OutOfBoundsAllocation);
+        }
+        let mut buffer = Self::new(initial_len)?;
+        buffer.bytes[..image.len()].copy_from_slice(&image.bytes);
+        Ok(buffer)
+    }
+
     /// Grows the byte buffer by the given delta.
     ///
     /// # Errors
@@ -55,6 +70,30 @@ impl ByteBuffer {
         Ok(())
     }
 
+    /// Shrinks the byte buffer to the given length.
+    ///
+    /// # Panics
+    ///
+    /// If the new length is greater than the current length of the byte buffer.
+    ///
+    /// # Errors
+    ///
+    /// Never fails but returns a `Result` for parity with the virtual memory
+    /// based implementation.
+    pub fn shrink(&mut self, new_len: usize) -> Result<(), MemoryError> {
+        assert!(new_len <= self.len());
+        self.bytes.truncate(new_len);
+        Ok(())
+    }
+
+    /// Does nothing since the `Vec` based byte buffer has no guard pages.
+    pub fn enable_guard_pages(&mut self) {}
+
+    /// Returns `None` since out of bounds accesses never trap via guard pages.
+    pub fn guard_region(&mut self) -> Option<(*const u8, usize)> {
+        None
+    }
+
     /// Returns the length of the byte buffer in bytes.
     pub fn len(&self) -> usize {
         self.bytes.len()
@@ -70,3 +109,27 @@ impl ByteBuffer {
         &mut self.bytes[..]
     }
 }
+
+/// The initial contents of a `Vec`-based byte buffer.
+///
+/// # Note
+///
+/// The contents are copied into every byte buffer created from the image.
+#[derive(Debug)]
+pub struct ByteBufferImage {
+    bytes: Box<[u8]>,
+}
+
+impl ByteBufferImage {
+    /// Creates a new byte buffer image with the given contents.
+    pub fn new(bytes: &[u8]) -> Result<Self, MemoryError> {
+        Ok(Self {
+            bytes: bytes.into(),
+        })
+    }
+
+    /// Returns the length of the image in bytes.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+}
@@ -1,7 +1,7 @@
-use super::{max_memory_len, MemoryError};
+use super::{guard, max_memory_len, MemoryError};
 use core::fmt::Debug;
 use wasmi_core::VirtualMemory;
-pub use wasmi_core::VirtualMemoryError;
+pub use wasmi_core::{VirtualMemoryError, VirtualMemoryImage as ByteBufferImage};
 
 /// A virtual memory based byte buffer implementation.
 ///
@@ -9,26 +9,41 @@ pub use wasmi_core::VirtualMemoryError;
 ///
 /// - This is a more efficient implementation of the byte buffer that
 ///   makes use of operating system provided virtual memory abstractions.
-/// - This implementation allocates 4GB of virtual memory up front so
-///   that grow operations later on are no-ops. The downside to this is
-///   that this implementation is only supported on 64-bit systems.
-///   32-bit systems will fall back to the `Vec`-based implementation
-///   even if the respective crate feature is enabled.
+/// - This implementation reserves 4GB of virtual memory up front so
+///   that grow operations later on never have to move the bytes. Only the
+///   bytes within the length of the byte buffer are accessible and backed
+///   by physical memory. Accessing reserved bytes beyond faults.
+///   The downside to this is that this implementation is only supported
+///   on 64-bit systems. 32-bit systems will fall back to the `Vec`-based
+///   implementation even if the respective crate feature is enabled.
+///
+/// - Since the reservation covers every address that a Wasm load or store
+///   could ever access the bytes beyond its length act as guard pages.
+///   Loads and stores skip their explicit bounds checks if guard pages are
+///   enabled and the trap handler that turns faults on guard pages into traps
+///   could be installed.
 #[derive(Debug)]
 pub struct ByteBuffer {
     bytes: VirtualMemory,
-    len: usize,
+    /// Is `true` if out of bounds accesses trap via the guard pages.
+    guarded: bool,
 }
 
 impl ByteBuffer {
-    /// Determines the initial size of the virtual memory allocation.
+    /// Determines the size of the virtual memory reservation.
     ///
     /// # Note
     ///
-    /// In this implementation we won't reallocate the virtually allocated
-    /// buffer and instead simply adjust the `len` field of the `ByteBuf`
-    /// wrapper in order to efficiently grow the virtual memory.
-    const ALLOCATION_SIZE: usize = u32::MAX as usize;
+    /// In this implementation we won't reallocate the virtually reserved
+    /// buffer and instead simply make more of it accessible in order to
+    /// efficiently grow the virtual memory.
+    ///
+    /// The reservation covers the whole 32-bit address space plus guard pages
+    /// for accesses of up to 8 bytes that start at the highest effective address.
+    const ALLOCATION_SIZE: usize = (1 << 32) + Self::GUARD_SIZE;
+
+    /// The size of the guard pages beyond the 32-bit address space.
+    const GUARD_SIZE: usize = 1 << 16;
 
     /// Creates a new byte buffer with the given initial length.
     ///
@@ -37,10 +52,34 @@ impl ByteBuffer {
     /// - If the initial length is 0.
     /// - If the initial length exceeds the maximum supported limit.
     pub fn new(initial_len: usize) -> Result<Self, MemoryError> {
-        let bytes = VirtualMemory::new(Self::ALLOCATION_SIZE)?;
+        let mut bytes = VirtualMemory::reserve(Self::ALLOCATION_SIZE)?;
+        bytes.resize(initial_len)?;
+        Ok(Self {
+            bytes,
+            guarded: false,
+        })
+    }
+
+    /// Creates a new byte buffer with the given initial length that starts with the `image`.
+    ///
+    /// # Note
+    ///
+    /// On Linux the `image` is mapped copy-on-write into the byte buffer
+    /// instead of being copied.
+    ///
+    /// # Errors
+    ///
+    /// - If the `image` does not fit into the initial length.
+    /// - Same as [`ByteBuffer::new`].
+    pub fn with_image(initial_len: usize, image: &ByteBufferImage) -> Result<Self, MemoryError> {
+        if image.len() > initial_len {
+            return Err(MemoryError::OutOfBoundsAllocation);
+        }
+        let mut bytes = VirtualMemory::with_image(Self::ALLOCATION_SIZE, image)?;
+        bytes.resize(initial_len)?;
         Ok(Self {
             bytes,
-            len: initial_len,
+            guarded: false,
         })
     }
 
@@ -56,22 +95,63 @@ impl ByteBuffer {
             .filter(|&new_len| new_len < max_memory_len())
             .ok_or(MemoryError::OutOfBoundsGrowth)?;
         assert!(new_len >= self.len());
-        self.len = new_len;
+        self.bytes.resize(new_len)?;
+        Ok(())
+    }
+
+    /// Shrinks the byte buffer to the given length.
+    ///
+    /// # Panics
+    ///
+    /// If the new length is greater than the current length of the byte buffer.
+    ///
+    /// # Errors
+    ///
+    /// If the operating system fails to make the bytes beyond inaccessible.
+    pub fn shrink(&mut self, new_len: usize) -> Result<(), MemoryError> {
+        assert!(new_len <= self.len());
+        // Note: Growing the byte buffer later on expects the bytes
+        //       beyond its length to be zero initialized which is
+        //       taken care of by resizing the virtual memory.
+        self.bytes.resize(new_len)?;
         Ok(())
     }
 
+    /// Enables eliding bounds checks via the guard pages of the byte buffer.
+    ///
+    /// # Note
+    ///
+    /// Installs the process wide trap handler if it has not been installed already.
+    /// The byte buffer stays bounds checked if the trap handler cannot be installed.
+    pub fn enable_guard_pages(&mut self) {
+        self.guarded = guard::install();
+    }
+
+    /// Returns the start and length of the guard region if out of bounds accesses trap.
+    ///
+    /// # Note
+    ///
+    /// The guard region spans the whole reservation so that loads and stores
+    /// may skip their bounds checks while the trap handler is active.
+    pub fn guard_region(&mut self) -> Option<(*const u8, usize)> {
+        if !self.guarded {
+            return None;
+        }
+        Some((self.bytes.data_mut().as_ptr(), Self::ALLOCATION_SIZE))
+    }
+
     /// Returns the length of the byte buffer in bytes.
     pub fn len(&self) -> usize {
-        self.len
+        self.bytes.data().len()
     }
 
     /// Returns a shared slice to the bytes underlying to the byte buffer.
     pub fn data(&self) -> &[u8] {
-        &self.bytes.data()[..self.len]
+        self.bytes.data()
     }
 
     /// Returns an exclusive slice to the bytes underlying to the byte buffer.
     pub fn data_mut(&mut self) -> &mut [u8] {
-        &mut self.bytes.data_mut()[..self.len]
+        self.bytes.data_mut()
     }
 }
@@ -0,0 +1,89 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+use wasmi_core::memory_units::{Bytes, Pages};
+
+/// The number of bits per word of a [`DirtyPages`] bitmap.
+const BITS: usize = u64::BITS as usize;
+
+/// Returns the size of a single linear memory page in bytes.
+fn page_size() -> usize {
+    Bytes::from(Pages(1)).0
+}
+
+/// A bitmap of the linear memory pages that have been written since the last clear.
+#[derive(Debug, Default)]
+pub(super) struct DirtyPages {
+    /// The bits of the bitmap where bit `n` is set if page `n` is dirty.
+    words: Vec<u64>,
+    /// The number of pages covered by the bitmap.
+    len: usize,
+}
+
+impl DirtyPages {
+    /// Creates a new [`DirtyPages`] bitmap for the given amount of pages with all pages dirty.
+    pub fn new(pages: Pages) -> Self {
+        let mut dirty = Self::default();
+        dirty.resize(pages);
+        dirty
+    }
+
+    /// Resizes the bitmap to the given amount of pages.
+    ///
+    /// # Note
+    ///
+    /// Pages that are added to the bitmap are marked dirty since their contents changed.
+    pub fn resize(&mut self, pages: Pages) {
+        let old_len = self.len;
+        let new_len = pages.0;
+        self.words.resize(new_len.div_ceil(BITS), 0);
+        if let Some(last) = self.words.last_mut() {
+            // Clear the bits of the last word that are beyond the new length.
+            let used = new_len % BITS;
+            if used != 0 {
+                *last &= (1_u64 << used) - 1;
+            }
+        }
+        self.len = new_len;
+        for page in old_len..new_len {
+            self.mark_page(page);
+        }
+    }
+
+    /// Marks the page with the given index dirty.
+    fn mark_page(&mut self, page: usize) {
+        self.words[page / BITS] |= 1 << (page % BITS);
+    }
+
+    /// Marks all pages overlapping the given byte range dirty.
+    pub fn mark(&mut self, bytes: Range<usize>) {
+        if bytes.is_empty() {
+            return;
+        }
+        let first = bytes.start / page_size();
+        let last = (bytes.end - 1) / page_size();
+        for page in first..=last.min(self.len.saturating_sub(1)) {
+            self.mark_page(page);
+        }
+    }
+
+    /// Marks all pages dirty.
+    pub fn mark_all(&mut self) {
+        for page in 0..self.len {
+            self.mark_page(page);
+        }
+    }
+
+    /// Marks all pages clean.
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Returns the indices of all dirty pages in ascending order.
+    pub fn pages(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(index, &word)| {
+            (0..BITS)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| index * BITS + bit)
+        })
+    }
+}
@@ -0,0 +1,65 @@
+//! Bounds check elision for linear memories guarded by inaccessible pages.
+//!
+//! # Note
+//!
+//! Guarded linear memories are only supported with the `virtual_memory`
+//! crate feature on Linux x86_64. On all other platforms no linear memory
+//! is ever guarded and the functions of this module are never used for
+//! memory accesses.
+
+#[cfg(all(
+    feature = "virtual_memory",
+    target_os = "linux",
+    target_arch = "x86_64"
+))]
+pub use wasmi_core::trap_handler::{catch_traps, install, load, set_guard_region, store};
+
+#[cfg(not(all(
+    feature = "virtual_memory",
+    target_os = "linux",
+    target_arch = "x86_64"
+)))]
+mod fallback {
+    use wasmi_core::TrapCode;
+
+    /// Returns `false` since guarded linear memories are unsupported.
+    #[cfg(feature = "virtual_memory")]
+    pub fn install() -> bool {
+        false
+    }
+
+    /// Does nothing since guarded linear memories are unsupported.
+    pub fn set_guard_region(_start: *const u8, _len: usize) {}
+
+    /// Calls `f` since there are no guarded memory accesses that could fault.
+    #[inline(always)]
+    pub fn catch_traps<F, R>(f: F) -> Result<R, TrapCode>
+    where
+        F: FnOnce() -> R,
+    {
+        Ok(f())
+    }
+
+    /// Never called since guarded linear memories are unsupported.
+    pub unsafe fn load<const N: usize>(_src: *const u8) -> [u8; N] {
+        unreachable!("encountered guarded load without guarded linear memory support")
+    }
+
+    /// Never called since guarded linear memories are unsupported.
+    pub unsafe fn store<const N: usize>(_dst: *mut u8, _bytes: [u8; N]) {
+        unreachable!("encountered guarded store without guarded linear memory support")
+    }
+}
+
+#[cfg(not(all(
+    feature = "virtual_memory",
+    target_os = "linux",
+    target_arch = "x86_64"
+)))]
+pub use self::fallback::{catch_traps, load, set_guard_region, store};
+
+#[cfg(all(
+    feature = "virtual_memory",
+    not(all(target_os = "linux", target_arch = "x86_64"))
+))]
+pub use self::fallback::install;
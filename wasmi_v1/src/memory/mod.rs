@@ -2,13 +2,37 @@
 #[path = "buffer_vmem.rs"]
 mod byte_buffer;
 
+mod backend;
 #[cfg(not(all(feature = "virtual_memory", target_pointer_width = "64")))]
 #[path = "buffer_vec.rs"]
 mod byte_buffer;
-
-use self::byte_buffer::{ByteBuffer, VirtualMemoryError};
-use super::{AsContext, AsContextMut, Index, StoreContext, StoreContextMut, Stored};
-use core::{fmt, fmt::Display};
+mod dirty;
+pub(crate) mod guard;
+mod ptr;
+
+#[cfg(test)]
+mod tests;
+
+use self::{
+    backend::MemoryBackend,
+    byte_buffer::{ByteBuffer, ByteBufferImage, VirtualMemoryError},
+    dirty::DirtyPages,
+};
+pub use self::{
+    backend::{LinearMemory, MemoryCreator},
+    ptr::{align_to, MemoryRepr, WasmPtr, WasmSlice},
+};
+use super::{
+    AsContext,
+    AsContextMut,
+    Index,
+    ResourceLimiter,
+    StoreContext,
+    StoreContextMut,
+    Stored,
+};
+use alloc::{boxed::Box, vec::Vec};
+use core::{fmt, fmt::Display, ptr::NonNull};
 use wasmi_core::memory_units::{Bytes, Pages};
 
 /// A raw index to a linear memory entity.
@@ -35,6 +59,19 @@ pub enum MemoryError {
     OutOfBoundsGrowth,
     /// Tried to access linear memory out of bounds.
     OutOfBoundsAccess,
+    /// Tried to read a string from linear memory that is not valid UTF-8.
+    InvalidUtf8(core::str::Utf8Error),
+    /// Tried to write a number of values that does not match the length of a [`WasmSlice`].
+    LengthMismatch {
+        /// The length of the [`WasmSlice`].
+        expected: usize,
+        /// The number of values written to the [`WasmSlice`].
+        actual: usize,
+    },
+    /// Tried to create or grow a linear memory beyond the limits of the [`ResourceLimiter`].
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    ResourceLimitReached,
     /// A generic virtual memory error.
     Vmem(byte_buffer::VirtualMemoryError),
     /// Occurs when a memory type does not satisfy the constraints of another.
@@ -44,6 +81,13 @@ pub enum MemoryError {
         /// The required [`MemoryType`].
         required: MemoryType,
     },
+    /// Occurs when a [`LinearMemory`] backend does not match the initial pages of its [`MemoryType`].
+    InvalidBackendLength {
+        /// The length in bytes required by the initial pages of the [`MemoryType`].
+        expected: usize,
+        /// The length in bytes of the [`LinearMemory`] backend.
+        actual: usize,
+    },
 }
 
 impl Display for MemoryError {
@@ -58,6 +102,26 @@ impl Display for MemoryError {
             MemoryError::OutOfBoundsAccess => {
                 write!(f, "tried to access virtual memory out of bounds")
             }
+            MemoryError::InvalidUtf8(error) => {
+                write!(
+                    f,
+                    "tried to read invalid UTF-8 from linear memory: {}",
+                    error
+                )
+            }
+            MemoryError::LengthMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "tried to write {} values to a slice of linear memory of length {}",
+                    actual, expected,
+                )
+            }
+            MemoryError::ResourceLimitReached => {
+                write!(
+                    f,
+                    "tried to allocate linear memory beyond the resource limits"
+                )
+            }
             MemoryError::Vmem(error) => Display::fmt(error, f),
             Self::UnsatisfyingMemoryType {
                 unsatisfying,
@@ -69,6 +133,13 @@ impl Display for MemoryError {
                     unsatisfying, required,
                 )
             }
+            Self::InvalidBackendLength { expected, actual } => {
+                write!(
+                    f,
+                    "linear memory backend has a length of {} bytes but {} bytes are required",
+                    actual, expected,
+                )
+            }
         }
     }
 }
@@ -142,12 +213,38 @@ impl MemoryType {
     }
 }
 
+/// The initial contents of a linear memory that can be shared by many linear memories.
+///
+/// # Note
+///
+/// With the `virtual_memory` crate feature enabled on Linux the image is mapped
+/// copy-on-write into new linear memories. Otherwise the image is copied.
+#[derive(Debug)]
+pub struct MemoryImage {
+    image: ByteBufferImage,
+}
+
+impl MemoryImage {
+    /// Creates a new [`MemoryImage`] with the given contents.
+    ///
+    /// # Errors
+    ///
+    /// If the image cannot be created.
+    pub fn new(bytes: &[u8]) -> Result<Self, MemoryError> {
+        Ok(Self {
+            image: ByteBufferImage::new(bytes)?,
+        })
+    }
+}
+
 /// A linear memory entity.
 #[derive(Debug)]
 pub struct MemoryEntity {
-    bytes: ByteBuffer,
+    bytes: MemoryBackend,
     memory_type: MemoryType,
     current_pages: Pages,
+    /// The pages written since the last clear if dirty page tracking is enabled.
+    dirty: Option<DirtyPages>,
 }
 
 impl MemoryEntity {
@@ -164,9 +261,58 @@ impl MemoryEntity {
         let initial_pages = memory_type.initial_pages();
         let initial_bytes = Bytes::from(initial_pages);
         let memory = Self {
-            bytes: ByteBuffer::new(initial_bytes.0)?,
+            bytes: MemoryBackend::Default(ByteBuffer::new(initial_bytes.0)?),
+            memory_type,
+            current_pages: initial_pages,
+            dirty: None,
+        };
+        Ok(memory)
+    }
+
+    /// Creates a new memory entity with the given memory type that starts with the `image`.
+    ///
+    /// # Errors
+    ///
+    /// - If the `image` does not fit into the initial pages of the memory type.
+    /// - If the linear memory cannot be allocated.
+    pub(crate) fn with_image(
+        memory_type: MemoryType,
+        image: &MemoryImage,
+    ) -> Result<Self, MemoryError> {
+        let initial_pages = memory_type.initial_pages();
+        let initial_bytes = Bytes::from(initial_pages);
+        let memory = Self {
+            bytes: MemoryBackend::Default(ByteBuffer::with_image(initial_bytes.0, &image.image)?),
+            memory_type,
+            current_pages: initial_pages,
+            dirty: None,
+        };
+        Ok(memory)
+    }
+
+    /// Creates a new memory entity with the given memory type that stores its bytes in `backend`.
+    ///
+    /// # Errors
+    ///
+    /// If the length of the `backend` does not match the initial pages of the memory type.
+    pub fn with_backend(
+        memory_type: MemoryType,
+        backend: Box<dyn LinearMemory>,
+    ) -> Result<Self, MemoryError> {
+        let initial_pages = memory_type.initial_pages();
+        let initial_bytes = Bytes::from(initial_pages);
+        let len = backend.data().len();
+        if len != initial_bytes.0 {
+            return Err(MemoryError::InvalidBackendLength {
+                expected: initial_bytes.0,
+                actual: len,
+            });
+        }
+        let memory = Self {
+            bytes: MemoryBackend::Custom(backend),
             memory_type,
             current_pages: initial_pages,
+            dirty: None,
         };
         Ok(memory)
     }
@@ -187,9 +333,14 @@ impl MemoryEntity {
     ///
     /// # Errors
     ///
-    /// If the linear memory would grow beyond its maximum limit after
-    /// the grow operation.
-    pub fn grow(&mut self, additional: Pages) -> Result<Pages, MemoryError> {
+    /// - If the linear memory would grow beyond its maximum limit after
+    ///   the grow operation.
+    /// - If the `limiter` denies the grow operation.
+    pub fn grow(
+        &mut self,
+        additional: Pages,
+        limiter: Option<&mut dyn ResourceLimiter>,
+    ) -> Result<Pages, MemoryError> {
         let current_pages = self.current_pages();
         if additional == Pages(0) {
             // Nothing to do in this case. Bail out early.
@@ -205,20 +356,159 @@ impl MemoryEntity {
             .filter(|&new_pages| new_pages <= maximum_pages.0)
             .map(Pages)
             .ok_or(MemoryError::OutOfBoundsGrowth)?;
+        if let Some(limiter) = limiter {
+            if !limiter.memory_growing(current_pages, new_pages, self.memory_type().maximum_pages())
+            {
+                return Err(MemoryError::ResourceLimitReached);
+            }
+        }
         // At this point it is okay to grow the underlying virtual memory
         // by the given amount of additional pages.
         self.bytes.grow(Bytes::from(additional).0)?;
         self.current_pages = new_pages;
+        if let Some(dirty) = &mut self.dirty {
+            dirty.resize(new_pages);
+        }
         Ok(current_pages)
     }
 
+    /// Resizes the linear memory to the given amount of pages.
+    ///
+    /// # Note
+    ///
+    /// Unlike [`MemoryEntity::grow`] this is also able to shrink the linear memory
+    /// and does not consult any resource limiter since it is used to restore
+    /// the linear memory to a previous state.
+    ///
+    /// # Errors
+    ///
+    /// If the linear memory cannot be resized to the given amount of pages.
+    pub(crate) fn resize(&mut self, new_pages: Pages) -> Result<(), MemoryError> {
+        let maximum_pages = self
+            .memory_type()
+            .maximum_pages()
+            .unwrap_or(Self::MAX_PAGES);
+        if new_pages > maximum_pages {
+            return Err(MemoryError::OutOfBoundsGrowth);
+        }
+        let current_len = self.bytes.len();
+        let new_len = Bytes::from(new_pages).0;
+        if new_len >= current_len {
+            self.bytes.grow(new_len - current_len)?;
+        } else {
+            self.bytes.shrink(new_len)?;
+        }
+        self.current_pages = new_pages;
+        if let Some(dirty) = &mut self.dirty {
+            dirty.resize(new_pages);
+        }
+        Ok(())
+    }
+
+    /// Enables dirty page tracking for the linear memory.
+    ///
+    /// # Note
+    ///
+    /// All pages are considered dirty right after enabling dirty page tracking.
+    /// Does nothing if dirty page tracking is already enabled.
+    pub(crate) fn track_dirty_pages(&mut self) {
+        if self.dirty.is_none() {
+            self.dirty = Some(DirtyPages::new(self.current_pages));
+        }
+    }
+
+    /// Returns the indices of the pages written since the last [`MemoryEntity::clear_dirty`].
+    ///
+    /// # Note
+    ///
+    /// Returns all pages if dirty page tracking is disabled.
+    pub fn dirty_pages(&self) -> Vec<usize> {
+        match &self.dirty {
+            Some(dirty) => dirty.pages().collect(),
+            None => (0..self.current_pages.0).collect(),
+        }
+    }
+
+    /// Marks all pages of the linear memory clean.
+    ///
+    /// # Note
+    ///
+    /// Does nothing if dirty page tracking is disabled.
+    pub fn clear_dirty(&mut self) {
+        if let Some(dirty) = &mut self.dirty {
+            dirty.clear();
+        }
+    }
+
+    /// Returns `true` if dirty page tracking is enabled for the linear memory.
+    pub(crate) fn is_tracking_dirty(&self) -> bool {
+        self.dirty.is_some()
+    }
+
     /// Returns a shared slice to the bytes underlying to the byte buffer.
     pub fn data(&self) -> &[u8] {
         self.bytes.data()
     }
 
+    /// Returns `true` if the linear memory uses a user provided [`LinearMemory`] backend.
+    pub(crate) fn has_custom_backend(&self) -> bool {
+        self.bytes.is_custom()
+    }
+
+    /// Returns a pointer to the bytes underlying to the byte buffer and their length.
+    ///
+    /// # Note
+    ///
+    /// - This is used by the `wasmi` interpreter to cache the bytes of
+    ///   the default linear memory for fast loads and stores.
+    /// - Unlike [`MemoryEntity::data_mut`] this does not mark any pages dirty.
+    /// - The pointer is invalidated by any later access to the bytes through
+    ///   [`MemoryEntity::data_mut`] or [`MemoryEntity::write`] as well as
+    ///   once the linear memory is resized.
+    ///
+    /// # Panics
+    ///
+    /// If the linear memory uses a custom [`LinearMemory`] backend since those
+    /// are free to move their bytes upon every access.
+    pub(crate) fn data_ptr(&mut self) -> (NonNull<u8>, usize) {
+        assert!(
+            !self.has_custom_backend(),
+            "cannot cache the bytes of a custom linear memory backend"
+        );
+        let data = self.bytes.data_mut();
+        (NonNull::from(&mut *data).cast(), data.len())
+    }
+
+    /// Enables eliding bounds checks via the guard pages of the linear memory if supported.
+    ///
+    /// # Note
+    ///
+    /// This installs the process wide trap handler upon first use.
+    pub(crate) fn enable_guard_pages(&mut self) {
+        self.bytes.enable_guard_pages()
+    }
+
+    /// Returns the start and length of the guard region of the linear memory if any.
+    ///
+    /// # Note
+    ///
+    /// Accesses to the bytes beyond the length of a guarded linear memory
+    /// that are within its guard region are turned into traps within
+    /// [`guard::catch_traps`] so that they do not need to be bounds checked.
+    pub(crate) fn guard_region(&mut self) -> Option<(*const u8, usize)> {
+        self.bytes.guard_region()
+    }
+
     /// Returns an exclusive slice to the bytes underlying to the byte buffer.
+    ///
+    /// # Note
+    ///
+    /// Marks all pages dirty if dirty page tracking is enabled
+    /// since the written bytes are unknown.
     pub fn data_mut(&mut self) -> &mut [u8] {
+        if let Some(dirty) = &mut self.dirty {
+            dirty.mark_all();
+        }
         self.bytes.data_mut()
     }
 
@@ -246,11 +536,16 @@ impl MemoryEntity {
     /// If this operation accesses out of bounds linear memory.
     pub fn write(&mut self, offset: usize, buffer: &[u8]) -> Result<(), MemoryError> {
         let len_buffer = buffer.len();
+        let range = offset..(offset + len_buffer);
         let slice = self
+            .bytes
             .data_mut()
-            .get_mut(offset..(offset + len_buffer))
+            .get_mut(range.clone())
             .ok_or(MemoryError::OutOfBoundsAccess)?;
         slice.copy_from_slice(buffer);
+        if let Some(dirty) = &mut self.dirty {
+            dirty.mark(range);
+        }
         Ok(())
     }
 }
@@ -272,9 +567,62 @@ impl Memory {
     }
 
     /// Creates a new linear memory to the store.
+    ///
+    /// # Note
+    ///
+    /// Uses the [`MemoryCreator`] of the store to create the backend
+    /// of the linear memory if any.
+    ///
+    /// # Errors
+    ///
+    /// - If the linear memory cannot be allocated.
+    /// - If the [`ResourceLimiter`] of the store denies creating the linear memory.
     pub fn new(mut ctx: impl AsContextMut, memory_type: MemoryType) -> Result<Self, MemoryError> {
-        let entity = MemoryEntity::new(memory_type)?;
-        let memory = ctx.as_context_mut().store.alloc_memory(entity);
+        let ctx = ctx.as_context_mut();
+        ctx.store.ensure_memory_permitted(memory_type)?;
+        let entity = match ctx.store.installed_memory_creator() {
+            Some(creator) => {
+                MemoryEntity::with_backend(memory_type, creator.new_memory(memory_type)?)?
+            }
+            None => MemoryEntity::new(memory_type)?,
+        };
+        let memory = ctx.store.alloc_memory(entity);
+        Ok(memory)
+    }
+
+    /// Creates a new linear memory to the store that stores its bytes in `backend`.
+    ///
+    /// # Errors
+    ///
+    /// - If the length of the `backend` does not match the initial pages of the `memory_type`.
+    /// - If the [`ResourceLimiter`] of the store denies creating the linear memory.
+    pub fn new_with_backend(
+        mut ctx: impl AsContextMut,
+        memory_type: MemoryType,
+        backend: impl LinearMemory + 'static,
+    ) -> Result<Self, MemoryError> {
+        let ctx = ctx.as_context_mut();
+        ctx.store.ensure_memory_permitted(memory_type)?;
+        let entity = MemoryEntity::with_backend(memory_type, Box::new(backend))?;
+        let memory = ctx.store.alloc_memory(entity);
+        Ok(memory)
+    }
+
+    /// Creates a new linear memory to the store that starts with the `image`.
+    ///
+    /// # Errors
+    ///
+    /// - If the linear memory cannot be allocated.
+    /// - If the [`ResourceLimiter`] of the store denies creating the linear memory.
+    pub(crate) fn with_image(
+        mut ctx: impl AsContextMut,
+        memory_type: MemoryType,
+        image: &MemoryImage,
+    ) -> Result<Self, MemoryError> {
+        let ctx = ctx.as_context_mut();
+        ctx.store.ensure_memory_permitted(memory_type)?;
+        let entity = MemoryEntity::with_image(memory_type, image)?;
+        let memory = ctx.store.alloc_memory(entity);
         Ok(memory)
     }
 
@@ -302,8 +650,9 @@ impl Memory {
     ///
     /// # Errors
     ///
-    /// If the linear memory would grow beyond its maximum limit after
-    /// the grow operation.
+    /// - If the linear memory would grow beyond its maximum limit after
+    ///   the grow operation.
+    /// - If the [`ResourceLimiter`] of the store denies the grow operation.
     ///
     /// # Panics
     ///
@@ -313,10 +662,13 @@ impl Memory {
         mut ctx: impl AsContextMut,
         additional: Pages,
     ) -> Result<Pages, MemoryError> {
-        ctx.as_context_mut()
-            .store
-            .resolve_memory_mut(*self)
-            .grow(additional)
+        let store = &mut ctx.as_context_mut().store;
+        let additional_bytes = additional.0.saturating_mul(Bytes::from(Pages(1)).0);
+        if !store.bytes_permitted(additional_bytes) {
+            return Err(MemoryError::ResourceLimitReached);
+        }
+        let (entity, limiter) = store.resolve_memory_and_limiter_mut(*self);
+        entity.grow(additional, limiter)
     }
 
     /// Returns a shared slice to the bytes underlying to the byte buffer.
@@ -330,6 +682,11 @@ impl Memory {
 
     /// Returns an exclusive slice to the bytes underlying to the byte buffer.
     ///
+    /// # Note
+    ///
+    /// Marks all pages dirty if dirty page tracking is enabled.
+    /// Use [`Memory::write`] in order to only mark the written pages dirty.
+    ///
     /// # Panics
     ///
     /// Panics if `ctx` does not own this [`Memory`].
@@ -337,6 +694,25 @@ impl Memory {
         ctx.into().store.resolve_memory_mut(*self).data_mut()
     }
 
+    /// Returns an exclusive slice to the bytes underlying to the byte buffer
+    /// and an exclusive reference to the user provided state of the store.
+    ///
+    /// # Note
+    ///
+    /// This allows host functions to operate on the linear memory
+    /// and their host state at the same time without copying.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own this [`Memory`].
+    pub fn data_and_state_mut<'a, T: 'a>(
+        &self,
+        ctx: impl Into<StoreContextMut<'a, T>>,
+    ) -> (&'a mut [u8], &'a mut T) {
+        let (memory, state) = ctx.into().store.resolve_memory_and_state_mut(*self);
+        (memory.data_mut(), state)
+    }
+
     /// Reads `n` bytes from `memory[offset..offset+n]` into `buffer`
     /// where `n` is the length of `buffer`.
     ///
@@ -380,4 +756,42 @@ impl Memory {
             .resolve_memory_mut(*self)
             .write(offset, buffer)
     }
+
+    /// Returns the indices of the pages written since the last [`Memory::clear_dirty`].
+    ///
+    /// # Note
+    ///
+    /// - Dirty page tracking is enabled via [`Config::enable_dirty_page_tracking`].
+    ///   Returns all pages if dirty page tracking is disabled.
+    /// - Pages are tracked at the granularity of Wasm pages of 64 KiB.
+    /// - Pages are marked dirty by Wasm store instructions, [`Memory::write`],
+    ///   growing the linear memory or restoring a [`Snapshot`].
+    ///   Any access via [`Memory::data_mut`] marks all pages dirty.
+    /// - All pages of a newly created linear memory are dirty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own this [`Memory`].
+    ///
+    /// [`Config::enable_dirty_page_tracking`]: crate::Config::enable_dirty_page_tracking
+    /// [`Snapshot`]: crate::Snapshot
+    pub fn dirty_pages(&self, ctx: impl AsContext) -> Vec<usize> {
+        ctx.as_context().store.resolve_memory(*self).dirty_pages()
+    }
+
+    /// Marks all pages of the linear memory clean.
+    ///
+    /// # Note
+    ///
+    /// Does nothing if dirty page tracking is disabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own this [`Memory`].
+    pub fn clear_dirty(&self, mut ctx: impl AsContextMut) {
+        ctx.as_context_mut()
+            .store
+            .resolve_memory_mut(*self)
+            .clear_dirty()
+    }
 }
@@ -0,0 +1,672 @@
+use super::{Memory, MemoryError};
+use crate::{
+    core::{FromValue, Value, ValueType, F32, F64},
+    func::WasmType,
+    AsContext,
+    AsContextMut,
+};
+use alloc::{string::String, vec::Vec};
+use core::{
+    fmt,
+    fmt::Debug,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+use wasmi_core::{LittleEndianConvert, UntypedValue};
+
+/// Types that have a defined little-endian representation in linear memory.
+///
+/// # Note
+///
+/// The layout of implementing types follows the C ABI of `wasm32` targets.
+/// Use the [`memory_repr!`] macro to implement this trait for `#[repr(C)]` structs.
+///
+/// [`memory_repr!`]: crate::memory_repr
+pub trait MemoryRepr: Sized {
+    /// The size of the type in linear memory in bytes.
+    const SIZE: usize;
+    /// The alignment of the type in linear memory in bytes.
+    const ALIGN: usize;
+
+    /// Decodes `Self` from its little-endian representation.
+    ///
+    /// # Note
+    ///
+    /// The length of `bytes` is guaranteed to be equal to [`MemoryRepr::SIZE`].
+    fn read_from(bytes: &[u8]) -> Self;
+
+    /// Encodes `self` as its little-endian representation into `bytes`.
+    ///
+    /// # Note
+    ///
+    /// The length of `bytes` is guaranteed to be equal to [`MemoryRepr::SIZE`].
+    fn write_to(&self, bytes: &mut [u8]);
+}
+
+macro_rules! impl_memory_repr_primitive {
+    ( $($primitive:ty),* $(,)? ) => {
+        $(
+            impl MemoryRepr for $primitive {
+                const SIZE: usize = ::core::mem::size_of::<$primitive>();
+                const ALIGN: usize = ::core::mem::size_of::<$primitive>();
+
+                #[inline]
+                fn read_from(bytes: &[u8]) -> Self {
+                    let mut buffer = <$primitive as LittleEndianConvert>::Bytes::default();
+                    buffer.copy_from_slice(bytes);
+                    <$primitive as LittleEndianConvert>::from_le_bytes(buffer)
+                }
+
+                #[inline]
+                fn write_to(&self, bytes: &mut [u8]) {
+                    bytes.copy_from_slice(
+                        <$primitive as LittleEndianConvert>::into_le_bytes(*self).as_ref()
+                    )
+                }
+            }
+        )*
+    };
+}
+impl_memory_repr_primitive!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64, F32, F64);
+
+impl<T, const N: usize> MemoryRepr for [T; N]
+where
+    T: MemoryRepr,
+{
+    const SIZE: usize = T::SIZE * N;
+    const ALIGN: usize = T::ALIGN;
+
+    fn read_from(bytes: &[u8]) -> Self {
+        let mut index = 0;
+        [(); N].map(|_| {
+            let value = T::read_from(element_bytes::<T>(bytes, index));
+            index += 1;
+            value
+        })
+    }
+
+    fn write_to(&self, bytes: &mut [u8]) {
+        for (index, value) in self.iter().enumerate() {
+            value.write_to(element_bytes_mut::<T>(bytes, index));
+        }
+    }
+}
+
+/// Returns the bytes of the element at `index` of a sequence of `T` elements.
+///
+/// # Note
+///
+/// Unlike [`slice::chunks_exact`] this also supports zero sized elements.
+///
+/// # Panics
+///
+/// If the element is out of bounds of `bytes`.
+fn element_bytes<T>(bytes: &[u8], index: usize) -> &[u8]
+where
+    T: MemoryRepr,
+{
+    &bytes[index * T::SIZE..][..T::SIZE]
+}
+
+/// Returns the exclusive bytes of the element at `index` of a sequence of `T` elements.
+///
+/// # Note
+///
+/// Unlike [`slice::chunks_exact_mut`] this also supports zero sized elements.
+///
+/// # Panics
+///
+/// If the element is out of bounds of `bytes`.
+fn element_bytes_mut<T>(bytes: &mut [u8], index: usize) -> &mut [u8]
+where
+    T: MemoryRepr,
+{
+    &mut bytes[index * T::SIZE..][..T::SIZE]
+}
+
+/// Rounds `offset` up to the next multiple of `align`.
+///
+/// # Note
+///
+/// Used by the [`memory_repr!`] macro to compute `#[repr(C)]` field offsets.
+///
+/// [`memory_repr!`]: crate::memory_repr
+#[doc(hidden)]
+pub const fn align_to(offset: usize, align: usize) -> usize {
+    offset.div_ceil(align) * align
+}
+
+/// Defines a `#[repr(C)]` struct and implements [`MemoryRepr`] for it.
+///
+/// The fields are laid out in declaration order with their natural alignment
+/// as defined by the C ABI of `wasm32` targets. All field types must implement
+/// [`MemoryRepr`] themselves.
+///
+/// # Example
+///
+/// ```
+/// wasmi_v1::memory_repr! {
+///     #[derive(Debug, Copy, Clone, PartialEq)]
+///     pub struct Point {
+///         pub x: i32,
+///         pub y: i64,
+///     }
+/// }
+/// # use wasmi_v1::MemoryRepr as _;
+/// assert_eq!(<Point as wasmi_v1::MemoryRepr>::SIZE, 16);
+/// ```
+#[macro_export]
+macro_rules! memory_repr {
+    (
+        $( #[$attr:meta] )*
+        $vis:vis struct $name:ident {
+            $(
+                $( #[$field_attr:meta] )*
+                $field_vis:vis $field:ident : $field_ty:ty
+            ),* $(,)?
+        }
+    ) => {
+        $( #[$attr] )*
+        #[repr(C)]
+        $vis struct $name {
+            $(
+                $( #[$field_attr] )*
+                $field_vis $field : $field_ty
+            ),*
+        }
+
+        impl $crate::MemoryRepr for $name {
+            const SIZE: usize = {
+                #[allow(unused_mut)]
+                let mut offset = 0;
+                $(
+                    offset = $crate::memory_align_to(
+                        offset,
+                        <$field_ty as $crate::MemoryRepr>::ALIGN,
+                    ) + <$field_ty as $crate::MemoryRepr>::SIZE;
+                )*
+                $crate::memory_align_to(offset, <Self as $crate::MemoryRepr>::ALIGN)
+            };
+            const ALIGN: usize = {
+                #[allow(unused_mut)]
+                let mut align = 1;
+                $(
+                    if <$field_ty as $crate::MemoryRepr>::ALIGN > align {
+                        align = <$field_ty as $crate::MemoryRepr>::ALIGN;
+                    }
+                )*
+                align
+            };
+
+            #[allow(unused_assignments, unused_variables, unused_mut)]
+            fn read_from(bytes: &[u8]) -> Self {
+                let mut offset = 0;
+                $(
+                    offset = $crate::memory_align_to(
+                        offset,
+                        <$field_ty as $crate::MemoryRepr>::ALIGN,
+                    );
+                    let $field = <$field_ty as $crate::MemoryRepr>::read_from(
+                        &bytes[offset..offset + <$field_ty as $crate::MemoryRepr>::SIZE],
+                    );
+                    offset += <$field_ty as $crate::MemoryRepr>::SIZE;
+                )*
+                Self { $( $field ),* }
+            }
+
+            #[allow(unused_assignments, unused_variables, unused_mut)]
+            fn write_to(&self, bytes: &mut [u8]) {
+                let mut offset = 0;
+                $(
+                    offset = $crate::memory_align_to(
+                        offset,
+                        <$field_ty as $crate::MemoryRepr>::ALIGN,
+                    );
+                    <$field_ty as $crate::MemoryRepr>::write_to(
+                        &self.$field,
+                        &mut bytes[offset..offset + <$field_ty as $crate::MemoryRepr>::SIZE],
+                    );
+                    offset += <$field_ty as $crate::MemoryRepr>::SIZE;
+                )*
+            }
+        }
+    };
+}
+
+/// Returns the byte range of `len` elements of type `T` starting at `offset`.
+///
+/// # Errors
+///
+/// If the end of the byte range overflows.
+fn byte_range<T>(offset: u32, len: u32) -> Result<core::ops::Range<usize>, MemoryError>
+where
+    T: MemoryRepr,
+{
+    let start = offset as usize;
+    let end = (len as usize)
+        .checked_mul(T::SIZE)
+        .and_then(|len_bytes| start.checked_add(len_bytes))
+        .ok_or(MemoryError::OutOfBoundsAccess)?;
+    Ok(start..end)
+}
+
+/// A typed pointer into the linear memory of a Wasm instance.
+///
+/// # Note
+///
+/// A [`WasmPtr`] is represented as a 32-bit offset into linear memory
+/// and can be used directly as parameter or result of host functions.
+/// All accesses through a [`WasmPtr`] are bounds checked.
+#[repr(transparent)]
+pub struct WasmPtr<T> {
+    offset: u32,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Debug for WasmPtr<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("WasmPtr").field(&self.offset).finish()
+    }
+}
+
+impl<T> Clone for WasmPtr<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for WasmPtr<T> {}
+
+impl<T> PartialEq for WasmPtr<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.offset == other.offset
+    }
+}
+
+impl<T> Eq for WasmPtr<T> {}
+
+impl<T> Hash for WasmPtr<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.offset.hash(state)
+    }
+}
+
+impl<T> WasmPtr<T> {
+    /// Creates a new [`WasmPtr`] pointing to the given `offset` in linear memory.
+    pub const fn new(offset: u32) -> Self {
+        Self {
+            offset,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the offset of the [`WasmPtr`] into linear memory.
+    pub const fn offset(self) -> u32 {
+        self.offset
+    }
+
+    /// Returns `true` if the [`WasmPtr`] points to offset `0`.
+    pub const fn is_null(self) -> bool {
+        self.offset == 0
+    }
+
+    /// Casts the [`WasmPtr`] to a pointer of another type at the same offset.
+    pub const fn cast<U>(self) -> WasmPtr<U> {
+        WasmPtr::new(self.offset)
+    }
+}
+
+impl<T> WasmPtr<T>
+where
+    T: MemoryRepr,
+{
+    /// Returns a [`WasmPtr`] advanced by `count` elements of type `T`.
+    ///
+    /// Returns `None` if the resulting offset overflows the 32-bit address space.
+    pub fn checked_add(self, count: u32) -> Option<Self> {
+        u32::try_from(T::SIZE)
+            .ok()
+            .and_then(|size| size.checked_mul(count))
+            .and_then(|delta| self.offset.checked_add(delta))
+            .map(Self::new)
+    }
+
+    /// Returns a [`WasmSlice`] of `len` elements starting at the [`WasmPtr`].
+    pub const fn slice(self, len: u32) -> WasmSlice<T> {
+        WasmSlice::new(self.offset, len)
+    }
+
+    /// Reads the value of type `T` pointed to from the linear `memory`.
+    ///
+    /// # Errors
+    ///
+    /// If the value is out of bounds of the linear `memory`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own the `memory`.
+    pub fn read(self, memory: Memory, ctx: impl AsContext) -> Result<T, MemoryError> {
+        self.slice(1).read_at(memory, ctx, 0)
+    }
+
+    /// Writes `value` to the location pointed to in the linear `memory`.
+    ///
+    /// # Errors
+    ///
+    /// If the value is out of bounds of the linear `memory`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own the `memory`.
+    pub fn write(
+        self,
+        memory: Memory,
+        ctx: impl AsContextMut,
+        value: T,
+    ) -> Result<(), MemoryError> {
+        self.slice(1).write_at(memory, ctx, 0, value)
+    }
+
+    /// Reads `len` consecutive values of type `T` starting at the [`WasmPtr`].
+    ///
+    /// # Errors
+    ///
+    /// If any of the values are out of bounds of the linear `memory`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own the `memory`.
+    pub fn read_array(
+        self,
+        memory: Memory,
+        ctx: impl AsContext,
+        len: u32,
+    ) -> Result<Vec<T>, MemoryError> {
+        self.slice(len).read(memory, ctx)
+    }
+}
+
+impl WasmPtr<u8> {
+    /// Reads a UTF-8 encoded string of `len` bytes starting at the [`WasmPtr`].
+    ///
+    /// # Errors
+    ///
+    /// - If the string is out of bounds of the linear `memory`.
+    /// - If the bytes do not represent valid UTF-8.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own the `memory`.
+    pub fn read_utf8_string(
+        self,
+        memory: Memory,
+        ctx: impl AsContext,
+        len: u32,
+    ) -> Result<String, MemoryError> {
+        self.slice(len).read_utf8_string(memory, ctx)
+    }
+}
+
+impl<T> MemoryRepr for WasmPtr<T> {
+    const SIZE: usize = 4;
+    const ALIGN: usize = 4;
+
+    fn read_from(bytes: &[u8]) -> Self {
+        Self::new(<u32 as MemoryRepr>::read_from(bytes))
+    }
+
+    fn write_to(&self, bytes: &mut [u8]) {
+        self.offset.write_to(bytes)
+    }
+}
+
+impl<T> FromValue for WasmPtr<T> {
+    fn from_value(value: Value) -> Option<Self> {
+        <u32 as FromValue>::from_value(value).map(Self::new)
+    }
+}
+
+impl<T> From<WasmPtr<T>> for Value {
+    fn from(ptr: WasmPtr<T>) -> Self {
+        Value::from(ptr.offset)
+    }
+}
+
+impl<T> From<UntypedValue> for WasmPtr<T> {
+    fn from(value: UntypedValue) -> Self {
+        Self::new(u32::from(value))
+    }
+}
+
+impl<T> From<WasmPtr<T>> for UntypedValue {
+    fn from(ptr: WasmPtr<T>) -> Self {
+        UntypedValue::from(ptr.offset)
+    }
+}
+
+impl<T> WasmType for WasmPtr<T> {
+    fn value_type() -> ValueType {
+        ValueType::I32
+    }
+}
+
+/// A typed slice of consecutive elements in the linear memory of a Wasm instance.
+///
+/// # Note
+///
+/// All accesses through a [`WasmSlice`] are bounds checked.
+pub struct WasmSlice<T> {
+    offset: u32,
+    len: u32,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Debug for WasmSlice<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WasmSlice")
+            .field("offset", &self.offset)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl<T> Clone for WasmSlice<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for WasmSlice<T> {}
+
+impl<T> PartialEq for WasmSlice<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.offset == other.offset && self.len == other.len
+    }
+}
+
+impl<T> Eq for WasmSlice<T> {}
+
+impl<T> Hash for WasmSlice<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.offset.hash(state);
+        self.len.hash(state);
+    }
+}
+
+impl<T> WasmSlice<T> {
+    /// Creates a new [`WasmSlice`] of `len` elements starting at `offset`.
+    pub const fn new(offset: u32, len: u32) -> Self {
+        Self {
+            offset,
+            len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the offset of the first element of the [`WasmSlice`].
+    pub const fn offset(self) -> u32 {
+        self.offset
+    }
+
+    /// Returns the number of elements of the [`WasmSlice`].
+    pub const fn len(self) -> u32 {
+        self.len
+    }
+
+    /// Returns `true` if the [`WasmSlice`] has no elements.
+    pub const fn is_empty(self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a [`WasmPtr`] to the first element of the [`WasmSlice`].
+    pub const fn as_ptr(self) -> WasmPtr<T> {
+        WasmPtr::new(self.offset)
+    }
+}
+
+impl<T> WasmSlice<T>
+where
+    T: MemoryRepr,
+{
+    /// Returns a [`WasmPtr`] to the element at `index` if it is within the [`WasmSlice`].
+    pub fn get(self, index: u32) -> Option<WasmPtr<T>> {
+        if index >= self.len {
+            return None;
+        }
+        self.as_ptr().checked_add(index)
+    }
+
+    /// Reads all elements of the [`WasmSlice`] from the linear `memory`.
+    ///
+    /// # Errors
+    ///
+    /// If the [`WasmSlice`] is out of bounds of the linear `memory`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own the `memory`.
+    pub fn read(self, memory: Memory, ctx: impl AsContext) -> Result<Vec<T>, MemoryError> {
+        let bytes = self.bytes(memory, &ctx)?;
+        Ok((0..self.len as usize)
+            .map(|index| T::read_from(element_bytes::<T>(bytes, index)))
+            .collect())
+    }
+
+    /// Reads the element at `index` of the [`WasmSlice`] from the linear `memory`.
+    ///
+    /// # Errors
+    ///
+    /// - If `index` is out of bounds of the [`WasmSlice`].
+    /// - If the element is out of bounds of the linear `memory`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own the `memory`.
+    pub fn read_at(
+        self,
+        memory: Memory,
+        ctx: impl AsContext,
+        index: u32,
+    ) -> Result<T, MemoryError> {
+        let ptr = self.get(index).ok_or(MemoryError::OutOfBoundsAccess)?;
+        let bytes = ptr.slice(1).bytes(memory, &ctx)?;
+        Ok(T::read_from(bytes))
+    }
+
+    /// Writes `value` to the element at `index` of the [`WasmSlice`] in the linear `memory`.
+    ///
+    /// # Errors
+    ///
+    /// - If `index` is out of bounds of the [`WasmSlice`].
+    /// - If the element is out of bounds of the linear `memory`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own the `memory`.
+    pub fn write_at(
+        self,
+        memory: Memory,
+        mut ctx: impl AsContextMut,
+        index: u32,
+        value: T,
+    ) -> Result<(), MemoryError> {
+        let ptr = self.get(index).ok_or(MemoryError::OutOfBoundsAccess)?;
+        let bytes = ptr.slice(1).bytes_mut(memory, &mut ctx)?;
+        value.write_to(bytes);
+        Ok(())
+    }
+
+    /// Writes all `values` to the [`WasmSlice`] in the linear `memory`.
+    ///
+    /// # Errors
+    ///
+    /// - If the number of `values` does not match the length of the [`WasmSlice`].
+    /// - If the [`WasmSlice`] is out of bounds of the linear `memory`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own the `memory`.
+    pub fn write(
+        self,
+        memory: Memory,
+        mut ctx: impl AsContextMut,
+        values: &[T],
+    ) -> Result<(), MemoryError> {
+        if values.len() != self.len as usize {
+            return Err(MemoryError::LengthMismatch {
+                expected: self.len as usize,
+                actual: values.len(),
+            });
+        }
+        let bytes = self.bytes_mut(memory, &mut ctx)?;
+        for (index, value) in values.iter().enumerate() {
+            value.write_to(element_bytes_mut::<T>(bytes, index));
+        }
+        Ok(())
+    }
+
+    /// Returns the bytes of the [`WasmSlice`] in the linear `memory`.
+    fn bytes(self, memory: Memory, ctx: &impl AsContext) -> Result<&[u8], MemoryError> {
+        let range = byte_range::<T>(self.offset, self.len)?;
+        memory
+            .data(ctx.as_context())
+            .get(range)
+            .ok_or(MemoryError::OutOfBoundsAccess)
+    }
+
+    /// Returns the exclusive bytes of the [`WasmSlice`] in the linear `memory`.
+    fn bytes_mut(
+        self,
+        memory: Memory,
+        ctx: &mut impl AsContextMut,
+    ) -> Result<&mut [u8], MemoryError> {
+        let range = byte_range::<T>(self.offset, self.len)?;
+        memory
+            .data_mut(ctx.as_context_mut())
+            .get_mut(range)
+            .ok_or(MemoryError::OutOfBoundsAccess)
+    }
+}
+
+impl WasmSlice<u8> {
+    /// Reads the bytes of the [`WasmSlice`] as UTF-8 encoded string.
+    ///
+    /// # Errors
+    ///
+    /// - If the [`WasmSlice`] is out of bounds of the linear `memory`.
+    /// - If the bytes do not represent valid UTF-8.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own the `memory`.
+    pub fn read_utf8_string(
+        self,
+        memory: Memory,
+        ctx: impl AsContext,
+    ) -> Result<String, MemoryError> {
+        let bytes = self.bytes(memory, &ctx)?;
+        core::str::from_utf8(bytes)
+            .map(String::from)
+            .map_err(MemoryError::InvalidUtf8)
+    }
+}
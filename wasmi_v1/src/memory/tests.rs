@@ -0,0 +1,181 @@
+use super::*;
+use crate::{Engine, Store};
+
+crate::memory_repr! {
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    struct Header {
+        tag: u8,
+        len: u32,
+        ptr: WasmPtr<u8>,
+        value: i64,
+    }
+}
+
+crate::memory_repr! {
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    struct Empty {}
+}
+
+/// Creates a new [`Store`] with a single linear memory of one page.
+fn setup_memory() -> (Store<()>, Memory) {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    let memory = Memory::new(&mut store, MemoryType::new(1, None)).unwrap();
+    (store, memory)
+}
+
+#[test]
+fn memory_repr_layout() {
+    assert_eq!(<Header as MemoryRepr>::ALIGN, 8);
+    assert_eq!(<Header as MemoryRepr>::SIZE, 24);
+    assert_eq!(<[u16; 3] as MemoryRepr>::SIZE, 6);
+    assert_eq!(<WasmPtr<Header> as MemoryRepr>::SIZE, 4);
+}
+
+#[test]
+fn ptr_read_write_works() {
+    let (mut store, memory) = setup_memory();
+    let ptr = <WasmPtr<u32>>::new(8);
+    ptr.write(memory, &mut store, 0xDEAD_BEEF).unwrap();
+    assert_eq!(ptr.read(memory, &store).unwrap(), 0xDEAD_BEEF);
+    assert_eq!(&memory.data(&store)[8..12], &[0xEF, 0xBE, 0xAD, 0xDE]);
+    let header = Header {
+        tag: 1,
+        len: 5,
+        ptr: WasmPtr::new(100),
+        value: -1,
+    };
+    let header_ptr = ptr.cast::<Header>().checked_add(1).unwrap();
+    assert_eq!(header_ptr.offset(), 8 + 24);
+    header_ptr.write(memory, &mut store, header).unwrap();
+    assert_eq!(header_ptr.read(memory, &store).unwrap(), header);
+}
+
+#[test]
+fn ptr_out_of_bounds_fails() {
+    let (mut store, memory) = setup_memory();
+    let last = <WasmPtr<u32>>::new(65536 - 4);
+    assert!(last.read(memory, &store).is_ok());
+    let ptr = <WasmPtr<u32>>::new(65536 - 3);
+    assert!(matches!(
+        ptr.read(memory, &store),
+        Err(MemoryError::OutOfBoundsAccess)
+    ));
+    assert!(matches!(
+        ptr.write(memory, &mut store, 1),
+        Err(MemoryError::OutOfBoundsAccess)
+    ));
+    let huge = <WasmPtr<u64>>::new(u32::MAX);
+    assert!(huge.checked_add(1).is_none());
+    assert!(matches!(
+        huge.read_array(memory, &store, u32::MAX),
+        Err(MemoryError::OutOfBoundsAccess)
+    ));
+}
+
+#[test]
+fn slice_read_write_works() {
+    let (mut store, memory) = setup_memory();
+    let slice = <WasmSlice<u16>>::new(16, 4);
+    slice.write(memory, &mut store, &[1, 2, 3, 4]).unwrap();
+    assert_eq!(slice.read(memory, &store).unwrap(), vec![1, 2, 3, 4]);
+    slice.write_at(memory, &mut store, 2, 42).unwrap();
+    assert_eq!(slice.read_at(memory, &store, 2).unwrap(), 42);
+    assert!(slice.read_at(memory, &store, 4).is_err());
+    assert!(matches!(
+        slice.write(memory, &mut store, &[1, 2]),
+        Err(MemoryError::LengthMismatch {
+            expected: 4,
+            actual: 2
+        })
+    ));
+    assert_eq!(
+        slice.as_ptr().read_array(memory, &store, 4).unwrap(),
+        vec![1, 2, 42, 4]
+    );
+}
+
+#[test]
+fn zero_sized_reprs_work() {
+    let (mut store, memory) = setup_memory();
+    assert_eq!(<Empty as MemoryRepr>::SIZE, 0);
+    assert_eq!(<[[u32; 0]; 2] as MemoryRepr>::SIZE, 0);
+    let arrays = <WasmSlice<[u32; 0]>>::new(0, 4);
+    assert_eq!(arrays.read(memory, &store).unwrap(), vec![[]; 4]);
+    arrays.write(memory, &mut store, &[[]; 4]).unwrap();
+    assert_eq!(arrays.read_at(memory, &store, 3).unwrap(), []);
+    let nested = <WasmPtr<[[u32; 0]; 2]>>::new(65536);
+    assert_eq!(nested.read(memory, &store).unwrap(), [[], []]);
+    let empty = <WasmSlice<Empty>>::new(16, 3);
+    empty.write(memory, &mut store, &[Empty {}; 3]).unwrap();
+    assert_eq!(empty.read(memory, &store).unwrap(), vec![Empty {}; 3]);
+    assert_eq!(empty.as_ptr().checked_add(2).unwrap().offset(), 16);
+    assert!(memory.data(&store).iter().all(|byte| *byte == 0));
+}
+
+#[test]
+fn read_utf8_string_works() {
+    let (mut store, memory) = setup_memory();
+    memory.write(&mut store, 10, "hello".as_bytes()).unwrap();
+    memory.write(&mut store, 20, &[0xFF, 0xFE]).unwrap();
+    let ptr = <WasmPtr<u8>>::new(10);
+    assert_eq!(ptr.read_utf8_string(memory, &store, 5).unwrap(), "hello");
+    assert!(matches!(
+        <WasmSlice<u8>>::new(20, 2).read_utf8_string(memory, &store),
+        Err(MemoryError::InvalidUtf8(_))
+    ));
+}
+
+#[test]
+fn data_and_state_mut_works() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, vec![1_u8, 2, 3]);
+    let memory = Memory::new(&mut store, MemoryType::new(1, None)).unwrap();
+    let (data, state) = memory.data_and_state_mut(&mut store);
+    data[..state.len()].copy_from_slice(state);
+    state.push(4);
+    assert_eq!(&memory.data(&store)[..4], &[1, 2, 3, 0]);
+    assert_eq!(store.state(), &vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn dirty_pages_bitmap_works() {
+    let mut dirty = DirtyPages::new(Pages(70));
+    assert_eq!(dirty.pages().count(), 70);
+    dirty.clear();
+    assert_eq!(dirty.pages().count(), 0);
+    // Writes spanning a page boundary mark both pages dirty.
+    dirty.mark(65535..65537);
+    dirty.mark(64 * 65536..64 * 65536 + 1);
+    assert_eq!(dirty.pages().collect::<Vec<_>>(), [0, 1, 64]);
+    // Shrinking drops dirty pages and growing marks the new pages dirty.
+    dirty.resize(Pages(2));
+    assert_eq!(dirty.pages().collect::<Vec<_>>(), [0, 1]);
+    dirty.clear();
+    dirty.resize(Pages(4));
+    assert_eq!(dirty.pages().collect::<Vec<_>>(), [2, 3]);
+}
+
+#[test]
+fn dirty_page_tracking_works() {
+    let engine = Engine::new(&crate::Config::default().enable_dirty_page_tracking(true));
+    let mut store = Store::new(&engine, ());
+    let memory = Memory::new(&mut store, MemoryType::new(2, None)).unwrap();
+    assert_eq!(memory.dirty_pages(&store), [0, 1]);
+    memory.clear_dirty(&mut store);
+    assert!(memory.dirty_pages(&store).is_empty());
+    memory.write(&mut store, 65536 + 10, &[1, 2, 3]).unwrap();
+    assert_eq!(memory.dirty_pages(&store), [1]);
+    memory.grow(&mut store, Pages(1)).unwrap();
+    assert_eq!(memory.dirty_pages(&store), [1, 2]);
+    memory.clear_dirty(&mut store);
+    memory.data_mut(&mut store);
+    assert_eq!(memory.dirty_pages(&store), [0, 1, 2]);
+}
+
+#[test]
+fn dirty_page_tracking_disabled_reports_all_pages() {
+    let (mut store, memory) = setup_memory();
+    memory.clear_dirty(&mut store);
+    assert_eq!(memory.dirty_pages(&store), [0]);
+}
@@ -52,33 +52,43 @@ pub struct ModuleImports {
 /// The resources of a [`Module`] required for translating function bodies.
 #[derive(Debug, Copy, Clone)]
 pub struct ModuleResources<'a> {
-    res: &'a ModuleBuilder<'a>,
+    func_types: &'a [DedupFuncType],
+    funcs: &'a [DedupFuncType],
+    globals: &'a [GlobalType],
 }
 
 impl<'a> ModuleResources<'a> {
-    /// Returns the [`Engine`] of the [`ModuleResources`].
-    pub fn engine(&'a self) -> &'a Engine {
-        self.res.engine
-    }
-
     /// Creates new [`ModuleResources`] from the given [`ModuleBuilder`].
     pub fn new(res: &'a ModuleBuilder) -> Self {
-        Self { res }
+        Self::from_parts(&res.func_types, &res.funcs, &res.globals)
+    }
+
+    /// Creates new [`ModuleResources`] from the function types, functions and global variables.
+    pub fn from_parts(
+        func_types: &'a [DedupFuncType],
+        funcs: &'a [DedupFuncType],
+        globals: &'a [GlobalType],
+    ) -> Self {
+        Self {
+            func_types,
+            funcs,
+            globals,
+        }
     }
 
     /// Returns the [`FuncType`] at the given index.
     pub fn get_func_type(&self, func_type_idx: FuncTypeIdx) -> DedupFuncType {
-        self.res.func_types[func_type_idx.into_usize()]
+        self.func_types[func_type_idx.into_usize()]
     }
 
     /// Returns the [`FuncType`] of the indexed function.
     pub fn get_type_of_func(&self, func_idx: FuncIdx) -> DedupFuncType {
-        self.res.funcs[func_idx.into_usize()]
+        self.funcs[func_idx.into_usize()]
     }
 
     /// Returns the [`GlobalType`] the the indexed global variable.
     pub fn get_type_of_global(&self, global_idx: GlobalIdx) -> GlobalType {
-        self.res.globals[global_idx.into_usize()]
+        self.globals[global_idx.into_usize()]
     }
 }
 
@@ -1,5 +1,9 @@
 use super::super::{utils::value_type_from_wasmparser, FuncTypeIdx, ModuleResources};
-use crate::{core::ValueType, engine::DedupFuncType, Engine, ModuleError};
+use crate::{
+    core::ValueType,
+    engine::{DedupFuncType, TranslationTarget},
+    ModuleError,
+};
 use core::slice;
 
 /// The type of a Wasm control flow block.
@@ -64,35 +68,42 @@ impl BlockType {
     }
 
     /// Returns the number of parameters of the [`BlockType`].
-    pub fn len_params(&self, engine: &Engine) -> u32 {
+    pub fn len_params<T>(&self, target: &T) -> u32
+    where
+        T: TranslationTarget,
+    {
         match &self.inner {
             BlockTypeInner::Empty | BlockTypeInner::Returns(_) => 0,
             BlockTypeInner::FuncType(func_type) => {
-                engine.resolve_func_type(*func_type, |func_type| func_type.params().len() as u32)
+                target.resolve_func_type(*func_type, |func_type| func_type.params().len() as u32)
             }
         }
     }
 
     /// Returns the number of results of the [`BlockType`].
-    pub fn len_results(&self, engine: &Engine) -> u32 {
+    pub fn len_results<T>(&self, target: &T) -> u32
+    where
+        T: TranslationTarget,
+    {
         match &self.inner {
             BlockTypeInner::Empty => 0,
             BlockTypeInner::Returns(_) => 1,
             BlockTypeInner::FuncType(func_type) => {
-                engine.resolve_func_type(*func_type, |func_type| func_type.results().len() as u32)
+                target.resolve_func_type(*func_type, |func_type| func_type.results().len() as u32)
             }
         }
     }
 
     /// Calls `f` for each block parameter type.
-    pub fn foreach_param<F>(&self, engine: &Engine, mut f: F)
+    pub fn foreach_param<T, F>(&self, target: &T, mut f: F)
     where
+        T: TranslationTarget,
         F: FnMut(ValueType),
     {
         match &self.inner {
             BlockTypeInner::Empty | BlockTypeInner::Returns(_) => (),
             BlockTypeInner::FuncType(func_type) => {
-                engine.resolve_func_type(*func_type, |func_type| {
+                target.resolve_func_type(*func_type, |func_type| {
                     for param in func_type.params() {
                         f(*param);
                     }
@@ -102,8 +113,9 @@ impl BlockType {
     }
 
     /// Calls `f` for each block result type.
-    pub fn foreach_result<F>(&self, engine: &Engine, mut f: F)
+    pub fn foreach_result<T, F>(&self, target: &T, mut f: F)
     where
+        T: TranslationTarget,
         F: FnMut(ValueType),
     {
         match &self.inner {
@@ -112,7 +124,7 @@ impl BlockType {
                 f(*result);
             }
             BlockTypeInner::FuncType(func_type) => {
-                engine.resolve_func_type(*func_type, |func_type| {
+                target.resolve_func_type(*func_type, |func_type| {
                     for result in func_type.results() {
                         f(*result);
                     }
@@ -1,20 +1,27 @@
-pub use self::block_type::BlockType;
+#[cfg(feature = "std")]
+pub use self::parallel::{translate_parallel, PendingFuncBody};
+pub use self::{
+    block_type::BlockType,
+    uncompiled::{UncompiledFuncBody, UncompiledResources},
+};
 use super::{utils::value_type_from_wasmparser, FuncIdx, ModuleResources};
 use crate::{
-    engine::{DropKeep, FuncBody, FunctionBuilder},
-    Engine,
+    engine::{FuncBody, FunctionBuilder, TranslationTarget},
     ModuleError,
 };
 use wasmparser::{FuncValidator, FunctionBody, Operator, ValidatorResources};
 
 mod block_type;
 mod operator;
+#[cfg(feature = "std")]
+mod parallel;
+mod uncompiled;
 
 /// Translates the Wasm bytecode into `wasmi` bytecode.
 ///
 /// # Note
 ///
-/// - Uses the given `engine` as target for the translation.
+/// - Uses the given `target` as target for the translation.
 /// - Uses the given `parser` and `validator` for parsing and validation of
 ///   the incoming Wasm bytecode stream.
 /// - Uses the given module resources `res` as shared immutable data of the
@@ -23,28 +30,55 @@ mod operator;
 /// # Errors
 ///
 /// If the function body fails to validate.
-pub fn translate<'parser>(
-    engine: &Engine,
+pub fn translate<'parser, T>(
+    target: T,
     func: FuncIdx,
     func_body: FunctionBody<'parser>,
     validator: FuncValidator<ValidatorResources>,
     res: ModuleResources<'parser>,
-) -> Result<FuncBody, ModuleError> {
-    FunctionTranslator::new(engine, func, func_body, validator, res).translate()
+) -> Result<FuncBody, ModuleError>
+where
+    T: TranslationTarget,
+{
+    FunctionTranslator::new(target, func, func_body, Some(validator), res).translate()
+}
+
+/// Translates the already validated Wasm bytecode into `wasmi` bytecode.
+///
+/// # Note
+///
+/// This is used for function bodies that have been validated ahead
+/// of time and whose translation has been deferred.
+///
+/// # Errors
+///
+/// If the function body fails to translate.
+pub fn translate_unchecked<'parser, T>(
+    target: T,
+    func: FuncIdx,
+    func_body: FunctionBody<'parser>,
+    res: ModuleResources<'parser>,
+) -> Result<FuncBody, ModuleError>
+where
+    T: TranslationTarget,
+{
+    FunctionTranslator::new(target, func, func_body, None, res).translate()
 }
 
 /// Translates Wasm bytecode into `wasmi` bytecode for a single Wasm function.
-struct FunctionTranslator<'engine, 'parser> {
-    /// The target `wasmi` engine for `wasmi` bytecode translation.
-    engine: &'engine Engine,
+struct FunctionTranslator<'parser, T> {
     /// The index of the translated function.
     func: FuncIdx,
     /// The function body that shall be translated.
     func_body: FunctionBody<'parser>,
     /// The interface to incrementally build up the `wasmi` bytecode function.
-    func_builder: FunctionBuilder<'engine, 'parser>,
+    func_builder: FunctionBuilder<'parser, T>,
     /// The Wasm validator.
-    validator: FuncValidator<ValidatorResources>,
+    ///
+    /// # Note
+    ///
+    /// This is `None` if the function body has already been validated.
+    validator: Option<FuncValidator<ValidatorResources>>,
     /// The `wasmi` module resources.
     ///
     /// Provides immutable information about the translated Wasm module
@@ -52,18 +86,20 @@ struct FunctionTranslator<'engine, 'parser> {
     res: ModuleResources<'parser>,
 }
 
-impl<'engine, 'parser> FunctionTranslator<'engine, 'parser> {
+impl<'parser, T> FunctionTranslator<'parser, T>
+where
+    T: TranslationTarget,
+{
     /// Creates a new Wasm to `wasmi` bytecode function translator.
     fn new(
-        engine: &'engine Engine,
+        target: T,
         func: FuncIdx,
         func_body: FunctionBody<'parser>,
-        validator: FuncValidator<ValidatorResources>,
+        validator: Option<FuncValidator<ValidatorResources>>,
         res: ModuleResources<'parser>,
     ) -> Self {
-        let func_builder = FunctionBuilder::new(engine, func, res);
+        let func_builder = FunctionBuilder::new(target, func, res);
         Self {
-            engine,
             func,
             func_body,
             func_builder,
@@ -92,7 +128,9 @@ impl<'engine, 'parser> FunctionTranslator<'engine, 'parser> {
         for _ in 0..len_locals {
             let offset = reader.original_position();
             let (amount, value_type) = reader.read()?;
-            self.validator.define_locals(offset, amount, value_type)?;
+            if let Some(validator) = &mut self.validator {
+                validator.define_locals(offset, amount, value_type)?;
+            }
             let value_type = value_type_from_wasmparser(&value_type)?;
             self.func_builder.translate_locals(amount, value_type)?;
         }
@@ -104,11 +142,15 @@ impl<'engine, 'parser> FunctionTranslator<'engine, 'parser> {
         let mut reader = self.func_body.get_operators_reader()?;
         while !reader.eof() {
             let (operator, offset) = reader.read_with_offset()?;
-            self.validator.op(offset, &operator)?;
+            if let Some(validator) = &mut self.validator {
+                validator.op(offset, &operator)?;
+            }
             self.translate_operator(operator)?;
         }
         reader.ensure_end()?;
-        self.validator.finish(reader.original_position())?;
+        if let Some(validator) = &mut self.validator {
+            validator.finish(reader.original_position())?;
+        }
         Ok(())
     }
 
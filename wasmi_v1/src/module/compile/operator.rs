@@ -1,12 +1,15 @@
 use super::{BlockType, FunctionTranslator};
 use crate::{
-    engine::RelativeDepth,
+    engine::{RelativeDepth, TranslationTarget},
     module::{export::TableIdx, import::FuncTypeIdx, FuncIdx, GlobalIdx, MemoryIdx},
     ModuleError,
 };
 use wasmparser::{Ieee32, Ieee64, TypeOrFuncType};
 
-impl<'engine, 'parser> FunctionTranslator<'engine, 'parser> {
+impl<'parser, T> FunctionTranslator<'parser, T>
+where
+    T: TranslationTarget,
+{
     /// Translate a Wasm `nop` (no operation) instruction.
     pub fn translate_nop(&mut self) -> Result<(), ModuleError> {
         // We can simply ignore Wasm `nop` instructions.
@@ -448,7 +451,10 @@ macro_rules! define_translate_fn {
     };
 }
 
-impl<'engine, 'parser> FunctionTranslator<'engine, 'parser> {
+impl<'parser, T> FunctionTranslator<'parser, T>
+where
+    T: TranslationTarget,
+{
     define_translate_fn! {
         /// Translate a Wasm `unreachable` instruction.
         fn translate_unreachable();
@@ -0,0 +1,59 @@
+use super::UncompiledFuncBody;
+use crate::{engine::FuncBody, Engine, ModuleError};
+use std::{num::NonZeroUsize, panic, thread, vec::Vec};
+use wasmparser::{FuncValidator, ValidatorResources};
+
+/// A function body awaiting its validation and translation.
+pub type PendingFuncBody = (UncompiledFuncBody, FuncValidator<ValidatorResources>);
+
+/// Validates and translates the `func_bodies` on multiple threads.
+///
+/// Returns the [`FuncBody`] references in the order of the `func_bodies`.
+///
+/// # Note
+///
+/// - The `func_bodies` are split into contiguous chunks, one per available thread.
+/// - Each thread translates its chunk into its own fork of the `engine` and
+///   all translated function bodies are committed to the `engine` in a single batch.
+///
+/// # Errors
+///
+/// If a function body fails to validate.
+/// Reports the error of the first invalid function body.
+pub fn translate_parallel(
+    engine: &Engine,
+    func_bodies: Vec<PendingFuncBody>,
+) -> Result<Vec<FuncBody>, ModuleError> {
+    let len_threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let chunk_size = func_bodies.len().div_ceil(len_threads).max(1);
+    let mut func_bodies = func_bodies.into_iter();
+    let chunks = core::iter::from_fn(|| {
+        let chunk = func_bodies.by_ref().take(chunk_size).collect::<Vec<_>>();
+        (!chunk.is_empty()).then_some(chunk)
+    });
+    let forks = thread::scope(|scope| {
+        let workers = chunks
+            .map(|chunk| {
+                let fork = engine.fork();
+                scope.spawn(move || {
+                    let func_bodies = chunk
+                        .into_iter()
+                        .map(|(func_body, validator)| {
+                            func_body.validate_and_translate(&fork, validator)
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Ok((fork, func_bodies))
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|payload| panic::resume_unwind(payload))
+            })
+            .collect::<Result<Vec<_>, ModuleError>>()
+    })?;
+    Ok(engine.commit_func_bodies(forks))
+}
@@ -0,0 +1,118 @@
+use super::{translate, translate_unchecked, FuncIdx, ModuleResources};
+use crate::{
+    engine::{DedupFuncType, FuncBody, TranslationTarget},
+    GlobalType,
+    ModuleError,
+};
+use alloc::sync::Arc;
+use wasmparser::{FuncValidator, FunctionBody, ValidatorResources};
+
+/// The resources of a Wasm module shared by all of its uncompiled function bodies.
+///
+/// # Note
+///
+/// These are the parts of the [`ModuleResources`] required to translate
+/// a function body after the module has been fully parsed.
+#[derive(Debug)]
+pub struct UncompiledResources {
+    func_types: Box<[DedupFuncType]>,
+    funcs: Box<[DedupFuncType]>,
+    globals: Box<[GlobalType]>,
+}
+
+impl UncompiledResources {
+    /// Creates new [`UncompiledResources`] from the function types, functions and global variables.
+    pub fn new(
+        func_types: &[DedupFuncType],
+        funcs: &[DedupFuncType],
+        globals: &[GlobalType],
+    ) -> Self {
+        Self {
+            func_types: func_types.into(),
+            funcs: funcs.into(),
+            globals: globals.into(),
+        }
+    }
+}
+
+/// A validated Wasm function body whose translation is deferred until its first call.
+#[derive(Debug)]
+pub struct UncompiledFuncBody {
+    /// The index of the function within its Wasm module.
+    func: FuncIdx,
+    /// The offset of the function body within the Wasm binary.
+    offset: usize,
+    /// The Wasm bytecode of the function body including its local variables.
+    bytes: Box<[u8]>,
+    /// The resources of the Wasm module required for the translation.
+    res: Arc<UncompiledResources>,
+}
+
+impl UncompiledFuncBody {
+    /// Creates a new [`UncompiledFuncBody`] for the `func_body` of `func`.
+    ///
+    /// # Note
+    ///
+    /// The `func_body` must have been validated already.
+    ///
+    /// # Errors
+    ///
+    /// If the Wasm bytecode of the `func_body` cannot be read.
+    pub fn new(
+        func: FuncIdx,
+        func_body: &FunctionBody,
+        res: Arc<UncompiledResources>,
+    ) -> Result<Self, ModuleError> {
+        let mut reader = func_body.get_binary_reader();
+        let offset = reader.original_position();
+        let bytes = reader.read_bytes(reader.bytes_remaining())?.into();
+        Ok(Self {
+            func,
+            offset,
+            bytes,
+            res,
+        })
+    }
+
+    /// Returns the [`ModuleResources`] required for the translation.
+    fn resources(&self) -> ModuleResources<'_> {
+        ModuleResources::from_parts(&self.res.func_types, &self.res.funcs, &self.res.globals)
+    }
+
+    /// Validates and translates the [`UncompiledFuncBody`] into `wasmi` bytecode of the `target`.
+    ///
+    /// # Errors
+    ///
+    /// If the function body fails to validate.
+    pub fn validate_and_translate<T>(
+        &self,
+        target: T,
+        validator: FuncValidator<ValidatorResources>,
+    ) -> Result<FuncBody, ModuleError>
+    where
+        T: TranslationTarget,
+    {
+        let func_body = FunctionBody::new(self.offset, &self.bytes);
+        translate(target, self.func, func_body, validator, self.resources())
+    }
+
+    /// Translates the [`UncompiledFuncBody`] into `wasmi` bytecode of the `target`.
+    ///
+    /// # Panics
+    ///
+    /// If the translation fails which is not expected to happen
+    /// since the function body has been validated already.
+    pub fn translate<T>(&self, target: T) -> FuncBody
+    where
+        T: TranslationTarget,
+    {
+        let func_body = FunctionBody::new(self.offset, &self.bytes);
+        let res = self.resources();
+        translate_unchecked(target, self.func, func_body, res).unwrap_or_else(|error| {
+            panic!(
+                "failed to translate validated function body of {:?}: {}",
+                self.func, error
+            )
+        })
+    }
+}
@@ -39,6 +39,15 @@ impl TryFrom<wasmparser::Data<'_>> for DataSegment {
 }
 
 impl DataSegment {
+    /// Creates a new [`DataSegment`] from its parts.
+    pub fn new(memory_index: MemoryIdx, offset: InitExpr, data: Box<[u8]>) -> Self {
+        Self {
+            memory_index,
+            offset,
+            data,
+        }
+    }
+
     /// Returns the index of the [`Memory`] manipulated by the [`DataSegment`].
     ///
     /// [`Memory`]: [`crate::Memory`]
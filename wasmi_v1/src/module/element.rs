@@ -55,6 +55,15 @@ impl TryFrom<wasmparser::Element<'_>> for ElementSegment {
 }
 
 impl ElementSegment {
+    /// Creates a new [`ElementSegment`] from its parts.
+    pub fn new(table_index: TableIdx, offset: InitExpr, items: Box<[FuncIdx]>) -> Self {
+        Self {
+            table_index,
+            offset,
+            items,
+        }
+    }
+
     /// Returns the index of the [`Table`] manipulated by the [`ElementSegment`].
     ///
     /// [`Table`]: [`crate::Table`]
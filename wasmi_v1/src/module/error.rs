@@ -1,4 +1,4 @@
-use super::ReadError;
+use super::{DeserializeError, ReadError};
 use core::{
     fmt,
     fmt::{Debug, Display},
@@ -13,6 +13,8 @@ pub enum ModuleError {
     Parser(ParserError),
     /// Encountered when unsupported Wasm proposal definitions are used.
     Unsupported { message: String },
+    /// Encountered when a serialized module cannot be deserialized.
+    Deserialize(DeserializeError),
 }
 
 impl ModuleError {
@@ -28,6 +30,7 @@ impl Display for ModuleError {
         match self {
             ModuleError::Read(error) => Display::fmt(error, f),
             ModuleError::Parser(error) => Display::fmt(error, f),
+            ModuleError::Deserialize(error) => Display::fmt(error, f),
             ModuleError::Unsupported { message } => {
                 write!(
                     f,
@@ -77,6 +77,11 @@ impl TryFrom<wasmparser::Export<'_>> for Export {
 }
 
 impl Export {
+    /// Creates a new [`Export`] from its field name and [`External`] item.
+    pub fn new(field: Box<str>, external: External) -> Self {
+        Self { field, external }
+    }
+
     /// Returns the field name of the [`Export`].
     pub fn field(&self) -> &str {
         &self.field
@@ -34,6 +34,11 @@ impl TryFrom<wasmparser::InitExpr<'_>> for InitExpr {
 }
 
 impl InitExpr {
+    /// Creates a new [`InitExpr`] from the given operand.
+    pub fn new(op: InitExprOperand) -> Self {
+        Self { op }
+    }
+
     /// Returns a slice over the operators of the [`InitExpr`].
     pub fn operators(&self) -> &[InitExprOperand] {
         core::slice::from_ref(&self.op)
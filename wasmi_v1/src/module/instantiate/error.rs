@@ -4,6 +4,7 @@ use crate::{
     errors::{MemoryError, TableError},
     Extern,
     GlobalType,
+    Memory,
     Table,
 };
 use core::{fmt, fmt::Display};
@@ -33,6 +34,8 @@ pub enum InstantiationError {
     Table(TableError),
     /// Occurs when an imported memory does not satisfy the required memory type.
     Memory(MemoryError),
+    /// Occurs when the system fails to allocate a linear memory of the instantiated module.
+    MemoryAllocation(MemoryError),
     /// Caused when a global variable has a mismatching global variable type and mutability.
     GlobalTypeMismatch {
         /// The expected global type for the global variable import.
@@ -49,11 +52,29 @@ pub enum InstantiationError {
         /// The amount of elements with which the table is initialized at the `offset`.
         amount: usize,
     },
+    /// Caused when a data segment does not fit into the specified linear memory instance.
+    DataSegmentDoesNotFit {
+        /// The linear memory of the data segment.
+        memory: Memory,
+        /// The offset to store the `amount` of bytes into the linear memory.
+        offset: usize,
+        /// The amount of bytes with which the linear memory is initialized at the `offset`.
+        amount: usize,
+    },
     /// Caused when the `start` function was unexpectedly found in the instantiated module.
     FoundStartFn {
         /// The index of the found `start` function.
         index: u32,
     },
+    /// Caused when the [`ResourceLimiter`] of the store denies creating another instance.
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    TooManyInstances,
+    /// Caused when an instance does not provide an export that is required by its bindings.
+    MissingExport {
+        /// The name of the missing export.
+        name: Box<str>,
+    },
 }
 
 #[cfg(feature = "std")]
@@ -92,11 +113,29 @@ impl Display for InstantiationError {
                 "table {:?} does not fit {} elements starting from offset {}",
                 table, offset, amount,
             ),
+            Self::DataSegmentDoesNotFit {
+                memory,
+                offset,
+                amount,
+            } => write!(
+                f,
+                "linear memory {:?} does not fit {} bytes starting from offset {}",
+                memory, amount, offset,
+            ),
             Self::FoundStartFn { index } => {
                 write!(f, "found an unexpected start function with index {}", index)
             }
+            Self::TooManyInstances => {
+                write!(f, "reached the resource limit for instances of the store")
+            }
+            Self::MissingExport { name } => {
+                write!(f, "encountered missing or mismatching export {:?}", name)
+            }
             Self::Table(error) => Display::fmt(error, f),
             Self::Memory(error) => Display::fmt(error, f),
+            Self::MemoryAllocation(error) => {
+                write!(f, "failed to allocate linear memory: {}", error)
+            }
         }
     }
 }
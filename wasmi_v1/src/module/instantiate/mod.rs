@@ -1,9 +1,13 @@
 mod error;
 mod pre;
+mod template;
+mod undo;
 
-pub use self::{error::InstantiationError, pre::InstancePre};
-use super::{export, InitExpr, Module, ModuleImportType};
+use self::undo::ImportsUndoLog;
+pub use self::{error::InstantiationError, pre::InstancePre, template::InstanceTemplate};
+use super::{export, DataSegment, ElementSegment, InitExpr, Module, ModuleImportType};
 use crate::{
+    errors::MemoryError,
     module::{init_expr::InitExprOperand, DEFAULT_MEMORY_INDEX},
     AsContext,
     AsContextMut,
@@ -22,6 +26,7 @@ use crate::{
     Table,
     TableType,
 };
+use alloc::vec::Vec;
 use wasmi_core::{Value, ValueType, F32, F64};
 
 impl Module {
@@ -35,11 +40,18 @@ impl Module {
     /// This is a very low-level API. For a more high-level API users should use the
     /// corresponding instantiation methods provided by the [`Linker`].
     ///
+    /// Instantiation is atomic: all element and data segments are checked to fit before
+    /// any of them is written and all entities allocated for the [`Instance`] are dropped
+    /// from the store again if the instantiation fails.
+    ///
     /// # Errors
     ///
-    /// If the given `externals` do not satisfy the required imports, e.g. if an externally
-    /// provided [`Func`] has a different function signature than required by the module import.
+    /// - If the given `externals` do not satisfy the required imports, e.g. if an externally
+    ///   provided [`Func`] has a different function signature than required by the module import.
+    /// - If the [`ResourceLimiter`] of the store denies the instantiation.
+    /// - If an element or data segment does not fit into its table or linear memory.
     ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
     /// [`Linker`]: struct.Linker.html
     /// [`Func`]: [`crate::v1::Func`]
     pub(crate) fn instantiate<I>(
@@ -50,23 +62,77 @@ impl Module {
     where
         I: IntoIterator<Item = Extern>,
     {
-        let handle = context.as_context_mut().store.alloc_instance();
+        context.as_context_mut().store.ensure_instance_permitted()?;
         let mut builder = InstanceEntity::build();
-
         self.extract_func_types(&mut context, &mut builder);
         self.extract_imports(&mut context, &mut builder, externals)?;
-        self.extract_functions(&mut context, &mut builder, handle);
-        self.extract_tables(&mut context, &mut builder);
-        self.extract_memories(&mut context, &mut builder);
-        self.extract_globals(&mut context, &mut builder);
-        self.extract_exports(&mut builder);
+        builder.finish_imports();
 
-        self.initialize_table_elements(&mut context, &mut builder)?;
-        self.initialize_memory_data(&mut context, &mut builder)?;
+        let handle = context.as_context_mut().store.alloc_instance();
+        match self.initialize_instance(&mut context, &mut builder, handle) {
+            // At this point the module instantiation is nearly done.
+            // The only thing that is missing is to run the `start` function.
+            Ok(undo) => Ok(InstancePre::new(handle, self, builder, undo)),
+            Err(error) => {
+                context
+                    .as_context_mut()
+                    .store
+                    .rollback_instance(handle, builder);
+                Err(error)
+            }
+        }
+    }
 
-        // At this point the module instantiation is nearly done.
-        // The only thing that is missing is to run the `start` function.
-        Ok(InstancePre::new(handle, self, builder))
+    /// Creates the entities owned by the [`Instance`] under construction and
+    /// initializes its tables and linear memories with the element and data segments.
+    ///
+    /// Returns the previous contents of the imported tables and linear memories
+    /// that have been overwritten by the segments.
+    ///
+    /// # Errors
+    ///
+    /// - If the [`ResourceLimiter`] of the store denies creating an entity.
+    /// - If an element or data segment does not fit into its table or linear memory.
+    ///   In this case no segment has been written.
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    fn initialize_instance(
+        &self,
+        context: &mut impl AsContextMut,
+        builder: &mut InstanceEntityBuilder,
+        handle: Instance,
+    ) -> Result<ImportsUndoLog, Error> {
+        self.extract_functions(context, builder, handle);
+        self.extract_tables(context, builder)?;
+        let from_image = self.extract_memories(context, builder)?;
+        self.extract_globals(context, builder);
+        self.extract_exports(builder);
+
+        let element_offsets = Self::eval_offsets(
+            context.as_context(),
+            builder,
+            self.inner
+                .element_segments
+                .iter()
+                .map(ElementSegment::offset),
+        );
+        // Note: The default linear memory already contains all data segments
+        //       if it has been created from the image of the module.
+        let data_offsets = match from_image {
+            true => Vec::new(),
+            false => Self::eval_offsets(
+                context.as_context(),
+                builder,
+                self.inner.data_segments.iter().map(DataSegment::offset),
+            ),
+        };
+        self.validate_element_segments(context.as_context(), builder, &element_offsets)?;
+        self.validate_data_segments(context.as_context(), builder, &data_offsets)?;
+
+        let mut undo = ImportsUndoLog::default();
+        self.initialize_table_elements(context, builder, &element_offsets, &mut undo)?;
+        self.initialize_memory_data(context, builder, &data_offsets, &mut undo)?;
+        Ok(undo)
     }
 
     /// Extracts the Wasm function signatures from the
@@ -192,33 +258,75 @@ impl Module {
     ///
     /// This also stores [`Table`] references into the [`Instance`] under construction.
     ///
+    /// # Errors
+    ///
+    /// If the [`ResourceLimiter`] of the [`Store`] denies creating a table.
+    ///
     /// [`Store`]: struct.Store.html
-    fn extract_tables(&self, context: &mut impl AsContextMut, builder: &mut InstanceEntityBuilder) {
-        for table_type in self.tables.iter().copied() {
-            builder.push_table(Table::new(context.as_context_mut(), table_type));
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    fn extract_tables(
+        &self,
+        context: &mut impl AsContextMut,
+        builder: &mut InstanceEntityBuilder,
+    ) -> Result<(), InstantiationError> {
+        for table_type in self.inner.tables.iter().copied() {
+            builder.push_table(Table::new(context.as_context_mut(), table_type)?);
         }
+        Ok(())
     }
 
     /// Extracts the Wasm linear memories from the module and stores them into the [`Store`].
     ///
     /// This also stores [`Memory`] references into the [`Instance`] under construction.
     ///
+    /// Returns `true` if the default linear memory has been created from the
+    /// memory image of the [`Module`] and thus already contains all data segments.
+    ///
+    /// # Note
+    ///
+    /// The memory image is not used if the [`Store`] has a [`MemoryCreator`] installed.
+    ///
+    /// # Errors
+    ///
+    /// - If the [`ResourceLimiter`] of the [`Store`] denies creating a linear memory.
+    /// - If the [`MemoryCreator`] of the [`Store`] fails to create a linear memory.
+    /// - If the system fails to allocate a linear memory.
+    ///
     /// [`Store`]: struct.Store.html
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    /// [`MemoryCreator`]: crate::MemoryCreator
     fn extract_memories(
         &self,
         context: &mut impl AsContextMut,
         builder: &mut InstanceEntityBuilder,
-    ) {
-        for memory_type in self.memories.iter().copied() {
-            let memory =
-                Memory::new(context.as_context_mut(), memory_type).unwrap_or_else(|error| {
-                    panic!(
-                        "encountered unexpected invalid memory type {:?} after Wasm validation: {}",
-                        memory_type, error,
-                    )
-                });
+    ) -> Result<bool, InstantiationError> {
+        let has_creator = context
+            .as_context()
+            .store
+            .installed_memory_creator()
+            .is_some();
+        let image = self.inner.memory_image.as_ref().filter(|_| !has_creator);
+        for (index, memory_type) in self.inner.memories.iter().copied().enumerate() {
+            let memory = match image {
+                Some(image) if index == DEFAULT_MEMORY_INDEX as usize => {
+                    Memory::with_image(context.as_context_mut(), memory_type, image)
+                }
+                _ => Memory::new(context.as_context_mut(), memory_type),
+            };
+            let memory = memory.map_err(|error| match error {
+                MemoryError::ResourceLimitReached => InstantiationError::Memory(error),
+                error if has_creator => InstantiationError::Memory(error),
+                error @ (MemoryError::Vmem(_) | MemoryError::OutOfBoundsAllocation) => {
+                    InstantiationError::MemoryAllocation(error)
+                }
+                error => panic!(
+                    "encountered unexpected invalid memory type {:?} after Wasm validation: {}",
+                    memory_type, error,
+                ),
+            })?;
             builder.push_memory(memory);
         }
+        Ok(image.is_some())
     }
 
     /// Extracts the Wasm global variables from the module and stores them into the [`Store`].
@@ -270,7 +378,7 @@ impl Module {
 
     /// Extracts the Wasm exports from the module and registers them into the [`Instance`].
     fn extract_exports(&self, builder: &mut InstanceEntityBuilder) {
-        for export in &self.exports[..] {
+        for export in &self.inner.exports[..] {
             let field = export.field();
             let external = match export.external() {
                 export::External::Func(func_index) => {
@@ -321,84 +429,214 @@ impl Module {
     /// The index of the default Wasm table.
     const DEFAULT_TABLE_INDEX: u32 = 0;
 
-    /// Initializes the [`Instance`] tables with the Wasm element segments of the [`Module`].
-    fn initialize_table_elements(
-        &self,
-        context: &mut impl AsContextMut,
-        builder: &mut InstanceEntityBuilder,
-    ) -> Result<(), Error> {
-        for element_segment in &self.element_segments[..] {
-            let offset_expr = element_segment.offset();
-            let offset = Self::eval_init_expr(context.as_context_mut(), builder, offset_expr)
-                .try_into::<u32>()
-                .unwrap_or_else(|| {
-                    panic!(
+    /// Evaluates the given offset initializer expressions of element or data segments.
+    fn eval_offsets<'a>(
+        context: impl AsContext,
+        builder: &InstanceEntityBuilder,
+        offset_exprs: impl IntoIterator<Item = &'a InitExpr>,
+    ) -> Vec<usize> {
+        offset_exprs
+            .into_iter()
+            .map(|offset_expr| Self::eval_offset(context.as_context(), builder, offset_expr))
+            .collect()
+    }
+
+    /// Evaluates the given offset initializer expression of an element or data segment.
+    ///
+    /// # Panics
+    ///
+    /// If the initializer expression does not evaluate to a value of type `i32`.
+    fn eval_offset(
+        context: impl AsContext,
+        builder: &InstanceEntityBuilder,
+        offset_expr: &InitExpr,
+    ) -> usize {
+        Self::eval_init_expr(context, builder, offset_expr)
+            .try_into::<u32>()
+            .unwrap_or_else(|| {
+                panic!(
                     "expected offset value of type `i32` due to Wasm validation but found: {:?}",
                     offset_expr,
                 )
-                }) as usize;
-            let table = builder
-                .get_table(Self::DEFAULT_TABLE_INDEX)
-                .unwrap_or_else(|| {
-                    panic!(
-                        "expected default table at index {} but found none",
-                        Self::DEFAULT_TABLE_INDEX
-                    )
-                });
-            // Note: This checks not only that the elements in the element segments properly
-            //       fit into the table at the given offset but also that the element segment
-            //       consists of at least 1 element member.
-            let len_table = table.len(&context);
+            }) as usize
+    }
+
+    /// Returns the default table of the [`Instance`] under construction.
+    ///
+    /// # Panics
+    ///
+    /// If there is no default table.
+    fn default_table(builder: &InstanceEntityBuilder) -> Table {
+        builder
+            .get_table(Self::DEFAULT_TABLE_INDEX)
+            .unwrap_or_else(|| {
+                panic!(
+                    "expected default table at index {} but found none",
+                    Self::DEFAULT_TABLE_INDEX
+                )
+            })
+    }
+
+    /// Returns the default linear memory of the [`Instance`] under construction.
+    ///
+    /// # Panics
+    ///
+    /// If there is no default linear memory.
+    fn default_memory(builder: &InstanceEntityBuilder) -> Memory {
+        builder.get_memory(DEFAULT_MEMORY_INDEX).unwrap_or_else(|| {
+            panic!(
+                "expected default memory at index {} but found none",
+                DEFAULT_MEMORY_INDEX
+            )
+        })
+    }
+
+    /// Checks that all element segments fit into the default table at the given `offsets`.
+    ///
+    /// # Errors
+    ///
+    /// If an element segment does not fit into the default table.
+    fn validate_element_segments(
+        &self,
+        context: impl AsContext,
+        builder: &InstanceEntityBuilder,
+        offsets: &[usize],
+    ) -> Result<(), InstantiationError> {
+        if self.inner.element_segments.is_empty() {
+            return Ok(());
+        }
+        let table = Self::default_table(builder);
+        let len_table = table.len(&context);
+        for (element_segment, &offset) in self.inner.element_segments.iter().zip(offsets) {
             let len_items = element_segment.items().len();
             if offset + len_items > len_table {
                 return Err(InstantiationError::ElementSegmentDoesNotFit {
                     table,
                     offset,
                     amount: len_items,
-                })
-                .map_err(Into::into);
+                });
             }
-            // Finally do the actual initialization of the table elements.
-            for (i, func_index) in element_segment.items().iter().enumerate() {
-                let func_index = func_index.into_u32();
-                let func = builder.get_func(func_index).unwrap_or_else(|| {
-                    panic!(
-                        "encountered missing function at index {} upon element initialization",
-                        func_index
-                    )
+        }
+        Ok(())
+    }
+
+    /// Checks that all data segments fit into the default linear memory at the given `offsets`.
+    ///
+    /// # Errors
+    ///
+    /// If a data segment does not fit into the default linear memory.
+    fn validate_data_segments(
+        &self,
+        context: impl AsContext,
+        builder: &InstanceEntityBuilder,
+        offsets: &[usize],
+    ) -> Result<(), InstantiationError> {
+        if offsets.is_empty() {
+            return Ok(());
+        }
+        let memory = Self::default_memory(builder);
+        let len_memory = memory.data(context.as_context()).len();
+        for (data_segment, &offset) in self.inner.data_segments.iter().zip(offsets) {
+            let len_data = data_segment.data().len();
+            if offset + len_data > len_memory {
+                return Err(InstantiationError::DataSegmentDoesNotFit {
+                    memory,
+                    offset,
+                    amount: len_data,
                 });
-                table.set(context.as_context_mut(), offset + i, Some(func))?;
             }
         }
         Ok(())
     }
 
-    /// Initializes the [`Instance`] linear memories with the Wasm data segments of the [`Module`].
-    fn initialize_memory_data(
+    /// Initializes the [`Instance`] tables with the Wasm element segments of the [`Module`]
+    /// at the given `offsets`.
+    ///
+    /// # Note
+    ///
+    /// The element segments must have been validated to fit beforehand.
+    fn initialize_table_elements(
         &self,
         context: &mut impl AsContextMut,
-        builder: &mut InstanceEntityBuilder,
+        builder: &InstanceEntityBuilder,
+        offsets: &[usize],
+        undo: &mut ImportsUndoLog,
     ) -> Result<(), Error> {
-        for data_segment in &self.data_segments[..] {
-            let offset_expr = data_segment.offset();
-            let offset = Self::eval_init_expr(context.as_context_mut(), builder, offset_expr)
-                .try_into::<u32>()
-                .unwrap_or_else(|| {
-                    panic!(
-                    "expected offset value of type `i32` due to Wasm validation but found: {:?}",
-                    offset_expr,
+        for (element_segment, &offset) in self.inner.element_segments.iter().zip(offsets) {
+            self.initialize_element_segment(context, builder, element_segment, offset, undo)?;
+        }
+        Ok(())
+    }
+
+    /// Initializes the default table with the element segment at the given `offset`.
+    ///
+    /// Records the overwritten elements in `undo` if the default table is imported.
+    fn initialize_element_segment(
+        &self,
+        context: &mut impl AsContextMut,
+        builder: &InstanceEntityBuilder,
+        element_segment: &ElementSegment,
+        offset: usize,
+        undo: &mut ImportsUndoLog,
+    ) -> Result<(), Error> {
+        let table = Self::default_table(builder);
+        let len_items = element_segment.items().len();
+        if self.inner.imports.len_tables != 0 {
+            undo.record_table(context.as_context(), table, offset, len_items);
+        }
+        for (i, func_index) in element_segment.items().iter().enumerate() {
+            let func_index = func_index.into_u32();
+            let func = builder.get_func(func_index).unwrap_or_else(|| {
+                panic!(
+                    "encountered missing function at index {} upon element initialization",
+                    func_index
                 )
-                }) as usize;
-            let memory = builder
-                .get_memory(Self::DEFAULT_TABLE_INDEX)
-                .unwrap_or_else(|| {
-                    panic!(
-                        "expected default memory at index {} but found none",
-                        DEFAULT_MEMORY_INDEX
-                    )
-                });
-            memory.write(context.as_context_mut(), offset, data_segment.data())?;
+            });
+            table.set(context.as_context_mut(), offset + i, Some(func))?;
+        }
+        Ok(())
+    }
+
+    /// Initializes the [`Instance`] linear memories with the Wasm data segments of the [`Module`]
+    /// at the given `offsets`.
+    ///
+    /// # Note
+    ///
+    /// The data segments must have been validated to fit beforehand.
+    fn initialize_memory_data(
+        &self,
+        context: &mut impl AsContextMut,
+        builder: &InstanceEntityBuilder,
+        offsets: &[usize],
+        undo: &mut ImportsUndoLog,
+    ) -> Result<(), Error> {
+        for (data_segment, &offset) in self.inner.data_segments.iter().zip(offsets) {
+            self.initialize_data_segment(context, builder, data_segment, offset, undo)?;
+        }
+        Ok(())
+    }
+
+    /// Initializes the default linear memory with the data segment at the given `offset`.
+    ///
+    /// Records the overwritten bytes in `undo` if the default linear memory is imported.
+    fn initialize_data_segment(
+        &self,
+        context: &mut impl AsContextMut,
+        builder: &InstanceEntityBuilder,
+        data_segment: &DataSegment,
+        offset: usize,
+        undo: &mut ImportsUndoLog,
+    ) -> Result<(), Error> {
+        let memory = Self::default_memory(builder);
+        if self.inner.imports.len_memories != 0 {
+            undo.record_memory(
+                context.as_context(),
+                memory,
+                offset,
+                data_segment.data().len(),
+            );
         }
+        memory.write(context.as_context_mut(), offset, data_segment.data())?;
         Ok(())
     }
 }
@@ -1,4 +1,4 @@
-use super::{InstantiationError, Module};
+use super::{ImportsUndoLog, InstantiationError, Module};
 use crate::{AsContextMut, Error, Instance, InstanceEntityBuilder};
 
 /// A partially instantiated [`Instance`] where the `start` function has not yet been executed.
@@ -13,6 +13,9 @@ pub struct InstancePre<'a> {
     handle: Instance,
     module: &'a Module,
     builder: InstanceEntityBuilder,
+    /// The previous contents of the imported tables and linear memories
+    /// that have been overwritten by the element and data segments.
+    undo: ImportsUndoLog,
 }
 
 impl<'a> InstancePre<'a> {
@@ -21,11 +24,13 @@ impl<'a> InstancePre<'a> {
         handle: Instance,
         module: &'a Module,
         builder: InstanceEntityBuilder,
+        undo: ImportsUndoLog,
     ) -> Self {
         Self {
             handle,
             module,
             builder,
+            undo,
         }
     }
 
@@ -33,7 +38,7 @@ impl<'a> InstancePre<'a> {
     ///
     /// Returns `None` if the [`Module`] does not have a `start` function.
     fn start_fn(&self) -> Option<u32> {
-        self.module.start.map(|idx| idx.into_u32())
+        self.module.inner.start.map(|idx| idx.into_u32())
     }
 
     /// Runs the `start` function of the [`Instance`] and returns its handle.
@@ -42,6 +47,11 @@ impl<'a> InstancePre<'a> {
     ///
     /// This finishes the instantiation procedure.
     ///
+    /// If executing the `start` function traps the [`Instance`] and all entities it
+    /// owns are dropped from the store. The imported tables and linear memories are
+    /// restored where they have been initialized by the element and data segments.
+    /// Other side effects of the `start` function on imported entities are not undone.
+    ///
     /// # Errors
     ///
     /// If executing the `start` function traps.
@@ -65,7 +75,14 @@ impl<'a> InstancePre<'a> {
                         start_index
                     )
                 });
-            start_func.call(context.as_context_mut(), &[], &mut [])?
+            if let Err(error) = start_func.call(context.as_context_mut(), &[], &mut []) {
+                self.undo.undo(&mut context);
+                context
+                    .as_context_mut()
+                    .store
+                    .dealloc_instance(self.handle, drop);
+                return Err(error);
+            }
         }
         Ok(self.handle)
     }
@@ -75,11 +92,17 @@ impl<'a> InstancePre<'a> {
     /// # Errors
     ///
     /// If a `start` function exists that needs to be called for conformant module instantiation.
+    /// In this case the instantiation is rolled back the same way as for a trapping `start` function.
     pub fn ensure_no_start(
         self,
         mut context: impl AsContextMut,
     ) -> Result<Instance, InstantiationError> {
         if let Some(index) = self.start_fn() {
+            self.undo.undo(&mut context);
+            context
+                .as_context_mut()
+                .store
+                .rollback_instance(self.handle, self.builder);
             return Err(InstantiationError::FoundStartFn { index });
         }
         context
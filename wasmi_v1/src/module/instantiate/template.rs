@@ -0,0 +1,361 @@
+use super::{ImportsUndoLog, InstancePre, InstantiationError, Module};
+use crate::{
+    errors::InstanceError,
+    module::{DataSegment, ElementSegment, DEFAULT_MEMORY_INDEX},
+    AsContextMut,
+    Error,
+    Extern,
+    Global,
+    Instance,
+    InstanceEntity,
+    InstanceEntityBuilder,
+    MemoryEntity,
+};
+use alloc::{boxed::Box, vec::Vec};
+use wasmi_core::Value;
+
+/// A pre-linked [`Module`] that can be instantiated many times in the same [`Store`].
+///
+/// # Note
+///
+/// Created by [`Linker::instantiate_template`].
+///
+/// Unlike [`Linker::instantiate`] the imports of the [`Module`] are resolved and
+/// type checked only once and the initial values of the global variables, the offsets
+/// of the element and data segments as well as the initial contents of the default
+/// linear memory are computed only once upon creation of the [`InstanceTemplate`].
+///
+/// Linear memories of instances that are handed back via [`InstanceTemplate::reclaim`]
+/// are zeroed and pooled so that later instantiations can reuse them instead of
+/// allocating new linear memories. The pool holds the linear memories of at most
+/// [`InstanceTemplate::DEFAULT_POOL_LIMIT`] instances unless configured otherwise
+/// via [`InstanceTemplate::set_pool_limit`] and can be emptied explicitly via
+/// [`InstanceTemplate::clear_pool`].
+///
+/// An [`InstanceTemplate`] is bound to the [`Store`] it has been created with
+/// since it keeps the resolved imports. Using it with another [`Store`] panics.
+///
+/// [`Store`]: crate::Store
+/// [`Linker::instantiate`]: crate::Linker::instantiate
+/// [`Linker::instantiate_template`]: crate::Linker::instantiate_template
+#[derive(Debug)]
+pub struct InstanceTemplate<'a> {
+    module: &'a Module,
+    /// The [`InstanceEntity`] under construction with all imports already resolved.
+    prototype: InstanceEntityBuilder,
+    /// The initial values of the internally defined global variables.
+    globals: Box<[Value]>,
+    /// The evaluated offsets of the element segments.
+    element_offsets: Box<[usize]>,
+    /// The precomputed initialization of the default linear memory.
+    memory_init: MemoryInit,
+    /// The zeroed linear memories of reclaimed instances.
+    ///
+    /// Each entry holds all linear memories owned by a single reclaimed instance.
+    pool: Vec<Box<[MemoryEntity]>>,
+    /// The maximum number of entries in the `pool`.
+    pool_limit: usize,
+}
+
+/// The precomputed initialization of the default linear memory of an [`InstanceTemplate`].
+#[derive(Debug)]
+enum MemoryInit {
+    /// The default linear memory is owned by the instances.
+    ///
+    /// Holds the contents of the default linear memory after applying all data segments
+    /// up to the last initialized byte. All bytes that follow are zero.
+    Image(Box<[u8]>),
+    /// The default linear memory is imported and therefore shared between instances.
+    ///
+    /// Holds the evaluated offsets of the data segments which are written
+    /// upon every instantiation.
+    Segments(Box<[usize]>),
+}
+
+impl<'a> InstanceTemplate<'a> {
+    /// The default maximum number of reclaimed instances whose linear memories are pooled.
+    pub const DEFAULT_POOL_LIMIT: usize = 16;
+
+    /// Creates a new [`InstanceTemplate`] for the `module` with the given `externals`.
+    ///
+    /// # Errors
+    ///
+    /// - If the given `externals` do not satisfy the required imports.
+    /// - If a data segment does not fit into the owned default linear memory.
+    pub(crate) fn new<I>(
+        mut context: impl AsContextMut,
+        module: &'a Module,
+        externals: I,
+    ) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = Extern>,
+    {
+        let mut prototype = InstanceEntity::build();
+        module.extract_func_types(&mut context, &mut prototype);
+        module.extract_imports(&mut context, &mut prototype, externals)?;
+        prototype.finish_imports();
+        let globals = module
+            .internal_globals()
+            .map(|(_, global_init)| {
+                Module::eval_init_expr(context.as_context(), &prototype, global_init)
+            })
+            .collect();
+        let element_offsets = Module::eval_offsets(
+            context.as_context(),
+            &prototype,
+            module
+                .inner
+                .element_segments
+                .iter()
+                .map(ElementSegment::offset),
+        )
+        .into();
+        let data_offsets = Module::eval_offsets(
+            context.as_context(),
+            &prototype,
+            module.inner.data_segments.iter().map(DataSegment::offset),
+        )
+        .into_boxed_slice();
+        let memory_init = match module.inner.memories.first() {
+            Some(memory_type) if prototype.get_memory(DEFAULT_MEMORY_INDEX).is_none() => {
+                let image = module
+                    .inner
+                    .memory_image_bytes(*memory_type, &data_offsets)?;
+                MemoryInit::Image(image.into())
+            }
+            _ => MemoryInit::Segments(data_offsets),
+        };
+        Ok(Self {
+            module,
+            prototype,
+            globals,
+            element_offsets,
+            memory_init,
+            pool: Vec::new(),
+            pool_limit: Self::DEFAULT_POOL_LIMIT,
+        })
+    }
+
+    /// Returns the number of reclaimed instances whose linear memories are pooled.
+    pub fn pool_len(&self) -> usize {
+        self.pool.len()
+    }
+
+    /// Sets the maximum number of reclaimed instances whose linear memories are pooled.
+    ///
+    /// # Note
+    ///
+    /// Drops pooled linear memories exceeding the new `limit`.
+    /// A `limit` of zero disables pooling altogether.
+    pub fn set_pool_limit(&mut self, limit: usize) {
+        self.pool_limit = limit;
+        self.pool.truncate(limit);
+    }
+
+    /// Drops all pooled linear memories.
+    pub fn clear_pool(&mut self) {
+        self.pool.clear();
+    }
+
+    /// Instantiates a new [`Instance`] from the [`InstanceTemplate`].
+    ///
+    /// # Note
+    ///
+    /// - Reuses the pooled linear memories of reclaimed instances if any.
+    /// - Instantiation is atomic the same way as for [`Linker::instantiate`].
+    ///   Upon failure the linear memories of the [`Instance`] are pooled again.
+    ///
+    /// # Errors
+    ///
+    /// - If the [`ResourceLimiter`] of the [`Store`] denies the instantiation.
+    /// - If an element segment does not fit into the default table.
+    /// - If a data segment does not fit into the imported default linear memory.
+    ///
+    /// [`Store`]: crate::Store
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    /// [`Linker::instantiate`]: crate::Linker::instantiate
+    pub fn instantiate(
+        &mut self,
+        mut context: impl AsContextMut,
+    ) -> Result<InstancePre<'a>, Error> {
+        let module = self.module;
+        context.as_context_mut().store.ensure_instance_permitted()?;
+        let handle = context.as_context_mut().store.alloc_instance();
+        let mut builder = self.prototype.clone();
+        match self.initialize_instance(&mut context, &mut builder, handle) {
+            Ok(undo) => Ok(InstancePre::new(handle, module, builder, undo)),
+            Err(error) => {
+                let store = &mut context.as_context_mut().store;
+                store.initialize_instance(handle, builder.finish());
+                self.pool_memories(context, handle);
+                Err(error)
+            }
+        }
+    }
+
+    /// Creates the entities owned by the [`Instance`] under construction and
+    /// initializes its tables and linear memories from the [`InstanceTemplate`].
+    ///
+    /// Returns the previous contents of the imported tables and linear memories
+    /// that have been overwritten by the segments.
+    ///
+    /// # Errors
+    ///
+    /// - If the [`ResourceLimiter`] of the store denies creating an entity.
+    /// - If an element segment does not fit into the default table or a data segment
+    ///   does not fit into the imported default linear memory.
+    ///   In this case no segment has been written.
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    fn initialize_instance(
+        &mut self,
+        context: &mut impl AsContextMut,
+        builder: &mut InstanceEntityBuilder,
+        handle: Instance,
+    ) -> Result<ImportsUndoLog, Error> {
+        let module = self.module;
+        module.extract_functions(context, builder, handle);
+        module.extract_tables(context, builder)?;
+        let from_image = self.extract_memories(context, builder)?;
+        self.extract_globals(context, builder);
+        module.extract_exports(builder);
+
+        module.validate_element_segments(context.as_context(), builder, &self.element_offsets)?;
+        if let MemoryInit::Segments(offsets) = &self.memory_init {
+            module.validate_data_segments(context.as_context(), builder, offsets)?;
+        }
+
+        let mut undo = ImportsUndoLog::default();
+        module.initialize_table_elements(context, builder, &self.element_offsets, &mut undo)?;
+        if !from_image {
+            self.initialize_memory_data(context, builder, &mut undo)?;
+        }
+        Ok(undo)
+    }
+
+    /// Drops the `instance` from the [`Store`] and pools its linear memories for reuse.
+    ///
+    /// # Note
+    ///
+    /// - This behaves like [`Store::drop_instance`] but the linear memories owned
+    ///   by the `instance` are zeroed and reused by later calls to
+    ///   [`InstanceTemplate::instantiate`].
+    /// - Linear memories that do not match the [`Module`] of the [`InstanceTemplate`]
+    ///   are dropped instead of pooled. The same happens if the pool is full.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Store::drop_instance`].
+    ///
+    /// # Panics
+    ///
+    /// Same as [`Store::drop_instance`].
+    ///
+    /// [`Store`]: crate::Store
+    /// [`Store::drop_instance`]: crate::Store::drop_instance
+    pub fn reclaim(
+        &mut self,
+        mut context: impl AsContextMut,
+        instance: Instance,
+    ) -> Result<(), InstanceError> {
+        context
+            .as_context_mut()
+            .store
+            .ensure_instance_unused(instance)?;
+        self.pool_memories(context, instance);
+        Ok(())
+    }
+
+    /// Drops the `instance` from the [`Store`] and pools its linear memories for reuse.
+    ///
+    /// # Note
+    ///
+    /// This does not check if the entities owned by the `instance` are still in use.
+    ///
+    /// [`Store`]: crate::Store
+    fn pool_memories(&mut self, mut context: impl AsContextMut, instance: Instance) {
+        let mut memories = Vec::new();
+        context
+            .as_context_mut()
+            .store
+            .dealloc_instance(instance, |memory| memories.push(memory));
+        let memory_types = &self.module.inner.memories[..];
+        if self.pool.len() >= self.pool_limit || memories.len() != memory_types.len() {
+            return;
+        }
+        for (memory, memory_type) in memories.iter_mut().zip(memory_types) {
+            if memory.memory_type() != *memory_type
+                || memory.resize(memory_type.initial_pages()).is_err()
+            {
+                return;
+            }
+            memory.data_mut().fill(0x00);
+        }
+        self.pool.push(memories.into());
+    }
+
+    /// Creates the linear memories of the [`Instance`] under construction.
+    ///
+    /// Reuses pooled linear memories if possible.
+    ///
+    /// Returns `true` if the default linear memory has been newly created
+    /// from the memory image of the [`Module`].
+    fn extract_memories(
+        &mut self,
+        context: &mut impl AsContextMut,
+        builder: &mut InstanceEntityBuilder,
+    ) -> Result<bool, InstantiationError> {
+        let memories = match self.pool.pop() {
+            Some(memories) => memories,
+            None => return self.module.extract_memories(context, builder),
+        };
+        let store = &mut context.as_context_mut().store;
+        for memory in Vec::from(memories) {
+            store
+                .ensure_memory_permitted(memory.memory_type())
+                .map_err(InstantiationError::Memory)?;
+            builder.push_memory(store.alloc_memory(memory));
+        }
+        Ok(false)
+    }
+
+    /// Creates the global variables of the [`Instance`] under construction
+    /// from their precomputed initial values.
+    fn extract_globals(
+        &self,
+        context: &mut impl AsContextMut,
+        builder: &mut InstanceEntityBuilder,
+    ) {
+        for ((global_type, _), init_value) in self.module.internal_globals().zip(&self.globals[..])
+        {
+            let mutability = global_type.mutability();
+            let global = Global::new(context.as_context_mut(), *init_value, mutability);
+            builder.push_global(global);
+        }
+    }
+
+    /// Initializes the default linear memory from its precomputed contents.
+    ///
+    /// Records the overwritten bytes in `undo` if the default linear memory is imported.
+    fn initialize_memory_data(
+        &self,
+        context: &mut impl AsContextMut,
+        builder: &InstanceEntityBuilder,
+        undo: &mut ImportsUndoLog,
+    ) -> Result<(), Error> {
+        match &self.memory_init {
+            MemoryInit::Image(image) => {
+                if image.is_empty() {
+                    return Ok(());
+                }
+                let memory = Module::default_memory(builder);
+                memory.data_mut(context.as_context_mut())[..image.len()].copy_from_slice(image);
+            }
+            MemoryInit::Segments(offsets) => {
+                self.module
+                    .initialize_memory_data(context, builder, offsets, undo)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{AsContext, AsContextMut, Func, Memory, Table};
+use alloc::{boxed::Box, vec::Vec};
+
+/// The previous contents of imported tables and linear memories
+/// that have been overwritten by the element and data segments upon instantiation.
+///
+/// # Note
+///
+/// Used to restore the imported tables and linear memories
+/// if the instantiation fails after the segments have been written,
+/// e.g. if the `start` function traps.
+#[derive(Debug, Default)]
+pub struct ImportsUndoLog {
+    /// The previous elements of imported tables.
+    tables: Vec<Record<Table, Option<Func>>>,
+    /// The previous bytes of imported linear memories.
+    memories: Vec<Record<Memory, u8>>,
+}
+
+/// The previous contents of an imported `entity` starting at `offset`.
+#[derive(Debug)]
+struct Record<E, T> {
+    entity: E,
+    offset: usize,
+    contents: Box<[T]>,
+}
+
+impl ImportsUndoLog {
+    /// Records the `len` elements of the imported `table` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// If the range of elements is out of bounds of the `table`.
+    pub fn record_table(&mut self, ctx: impl AsContext, table: Table, offset: usize, len: usize) {
+        let elements = (offset..offset + len)
+            .map(|index| {
+                table
+                    .get(&ctx, index)
+                    .unwrap_or_else(|error| panic!("validated table element: {}", error))
+            })
+            .collect();
+        self.tables.push(Record {
+            entity: table,
+            offset,
+            contents: elements,
+        });
+    }
+
+    /// Records the `len` bytes of the imported linear `memory` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// If the range of bytes is out of bounds of the linear `memory`.
+    pub fn record_memory(
+        &mut self,
+        ctx: impl AsContext,
+        memory: Memory,
+        offset: usize,
+        len: usize,
+    ) {
+        let bytes = memory.data(ctx.as_context())[offset..offset + len].into();
+        self.memories.push(Record {
+            entity: memory,
+            offset,
+            contents: bytes,
+        });
+    }
+
+    /// Restores all recorded imported tables and linear memories.
+    ///
+    /// # Note
+    ///
+    /// Records are restored in reverse order so that overlapping
+    /// segments restore the contents prior to the instantiation.
+    pub fn undo(self, mut ctx: impl AsContextMut) {
+        for record in self.tables.into_iter().rev() {
+            for (index, element) in record.contents.iter().enumerate() {
+                // Note: Tables cannot shrink so restoring the elements cannot fail.
+                let _ = record.entity.set(&mut ctx, record.offset + index, *element);
+            }
+        }
+        for record in self.memories.into_iter().rev() {
+            // Note: Linear memories cannot shrink so restoring the bytes cannot fail.
+            let _ = record
+                .entity
+                .write(&mut ctx, record.offset, &record.contents);
+        }
+    }
+}
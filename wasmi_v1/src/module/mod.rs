@@ -12,6 +12,7 @@ mod init_expr;
 mod instantiate;
 mod parser;
 mod read;
+mod serialize;
 mod utils;
 
 #[cfg(test)]
@@ -21,7 +22,7 @@ use self::{
     builder::ModuleBuilder,
     data::DataSegment,
     element::ElementSegment,
-    export::Export,
+    export::{Export, External},
     global::Global,
     import::{Import, ImportKind},
     init_expr::{InitExpr, InitExprOperand},
@@ -30,16 +31,19 @@ use self::{
 };
 pub use self::{
     builder::ModuleResources,
-    compile::BlockType,
+    compile::{BlockType, UncompiledFuncBody, UncompiledResources},
     error::ModuleError,
     export::{FuncIdx, MemoryIdx, TableIdx},
     global::GlobalIdx,
     import::{FuncTypeIdx, ImportName},
-    instantiate::{InstancePre, InstantiationError},
+    instantiate::{InstancePre, InstanceTemplate, InstantiationError},
     read::Read,
+    serialize::DeserializeError,
 };
 use crate::{
     engine::{DedupFuncType, FuncBody},
+    errors::MemoryError,
+    memory::MemoryImage,
     Engine,
     Error,
     FuncType,
@@ -47,12 +51,31 @@ use crate::{
     MemoryType,
     TableType,
 };
+use alloc::{sync::Arc, vec::Vec};
 use core::{iter, slice::Iter as SliceIter};
+use wasmi_core::memory_units::Bytes;
 
 /// A parsed and validated WebAssembly module.
 #[derive(Debug)]
 pub struct Module {
     engine: Engine,
+    /// The parsed and translated contents of the [`Module`].
+    ///
+    /// # Note
+    ///
+    /// Shared by all [`Module`]s created from identical Wasm bytes
+    /// if the compilation cache of the [`Engine`] is enabled.
+    inner: Arc<ModuleInner>,
+}
+
+/// The parsed and translated contents of a [`Module`].
+///
+/// # Note
+///
+/// Refers to the function types and function bodies owned by the [`Engine`]
+/// but does not keep the [`Engine`] alive so that it can be cached by it.
+#[derive(Debug)]
+pub(crate) struct ModuleInner {
     func_types: Box<[DedupFuncType]>,
     imports: ModuleImports,
     funcs: Box<[DedupFuncType]>,
@@ -65,6 +88,14 @@ pub struct Module {
     func_bodies: Box<[FuncBody]>,
     element_segments: Box<[ElementSegment]>,
     data_segments: Box<[DataSegment]>,
+    /// The initial contents of the default linear memory if precomputed.
+    ///
+    /// # Note
+    ///
+    /// Only built if enabled by the [`Config`] of the [`Engine`].
+    ///
+    /// [`Config`]: crate::Config
+    memory_image: Option<MemoryImage>,
 }
 
 /// The index of the default Wasm linear memory.
@@ -101,6 +132,14 @@ pub struct ModuleImports {
     ///
     /// [`Func`]: [`crate::Func`]
     len_funcs: usize,
+    /// The amount of imported [`Table`].
+    ///
+    /// [`Table`]: [`crate::Table`]
+    len_tables: usize,
+    /// The amount of imported [`Memory`].
+    ///
+    /// [`Memory`]: [`crate::Memory`]
+    len_memories: usize,
     /// The amount of imported [`Global`].
     len_globals: usize,
 }
@@ -109,6 +148,8 @@ impl ModuleImports {
     /// Creates a new [`ModuleImports`] from the [`ModuleBuilder`] definitions.
     fn from_builder(imports: builder::ModuleImports) -> Self {
         let len_funcs = imports.funcs.len();
+        let len_tables = imports.tables.len();
+        let len_memories = imports.memories.len();
         let len_globals = imports.globals.len();
         let funcs = imports.funcs.into_iter().map(Imported::Func);
         let tables = imports.tables.into_iter().map(Imported::Table);
@@ -123,6 +164,8 @@ impl ModuleImports {
         Self {
             items,
             len_funcs,
+            len_tables,
+            len_memories,
             len_globals,
         }
     }
@@ -135,10 +178,41 @@ impl Module {
     ///
     /// - If the `stream` cannot be decoded into a valid Wasm module.
     /// - If unsupported Wasm proposals are encounterd.
+    ///
+    /// # Note
+    ///
+    /// If the compilation cache is enabled by the [`Config`] of the [`Engine`] the returned
+    /// [`Module`] shares its translation with all [`Module`]s created from identical bytes.
+    ///
+    /// [`Config`]: crate::Config
     pub fn new(engine: &Engine, stream: impl Read) -> Result<Self, Error> {
+        if engine.config().compilation_cache() {
+            return Self::new_cached(engine, stream).map_err(Into::into);
+        }
         parse(engine, stream).map_err(Into::into)
     }
 
+    /// Creates a new Wasm [`Module`] using the compilation cache of the [`Engine`].
+    ///
+    /// # Note
+    ///
+    /// Reads the whole `stream` up front since the cache is keyed by the Wasm bytes.
+    fn new_cached(engine: &Engine, mut stream: impl Read) -> Result<Self, ModuleError> {
+        let bytes = read_to_end(&mut stream)?;
+        if let Some(inner) = engine.get_cached_module(&bytes) {
+            return Ok(Self {
+                engine: engine.clone(),
+                inner,
+            });
+        }
+        let module = parse(engine, &bytes[..])?;
+        let inner = engine.cache_module(bytes.into(), module.inner);
+        Ok(Self {
+            engine: module.engine,
+            inner,
+        })
+    }
+
     /// Returns the [`Engine`] used during creation of the [`Module`].
     pub fn engine(&self) -> &Engine {
         &self.engine
@@ -146,8 +220,8 @@ impl Module {
 
     /// Creates a new [`Module`] from the [`ModuleBuilder`].
     fn from_builder(builder: ModuleBuilder) -> Self {
-        Self {
-            engine: builder.engine.clone(),
+        let engine = builder.engine.clone();
+        let mut inner = ModuleInner {
             func_types: builder.func_types.into(),
             imports: ModuleImports::from_builder(builder.imports),
             funcs: builder.funcs.into(),
@@ -160,24 +234,97 @@ impl Module {
             func_bodies: builder.func_bodies.into(),
             element_segments: builder.element_segments.into(),
             data_segments: builder.data_segments.into(),
+            memory_image: None,
+        };
+        if engine.config().copy_on_write_memory_init() {
+            inner.memory_image = inner.build_memory_image();
+        }
+        Self {
+            engine,
+            inner: Arc::new(inner),
         }
     }
+}
 
+impl ModuleInner {
+    /// Builds the initial contents of the default linear memory from the data segments.
+    ///
+    /// Returns `None` if the default linear memory is imported, if there are no
+    /// data segments or if the offset of a data segment is not a constant.
+    fn build_memory_image(&self) -> Option<MemoryImage> {
+        if self.imports.len_memories != 0 || self.data_segments.is_empty() {
+            return None;
+        }
+        let memory_type = self.memories.first()?;
+        let offsets = self
+            .data_segments
+            .iter()
+            .map(|segment| match segment.offset().operators() {
+                &[InitExprOperand::Const(value)] => value.try_into::<u32>().map(|x| x as usize),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let image = self.memory_image_bytes(*memory_type, &offsets).ok()?;
+        MemoryImage::new(&image).ok()
+    }
+
+    /// Returns the contents of the default linear memory after writing
+    /// all data segments at the given `offsets`.
+    ///
+    /// # Note
+    ///
+    /// The returned bytes end with the last initialized byte.
+    /// All bytes of the linear memory that follow are zero.
+    ///
+    /// # Errors
+    ///
+    /// If a data segment does not fit into the initial pages of the `memory_type`.
+    fn memory_image_bytes(
+        &self,
+        memory_type: MemoryType,
+        offsets: &[usize],
+    ) -> Result<Vec<u8>, MemoryError> {
+        let len_memory = Bytes::from(memory_type.initial_pages()).0;
+        let mut image = Vec::new();
+        for (segment, &offset) in self.data_segments.iter().zip(offsets) {
+            let data = segment.data();
+            let end = offset
+                .checked_add(data.len())
+                .filter(|&end| end <= len_memory)
+                .ok_or(MemoryError::OutOfBoundsAccess)?;
+            if image.len() < end {
+                image.resize(end, 0x00);
+            }
+            image[offset..end].copy_from_slice(data);
+        }
+        Ok(image)
+    }
+}
+
+impl Module {
     /// Returns a slice over the [`FuncType`] of the [`Module`].
     fn func_types(&self) -> &[DedupFuncType] {
-        &self.func_types[..]
+        &self.inner.func_types[..]
     }
 
     /// Returns an iterator over the imports of the [`Module`].
     pub(crate) fn imports(&self) -> ModuleImportsIter {
-        let len_imported_funcs = self.imports.len_funcs;
-        let len_imported_globals = self.imports.len_globals;
+        let len_imported_funcs = self.inner.imports.len_funcs;
+        let len_imported_globals = self.inner.imports.len_globals;
         ModuleImportsIter {
-            names: self.imports.items.iter(),
-            funcs: self.funcs[..len_imported_funcs].iter(),
-            tables: self.tables.iter(),
-            memories: self.memories.iter(),
-            globals: self.globals[..len_imported_globals].iter(),
+            names: self.inner.imports.items.iter(),
+            funcs: self.inner.funcs[..len_imported_funcs].iter(),
+            tables: self.inner.tables.iter(),
+            memories: self.inner.memories.iter(),
+            globals: self.inner.globals[..len_imported_globals].iter(),
+        }
+    }
+
+    /// Returns an iterator over the exports of the [`Module`].
+    pub(crate) fn exports(&self) -> ModuleExportsIter {
+        ModuleExportsIter {
+            module: self,
+            exports: self.inner.exports.iter(),
         }
     }
 
@@ -185,12 +332,12 @@ impl Module {
     ///
     /// [`Func`]: [`crate::Func`]
     fn internal_funcs(&self) -> InternalFuncsIter {
-        let len_imported = self.imports.len_funcs;
+        let len_imported = self.inner.imports.len_funcs;
         // We skip the first `len_imported` elements in `funcs`
         // since they refer to imported and not internally defined
         // functions.
-        let funcs = &self.funcs[len_imported..];
-        let func_bodies = &self.func_bodies[..];
+        let funcs = &self.inner.funcs[len_imported..];
+        let func_bodies = &self.inner.func_bodies[..];
         assert_eq!(funcs.len(), func_bodies.len());
         InternalFuncsIter {
             iter: funcs.iter().zip(func_bodies),
@@ -199,18 +346,31 @@ impl Module {
 
     /// Returns an iterator over the internally defined [`Global`].
     fn internal_globals(&self) -> InternalGlobalsIter {
-        let len_imported = self.imports.len_globals;
+        let len_imported = self.inner.imports.len_globals;
         // We skip the first `len_imported` elements in `globals`
         // since they refer to imported and not internally defined
         // global variables.
-        let globals = self.globals[len_imported..].iter();
-        let global_inits = self.globals_init.iter();
+        let globals = self.inner.globals[len_imported..].iter();
+        let global_inits = self.inner.globals_init.iter();
         InternalGlobalsIter {
             iter: globals.zip(global_inits),
         }
     }
 }
 
+/// Reads all remaining bytes of the `stream`.
+fn read_to_end(stream: &mut impl Read) -> Result<Vec<u8>, ReadError> {
+    let mut bytes = Vec::new();
+    let mut chunk = [0x00_u8; 4096];
+    loop {
+        let len_read = stream.read(&mut chunk)?;
+        if len_read == 0 {
+            return Ok(bytes);
+        }
+        bytes.extend_from_slice(&chunk[..len_read]);
+    }
+}
+
 /// An iterator over the imports of a [`Module`].
 #[derive(Debug)]
 pub struct ModuleImportsIter<'a> {
@@ -295,12 +455,12 @@ impl<'a> ModuleImport<'a> {
     }
 
     /// Returns the module import name.
-    pub fn module(&self) -> &str {
+    pub fn module(&self) -> &'a str {
         self.name.module()
     }
 
     /// Returns the field import name.
-    pub fn field(&self) -> Option<&str> {
+    pub fn field(&self) -> Option<&'a str> {
         self.name.field()
     }
 
@@ -353,6 +513,70 @@ impl From<GlobalType> for ModuleImportType {
     }
 }
 
+/// An iterator over the exports of a [`Module`].
+#[derive(Debug)]
+pub struct ModuleExportsIter<'a> {
+    module: &'a Module,
+    exports: SliceIter<'a, Export>,
+}
+
+impl<'a> Iterator for ModuleExportsIter<'a> {
+    type Item = ModuleExport<'a>;
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.exports.size_hint()
+    }
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let export = self.exports.next()?;
+        let item_type = match export.external() {
+            External::Func(index) => {
+                ModuleImportType::from(self.module.inner.funcs[index.into_usize()])
+            }
+            External::Table(index) => {
+                ModuleImportType::from(self.module.inner.tables[index.into_usize()])
+            }
+            External::Memory(index) => {
+                ModuleImportType::from(self.module.inner.memories[index.into_usize()])
+            }
+            External::Global(index) => {
+                ModuleImportType::from(self.module.inner.globals[index.into_usize()])
+            }
+        };
+        Some(ModuleExport {
+            field: export.field(),
+            item_type,
+        })
+    }
+}
+
+impl<'a> ExactSizeIterator for ModuleExportsIter<'a> {
+    fn len(&self) -> usize {
+        ExactSizeIterator::len(&self.exports)
+    }
+}
+
+/// A [`Module`] export item.
+#[derive(Debug)]
+pub struct ModuleExport<'a> {
+    /// The name of the exported item.
+    field: &'a str,
+    /// The external item type.
+    item_type: ModuleImportType,
+}
+
+impl<'a> ModuleExport<'a> {
+    /// Returns the export name.
+    pub fn field(&self) -> &'a str {
+        self.field
+    }
+
+    /// Returns the export item type.
+    pub fn item_type(&self) -> &ModuleImportType {
+        &self.item_type
+    }
+}
+
 /// An iterator over the internally defined functions of a [`Module`].
 #[derive(Debug)]
 pub struct InternalFuncsIter<'a> {
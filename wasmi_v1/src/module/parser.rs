@@ -1,5 +1,7 @@
+#[cfg(feature = "std")]
+use super::compile::{translate_parallel, PendingFuncBody};
 use super::{
-    compile::translate,
+    compile::{translate, UncompiledFuncBody, UncompiledResources},
     import::FuncTypeIdx,
     FuncIdx,
     Module,
@@ -8,7 +10,10 @@ use super::{
     ModuleResources,
     Read,
 };
-use crate::Engine;
+use crate::{CompilationMode, Engine};
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
 use wasmparser::{
     Chunk,
     DataSectionReader,
@@ -50,6 +55,27 @@ pub struct ModuleParser<'engine> {
     parser: WasmParser,
     /// Currently processed function.
     func: FuncIdx,
+    /// Determines how function bodies are translated.
+    code: CodeTranslation,
+}
+
+/// Determines how the [`ModuleParser`] translates function bodies.
+enum CodeTranslation {
+    /// Each function body is translated while parsing.
+    Eager,
+    /// Each function body is validated while parsing and translated upon its first call.
+    Lazy(Arc<UncompiledResources>),
+    /// All function bodies are collected while parsing and then
+    /// validated and translated on multiple threads.
+    #[cfg(feature = "std")]
+    Parallel {
+        /// The resources shared by all collected function bodies.
+        res: Arc<UncompiledResources>,
+        /// The collected function bodies.
+        pending: Vec<PendingFuncBody>,
+        /// The number of function bodies of the code section.
+        len_func_bodies: usize,
+    },
 }
 
 impl<'engine> ModuleParser<'engine> {
@@ -64,6 +90,7 @@ impl<'engine> ModuleParser<'engine> {
             validator,
             parser,
             func: FuncIdx(0),
+            code: CodeTranslation::Eager,
         }
     }
 
@@ -434,6 +461,28 @@ impl<'engine> ModuleParser<'engine> {
         // internal function before we process any of the internal functions.
         let len_func_imports = self.builder.imports.funcs.len() as u32;
         self.func = FuncIdx(len_func_imports);
+        // Note: The code section is preceeded by all sections that are required
+        //       for the translation of the function bodies.
+        let res = || {
+            Arc::new(UncompiledResources::new(
+                &self.builder.func_types,
+                &self.builder.funcs,
+                &self.builder.globals,
+            ))
+        };
+        let config = self.builder.engine().config();
+        self.code = match config.compilation_mode() {
+            CompilationMode::Lazy => CodeTranslation::Lazy(res()),
+            #[cfg(feature = "std")]
+            CompilationMode::Eager if config.parallel_compilation() && count > 0 => {
+                CodeTranslation::Parallel {
+                    res: res(),
+                    pending: Vec::with_capacity(count as usize),
+                    len_func_bodies: count as usize,
+                }
+            }
+            CompilationMode::Eager => CodeTranslation::Eager,
+        };
         Ok(())
     }
 
@@ -450,7 +499,9 @@ impl<'engine> ModuleParser<'engine> {
     ///
     /// This contains the local variables and Wasm instructions of
     /// a single function body.
-    /// This procedure is translating the Wasm bytecode into `wasmi` bytecode.
+    /// This procedure is translating the Wasm bytecode into `wasmi` bytecode
+    /// unless [`CompilationMode::Lazy`] defers the translation to its first call
+    /// or parallel compilation defers it to the last function body.
     ///
     /// # Errors
     ///
@@ -458,9 +509,32 @@ impl<'engine> ModuleParser<'engine> {
     fn process_code_entry(&mut self, func_body: FunctionBody) -> Result<(), ModuleError> {
         let func = self.next_func();
         let engine = self.builder.engine();
-        let validator = self.validator.code_section_entry()?;
-        let module_resources = ModuleResources::new(&self.builder);
-        let func_body = translate(engine, func, func_body, validator, module_resources)?;
+        let mut validator = self.validator.code_section_entry()?;
+        let func_body = match &mut self.code {
+            CodeTranslation::Eager => {
+                let module_resources = ModuleResources::new(&self.builder);
+                translate(engine, func, func_body, validator, module_resources)?
+            }
+            CodeTranslation::Lazy(res) => {
+                validator.validate(&func_body)?;
+                let uncompiled = UncompiledFuncBody::new(func, &func_body, res.clone())?;
+                engine.alloc_uncompiled_func_body(uncompiled)
+            }
+            #[cfg(feature = "std")]
+            CodeTranslation::Parallel {
+                res,
+                pending,
+                len_func_bodies,
+            } => {
+                let uncompiled = UncompiledFuncBody::new(func, &func_body, res.clone())?;
+                pending.push((uncompiled, validator));
+                if pending.len() == *len_func_bodies {
+                    let func_bodies = translate_parallel(engine, core::mem::take(pending))?;
+                    self.builder.func_bodies.extend(func_bodies);
+                }
+                return Ok(());
+            }
+        };
         self.builder.func_bodies.push(func_body);
         Ok(())
     }
@@ -0,0 +1,487 @@
+//! Serialization of translated Wasm modules.
+
+use super::{
+    DataSegment,
+    ElementSegment,
+    Export,
+    External,
+    FuncIdx,
+    GlobalIdx,
+    ImportName,
+    Imported,
+    InitExpr,
+    InitExprOperand,
+    MemoryIdx,
+    Module,
+    ModuleError,
+    ModuleImports,
+    ModuleInner,
+    TableIdx,
+};
+use crate::{
+    codec::{DecodeError, Decoder, Encoder},
+    engine::{bytecode::Instruction, DedupFuncType, InstructionIdx, Target},
+    Config,
+    Engine,
+    Error,
+    FuncType,
+    GlobalType,
+    MemoryType,
+    Mutability,
+    TableType,
+};
+use alloc::{sync::Arc, vec::Vec};
+use core::{fmt, fmt::Display};
+use wasmi_core::{UntypedValue, Value, ValueType};
+
+/// The magic bytes at the start of a serialized [`Module`].
+const MAGIC: &[u8; 8] = b"wasmimod";
+
+/// The current version of the serialized [`Module`] format.
+const VERSION: u32 = 3;
+
+/// The version of the `wasmi` crate that serialized a [`Module`].
+const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// An error that may occur upon deserializing a [`Module`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DeserializeError {
+    /// The bytes do not encode a valid serialized [`Module`].
+    InvalidEncoding,
+    /// The [`Module`] has been serialized with an unsupported format version.
+    UnsupportedVersion {
+        /// The format version of the serialized [`Module`].
+        version: u32,
+    },
+    /// The [`Module`] has been serialized by a different version of `wasmi`.
+    MismatchingCrateVersion,
+    /// The [`Module`] has been serialized by an [`Engine`] with a different [`Config`].
+    MismatchingConfig,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DeserializeError {}
+
+impl Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidEncoding => write!(f, "encountered invalid serialized module encoding"),
+            Self::UnsupportedVersion { version } => {
+                write!(
+                    f,
+                    "encountered unsupported serialized module version {}",
+                    version
+                )
+            }
+            Self::MismatchingCrateVersion => {
+                write!(
+                    f,
+                    "module has been serialized by a different version of wasmi"
+                )
+            }
+            Self::MismatchingConfig => {
+                write!(
+                    f,
+                    "module has been serialized by an engine with a different config"
+                )
+            }
+        }
+    }
+}
+
+impl From<DecodeError> for DeserializeError {
+    fn from(_: DecodeError) -> Self {
+        Self::InvalidEncoding
+    }
+}
+
+/// Returns the [`Config`] flags that affect the translation of a [`Module`].
+fn config_flags(config: &Config) -> u8 {
+    [
+        config.mutable_global(),
+        config.sign_extension(),
+        config.saturating_float_to_int(),
+        config.multi_value(),
+    ]
+    .iter()
+    .enumerate()
+    .fold(0, |flags, (n, enabled)| flags | (u8::from(*enabled) << n))
+}
+
+/// A function body decoded from a serialized [`Module`] before it is allocated in the [`Engine`].
+struct DecodedFuncBody {
+    len_params: usize,
+    len_locals: usize,
+    max_stack_height: usize,
+    consts: Vec<UntypedValue>,
+    br_targets: Vec<Target>,
+    insts: Vec<Instruction>,
+}
+
+impl Module {
+    /// Serializes the translated [`Module`] into bytes.
+    ///
+    /// # Note
+    ///
+    /// The bytes contain the translated `wasmi` bytecode of all functions
+    /// and can be turned back into a [`Module`] via [`Module::deserialize`]
+    /// without parsing, validating and translating the Wasm input again.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut encoder = Encoder::default();
+        encoder.bytes(MAGIC);
+        encoder.u32(VERSION);
+        encoder.str(CRATE_VERSION);
+        encoder.u8(config_flags(&self.engine.config()));
+        encoder.vec(&self.inner.func_types, |encoder, func_type| {
+            self.engine.resolve_func_type(*func_type, |func_type| {
+                encoder.vec(func_type.params(), encode_value_type);
+                encoder.vec(func_type.results(), encode_value_type);
+            })
+        });
+        encoder.vec(&self.inner.imports.items, |encoder, imported| {
+            let (kind, name) = match imported {
+                Imported::Func(name) => (0, name),
+                Imported::Table(name) => (1, name),
+                Imported::Memory(name) => (2, name),
+                Imported::Global(name) => (3, name),
+            };
+            encoder.u8(kind);
+            encoder.str(name.module());
+            encode_option(encoder, name.field(), Encoder::str);
+        });
+        encoder.vec(&self.inner.funcs, |encoder, func_type| {
+            encoder.len(self.func_type_index(*func_type));
+        });
+        encoder.vec(&self.inner.tables, |encoder, table_type| {
+            encoder.len(table_type.initial());
+            encode_option(encoder, table_type.maximum(), Encoder::len);
+        });
+        encoder.vec(&self.inner.memories, |encoder, memory_type| {
+            encoder.len(memory_type.initial_pages().0);
+            encode_option(encoder, memory_type.maximum_pages(), |encoder, pages| {
+                encoder.len(pages.0)
+            });
+        });
+        encoder.vec(&self.inner.globals, |encoder, global_type| {
+            encode_value_type(encoder, &global_type.value_type());
+            encoder.u8(match global_type.mutability() {
+                Mutability::Const => 0,
+                Mutability::Mutable => 1,
+            });
+        });
+        encoder.vec(&self.inner.globals_init, encode_init_expr);
+        encoder.vec(&self.inner.exports, |encoder, export| {
+            encoder.str(export.field());
+            let (kind, index) = match export.external() {
+                External::Func(index) => (0, index.into_u32()),
+                External::Table(index) => (1, index.into_u32()),
+                External::Memory(index) => (2, index.into_u32()),
+                External::Global(index) => (3, index.into_u32()),
+            };
+            encoder.u8(kind);
+            encoder.u32(index);
+        });
+        encode_option(&mut encoder, self.inner.start, |encoder, start| {
+            encoder.u32(start.into_u32())
+        });
+        encoder.vec(&self.inner.func_bodies, |encoder, func_body| {
+            self.engine.resolve_func_body(
+                *func_body,
+                |len_params, len_locals, max_stack_height, consts, br_targets, insts| {
+                    encoder.len(len_params);
+                    encoder.len(len_locals);
+                    encoder.len(max_stack_height);
+                    encoder.vec(consts, |encoder, value| encoder.u64(value.to_bits()));
+                    encoder.vec(br_targets, |encoder, target| {
+                        encoder.len(target.destination_pc().into_usize())
+                    });
+                    encoder.vec(insts, |encoder, inst| inst.encode(encoder));
+                },
+            )
+        });
+        encoder.vec(&self.inner.element_segments, |encoder, segment| {
+            encoder.u32(segment.table_index().into_u32());
+            encode_init_expr(encoder, segment.offset());
+            encoder.vec(segment.items(), |encoder, item| {
+                encoder.u32(item.into_u32())
+            });
+        });
+        encoder.vec(&self.inner.data_segments, |encoder, segment| {
+            encoder.u32(segment.memory_index().into_u32());
+            encode_init_expr(encoder, segment.offset());
+            encoder.slice(segment.data());
+        });
+        encoder.into_bytes()
+    }
+
+    /// Deserializes a [`Module`] from bytes produced by [`Module::serialize`].
+    ///
+    /// # Errors
+    ///
+    /// - If the `bytes` do not encode a valid serialized [`Module`].
+    /// - If the `bytes` have been serialized with an unsupported format version.
+    /// - If the `bytes` have been serialized by a different version of `wasmi`.
+    /// - If the `bytes` have been serialized by an [`Engine`] with a different [`Config`].
+    ///
+    /// # Safety
+    ///
+    /// The translated `wasmi` bytecode is not validated again upon deserialization
+    /// and is executed under the assumption that it has been produced by a `wasmi`
+    /// translation. The caller must guarantee that the `bytes` have been produced
+    /// by [`Module::serialize`] and have not been tampered with since.
+    pub unsafe fn deserialize(engine: &Engine, bytes: &[u8]) -> Result<Self, Error> {
+        Self::decode(engine, bytes).map_err(|error| Error::Module(ModuleError::Deserialize(error)))
+    }
+
+    /// Decodes a [`Module`] for the [`Engine`] from bytes produced by [`Module::serialize`].
+    fn decode(engine: &Engine, bytes: &[u8]) -> Result<Self, DeserializeError> {
+        let mut decoder = Decoder::new(bytes);
+        if decoder.bytes(MAGIC.len())? != MAGIC {
+            return Err(DeserializeError::InvalidEncoding);
+        }
+        let version = decoder.u32()?;
+        if version != VERSION {
+            return Err(DeserializeError::UnsupportedVersion { version });
+        }
+        if decoder.str()? != CRATE_VERSION {
+            return Err(DeserializeError::MismatchingCrateVersion);
+        }
+        if decoder.u8()? != config_flags(&engine.config()) {
+            return Err(DeserializeError::MismatchingConfig);
+        }
+        // Function types and bodies are decoded into local buffers first and
+        // allocated into the engine only after the whole input has been decoded
+        // so that invalid inputs do not leak them into the engine.
+        let func_types = decoder.vec(|decoder| {
+            let params = decoder.vec(decode_value_type)?;
+            let results = decoder.vec(decode_value_type)?;
+            Ok(FuncType::new(params, results))
+        })?;
+        let items = decoder.vec(|decoder| {
+            let kind = decoder.u8()?;
+            let module = decoder.str()?;
+            let field = decode_option(decoder, |decoder| decoder.str())?;
+            let name = ImportName::new(&module, field.as_deref());
+            match kind {
+                0 => Ok(Imported::Func(name)),
+                1 => Ok(Imported::Table(name)),
+                2 => Ok(Imported::Memory(name)),
+                3 => Ok(Imported::Global(name)),
+                _ => Err(DecodeError),
+            }
+        })?;
+        let count = |f: fn(&Imported) -> bool| items.iter().filter(|item| f(item)).count();
+        let imports = ModuleImports {
+            len_funcs: count(|item| matches!(item, Imported::Func(_))),
+            len_tables: count(|item| matches!(item, Imported::Table(_))),
+            len_memories: count(|item| matches!(item, Imported::Memory(_))),
+            len_globals: count(|item| matches!(item, Imported::Global(_))),
+            items: items.into(),
+        };
+        let funcs = decoder.vec(|decoder| {
+            let index = decoder.len()?;
+            match index < func_types.len() {
+                true => Ok(index),
+                false => Err(DecodeError),
+            }
+        })?;
+        let tables = decoder.vec(|decoder| {
+            let initial = decoder.len()?;
+            let maximum = decode_option(decoder, |decoder| decoder.len())?;
+            Ok(TableType::new(initial, maximum))
+        })?;
+        let memories = decoder.vec(|decoder| {
+            let initial = decoder.u32()?;
+            let maximum = decode_option(decoder, |decoder| decoder.u32())?;
+            Ok(MemoryType::new(initial, maximum))
+        })?;
+        let globals = decoder.vec(|decoder| {
+            let value_type = decode_value_type(decoder)?;
+            let mutability = match decoder.u8()? {
+                0 => Mutability::Const,
+                1 => Mutability::Mutable,
+                _ => return Err(DecodeError),
+            };
+            Ok(GlobalType::new(value_type, mutability))
+        })?;
+        let globals_init = decoder.vec(decode_init_expr)?;
+        let exports = decoder.vec(|decoder| {
+            let field = decoder.str()?.into();
+            let kind = decoder.u8()?;
+            let index = decoder.u32()?;
+            let external = match kind {
+                0 => External::Func(FuncIdx(index)),
+                1 => External::Table(TableIdx(index)),
+                2 => External::Memory(MemoryIdx(index)),
+                3 => External::Global(GlobalIdx(index)),
+                _ => return Err(DecodeError),
+            };
+            Ok(Export::new(field, external))
+        })?;
+        let start = decode_option(&mut decoder, |decoder| decoder.u32().map(FuncIdx))?;
+        let func_bodies = decoder.vec(|decoder| {
+            let len_params = decoder.len()?;
+            let len_locals = decoder.len()?;
+            let max_stack_height = decoder.len()?;
+            if len_params > usize::from(u16::MAX) || len_locals > usize::from(u16::MAX) {
+                // Wasm validation limits the amount of parameters and local variables.
+                return Err(DecodeError);
+            }
+            let consts = decoder.vec(|decoder| decoder.u64().map(UntypedValue::from))?;
+            let br_targets = decoder.vec(|decoder| {
+                let dst_pc = InstructionIdx::from_usize(decoder.len()?);
+                Ok(Target::new(dst_pc))
+            })?;
+            let insts = decoder.vec(Instruction::decode)?;
+            Ok(DecodedFuncBody {
+                len_params,
+                len_locals,
+                max_stack_height,
+                consts,
+                br_targets,
+                insts,
+            })
+        })?;
+        let element_segments = decoder.vec(|decoder| {
+            let table_index = TableIdx(decoder.u32()?);
+            let offset = decode_init_expr(decoder)?;
+            let items = decoder.vec(|decoder| decoder.u32().map(FuncIdx))?;
+            Ok(ElementSegment::new(table_index, offset, items.into()))
+        })?;
+        let data_segments = decoder.vec(|decoder| {
+            let memory_index = MemoryIdx(decoder.u32()?);
+            let offset = decode_init_expr(decoder)?;
+            let data = decoder.slice()?;
+            Ok(DataSegment::new(memory_index, offset, data.into()))
+        })?;
+        decoder.finish()?;
+        let func_types = func_types
+            .into_iter()
+            .map(|func_type| engine.alloc_func_type(func_type))
+            .collect::<Vec<_>>();
+        let funcs = funcs
+            .into_iter()
+            .map(|index| func_types[index])
+            .collect::<Vec<_>>();
+        let func_bodies = func_bodies
+            .into_iter()
+            .map(|body| {
+                engine.alloc_func_body(
+                    body.len_params,
+                    body.len_locals,
+                    body.max_stack_height,
+                    body.consts,
+                    body.br_targets,
+                    body.insts,
+                )
+            })
+            .collect::<Vec<_>>();
+        let mut inner = ModuleInner {
+            func_types: func_types.into(),
+            imports,
+            funcs: funcs.into(),
+            tables: tables.into(),
+            memories: memories.into(),
+            globals: globals.into(),
+            globals_init: globals_init.into(),
+            exports: exports.into(),
+            start,
+            func_bodies: func_bodies.into(),
+            element_segments: element_segments.into(),
+            data_segments: data_segments.into(),
+            memory_image: None,
+        };
+        if engine.config().copy_on_write_memory_init() {
+            inner.memory_image = inner.build_memory_image();
+        }
+        Ok(Self {
+            engine: engine.clone(),
+            inner: Arc::new(inner),
+        })
+    }
+
+    /// Returns the index of the deduplicated `func_type` within the [`Module`].
+    ///
+    /// # Panics
+    ///
+    /// If the `func_type` is not a function type of the [`Module`].
+    fn func_type_index(&self, func_type: DedupFuncType) -> usize {
+        self.inner
+            .func_types
+            .iter()
+            .position(|candidate| *candidate == func_type)
+            .unwrap_or_else(|| panic!("missing function type in module: {:?}", func_type))
+    }
+}
+
+fn encode_option<T>(encoder: &mut Encoder, value: Option<T>, f: impl FnOnce(&mut Encoder, T)) {
+    match value {
+        Some(value) => {
+            encoder.u8(1);
+            f(encoder, value);
+        }
+        None => encoder.u8(0),
+    }
+}
+
+fn decode_option<T>(
+    decoder: &mut Decoder,
+    f: impl FnOnce(&mut Decoder) -> Result<T, DecodeError>,
+) -> Result<Option<T>, DecodeError> {
+    match decoder.u8()? {
+        0 => Ok(None),
+        1 => f(decoder).map(Some),
+        _ => Err(DecodeError),
+    }
+}
+
+fn encode_value_type(encoder: &mut Encoder, value_type: &ValueType) {
+    encoder.u8(match value_type {
+        ValueType::I32 => 0,
+        ValueType::I64 => 1,
+        ValueType::F32 => 2,
+        ValueType::F64 => 3,
+    });
+}
+
+fn decode_value_type(decoder: &mut Decoder) -> Result<ValueType, DecodeError> {
+    match decoder.u8()? {
+        0 => Ok(ValueType::I32),
+        1 => Ok(ValueType::I64),
+        2 => Ok(ValueType::F32),
+        3 => Ok(ValueType::F64),
+        _ => Err(DecodeError),
+    }
+}
+
+fn encode_init_expr(encoder: &mut Encoder, init_expr: &InitExpr) {
+    encoder.vec(init_expr.operators(), |encoder, op| match op {
+        InitExprOperand::Const(value) => {
+            encoder.u8(0);
+            encode_value_type(encoder, &value.value_type());
+            encoder.u64(UntypedValue::from(*value).to_bits());
+        }
+        InitExprOperand::GlobalGet(global_index) => {
+            encoder.u8(1);
+            encoder.u32(global_index.into_u32());
+        }
+    });
+}
+
+fn decode_init_expr(decoder: &mut Decoder) -> Result<InitExpr, DecodeError> {
+    // Note: Initializer expressions are limited to a single operand for now.
+    if decoder.len()? != 1 {
+        return Err(DecodeError);
+    }
+    let op = match decoder.u8()? {
+        0 => {
+            let value_type = decode_value_type(decoder)?;
+            let value: Value = UntypedValue::from(decoder.u64()?).with_type(value_type);
+            InitExprOperand::Const(value)
+        }
+        1 => InitExprOperand::GlobalGet(GlobalIdx(decoder.u32()?)),
+        _ => return Err(DecodeError),
+    };
+    Ok(InitExpr::new(op))
+}
@@ -1,8 +1,16 @@
 use super::*;
 use crate::{
     engine::{
-        bytecode::{Instruction, LocalIdx},
-        DropKeep,
+        bytecode::{
+            BinaryOp,
+            BranchCmpOp,
+            FuncIdx,
+            ImmOp,
+            Instruction,
+            Register,
+            RegisterSpan,
+            UnaryOp,
+        },
         InstructionIdx,
         Target,
     },
@@ -91,6 +99,50 @@ where
     }
 }
 
+/// Asserts that the first function of the `wasm_bytes` has the expected `br_table` targets.
+///
+/// # Panics
+///
+/// If the `br_table` target pool of the function differs from `expected`.
+fn assert_br_targets<E>(wasm_bytes: impl AsRef<[u8]>, expected: E)
+where
+    E: IntoIterator<Item = Target>,
+{
+    let module = create_module(wasm_bytes.as_ref());
+    let engine = module.engine();
+    let (_, func_body) = module
+        .internal_funcs()
+        .next()
+        .unwrap_or_else(|| panic!("expected at least one internal function"));
+    assert_eq!(
+        engine.resolve_br_targets(func_body),
+        expected.into_iter().collect::<Vec<_>>(),
+    );
+}
+
+/// Creates a [`Register`] at the given `index`.
+fn r(index: u32) -> Register {
+    Register::from(index)
+}
+
+/// Creates a [`RegisterSpan`] of `len` registers starting at `start`.
+fn span(start: u32, len: u32) -> RegisterSpan {
+    RegisterSpan::new(r(start), len)
+}
+
+/// Creates a [`Target`] to the instruction at `inst_idx`.
+fn target(inst_idx: usize) -> Target {
+    Target::new(InstructionIdx::from_usize(inst_idx))
+}
+
+/// Creates an [`Instruction::Copy`] from `input` to `result`.
+fn copy(result: u32, input: u32) -> Instruction {
+    Instruction::Copy(UnaryOp {
+        result: r(result),
+        input: r(input),
+    })
+}
+
 #[test]
 fn implicit_return_no_value() {
     let wasm = wat2wasm(
@@ -101,7 +153,7 @@ fn implicit_return_no_value() {
         )
     "#,
     );
-    let expected = [Instruction::Return(DropKeep::new(0, 0))];
+    let expected = [Instruction::Return(span(0, 0))];
     assert_func_bodies(&wasm, [expected]);
 }
 
@@ -116,10 +168,8 @@ fn implicit_return_with_value() {
         )
     "#,
     );
-    let expected = [
-        Instruction::constant(0),
-        Instruction::Return(DropKeep::new(0, 1)),
-    ];
+    // The constant resides in the register following the parameters and locals.
+    let expected = [Instruction::Return(span(0, 1))];
     assert_func_bodies(&wasm, [expected]);
 }
 
@@ -133,7 +183,7 @@ fn implicit_return_param() {
         )
     "#,
     );
-    let expected = [Instruction::Return(DropKeep::new(1, 0))];
+    let expected = [Instruction::Return(span(0, 0))];
     assert_func_bodies(&wasm, [expected]);
 }
 
@@ -148,10 +198,7 @@ fn get_local() {
         )
     "#,
     );
-    let expected = [
-        Instruction::local_get(1),
-        Instruction::Return(DropKeep::new(1, 1)),
-    ];
+    let expected = [Instruction::Return(span(0, 1))];
     assert_func_bodies(&wasm, [expected]);
 }
 
@@ -168,12 +215,7 @@ fn get_local_2() {
         )
     "#,
     );
-    let expected = [
-        Instruction::local_get(2),
-        Instruction::local_get(2),
-        Instruction::Drop,
-        Instruction::Return(DropKeep::new(2, 1)),
-    ];
+    let expected = [Instruction::Return(span(0, 1))];
     assert_func_bodies(&wasm, [expected]);
 }
 
@@ -191,13 +233,7 @@ fn get_local_3() {
         )
     "#,
     );
-    let expected = [
-        Instruction::local_get(2),
-        Instruction::local_get(2),
-        Instruction::Drop,
-        Instruction::Drop,
-        Instruction::Return(DropKeep::new(2, 0)),
-    ];
+    let expected = [Instruction::Return(span(0, 0))];
     assert_func_bodies(&wasm, [expected]);
 }
 
@@ -213,10 +249,7 @@ fn explicit_return() {
         )
     "#,
     );
-    let expected = [
-        Instruction::local_get(1),
-        Instruction::Return(DropKeep::new(1, 1)),
-    ];
+    let expected = [Instruction::Return(span(0, 1))];
     assert_func_bodies(&wasm, [expected]);
 }
 
@@ -234,10 +267,12 @@ fn simple_add() {
     "#,
     );
     let expected = [
-        Instruction::local_get(2),
-        Instruction::local_get(2),
-        Instruction::I32Add,
-        Instruction::Return(DropKeep::new(2, 1)),
+        Instruction::I32Add(BinaryOp {
+            result: r(2),
+            lhs: r(0),
+            rhs: r(1),
+        }),
+        Instruction::Return(span(2, 1)),
     ];
     assert_func_bodies(&wasm, [expected]);
 }
@@ -260,14 +295,22 @@ fn simple_mul_add() {
     "#,
     );
     let expected = [
-        Instruction::local_get(2),
-        Instruction::local_get(2),
-        Instruction::local_get(4),
-        Instruction::local_get(4),
-        Instruction::I32Add,
-        Instruction::I32Add,
-        Instruction::I32Mul,
-        Instruction::Return(DropKeep::new(2, 1)),
+        Instruction::I32Add(BinaryOp {
+            result: r(4),
+            lhs: r(0),
+            rhs: r(1),
+        }),
+        Instruction::I32Add(BinaryOp {
+            result: r(3),
+            lhs: r(1),
+            rhs: r(4),
+        }),
+        Instruction::I32Mul(BinaryOp {
+            result: r(2),
+            lhs: r(0),
+            rhs: r(3),
+        }),
+        Instruction::Return(span(2, 1)),
     ];
     assert_func_bodies(&wasm, [expected]);
 }
@@ -285,21 +328,118 @@ fn drop_locals() {
         )
     "#,
     );
+    let expected = [copy(1, 0), Instruction::Return(span(0, 0))];
+    assert_func_bodies(&wasm, [expected]);
+}
+
+#[test]
+fn local_set_writes_result_directly() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (export "call") (param i32) (param i32)
+                (local i32)
+                local.get 0
+                local.get 1
+                i32.add
+                local.set 2
+            )
+        )
+    "#,
+    );
     let expected = [
-        Instruction::local_get(2),
-        Instruction::local_set(1),
-        Instruction::Return(DropKeep::new(2, 0)),
+        Instruction::I32Add(BinaryOp {
+            result: r(2),
+            lhs: r(0),
+            rhs: r(1),
+        }),
+        Instruction::Return(span(0, 0)),
     ];
     assert_func_bodies(&wasm, [expected]);
 }
 
-macro_rules! target {
-    ( $inst_idx:expr, drop: $drop:expr, keep: $keep:expr ) => {
-        Target::new(
-            InstructionIdx::from_usize($inst_idx),
-            DropKeep::new($drop, $keep),
+#[test]
+fn local_set_preserves_stack_values() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (export "call") (param i32) (result i32)
+                local.get 0
+                i32.const 1
+                local.set 0
+            )
+        )
+    "#,
+    );
+    let expected = [copy(2, 0), copy(0, 1), Instruction::Return(span(2, 1))];
+    assert_func_bodies(&wasm, [expected]);
+}
+
+#[test]
+fn call_params_are_consecutive() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func $add (param i32) (param i32) (result i32)
+                local.get 0
+                local.get 1
+                i32.add
+            )
+            (func (export "call") (param i32) (result i32)
+                local.get 0
+                i32.const 1
+                call $add
+            )
+        )
+    "#,
+    );
+    let add = [
+        Instruction::I32Add(BinaryOp {
+            result: r(2),
+            lhs: r(0),
+            rhs: r(1),
+        }),
+        Instruction::Return(span(2, 1)),
+    ];
+    let call = [
+        copy(2, 0),
+        copy(3, 1),
+        Instruction::Call {
+            func: FuncIdx::from(0),
+            params: r(2),
+        },
+        Instruction::Return(span(2, 1)),
+    ];
+    assert_func_bodies(
+        &wasm,
+        [&add[..], &call[..]].map(|insts| insts.iter().copied()),
+    );
+}
+
+#[test]
+fn select() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (export "call") (param i32) (param i32) (param i32) (result i32)
+                local.get 0
+                local.get 1
+                local.get 2
+                select
+            )
         )
-    };
+    "#,
+    );
+    let expected = [
+        copy(3, 0),
+        Instruction::Select {
+            result: r(3),
+            condition: r(2),
+            if_false: r(1),
+        },
+        Instruction::Return(span(3, 1)),
+    ];
+    assert_func_bodies(&wasm, [expected]);
 }
 
 #[test]
@@ -308,7 +448,7 @@ fn if_without_else() {
         r#"
         (module
             (func (export "call") (param i32) (result i32)
-                i32.const 1
+                local.get 0
                 if
                     i32.const 2
                     return
@@ -319,12 +459,13 @@ fn if_without_else() {
     "#,
     );
     let expected = [
-        /* 0 */ Instruction::constant(1),
-        /* 1 */ Instruction::BrIfEqz(target!(4, drop: 0, keep: 0)),
-        /* 2 */ Instruction::constant(2),
-        /* 3 */ Instruction::Return(DropKeep::new(1, 1)),
-        /* 4 */ Instruction::constant(3),
-        /* 5 */ Instruction::Return(DropKeep::new(1, 1)),
+        /* 0 */
+        Instruction::BrIfEqz {
+            condition: r(0),
+            target: target(2),
+        },
+        /* 1 */ Instruction::Return(span(1, 1)),
+        /* 2 */ Instruction::Return(span(2, 1)),
     ];
     assert_func_bodies(&wasm, [expected]);
 }
@@ -334,29 +475,30 @@ fn if_else() {
     let wasm = wat2wasm(
         r#"
         (module
-            (func (export "call")
+            (func (export "call") (param i32)
                 (local i32)
-                i32.const 1
+                local.get 0
                 if
                     i32.const 2
-                    local.set 0
+                    local.set 1
                 else
                     i32.const 3
-                    local.set 0
+                    local.set 1
                 end
             )
         )
     "#,
     );
     let expected = [
-        Instruction::constant(1),
-        Instruction::BrIfEqz(target!(5, drop: 0, keep: 0)),
-        Instruction::constant(2),
-        Instruction::local_set(1),
-        Instruction::Br(target!(7, drop: 0, keep: 0)),
-        Instruction::constant(3),
-        Instruction::local_set(1),
-        Instruction::Return(DropKeep::new(1, 0)),
+        /* 0 */
+        Instruction::BrIfEqz {
+            condition: r(0),
+            target: target(3),
+        },
+        /* 1 */ copy(1, 2),
+        /* 2 */ Instruction::Br(target(4)),
+        /* 3 */ copy(1, 3),
+        /* 4 */ Instruction::Return(span(0, 0)),
     ];
     assert_func_bodies(&wasm, [expected]);
 }
@@ -366,8 +508,8 @@ fn if_else_returns_result() {
     let wasm = wat2wasm(
         r#"
         (module
-            (func (export "call")
-                i32.const 1
+            (func (export "call") (param i32)
+                local.get 0
                 if (result i32)
                     i32.const 2
                 else
@@ -379,13 +521,15 @@ fn if_else_returns_result() {
     "#,
     );
     let expected = [
-        /* 0 */ Instruction::constant(1),
-        /* 1 */ Instruction::BrIfEqz(target!(4, drop: 0, keep: 0)),
-        /* 2 */ Instruction::constant(2),
-        /* 3 */ Instruction::Br(target!(5, drop: 0, keep: 0)),
-        /* 4 */ Instruction::constant(3),
-        /* 5 */ Instruction::Drop,
-        /* 6 */ Instruction::Return(DropKeep::new(0, 0)),
+        /* 0 */
+        Instruction::BrIfEqz {
+            condition: r(0),
+            target: target(3),
+        },
+        /* 1 */ copy(3, 1),
+        /* 2 */ Instruction::Br(target(4)),
+        /* 3 */ copy(3, 2),
+        /* 4 */ Instruction::Return(span(0, 0)),
     ];
     assert_func_bodies(&wasm, [expected]);
 }
@@ -395,11 +539,11 @@ fn if_else_branch_from_true_branch() {
     let wasm = wat2wasm(
         r#"
         (module
-            (func (export "call")
-                i32.const 1
+            (func (export "call") (param i32) (param i32)
+                local.get 0
                 if (result i32)
                     i32.const 1
-                    i32.const 1
+                    local.get 1
                     br_if 0
                     drop
                     i32.const 2
@@ -412,17 +556,21 @@ fn if_else_branch_from_true_branch() {
     "#,
     );
     let expected = [
-        /*  0 */ Instruction::constant(1),
-        /*  1 */ Instruction::BrIfEqz(target!(8, drop: 0, keep: 0)),
-        /*  2 */ Instruction::constant(1),
-        /*  3 */ Instruction::constant(1),
-        /*  4 */ Instruction::BrIfNez(target!(9, drop: 0, keep: 1)),
-        /*  5 */ Instruction::Drop,
-        /*  6 */ Instruction::constant(2),
-        /*  7 */ Instruction::Br(target!(9, drop: 0, keep: 0)),
-        /*  8 */ Instruction::constant(3),
-        /*  9 */ Instruction::Drop,
-        /* 10 */ Instruction::Return(DropKeep::new(0, 0)),
+        /* 0 */
+        Instruction::BrIfEqz {
+            condition: r(0),
+            target: target(5),
+        },
+        /* 1 */ copy(5, 2),
+        /* 2 */
+        Instruction::BrIfNez {
+            condition: r(1),
+            target: target(6),
+        },
+        /* 3 */ copy(5, 3),
+        /* 4 */ Instruction::Br(target(6)),
+        /* 5 */ copy(5, 4),
+        /* 6 */ Instruction::Return(span(0, 0)),
     ];
     assert_func_bodies(&wasm, [expected]);
 }
@@ -432,13 +580,13 @@ fn if_else_branch_from_false_branch() {
     let wasm = wat2wasm(
         r#"
         (module
-            (func (export "call")
-                i32.const 1
+            (func (export "call") (param i32) (param i32)
+                local.get 0
                 if (result i32)
                     i32.const 1
                 else
                     i32.const 2
-                    i32.const 1
+                    local.get 1
                     br_if 0
                     drop
                     i32.const 3
@@ -449,17 +597,21 @@ fn if_else_branch_from_false_branch() {
     "#,
     );
     let expected = [
-        /*  0 */ Instruction::constant(1),
-        /*  1 */ Instruction::BrIfEqz(target!(4, drop: 0, keep: 0)),
-        /*  2 */ Instruction::constant(1),
-        /*  3 */ Instruction::Br(target!(9, drop: 0, keep: 0)),
-        /*  4 */ Instruction::constant(2),
-        /*  5 */ Instruction::constant(1),
-        /*  6 */ Instruction::BrIfNez(target!(9, drop: 0, keep: 1)),
-        /*  7 */ Instruction::Drop,
-        /*  8 */ Instruction::constant(3),
-        /*  9 */ Instruction::Drop,
-        /* 10 */ Instruction::Return(DropKeep::new(0, 0)),
+        /* 0 */
+        Instruction::BrIfEqz {
+            condition: r(0),
+            target: target(3),
+        },
+        /* 1 */ copy(5, 2),
+        /* 2 */ Instruction::Br(target(6)),
+        /* 3 */ copy(5, 3),
+        /* 4 */
+        Instruction::BrIfNez {
+            condition: r(1),
+            target: target(6),
+        },
+        /* 5 */ copy(5, 4),
+        /* 6 */ Instruction::Return(span(0, 0)),
     ];
     assert_func_bodies(&wasm, [expected]);
 }
@@ -487,15 +639,14 @@ fn if_else_both_unreachable_before_end() {
     "#,
     );
     let expected = [
-        /* 0 */ Instruction::local_get(1),
-        /* 1 */ Instruction::BrIfEqz(target!(4, drop: 0, keep: 0)),
-        /* 2 */ Instruction::constant(1),
-        /* 3 */ Instruction::Return(DropKeep::new(1, 1)),
-        /* 4 */ Instruction::constant(2),
-        /* 5 */ Instruction::Return(DropKeep::new(1, 1)),
-        /* 6 */ Instruction::Drop,
-        /* 7 */ Instruction::constant(3),
-        /* 8 */ Instruction::Return(DropKeep::new(1, 1)),
+        /* 0 */
+        Instruction::BrIfEqz {
+            condition: r(0),
+            target: target(2),
+        },
+        /* 1 */ Instruction::Return(span(1, 1)),
+        /* 2 */ Instruction::Return(span(2, 1)),
+        /* 3 */ Instruction::Return(span(3, 1)),
     ];
     assert_func_bodies(&wasm, [expected]);
 }
@@ -505,23 +656,23 @@ fn loop_() {
     let wasm = wat2wasm(
         r#"
         (module
-            (func (export "call")
+            (func (export "call") (param i32) (result i32)
                 loop (result i32)
-                    i32.const 1
+                    local.get 0
                     br_if 0
                     i32.const 2
                 end
-                drop
             )
         )
     "#,
     );
     let expected = [
-        Instruction::constant(1),
-        Instruction::BrIfNez(target!(0, drop: 0, keep: 0)),
-        Instruction::constant(2),
-        Instruction::Drop,
-        Instruction::Return(DropKeep::new(0, 0)),
+        Instruction::BrIfNez {
+            condition: r(0),
+            target: target(0),
+        },
+        copy(2, 1),
+        Instruction::Return(span(2, 1)),
     ];
     assert_func_bodies(&wasm, [expected]);
 }
@@ -538,7 +689,7 @@ fn loop_empty() {
         )
     "#,
     );
-    let expected = [Instruction::Return(DropKeep::new(0, 0))];
+    let expected = [Instruction::Return(span(0, 0))];
     assert_func_bodies(&wasm, [expected]);
 }
 
@@ -563,15 +714,21 @@ fn spec_as_br_if_value_cond() {
     "#,
     );
     let expected = [
-        /* 0 */ Instruction::constant(6),
-        /* 1 */ Instruction::constant(9),
-        /* 2 */ Instruction::constant(0),
-        /* 3 */ Instruction::BrTable { len_targets: 2 },
-        /* 4 */ Instruction::Br(target!(6, drop: 1, keep: 1)),
-        /* 5 */ Instruction::Br(target!(6, drop: 1, keep: 1)),
-        /* 6 */ Instruction::Return(DropKeep::new(0, 1)),
+        /* 0 */ copy(3, 1),
+        /* 1 */
+        Instruction::BrTable {
+            index: r(0),
+            offset: 0,
+            len_targets: 2,
+        },
+        /* 2 */ copy(2, 3),
+        /* 3 */ Instruction::Br(target(6)),
+        /* 4 */ copy(2, 3),
+        /* 5 */ Instruction::Br(target(6)),
+        /* 6 */ Instruction::Return(span(2, 1)),
     ];
     assert_func_bodies(&wasm, [expected]);
+    assert_br_targets(&wasm, [target(2), target(4)]);
 }
 
 #[test]
@@ -591,13 +748,16 @@ fn br_table() {
     "#,
     );
     let expected = [
-        /* 0 */ Instruction::constant(0),
-        /* 1 */ Instruction::BrTable { len_targets: 2 },
-        /* 2 */ Instruction::Br(target!(0, drop: 0, keep: 0)),
-        /* 3 */ Instruction::Br(target!(4, drop: 0, keep: 0)),
-        /* 4 */ Instruction::Return(DropKeep::new(0, 0)),
+        /* 0 */
+        Instruction::BrTable {
+            index: r(0),
+            offset: 0,
+            len_targets: 2,
+        },
+        /* 1 */ Instruction::Return(span(1, 0)),
     ];
     assert_func_bodies(&wasm, [expected]);
+    assert_br_targets(&wasm, [target(0), target(1)]);
 }
 
 #[test]
@@ -620,16 +780,58 @@ fn br_table_returns_result() {
     "#,
     );
     let expected = [
-        /* 0 */ Instruction::constant(0),
-        /* 1 */ Instruction::constant(1),
-        /* 2 */ Instruction::BrTable { len_targets: 2 },
-        /* 3 */ Instruction::Br(target!(5, drop: 0, keep: 1)),
-        /* 4 */ Instruction::Br(target!(6, drop: 0, keep: 1)),
-        /* 5 */ Instruction::Unreachable,
-        /* 6 */ Instruction::Drop,
-        /* 7 */ Instruction::Return(DropKeep::new(0, 0)),
+        /* 0 */ copy(2, 1),
+        /* 1 */
+        Instruction::BrTable {
+            index: r(0),
+            offset: 0,
+            len_targets: 2,
+        },
+        /* 2 */ Instruction::Unreachable,
+        /* 3 */ Instruction::Return(span(2, 0)),
+    ];
+    assert_func_bodies(&wasm, [expected]);
+    assert_br_targets(&wasm, [target(2), target(3)]);
+}
+
+#[test]
+fn br_table_multiple() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (export "call") (param i32)
+                block $1
+                    block $0
+                        local.get 0
+                        br_table $0 $1
+                    end
+                    local.get 0
+                    br_table $1 $1 $1
+                end
+            )
+        )
+    "#,
+    );
+    let expected = [
+        /* 0 */
+        Instruction::BrTable {
+            index: r(0),
+            offset: 0,
+            len_targets: 2,
+        },
+        /* 1 */
+        Instruction::BrTable {
+            index: r(0),
+            offset: 2,
+            len_targets: 3,
+        },
+        /* 2 */ Instruction::Return(span(0, 0)),
     ];
     assert_func_bodies(&wasm, [expected]);
+    assert_br_targets(
+        &wasm,
+        [target(1), target(2), target(2), target(2), target(2)],
+    );
 }
 
 #[test]
@@ -651,12 +853,13 @@ fn wabt_example() {
     "#,
     );
     let expected = [
-        /* 0 */ Instruction::local_get(1),
-        /* 1 */ Instruction::BrIfNez(target!(4, drop: 0, keep: 0)),
-        /* 2 */ Instruction::constant(1),
-        /* 3 */ Instruction::Return(DropKeep::new(1, 1)),
-        /* 4 */ Instruction::constant(2),
-        /* 5 */ Instruction::Return(DropKeep::new(1, 1)),
+        /* 0 */
+        Instruction::BrIfNez {
+            condition: r(0),
+            target: target(2),
+        },
+        /* 1 */ Instruction::Return(span(1, 1)),
+        /* 2 */ Instruction::Return(span(2, 1)),
     ];
     assert_func_bodies(&wasm, [expected]);
 }
@@ -673,7 +876,7 @@ fn br_return() {
         )
     "#,
     );
-    let expected = [Instruction::Return(DropKeep::new(0, 0))];
+    let expected = [Instruction::Return(span(0, 0))];
     assert_func_bodies(&wasm, [expected]);
 }
 
@@ -691,9 +894,11 @@ fn br_if_return() {
     "#,
     );
     let expected = [
-        Instruction::local_get(1),
-        Instruction::ReturnIfNez(DropKeep::new(1, 0)),
-        Instruction::Return(DropKeep::new(1, 0)),
+        Instruction::ReturnIfNez {
+            condition: r(0),
+            results: span(0, 0),
+        },
+        Instruction::Return(span(0, 0)),
     ];
     assert_func_bodies(&wasm, [expected]);
 }
@@ -716,12 +921,446 @@ fn br_table_return() {
     "#,
     );
     let expected = [
-        /* 0 */ Instruction::local_get(1),
-        /* 1 */ Instruction::BrTable { len_targets: 3 },
-        /* 2 */ Instruction::Br(target!(5, drop: 0, keep: 0)),
-        /* 3 */ Instruction::Br(target!(5, drop: 0, keep: 0)),
-        /* 4 */ Instruction::Return(DropKeep::new(1, 0)),
-        /* 5 */ Instruction::Return(DropKeep::new(1, 0)),
+        /* 0 */
+        Instruction::BrTable {
+            index: r(0),
+            offset: 0,
+            len_targets: 3,
+        },
+        /* 1 */ Instruction::Return(span(0, 0)),
+        /* 2 */ Instruction::Return(span(0, 0)),
+    ];
+    assert_func_bodies(&wasm, [expected]);
+    assert_br_targets(&wasm, [target(2), target(2), target(1)]);
+}
+
+#[test]
+fn br_if_fuses_compare() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (export "call") (param i32) (param i32)
+                block
+                    local.get 0
+                    local.get 1
+                    i32.lt_s
+                    br_if 0
+                end
+            )
+        )
+    "#,
+    );
+    let expected = [
+        /* 0 */
+        Instruction::BrIfI32LtS(BranchCmpOp {
+            lhs: r(0),
+            rhs: r(1),
+            target: target(1),
+        }),
+        /* 1 */ Instruction::Return(span(0, 0)),
+    ];
+    assert_func_bodies(&wasm, [expected]);
+}
+
+#[test]
+fn br_if_fuses_compare_in_loop() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (export "call") (param i32) (param i64)
+                loop
+                    local.get 1
+                    i64.const 1
+                    i64.sub
+                    local.tee 1
+                    local.get 0
+                    i64.extend_i32_u
+                    i64.gt_u
+                    br_if 0
+                end
+            )
+        )
+    "#,
+    );
+    let expected = [
+        /* 0 */
+        Instruction::I64AddImm(ImmOp {
+            result: r(1),
+            input: r(1),
+            imm: -1,
+        }),
+        /* 1 */
+        Instruction::I64ExtendUI32(UnaryOp {
+            result: r(3),
+            input: r(0),
+        }),
+        /* 2 */
+        Instruction::BrIfI64GtU(BranchCmpOp {
+            lhs: r(1),
+            rhs: r(3),
+            target: target(0),
+        }),
+        /* 3 */ Instruction::Return(span(0, 0)),
+    ];
+    assert_func_bodies(&wasm, [expected]);
+}
+
+#[test]
+fn br_if_fuses_eqz() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (export "call") (param i32)
+                block
+                    local.get 0
+                    i32.eqz
+                    br_if 0
+                end
+            )
+        )
+    "#,
+    );
+    let expected = [
+        /* 0 */
+        Instruction::BrIfEqz {
+            condition: r(0),
+            target: target(1),
+        },
+        /* 1 */ Instruction::Return(span(0, 0)),
+    ];
+    assert_func_bodies(&wasm, [expected]);
+}
+
+#[test]
+fn br_if_with_values_fuses_inverted_compare() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (export "call") (param i32) (param i32) (result i32)
+                block (result i32)
+                    local.get 0
+                    local.get 1
+                    local.get 0
+                    local.get 1
+                    i32.eq
+                    br_if 0
+                    drop
+                end
+            )
+        )
+    "#,
+    );
+    let expected = [
+        /* 0 */
+        Instruction::BrIfI32Ne(BranchCmpOp {
+            lhs: r(0),
+            rhs: r(1),
+            target: target(3),
+        }),
+        /* 1 */ copy(2, 1),
+        /* 2 */ Instruction::Br(target(4)),
+        /* 3 */ copy(2, 0),
+        /* 4 */ Instruction::Return(span(2, 1)),
+    ];
+    assert_func_bodies(&wasm, [expected]);
+}
+
+#[test]
+fn if_fuses_inverted_compare() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (export "call") (param i32) (param i32) (result i32)
+                local.get 0
+                local.get 1
+                i32.ge_u
+                if (result i32)
+                    local.get 0
+                else
+                    local.get 1
+                end
+            )
+        )
+    "#,
+    );
+    let expected = [
+        /* 0 */
+        Instruction::BrIfI32LtU(BranchCmpOp {
+            lhs: r(0),
+            rhs: r(1),
+            target: target(3),
+        }),
+        /* 1 */ copy(2, 0),
+        /* 2 */ Instruction::Br(target(4)),
+        /* 3 */ copy(2, 1),
+        /* 4 */ Instruction::Return(span(2, 1)),
+    ];
+    assert_func_bodies(&wasm, [expected]);
+}
+
+#[test]
+fn br_if_does_not_fuse_teed_compare() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (export "call") (param i32) (param i32)
+                (local i32)
+                block
+                    local.get 0
+                    local.get 1
+                    i32.lt_s
+                    local.tee 2
+                    br_if 0
+                end
+            )
+        )
+    "#,
+    );
+    let expected = [
+        /* 0 */
+        Instruction::I32LtS(BinaryOp {
+            result: r(2),
+            lhs: r(0),
+            rhs: r(1),
+        }),
+        /* 1 */
+        Instruction::BrIfNez {
+            condition: r(2),
+            target: target(2),
+        },
+        /* 2 */ Instruction::Return(span(0, 0)),
+    ];
+    assert_func_bodies(&wasm, [expected]);
+}
+
+#[test]
+fn add_sub_with_immediates() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (export "call") (param i32) (result i32)
+                i32.const 7
+                local.get 0
+                i32.add
+                i32.const 5
+                i32.sub
+            )
+            (func (export "call64") (param i64) (result i64)
+                local.get 0
+                i64.const 0x8000_0001
+                i64.sub
+            )
+        )
+    "#,
+    );
+    let call = [
+        Instruction::I32AddImm(ImmOp {
+            result: r(1),
+            input: r(0),
+            imm: 7,
+        }),
+        Instruction::I32AddImm(ImmOp {
+            result: r(1),
+            input: r(1),
+            imm: -5,
+        }),
+        Instruction::Return(span(1, 1)),
+    ];
+    let call64 = [
+        Instruction::I64Sub(BinaryOp {
+            result: r(2),
+            lhs: r(0),
+            rhs: r(1),
+        }),
+        Instruction::Return(span(2, 1)),
+    ];
+    assert_func_bodies(
+        &wasm,
+        [&call[..], &call64[..]].map(|insts| insts.iter().copied()),
+    );
+}
+
+#[test]
+fn const_fold_arithmetic() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (export "call") (result i32)
+                i32.const 2
+                i32.const 3
+                i32.add
+                i32.const 4
+                i32.mul
+                i32.const 7
+                i32.div_s
+                i32.eqz
+            )
+        )
+    "#,
+    );
+    let expected = [Instruction::Return(span(0, 1))];
+    assert_func_bodies(&wasm, [expected]);
+}
+
+#[test]
+fn const_fold_preserves_traps() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (export "div") (result i32)
+                i32.const 1
+                i32.const 0
+                i32.div_s
+            )
+            (func (export "trunc") (result i32)
+                f32.const nan
+                i32.trunc_f32_s
+            )
+        )
+    "#,
+    );
+    let div = [
+        Instruction::I32DivS(BinaryOp {
+            result: r(2),
+            lhs: r(1),
+            rhs: r(0),
+        }),
+        Instruction::Return(span(2, 1)),
+    ];
+    let trunc = [
+        Instruction::I32TruncSF32(UnaryOp {
+            result: r(1),
+            input: r(0),
+        }),
+        Instruction::Return(span(1, 1)),
+    ];
+    assert_func_bodies(
+        &wasm,
+        [&div[..], &trunc[..]].map(|insts| insts.iter().copied()),
+    );
+}
+
+#[test]
+fn br_if_constant_condition() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (export "call") (param i32) (result i32)
+                block (result i32)
+                    local.get 0
+                    i32.const 0
+                    br_if 0
+                    drop
+                    local.get 0
+                    i32.const 1
+                    br_if 0
+                    drop
+                    i32.const 2
+                end
+            )
+        )
+    "#,
+    );
+    let expected = [
+        /* 0 */ copy(1, 0),
+        /* 1 */ Instruction::Br(target(2)),
+        /* 2 */ Instruction::Return(span(1, 1)),
+    ];
+    assert_func_bodies(&wasm, [expected]);
+}
+
+#[test]
+fn if_constant_condition() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (export "then") (param i32) (param i32) (result i32)
+                i32.const 1
+                if (result i32)
+                    local.get 0
+                else
+                    local.get 1
+                end
+            )
+            (func (export "else") (param i32) (param i32) (result i32)
+                i32.const 0
+                if (result i32)
+                    local.get 0
+                else
+                    local.get 1
+                end
+            )
+        )
+    "#,
+    );
+    let then = [copy(2, 0), Instruction::Return(span(2, 1))];
+    let else_ = [copy(2, 1), Instruction::Return(span(2, 1))];
+    assert_func_bodies(&wasm, [then, else_]);
+}
+
+#[test]
+fn select_constant_condition() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (export "true") (param i32) (param i32) (result i32)
+                local.get 0
+                local.get 1
+                i32.const 1
+                select
+            )
+            (func (export "false") (param i32) (param i32) (result i32)
+                local.get 0
+                local.get 0
+                local.get 1
+                i32.add
+                i32.const 0
+                select
+            )
+        )
+    "#,
+    );
+    let true_ = [Instruction::Return(span(0, 1))];
+    let false_ = [
+        Instruction::I32Add(BinaryOp {
+            result: r(3),
+            lhs: r(0),
+            rhs: r(1),
+        }),
+        copy(2, 3),
+        Instruction::Return(span(2, 1)),
+    ];
+    assert_func_bodies(
+        &wasm,
+        [&true_[..], &false_[..]].map(|insts| insts.iter().copied()),
+    );
+}
+
+#[test]
+fn drop_removes_pure_instruction() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (export "call") (param i32) (param i32)
+                local.get 0
+                local.get 1
+                i32.add
+                drop
+                local.get 0
+                local.get 1
+                i32.div_s
+                drop
+            )
+        )
+    "#,
+    );
+    let expected = [
+        Instruction::I32DivS(BinaryOp {
+            result: r(2),
+            lhs: r(0),
+            rhs: r(1),
+        }),
+        Instruction::Return(span(0, 0)),
     ];
     assert_func_bodies(&wasm, [expected]);
 }
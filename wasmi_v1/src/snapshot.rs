@@ -0,0 +1,245 @@
+//! Snapshots of the state of all linear memories, tables and global variables of a [`Store`].
+//!
+//! [`Store`]: crate::Store
+
+use crate::codec::{DecodeError, Decoder, Encoder};
+use alloc::{sync::Arc, vec::Vec};
+use core::{fmt, fmt::Display};
+use wasmi_core::memory_units::{Bytes, Pages};
+
+/// The magic bytes at the start of an encoded [`Snapshot`].
+const MAGIC: &[u8; 8] = b"wasmisnp";
+
+/// The current version of the [`Snapshot`] encoding.
+const VERSION: u32 = 1;
+
+/// Returns the size of a single linear memory page in bytes.
+pub(crate) fn page_size() -> usize {
+    Bytes::from(Pages(1)).0
+}
+
+/// An error that may occur upon restoring or decoding a [`Snapshot`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SnapshotError {
+    /// The bytes do not encode a valid [`Snapshot`].
+    InvalidEncoding,
+    /// The [`Snapshot`] has been encoded with an unsupported version.
+    UnsupportedVersion {
+        /// The version of the encoded [`Snapshot`].
+        version: u32,
+    },
+    /// The [`Snapshot`] refers to an entity that does not exist in the [`Store`].
+    ///
+    /// [`Store`]: crate::Store
+    MissingEntity,
+    /// The [`Snapshot`] of an entity does not match the entity in the [`Store`].
+    ///
+    /// [`Store`]: crate::Store
+    MismatchingEntity,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SnapshotError {}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidEncoding => write!(f, "encountered invalid snapshot encoding"),
+            Self::UnsupportedVersion { version } => {
+                write!(f, "encountered unsupported snapshot version {}", version)
+            }
+            Self::MissingEntity => {
+                write!(
+                    f,
+                    "snapshot refers to an entity that is missing in the store"
+                )
+            }
+            Self::MismatchingEntity => {
+                write!(f, "snapshot does not match an entity of the store")
+            }
+        }
+    }
+}
+
+/// The raw slot index and generation of a stored entity.
+pub(crate) type RawEntity = (u32, u32);
+
+/// A single page of a linear memory snapshot.
+///
+/// # Note
+///
+/// Pages that contain only zeros are not stored at all.
+/// Pages that did not change between snapshots are shared.
+pub(crate) type PageSnapshot = Option<Arc<[u8]>>;
+
+/// The snapshot of a linear memory.
+#[derive(Debug, Clone)]
+pub(crate) struct MemorySnapshot {
+    /// The linear memory of the snapshot.
+    pub entity: RawEntity,
+    /// The pages of the linear memory.
+    pub pages: Vec<PageSnapshot>,
+}
+
+/// The snapshot of a table.
+#[derive(Debug, Clone)]
+pub(crate) struct TableSnapshot {
+    /// The table of the snapshot.
+    pub entity: RawEntity,
+    /// The functions referenced by the table elements.
+    pub elements: Vec<Option<RawEntity>>,
+}
+
+/// The snapshot of a mutable global variable.
+#[derive(Debug, Clone)]
+pub(crate) struct GlobalSnapshot {
+    /// The global variable of the snapshot.
+    pub entity: RawEntity,
+    /// The bits of the value of the global variable.
+    pub bits: u64,
+}
+
+/// A snapshot of all linear memories, tables and mutable global variables of a [`Store`].
+///
+/// # Note
+///
+/// - Created by [`Store::snapshot`] and restored by [`Store::restore`].
+/// - Entities are identified by the order in which they have been allocated
+///   in the [`Store`]. Therefore a [`Snapshot`] can be restored into another
+///   [`Store`], possibly in another process, as long as it has allocated the
+///   same entities in the same order, e.g. by instantiating the same [`Module`]
+///   with the same imports.
+/// - Entities created after taking the [`Snapshot`] are not affected by restoring it.
+/// - Cloning a [`Snapshot`] is cheap since its linear memory pages are shared.
+///
+/// [`Store`]: crate::Store
+/// [`Store::snapshot`]: crate::Store::snapshot
+/// [`Store::restore`]: crate::Store::restore
+/// [`Module`]: crate::Module
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub(crate) memories: Vec<MemorySnapshot>,
+    pub(crate) tables: Vec<TableSnapshot>,
+    pub(crate) globals: Vec<GlobalSnapshot>,
+}
+
+impl Snapshot {
+    /// Returns the snapshot of the linear memory if any.
+    pub(crate) fn get_memory(&self, entity: RawEntity) -> Option<&MemorySnapshot> {
+        self.memories
+            .iter()
+            .find(|snapshot| snapshot.entity == entity)
+    }
+
+    /// Encodes the [`Snapshot`] into bytes.
+    ///
+    /// # Note
+    ///
+    /// Use [`Snapshot::from_bytes`] to decode the bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut encoder = Encoder::default();
+        encoder.bytes(MAGIC);
+        encoder.u32(VERSION);
+        encoder.len(self.memories.len());
+        for memory in &self.memories {
+            encode_entity(&mut encoder, memory.entity);
+            encoder.len(memory.pages.len());
+            for page in &memory.pages {
+                match page {
+                    Some(page) => {
+                        encoder.u8(1);
+                        encoder.bytes(page);
+                    }
+                    None => encoder.u8(0),
+                }
+            }
+        }
+        encoder.len(self.tables.len());
+        for table in &self.tables {
+            encode_entity(&mut encoder, table.entity);
+            encoder.len(table.elements.len());
+            for element in &table.elements {
+                match element {
+                    Some(func) => {
+                        encoder.u8(1);
+                        encode_entity(&mut encoder, *func);
+                    }
+                    None => encoder.u8(0),
+                }
+            }
+        }
+        encoder.len(self.globals.len());
+        for global in &self.globals {
+            encode_entity(&mut encoder, global.entity);
+            encoder.u64(global.bits);
+        }
+        encoder.into_bytes()
+    }
+
+    /// Decodes a [`Snapshot`] from bytes encoded by [`Snapshot::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// - If the `bytes` do not encode a valid [`Snapshot`].
+    /// - If the `bytes` have been encoded with an unsupported version.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        let mut decoder = Decoder::new(bytes);
+        if decoder.bytes(MAGIC.len())? != MAGIC {
+            return Err(SnapshotError::InvalidEncoding);
+        }
+        let version = decoder.u32()?;
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion { version });
+        }
+        let mut snapshot = Self::default();
+        for _ in 0..decoder.u32()? {
+            let entity = decode_entity(&mut decoder)?;
+            let len_pages = decoder.u32()?;
+            let pages = (0..len_pages)
+                .map(|_| match decoder.u8()? {
+                    0 => Ok(None),
+                    1 => Ok(Some(Arc::from(decoder.bytes(page_size())?))),
+                    _ => Err(SnapshotError::InvalidEncoding),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            snapshot.memories.push(MemorySnapshot { entity, pages });
+        }
+        for _ in 0..decoder.u32()? {
+            let entity = decode_entity(&mut decoder)?;
+            let len_elements = decoder.u32()?;
+            let elements = (0..len_elements)
+                .map(|_| match decoder.u8()? {
+                    0 => Ok(None),
+                    1 => Ok(Some(decode_entity(&mut decoder)?)),
+                    _ => Err(SnapshotError::InvalidEncoding),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            snapshot.tables.push(TableSnapshot { entity, elements });
+        }
+        for _ in 0..decoder.u32()? {
+            let entity = decode_entity(&mut decoder)?;
+            let bits = decoder.u64()?;
+            snapshot.globals.push(GlobalSnapshot { entity, bits });
+        }
+        decoder.finish()?;
+        Ok(snapshot)
+    }
+}
+
+impl From<DecodeError> for SnapshotError {
+    fn from(_: DecodeError) -> Self {
+        Self::InvalidEncoding
+    }
+}
+
+/// Encodes the raw slot index and generation of a stored entity.
+fn encode_entity(encoder: &mut Encoder, (index, generation): RawEntity) {
+    encoder.u32(index);
+    encoder.u32(generation);
+}
+
+/// Decodes the raw slot index and generation of a stored entity.
+fn decode_entity(decoder: &mut Decoder) -> Result<RawEntity, DecodeError> {
+    Ok((decoder.u32()?, decoder.u32()?))
+}
@@ -0,0 +1,189 @@
+//! Resource usage reporting of [`Store`] and [`Engine`].
+//!
+//! [`Store`]: crate::Store
+//! [`Engine`]: crate::Engine
+
+use core::fmt;
+
+/// The resources in use by the entities of a [`Store`].
+///
+/// # Note
+///
+/// Created via [`Store::resource_usage`].
+///
+/// [`Store`]: crate::Store
+/// [`Store::resource_usage`]: crate::Store::resource_usage
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ResourceUsage {
+    /// The number of module instances.
+    pub instances: usize,
+    /// The number of Wasm and host functions.
+    pub funcs: usize,
+    /// The number of global variables.
+    pub globals: usize,
+    /// The number of tables.
+    pub tables: usize,
+    /// The total number of elements of all tables.
+    pub table_elements: usize,
+    /// The number of linear memories.
+    pub memories: usize,
+    /// The total number of bytes of all linear memories.
+    pub memory_bytes: usize,
+}
+
+/// Statistics about the compiled code of an [`Engine`].
+///
+/// # Note
+///
+/// Created via [`Engine::stats`].
+///
+/// [`Engine`]: crate::Engine
+/// [`Engine::stats`]: crate::Engine::stats
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct EngineStats {
+    /// The number of deduplicated function types.
+    pub func_types: usize,
+    /// The number of compiled function bodies.
+    pub func_bodies: usize,
+    /// The total number of compiled `wasmi` bytecode instructions of all function bodies.
+    pub instructions: usize,
+    /// The number of bytes allocated by the code map storing all function bodies.
+    pub code_map_bytes: usize,
+    /// The number of modules in the compilation cache.
+    pub cached_modules: usize,
+}
+
+impl ResourceUsage {
+    /// Writes the [`ResourceUsage`] in the Prometheus text exposition format.
+    ///
+    /// # Note
+    ///
+    /// The given `labels` are attached to every metric,
+    /// e.g. in order to identify the tenant owning the [`Store`].
+    ///
+    /// # Errors
+    ///
+    /// If writing to `out` fails.
+    ///
+    /// [`Store`]: crate::Store
+    pub fn write_metrics(&self, out: &mut impl fmt::Write, labels: &[(&str, &str)]) -> fmt::Result {
+        let metrics = [
+            (
+                "wasmi_store_instances",
+                "The number of module instances.",
+                self.instances,
+            ),
+            (
+                "wasmi_store_funcs",
+                "The number of Wasm and host functions.",
+                self.funcs,
+            ),
+            (
+                "wasmi_store_globals",
+                "The number of global variables.",
+                self.globals,
+            ),
+            ("wasmi_store_tables", "The number of tables.", self.tables),
+            (
+                "wasmi_store_table_elements",
+                "The total number of elements of all tables.",
+                self.table_elements,
+            ),
+            (
+                "wasmi_store_memories",
+                "The number of linear memories.",
+                self.memories,
+            ),
+            (
+                "wasmi_store_memory_bytes",
+                "The total number of bytes of all linear memories.",
+                self.memory_bytes,
+            ),
+        ];
+        write_gauges(out, labels, &metrics)
+    }
+}
+
+impl EngineStats {
+    /// Writes the [`EngineStats`] in the Prometheus text exposition format.
+    ///
+    /// # Note
+    ///
+    /// The given `labels` are attached to every metric.
+    ///
+    /// # Errors
+    ///
+    /// If writing to `out` fails.
+    pub fn write_metrics(&self, out: &mut impl fmt::Write, labels: &[(&str, &str)]) -> fmt::Result {
+        let metrics = [
+            (
+                "wasmi_engine_func_types",
+                "The number of deduplicated function types.",
+                self.func_types,
+            ),
+            (
+                "wasmi_engine_func_bodies",
+                "The number of compiled function bodies.",
+                self.func_bodies,
+            ),
+            (
+                "wasmi_engine_instructions",
+                "The total number of compiled bytecode instructions.",
+                self.instructions,
+            ),
+            (
+                "wasmi_engine_code_map_bytes",
+                "The number of bytes allocated for compiled function bodies.",
+                self.code_map_bytes,
+            ),
+            (
+                "wasmi_engine_cached_modules",
+                "The number of modules in the compilation cache.",
+                self.cached_modules,
+            ),
+        ];
+        write_gauges(out, labels, &metrics)
+    }
+}
+
+/// Writes the `metrics` as gauges with the given `labels` in the Prometheus text exposition format.
+fn write_gauges(
+    out: &mut impl fmt::Write,
+    labels: &[(&str, &str)],
+    metrics: &[(&str, &str, usize)],
+) -> fmt::Result {
+    for &(name, help, value) in metrics {
+        writeln!(out, "# HELP {} {}", name, help)?;
+        writeln!(out, "# TYPE {} gauge", name)?;
+        write!(out, "{}", name)?;
+        if !labels.is_empty() {
+            write!(out, "{{")?;
+            for (n, (label, label_value)) in labels.iter().enumerate() {
+                if n != 0 {
+                    write!(out, ",")?;
+                }
+                write!(out, "{}=\"", label)?;
+                write_escaped(out, label_value)?;
+                write!(out, "\"")?;
+            }
+            write!(out, "}}")?;
+        }
+        writeln!(out, " {}", value)?;
+    }
+    Ok(())
+}
+
+/// Writes the label `value` escaping backslashes, double quotes and line feeds.
+fn write_escaped(out: &mut impl fmt::Write, value: &str) -> fmt::Result {
+    for c in value.chars() {
+        match c {
+            '\\' => out.write_str("\\\\")?,
+            '"' => out.write_str("\\\"")?,
+            '\n' => out.write_str("\\n")?,
+            c => out.write_char(c)?,
+        }
+    }
+    Ok(())
+}
@@ -1,7 +1,8 @@
 use super::{
-    arena::Arena,
+    arena::{GenerationalArena, GenerationalIdx},
     engine::DedupFuncType,
     Engine,
+    Extern,
     Func,
     FuncEntity,
     FuncIdx,
@@ -11,6 +12,7 @@ use super::{
     GlobalIdx,
     Instance,
     InstanceEntity,
+    InstanceEntityBuilder,
     InstanceIdx,
     Memory,
     MemoryEntity,
@@ -19,8 +21,27 @@ use super::{
     TableEntity,
     TableIdx,
 };
-use crate::{GuardedEntity, Index};
-use core::sync::atomic::{AtomicUsize, Ordering};
+use crate::{
+    errors::{InstanceError, InstantiationError, MemoryError, SnapshotError, TableError},
+    snapshot::{page_size, GlobalSnapshot, MemorySnapshot, PageSnapshot, Snapshot, TableSnapshot},
+    GuardedEntity,
+    Index,
+    MemoryCreator,
+    MemoryType,
+    ResourceLimiter,
+    ResourceUsage,
+    TableType,
+    TABLE_ELEMENT_BYTES,
+};
+use alloc::{boxed::Box, collections::BTreeSet, sync::Arc, vec::Vec};
+use core::{
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use wasmi_core::{
+    memory_units::{Bytes, Pages},
+    UntypedValue,
+};
 
 /// A unique store index.
 ///
@@ -51,7 +72,32 @@ impl StoreIdx {
 }
 
 /// A stored entity.
-pub type Stored<Idx> = GuardedEntity<StoreIdx, Idx>;
+///
+/// # Note
+///
+/// The entity index is checked against the generation of its slot
+/// so that entities that have been dropped from the store are detected.
+pub type Stored<Idx> = GuardedEntity<StoreIdx, GenerationalIdx<Idx>>;
+
+/// Queries the [`ResourceLimiter`] from the user provided state of a [`Store`].
+struct ResourceLimiterQuery<T>(Box<dyn FnMut(&mut T) -> &mut (dyn ResourceLimiter) + Send + Sync>);
+
+impl<T> fmt::Debug for ResourceLimiterQuery<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResourceLimiterQuery")
+            .finish_non_exhaustive()
+    }
+}
+
+/// Creates the backends of the linear memories of a [`Store`].
+struct MemoryCreatorHandle(Box<dyn MemoryCreator>);
+
+impl fmt::Debug for MemoryCreatorHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryCreatorHandle")
+            .finish_non_exhaustive()
+    }
+}
 
 /// The store that owns all data associated to Wasm modules.
 #[derive(Debug)]
@@ -61,21 +107,25 @@ pub struct Store<T> {
     /// Used to protect against invalid entity indices.
     store_idx: StoreIdx,
     /// Stored linear memories.
-    memories: Arena<MemoryIdx, MemoryEntity>,
+    memories: GenerationalArena<MemoryIdx, MemoryEntity>,
     /// Stored tables.
-    tables: Arena<TableIdx, TableEntity>,
+    tables: GenerationalArena<TableIdx, TableEntity>,
     /// Stored global variables.
-    globals: Arena<GlobalIdx, GlobalEntity>,
+    globals: GenerationalArena<GlobalIdx, GlobalEntity>,
     /// Stored Wasm or host functions.
-    funcs: Arena<FuncIdx, FuncEntity<T>>,
+    funcs: GenerationalArena<FuncIdx, FuncEntity<T>>,
     /// Stored module instances.
-    instances: Arena<InstanceIdx, InstanceEntity>,
+    instances: GenerationalArena<InstanceIdx, InstanceEntity>,
     /// The [`Engine`] in use by the [`Store`].
     ///
     /// Amongst others the [`Engine`] stores the Wasm function definitions.
     engine: Engine,
     /// User provided state.
     user_state: T,
+    /// Queries the installed [`ResourceLimiter`] if any.
+    limiter: Option<ResourceLimiterQuery<T>>,
+    /// Creates the backends of new linear memories if installed.
+    memory_creator: Option<MemoryCreatorHandle>,
 }
 
 impl<T> Store<T> {
@@ -83,13 +133,15 @@ impl<T> Store<T> {
     pub fn new(engine: &Engine, user_state: T) -> Self {
         Self {
             store_idx: StoreIdx::new(),
-            memories: Arena::new(),
-            tables: Arena::new(),
-            globals: Arena::new(),
-            funcs: Arena::new(),
-            instances: Arena::new(),
+            memories: GenerationalArena::new(),
+            tables: GenerationalArena::new(),
+            globals: GenerationalArena::new(),
+            funcs: GenerationalArena::new(),
+            instances: GenerationalArena::new(),
             engine: engine.clone(),
             user_state,
+            limiter: None,
+            memory_creator: None,
         }
     }
 
@@ -113,6 +165,134 @@ impl<T> Store<T> {
         self.user_state
     }
 
+    /// Installs the [`ResourceLimiter`] returned by `limiter` for the store.
+    ///
+    /// # Note
+    ///
+    /// The `limiter` queries the [`ResourceLimiter`] from the user provided state
+    /// and is invoked whenever resources are about to be allocated by the store.
+    /// Only applies to resources allocated after installing the `limiter`.
+    pub fn limiter(
+        &mut self,
+        limiter: impl FnMut(&mut T) -> &mut (dyn ResourceLimiter) + Send + Sync + 'static,
+    ) {
+        self.limiter = Some(ResourceLimiterQuery(Box::new(limiter)));
+    }
+
+    /// Installs a [`MemoryCreator`] that creates the backends of all new linear memories.
+    ///
+    /// # Note
+    ///
+    /// Only applies to linear memories created after installing the `creator`,
+    /// including those created upon instantiation of Wasm modules.
+    /// Linear memories created via [`Memory::new_with_backend`] are not affected.
+    pub fn memory_creator(&mut self, creator: impl MemoryCreator + 'static) {
+        self.memory_creator = Some(MemoryCreatorHandle(Box::new(creator)));
+    }
+
+    /// Returns the installed [`MemoryCreator`] if any.
+    pub(super) fn installed_memory_creator(&self) -> Option<&dyn MemoryCreator> {
+        self.memory_creator
+            .as_ref()
+            .map(|MemoryCreatorHandle(creator)| &**creator)
+    }
+
+    /// Returns the installed [`ResourceLimiter`] if any.
+    ///
+    /// # Note
+    ///
+    /// Takes the fields of the store separately in order to allow
+    /// split borrows with the other entities of the store.
+    fn query_limiter<'a>(
+        limiter: &'a mut Option<ResourceLimiterQuery<T>>,
+        user_state: &'a mut T,
+    ) -> Option<&'a mut dyn ResourceLimiter> {
+        match limiter {
+            Some(ResourceLimiterQuery(query)) => Some(query(user_state)),
+            None => None,
+        }
+    }
+
+    /// Returns an error if the [`ResourceLimiter`] denies creating a new instance.
+    pub(super) fn ensure_instance_permitted(&mut self) -> Result<(), InstantiationError> {
+        let len_instances = self.instances.len();
+        if let Some(limiter) = Self::query_limiter(&mut self.limiter, &mut self.user_state) {
+            if len_instances >= limiter.instances() {
+                return Err(InstantiationError::TooManyInstances);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns an error if the [`ResourceLimiter`] denies creating a new linear memory.
+    pub(super) fn ensure_memory_permitted(
+        &mut self,
+        memory_type: MemoryType,
+    ) -> Result<(), MemoryError> {
+        let len_memories = self.memories.len();
+        let initial_bytes = Bytes::from(memory_type.initial_pages()).0;
+        if !self.bytes_permitted(initial_bytes) {
+            return Err(MemoryError::ResourceLimitReached);
+        }
+        if let Some(limiter) = Self::query_limiter(&mut self.limiter, &mut self.user_state) {
+            if len_memories >= limiter.memories()
+                || !limiter.memory_growing(
+                    Pages(0),
+                    memory_type.initial_pages(),
+                    memory_type.maximum_pages(),
+                )
+            {
+                return Err(MemoryError::ResourceLimitReached);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns an error if the [`ResourceLimiter`] denies creating a new table.
+    pub(super) fn ensure_table_permitted(
+        &mut self,
+        table_type: TableType,
+    ) -> Result<(), TableError> {
+        let len_tables = self.tables.len();
+        let initial_bytes = table_type.initial().saturating_mul(TABLE_ELEMENT_BYTES);
+        if !self.bytes_permitted(initial_bytes) {
+            return Err(TableError::ResourceLimitReached);
+        }
+        if let Some(limiter) = Self::query_limiter(&mut self.limiter, &mut self.user_state) {
+            if len_tables >= limiter.tables()
+                || !limiter.table_growing(0, table_type.initial(), table_type.maximum())
+            {
+                return Err(TableError::ResourceLimitReached);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the [`ResourceLimiter`] permits allocating `additional` bytes
+    /// on top of the bytes of all linear memories and tables of the store.
+    ///
+    /// # Note
+    ///
+    /// The current bytes are only computed if [`ResourceLimiter::total_bytes`] is set.
+    pub(super) fn bytes_permitted(&mut self, additional: usize) -> bool {
+        let budget = match Self::query_limiter(&mut self.limiter, &mut self.user_state)
+            .and_then(|limiter| limiter.total_bytes())
+        {
+            Some(budget) => budget,
+            None => return true,
+        };
+        if additional == 0 {
+            return true;
+        }
+        let usage = self.resource_usage();
+        usage
+            .table_elements
+            .saturating_mul(TABLE_ELEMENT_BYTES)
+            .saturating_add(usage.memory_bytes)
+            .saturating_add(additional)
+            <= budget
+    }
+
     /// Allocates a new function type to the store.
     pub(super) fn alloc_func_type(&mut self, func_type: FuncType) -> DedupFuncType {
         self.engine.alloc_func_type(func_type)
@@ -129,7 +309,18 @@ impl<T> Store<T> {
     }
 
     /// Allocates a new linear memory to the store.
-    pub(super) fn alloc_memory(&mut self, memory: MemoryEntity) -> Memory {
+    ///
+    /// # Note
+    ///
+    /// Enables dirty page tracking and guard pages for the linear memory if configured.
+    pub(super) fn alloc_memory(&mut self, mut memory: MemoryEntity) -> Memory {
+        let config = self.engine.config();
+        if config.dirty_page_tracking() {
+            memory.track_dirty_pages();
+        }
+        if config.guard_pages() {
+            memory.enable_guard_pages();
+        }
         Memory::from_inner(Stored::new(self.store_idx, self.memories.alloc(memory)))
     }
 
@@ -185,12 +376,468 @@ impl<T> Store<T> {
         *entity = initialized;
     }
 
+    /// Drops the [`Instance`] and all entities it owns from the store.
+    ///
+    /// # Note
+    ///
+    /// - The owned entities are the functions, tables, linear memories and
+    ///   global variables that have been created upon instantiation.
+    ///   Imported entities are not dropped.
+    /// - The store reclaims the resources of all dropped entities.
+    ///   The compiled function bodies are not reclaimed since they belong
+    ///   to the [`Module`] and are shared by all of its instances.
+    /// - Using the [`Instance`] or any of its owned entities afterwards from
+    ///   the host side panics.
+    ///
+    /// # Errors
+    ///
+    /// If an entity owned by the [`Instance`] is still in use by another instance,
+    /// either because it is imported by the other instance or because it is a
+    /// function that is an element of a table not owned by the [`Instance`].
+    /// In this case nothing is dropped.
+    ///
+    /// # Panics
+    ///
+    /// - If the [`Instance`] does not originate from this store.
+    /// - If the [`Instance`] has already been dropped.
+    ///
+    /// [`Module`]: crate::Module
+    pub fn drop_instance(&mut self, instance: Instance) -> Result<(), InstanceError> {
+        self.ensure_instance_unused(instance)?;
+        self.dealloc_instance(instance, drop);
+        Ok(())
+    }
+
+    /// Ensures that no entity owned by the [`Instance`] is in use by another instance.
+    ///
+    /// # Errors
+    ///
+    /// If an entity owned by the [`Instance`] is imported by another instance
+    /// or is a function that is an element of a table not owned by the [`Instance`].
+    ///
+    /// # Panics
+    ///
+    /// Same as [`Store::drop_instance`].
+    pub(super) fn ensure_instance_unused(&self, instance: Instance) -> Result<(), InstanceError> {
+        let entity_index = self.unwrap_index(instance.into_inner());
+        let entity = self.instances.get(entity_index).unwrap_or_else(|| {
+            panic!(
+                "failed to resolve stored module instance: {:?}",
+                entity_index
+            )
+        });
+        let owned_tables = self.owned_indices(entity.owned_tables(), Table::into_inner);
+        let owned_funcs = self.owned_indices(entity.owned_funcs(), Func::into_inner);
+        let owned_memories = self.owned_indices(entity.owned_memories(), Memory::into_inner);
+        let owned_globals = self.owned_indices(entity.owned_globals(), Global::into_inner);
+        let in_use = |entity: Extern| Err(InstanceError::EntityInUse { entity });
+        for (index, other) in self.instances.iter() {
+            if index == entity_index {
+                continue;
+            }
+            for &table in other.imported_tables() {
+                if owned_tables.contains(&self.unwrap_index(table.into_inner())) {
+                    return in_use(Extern::Table(table));
+                }
+            }
+            for &func in other.imported_funcs() {
+                if owned_funcs.contains(&self.unwrap_index(func.into_inner())) {
+                    return in_use(Extern::Func(func));
+                }
+            }
+            for &memory in other.imported_memories() {
+                if owned_memories.contains(&self.unwrap_index(memory.into_inner())) {
+                    return in_use(Extern::Memory(memory));
+                }
+            }
+            for &global in other.imported_globals() {
+                if owned_globals.contains(&self.unwrap_index(global.into_inner())) {
+                    return in_use(Extern::Global(global));
+                }
+            }
+        }
+        for (index, table) in self.tables.iter() {
+            if owned_tables.contains(&index) {
+                continue;
+            }
+            for &func in table.elements().iter().flatten() {
+                if owned_funcs.contains(&self.unwrap_index(func.into_inner())) {
+                    return in_use(Extern::Func(func));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the set of entity indices of the owned `entities`.
+    fn owned_indices<E, Idx>(
+        &self,
+        entities: &[E],
+        into_inner: impl Fn(E) -> Stored<Idx>,
+    ) -> BTreeSet<GenerationalIdx<Idx>>
+    where
+        E: Copy,
+        Idx: Index + Ord,
+    {
+        entities
+            .iter()
+            .map(|&entity| self.unwrap_index(into_inner(entity)))
+            .collect()
+    }
+
+    /// Drops the partially constructed [`Instance`] and all entities it owns from the store.
+    ///
+    /// # Note
+    ///
+    /// Used to roll back a failed instantiation. The `builder` must have finished its imports.
+    ///
+    /// # Panics
+    ///
+    /// Same as [`Store::initialize_instance`].
+    pub(super) fn rollback_instance(&mut self, instance: Instance, builder: InstanceEntityBuilder) {
+        self.initialize_instance(instance, builder.finish());
+        self.dealloc_instance(instance, drop);
+    }
+
+    /// Drops the [`Instance`] and all entities it owns from the store.
+    ///
+    /// # Note
+    ///
+    /// This does not check if the owned entities are still in use.
+    /// Use [`Store::ensure_instance_unused`] to check this beforehand.
+    ///
+    /// The dropped linear memories of the [`Instance`] are handed over to
+    /// `reclaim_memory` in the order in which they have been created.
+    ///
+    /// # Panics
+    ///
+    /// Same as [`Store::drop_instance`].
+    pub(super) fn dealloc_instance(
+        &mut self,
+        instance: Instance,
+        mut reclaim_memory: impl FnMut(MemoryEntity),
+    ) {
+        let entity_index = self.unwrap_index(instance.into_inner());
+        let entity = self.instances.dealloc(entity_index).unwrap_or_else(|| {
+            panic!(
+                "failed to resolve stored module instance: {:?}",
+                entity_index
+            )
+        });
+        for table in entity.owned_tables() {
+            let entity_index = self.unwrap_index(table.into_inner());
+            self.tables.dealloc(entity_index);
+        }
+        for func in entity.owned_funcs() {
+            let entity_index = self.unwrap_index(func.into_inner());
+            self.funcs.dealloc(entity_index);
+        }
+        for memory in entity.owned_memories() {
+            let entity_index = self.unwrap_index(memory.into_inner());
+            if let Some(memory) = self.memories.dealloc(entity_index) {
+                reclaim_memory(memory);
+            }
+        }
+        for global in entity.owned_globals() {
+            let entity_index = self.unwrap_index(global.into_inner());
+            self.globals.dealloc(entity_index);
+        }
+    }
+
+    /// Returns the resources in use by the entities of the [`Store`].
+    ///
+    /// # Note
+    ///
+    /// This iterates over all tables and linear memories of the [`Store`].
+    pub fn resource_usage(&self) -> ResourceUsage {
+        let table_elements = self.tables.iter().map(|(_, table)| table.len()).sum();
+        let memory_bytes = self
+            .memories
+            .iter()
+            .map(|(_, memory)| memory.data().len())
+            .sum();
+        ResourceUsage {
+            instances: self.instances.len(),
+            funcs: self.funcs.len(),
+            globals: self.globals.len(),
+            tables: self.tables.len(),
+            table_elements,
+            memories: self.memories.len(),
+            memory_bytes,
+        }
+    }
+
+    /// Takes a [`Snapshot`] of all linear memories, tables and mutable global variables.
+    ///
+    /// # Note
+    ///
+    /// Linear memory pages that contain only zeros are not copied.
+    /// Use [`Store::snapshot_incremental`] in order to also avoid copying
+    /// pages that did not change since a previous [`Snapshot`].
+    pub fn snapshot(&self) -> Snapshot {
+        self.snapshot_incremental(&Snapshot::default())
+    }
+
+    /// Takes a [`Snapshot`] that shares all unchanged linear memory pages with `base`.
+    ///
+    /// # Note
+    ///
+    /// This is useful when taking a series of snapshots of the same [`Store`]
+    /// since only pages that changed since the `base` [`Snapshot`] are copied.
+    pub fn snapshot_incremental(&self, base: &Snapshot) -> Snapshot {
+        let page_size = page_size();
+        let memories = self
+            .memories
+            .iter()
+            .map(|(index, memory)| {
+                let entity = index.into_raw();
+                let base_pages = base
+                    .get_memory(entity)
+                    .map(|base| &base.pages[..])
+                    .unwrap_or_default();
+                let pages = memory
+                    .data()
+                    .chunks(page_size)
+                    .enumerate()
+                    .map(|(n, page)| {
+                        if let Some(base_page) = base_pages.get(n) {
+                            let unchanged = match base_page {
+                                Some(base_page) => &base_page[..] == page,
+                                None => page.iter().all(|&byte| byte == 0x00),
+                            };
+                            if unchanged {
+                                return base_page.clone();
+                            }
+                        }
+                        if page.iter().all(|&byte| byte == 0x00) {
+                            return None;
+                        }
+                        Some(Arc::from(page))
+                    })
+                    .collect::<Vec<PageSnapshot>>();
+                MemorySnapshot { entity, pages }
+            })
+            .collect();
+        let tables = self
+            .tables
+            .iter()
+            .map(|(index, table)| TableSnapshot {
+                entity: index.into_raw(),
+                elements: table
+                    .elements()
+                    .iter()
+                    .map(|func| func.map(|func| self.unwrap_index(func.into_inner()).into_raw()))
+                    .collect(),
+            })
+            .collect();
+        let globals = self
+            .globals
+            .iter()
+            .filter(|(_, global)| global.is_mutable())
+            .map(|(index, global)| GlobalSnapshot {
+                entity: index.into_raw(),
+                bits: UntypedValue::from(global.get()).to_bits(),
+            })
+            .collect();
+        Snapshot {
+            memories,
+            tables,
+            globals,
+        }
+    }
+
+    /// Restores all linear memories, tables and mutable global variables to the [`Snapshot`].
+    ///
+    /// # Note
+    ///
+    /// - Only linear memory pages that differ from the [`Snapshot`] are written to.
+    /// - Entities created after taking the [`Snapshot`] are not affected.
+    /// - Either the whole [`Snapshot`] is restored or nothing at all.
+    ///
+    /// # Errors
+    ///
+    /// - If the [`Snapshot`] refers to entities that do not exist in the [`Store`].
+    /// - If the [`Snapshot`] of an entity does not match the entity in the [`Store`].
+    pub fn restore(&mut self, snapshot: &Snapshot) -> Result<(), SnapshotError> {
+        // Validate the whole snapshot before restoring anything.
+        for memory in &snapshot.memories {
+            let entity = self
+                .memories
+                .get(GenerationalIdx::from_raw(memory.entity.0, memory.entity.1))
+                .ok_or(SnapshotError::MissingEntity)?;
+            let memory_type = entity.memory_type();
+            if memory.pages.len() < memory_type.initial_pages().0 {
+                return Err(SnapshotError::MismatchingEntity);
+            }
+            if let Some(maximum_pages) = memory_type.maximum_pages() {
+                if memory.pages.len() > maximum_pages.0 {
+                    return Err(SnapshotError::MismatchingEntity);
+                }
+            }
+            if memory
+                .pages
+                .iter()
+                .flatten()
+                .any(|page| page.len() != page_size())
+            {
+                return Err(SnapshotError::MismatchingEntity);
+            }
+        }
+        for table in &snapshot.tables {
+            let entity = self
+                .tables
+                .get(GenerationalIdx::from_raw(table.entity.0, table.entity.1))
+                .ok_or(SnapshotError::MissingEntity)?;
+            let table_type = entity.table_type();
+            if table.elements.len() < table_type.initial() {
+                return Err(SnapshotError::MismatchingEntity);
+            }
+            if let Some(maximum) = table_type.maximum() {
+                if table.elements.len() > maximum {
+                    return Err(SnapshotError::MismatchingEntity);
+                }
+            }
+            for &(index, generation) in table.elements.iter().flatten() {
+                self.funcs
+                    .get(GenerationalIdx::from_raw(index, generation))
+                    .ok_or(SnapshotError::MissingEntity)?;
+            }
+        }
+        for global in &snapshot.globals {
+            let entity = self
+                .globals
+                .get(GenerationalIdx::from_raw(global.entity.0, global.entity.1))
+                .ok_or(SnapshotError::MissingEntity)?;
+            if !entity.is_mutable() {
+                return Err(SnapshotError::MismatchingEntity);
+            }
+        }
+        // Resize all linear memories before anything else is restored
+        // since resizing is the only operation of restoration that may fail.
+        self.resize_memories(snapshot)?;
+        // Restore the snapshot now that it has been validated and all linear memories are resized.
+        for memory in &snapshot.memories {
+            let entity = self
+                .memories
+                .get_mut(GenerationalIdx::from_raw(memory.entity.0, memory.entity.1))
+                .expect("validated linear memory snapshot");
+            for (page, snapshot) in entity.data_mut().chunks_mut(page_size()).zip(&memory.pages) {
+                match snapshot {
+                    Some(snapshot) => {
+                        if page[..] != snapshot[..] {
+                            page.copy_from_slice(snapshot);
+                        }
+                    }
+                    None => {
+                        if page.iter().any(|&byte| byte != 0x00) {
+                            page.fill(0x00);
+                        }
+                    }
+                }
+            }
+        }
+        let store_idx = self.store_idx;
+        for table in &snapshot.tables {
+            let entity = self
+                .tables
+                .get_mut(GenerationalIdx::from_raw(table.entity.0, table.entity.1))
+                .expect("validated table snapshot");
+            entity.set_elements(table.elements.iter().map(|element| {
+                element.map(|(index, generation)| {
+                    Func::from_inner(Stored::new(
+                        store_idx,
+                        GenerationalIdx::from_raw(index, generation),
+                    ))
+                })
+            }));
+        }
+        for global in &snapshot.globals {
+            let entity = self
+                .globals
+                .get_mut(GenerationalIdx::from_raw(global.entity.0, global.entity.1))
+                .expect("validated global variable snapshot");
+            let value = UntypedValue::from(global.bits).with_type(entity.value_type());
+            entity
+                .set(value)
+                .expect("validated mutable global variable snapshot");
+        }
+        Ok(())
+    }
+
+    /// Resizes all linear memories of the [`Snapshot`] to their amount of pages in the [`Snapshot`].
+    ///
+    /// # Note
+    ///
+    /// All linear memories are grown before any linear memory is shrunk. The bytes
+    /// discarded by shrinking a linear memory are kept until all linear memories
+    /// have been resized successfully.
+    ///
+    /// # Errors
+    ///
+    /// If a linear memory cannot be resized. In this case all linear memories
+    /// that have already been resized are reverted to their previous state.
+    ///
+    /// # Panics
+    ///
+    /// - If the [`Snapshot`] has not been validated.
+    /// - If a linear memory cannot be reverted to its previous state.
+    fn resize_memories(&mut self, snapshot: &Snapshot) -> Result<(), SnapshotError> {
+        let entity_index =
+            |memory: &MemorySnapshot| GenerationalIdx::from_raw(memory.entity.0, memory.entity.1);
+        let mut resizes = snapshot
+            .memories
+            .iter()
+            .map(|memory| {
+                let current_pages = self
+                    .memories
+                    .get(entity_index(memory))
+                    .expect("validated linear memory snapshot")
+                    .current_pages();
+                (memory, current_pages, Pages(memory.pages.len()))
+            })
+            .filter(|(_, current_pages, new_pages)| current_pages != new_pages)
+            .collect::<Vec<_>>();
+        // Grow before shrinking so that a failing grow never has to undo a shrink.
+        resizes.sort_by_key(|(_, current_pages, new_pages)| current_pages > new_pages);
+        // The resized linear memories with their previous amount of pages and discarded bytes.
+        let mut resized: Vec<(&MemorySnapshot, Pages, usize, Vec<u8>)> = Vec::new();
+        for (memory, current_pages, new_pages) in resizes {
+            let entity = self
+                .memories
+                .get_mut(entity_index(memory))
+                .expect("validated linear memory snapshot");
+            let new_len = Bytes::from(new_pages).0;
+            let discarded = entity
+                .data()
+                .get(new_len..)
+                .map(<[u8]>::to_vec)
+                .unwrap_or_default();
+            if entity.resize(new_pages).is_err() {
+                for (memory, previous_pages, new_len, discarded) in resized.into_iter().rev() {
+                    let entity = self
+                        .memories
+                        .get_mut(entity_index(memory))
+                        .expect("validated linear memory snapshot");
+                    entity
+                        .resize(previous_pages)
+                        .expect("failed to revert resized linear memory");
+                    if !discarded.is_empty() {
+                        entity.data_mut()[new_len..].copy_from_slice(&discarded);
+                    }
+                }
+                return Err(SnapshotError::MismatchingEntity);
+            }
+            resized.push((memory, current_pages, new_len, discarded));
+        }
+        Ok(())
+    }
+
     /// Unpacks and checks the stored entity index.
     ///
     /// # Panics
     ///
     /// If the stored entity does not originate from this store.
-    fn unwrap_index<Idx>(&self, stored: Stored<Idx>) -> Idx
+    fn unwrap_index<Idx>(&self, stored: Stored<Idx>) -> GenerationalIdx<Idx>
     where
         Idx: Index,
     {
@@ -296,6 +943,63 @@ impl<T> Store<T> {
             .unwrap_or_else(|| panic!("failed to resolve stored linear memory: {:?}", entity_index))
     }
 
+    /// Returns an exclusive reference to the associated entity of the linear memory
+    /// and an exclusive reference to the user provided state.
+    ///
+    /// # Panics
+    ///
+    /// - If the linear memory does not originate from this store.
+    /// - If the linear memory cannot be resolved to its entity.
+    pub(super) fn resolve_memory_and_state_mut(
+        &mut self,
+        memory: Memory,
+    ) -> (&mut MemoryEntity, &mut T) {
+        let entity_index = self.unwrap_index(memory.into_inner());
+        let entity = self.memories.get_mut(entity_index).unwrap_or_else(|| {
+            panic!("failed to resolve stored linear memory: {:?}", entity_index)
+        });
+        (entity, &mut self.user_state)
+    }
+
+    /// Returns an exclusive reference to the associated entity of the table
+    /// and the installed [`ResourceLimiter`] if any.
+    ///
+    /// # Panics
+    ///
+    /// - If the table does not originate from this store.
+    /// - If the table cannot be resolved to its entity.
+    pub(super) fn resolve_table_and_limiter_mut(
+        &mut self,
+        table: Table,
+    ) -> (&mut TableEntity, Option<&mut dyn ResourceLimiter>) {
+        let entity_index = self.unwrap_index(table.into_inner());
+        let entity = self
+            .tables
+            .get_mut(entity_index)
+            .unwrap_or_else(|| panic!("failed to resolve stored table: {:?}", entity_index));
+        let limiter = Self::query_limiter(&mut self.limiter, &mut self.user_state);
+        (entity, limiter)
+    }
+
+    /// Returns an exclusive reference to the associated entity of the linear memory
+    /// and the installed [`ResourceLimiter`] if any.
+    ///
+    /// # Panics
+    ///
+    /// - If the linear memory does not originate from this store.
+    /// - If the linear memory cannot be resolved to its entity.
+    pub(super) fn resolve_memory_and_limiter_mut(
+        &mut self,
+        memory: Memory,
+    ) -> (&mut MemoryEntity, Option<&mut dyn ResourceLimiter>) {
+        let entity_index = self.unwrap_index(memory.into_inner());
+        let entity = self.memories.get_mut(entity_index).unwrap_or_else(|| {
+            panic!("failed to resolve stored linear memory: {:?}", entity_index)
+        });
+        let limiter = Self::query_limiter(&mut self.limiter, &mut self.user_state);
+        (entity, limiter)
+    }
+
     /// Returns a shared reference to the associated entity of the Wasm or host function.
     ///
     /// # Panics
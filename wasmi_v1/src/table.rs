@@ -1,6 +1,6 @@
 #![allow(clippy::len_without_is_empty)]
 
-use super::{AsContext, AsContextMut, Func, Index, Stored};
+use super::{AsContext, AsContextMut, Func, Index, ResourceLimiter, Stored, TABLE_ELEMENT_BYTES};
 use alloc::vec::Vec;
 use core::{fmt, fmt::Display};
 
@@ -38,6 +38,8 @@ pub enum TableError {
         /// The accessed index that is out of bounds.
         offset: usize,
     },
+    /// Occurs when creating or growing a table beyond the limits of the [`ResourceLimiter`].
+    ResourceLimitReached,
     /// Occurs when a table type does not satisfy the constraints of another.
     UnsatisfyingTableType {
         /// The unsatisfying [`TableType`].
@@ -68,6 +70,12 @@ impl Display for TableError {
                     offset, current,
                 )
             }
+            Self::ResourceLimitReached => {
+                write!(
+                    f,
+                    "tried to allocate table elements beyond the resource limits"
+                )
+            }
             Self::UnsatisfyingTableType {
                 unsatisfying,
                 required,
@@ -180,8 +188,13 @@ impl TableEntity {
     ///
     /// # Errors
     ///
-    /// If the table is grown beyond its maximum limits.
-    pub fn grow(&mut self, grow_by: usize) -> Result<(), TableError> {
+    /// - If the table is grown beyond its maximum limits.
+    /// - If the `limiter` denies the grow operation.
+    pub fn grow(
+        &mut self,
+        grow_by: usize,
+        limiter: Option<&mut dyn ResourceLimiter>,
+    ) -> Result<(), TableError> {
         let maximum = self.table_type.maximum().unwrap_or(u32::MAX as usize);
         let current = self.len();
         let new_len = current
@@ -192,10 +205,42 @@ impl TableEntity {
                 current,
                 grow_by,
             })?;
+        if let Some(limiter) = limiter {
+            if !limiter.table_growing(current, new_len, self.table_type.maximum()) {
+                return Err(TableError::ResourceLimitReached);
+            }
+        }
         self.elements.resize(new_len, None);
         Ok(())
     }
 
+    /// Returns all elements of the table.
+    pub(crate) fn elements(&self) -> &[Option<Func>] {
+        &self.elements[..]
+    }
+
+    /// Replaces all elements of the table.
+    ///
+    /// # Note
+    ///
+    /// This does not consult any resource limiter since it is used
+    /// to restore the table to a previous state.
+    ///
+    /// # Panics
+    ///
+    /// If the number of `elements` is out of the bounds of the [`TableType`].
+    pub(crate) fn set_elements(&mut self, elements: impl ExactSizeIterator<Item = Option<Func>>) {
+        let maximum = self.table_type.maximum().unwrap_or(u32::MAX as usize);
+        assert!(
+            (self.table_type.initial()..=maximum).contains(&elements.len()),
+            "encountered {} table elements out of bounds of {:?}",
+            elements.len(),
+            self.table_type,
+        );
+        self.elements.clear();
+        self.elements.extend(elements);
+    }
+
     /// Returns the element at the given offset if any.
     ///
     /// # Errors
@@ -246,10 +291,14 @@ impl Table {
     }
 
     /// Creates a new table to the store.
-    pub fn new(mut ctx: impl AsContextMut, table_type: TableType) -> Self {
-        ctx.as_context_mut()
-            .store
-            .alloc_table(TableEntity::new(table_type))
+    ///
+    /// # Errors
+    ///
+    /// If the [`ResourceLimiter`] of the store denies creating the table.
+    pub fn new(mut ctx: impl AsContextMut, table_type: TableType) -> Result<Self, TableError> {
+        let ctx = ctx.as_context_mut();
+        ctx.store.ensure_table_permitted(table_type)?;
+        Ok(ctx.store.alloc_table(TableEntity::new(table_type)))
     }
 
     /// Returns the type and limits of the table.
@@ -283,16 +332,19 @@ impl Table {
     ///
     /// # Errors
     ///
-    /// If the table is grown beyond its maximum limits.
+    /// - If the table is grown beyond its maximum limits.
+    /// - If the [`ResourceLimiter`] of the store denies the grow operation.
     ///
     /// # Panics
     ///
     /// Panics if `ctx` does not own this [`Table`].
     pub fn grow(&self, mut ctx: impl AsContextMut, grow_by: usize) -> Result<(), TableError> {
-        ctx.as_context_mut()
-            .store
-            .resolve_table_mut(*self)
-            .grow(grow_by)
+        let store = &mut ctx.as_context_mut().store;
+        if !store.bytes_permitted(grow_by.saturating_mul(TABLE_ELEMENT_BYTES)) {
+            return Err(TableError::ResourceLimitReached);
+        }
+        let (entity, limiter) = store.resolve_table_and_limiter_mut(*self);
+        entity.grow(grow_by, limiter)
     }
 
     /// Returns the element at the given offset if any.
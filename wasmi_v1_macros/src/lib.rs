@@ -0,0 +1,528 @@
+//! Procedural macros for the `wasmi_v1` WebAssembly interpreter.
+//!
+//! These macros are re-exported by `wasmi_v1` when its `macros` feature is enabled
+//! and should be used through it instead of depending on this crate directly.
+
+use core::mem;
+use proc_macro::TokenStream;
+use proc_macro2::{Group, Ident, Span, TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::{
+    parse_macro_input,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit_mut::{self, VisitMut},
+    Attribute,
+    Error,
+    Expr,
+    ExprLit,
+    FnArg,
+    ImplItem,
+    ImplItemFn,
+    Item,
+    ItemImpl,
+    Lit,
+    LitStr,
+    Macro,
+    Meta,
+    Pat,
+    Token,
+    Type,
+};
+
+/// Exposes the methods of an `impl` block as host functions of a Wasm host module.
+///
+/// Generates an associated `add_to_linker` function that wraps every method
+/// of the `impl` block as host function and defines it in a `Linker<Self>`
+/// under the given module name.
+///
+/// # Methods
+///
+/// - Methods with a `&self` or `&mut self` receiver operate on the host state
+///   of the `Store<Self>`.
+/// - Associated functions may take a `Caller<Self>` as first parameter.
+/// - Methods with a `&self` or `&mut self` receiver may take a `Caller<Self>`
+///   as first parameter after the receiver. Since the host state is owned by
+///   the `Caller` those methods are rewritten into associated functions taking
+///   the `Caller` that borrow the host state once via `Caller::host_data` or
+///   `Caller::host_data_mut` respectively and route all uses of `self` through it.
+///   Therefore their body must not use both `self` and the `Caller`.
+///   Uses of `self` within nested items and closures are not rewritten.
+///
+/// Parameters must be Wasm types or borrowed `&str`, `&[u8]` or `&mut [u8]`
+/// views into the linear memory of the caller. Methods with a `self` receiver
+/// or `Caller` receive views into copies of the borrowed regions which are
+/// written back for `&mut [u8]` views after the method returned successfully.
+///
+/// Methods may return `()`, a Wasm type, a tuple of Wasm types or a `Result` of those
+/// whose error type converts into a `Trap`.
+///
+/// # Names
+///
+/// The host function is defined under the first of the following that applies:
+///
+/// - The name given via `#[wasmi(name = "...")]`.
+/// - All names given via `#[doc(alias = "...")]` or `#[doc(alias("...", ..))]`.
+///   This allows to keep the Wasm names of host functions in their documentation.
+/// - The name of the method.
+///
+/// # Attributes
+///
+/// - `#[wasmi(name = "...")]`: Overrides the name of the host function.
+/// - `#[wasmi(skip)]`: Does not expose the method as host function.
+///
+/// # Example
+///
+/// ```ignore
+/// struct Host {
+///     counter: i32,
+///     messages: Vec<String>,
+/// }
+///
+/// #[wasmi_v1::host_module("env")]
+/// impl Host {
+///     /// Increases the counter and returns its new value.
+///     fn bump(&mut self, delta: i32) -> i32 {
+///         self.counter += delta;
+///         self.counter
+///     }
+///
+///     /// Logs the UTF-8 encoded string at `ptr` with length `len`.
+///     fn log(mut caller: Caller<Host>, ptr: u32, len: u32) -> Result<(), Trap> {
+///         let memory = caller
+///             .get_export("memory")
+///             .and_then(Extern::into_memory)
+///             .ok_or(TrapCode::MemoryAccessOutOfBounds)?;
+///         let message = WasmPtr::<u8>::new(ptr)
+///             .read_utf8_string(memory, &caller, len)
+///             .map_err(|_| TrapCode::MemoryAccessOutOfBounds)?;
+///         caller.host_data_mut().messages.push(message);
+///         Ok(())
+///     }
+///
+///     #[doc(alias = "strlen")]
+///     fn str_len(message: &str) -> u32 {
+///         message.len() as u32
+///     }
+/// }
+///
+/// let mut linker = <Linker<Host>>::new();
+/// Host::add_to_linker(&mut store, &mut linker)?;
+/// ```
+#[proc_macro_attribute]
+pub fn host_module(args: TokenStream, input: TokenStream) -> TokenStream {
+    let module = parse_macro_input!(args as LitStr);
+    let item = parse_macro_input!(input as ItemImpl);
+    expand_host_module(module, item)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// The host function options of a method given via `#[wasmi(..)]` attributes.
+#[derive(Default)]
+struct HostFuncOptions {
+    /// The name of the host function if overridden.
+    name: Option<LitStr>,
+    /// Is `true` if the method is not exposed as host function.
+    skip: bool,
+}
+
+impl HostFuncOptions {
+    /// Extracts the `#[wasmi(..)]` attributes from `attrs`.
+    ///
+    /// # Errors
+    ///
+    /// If a `#[wasmi(..)]` attribute is malformed.
+    fn extract(attrs: &mut Vec<Attribute>) -> syn::Result<Self> {
+        let mut options = Self::default();
+        let mut error = None;
+        attrs.retain(|attr| {
+            if !attr.path().is_ident("wasmi") {
+                return true;
+            }
+            let result = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    options.name = Some(meta.value()?.parse()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("skip") {
+                    options.skip = true;
+                    return Ok(());
+                }
+                Err(meta.error("expected `name = \"...\"` or `skip`"))
+            });
+            if let Err(err) = result {
+                error.get_or_insert(err);
+            }
+            false
+        });
+        match error {
+            Some(error) => Err(error),
+            None => Ok(options),
+        }
+    }
+}
+
+/// Returns the names given via `#[doc(alias = "...")]` or `#[doc(alias(...))]` in `attrs`.
+///
+/// # Errors
+///
+/// If a `#[doc(alias ..)]` attribute is malformed.
+fn doc_aliases(attrs: &[Attribute]) -> syn::Result<Vec<LitStr>> {
+    let mut aliases = Vec::new();
+    for attr in attrs {
+        let list = match &attr.meta {
+            Meta::List(list) if list.path.is_ident("doc") => list,
+            _ => continue,
+        };
+        let metas = list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        for meta in metas {
+            match meta {
+                Meta::NameValue(meta) if meta.path.is_ident("alias") => match meta.value {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(alias),
+                        ..
+                    }) => aliases.push(alias),
+                    value => {
+                        return Err(Error::new(value.span(), "expected a string literal"));
+                    }
+                },
+                Meta::List(meta) if meta.path.is_ident("alias") => {
+                    aliases.extend(
+                        meta.parse_args_with(Punctuated::<LitStr, Token![,]>::parse_terminated)?,
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(aliases)
+}
+
+/// The way a host function accesses its environment.
+enum Receiver {
+    /// Takes `&self` and operates on the host state.
+    Ref,
+    /// Takes `&mut self` and operates on the host state.
+    RefMut,
+    /// Takes a `Caller` as first parameter.
+    Caller,
+    /// Takes neither a receiver nor a `Caller`.
+    None,
+}
+
+/// Expands the `#[host_module]` attribute applied to `item`.
+fn expand_host_module(module: LitStr, mut item: ItemImpl) -> syn::Result<TokenStream2> {
+    if !item.generics.params.is_empty() {
+        return Err(Error::new(
+            item.generics.span(),
+            "`host_module` does not support generic impl blocks",
+        ));
+    }
+    if let Some((_, path, _)) = &item.trait_ {
+        return Err(Error::new(
+            path.span(),
+            "`host_module` does not support trait impl blocks",
+        ));
+    }
+    let mut definitions = Vec::new();
+    for impl_item in &mut item.items {
+        if let ImplItem::Fn(method) = impl_item {
+            let options = HostFuncOptions::extract(&mut method.attrs)?;
+            if options.skip {
+                continue;
+            }
+            definitions.push(expand_host_func(&module, options, method)?);
+        }
+    }
+    let self_ty = &item.self_ty;
+    let module_doc = format!(
+        " Defines all host functions of the `{}` host module in the `linker`.",
+        module.value()
+    );
+    item.items.push(syn::parse_quote! {
+        #[doc = #module_doc]
+        ///
+        /// # Errors
+        ///
+        /// If any of the host functions is already defined in the `linker`.
+        pub fn add_to_linker(
+            mut ctx: impl ::wasmi_v1::AsContextMut<UserState = #self_ty>,
+            linker: &mut ::wasmi_v1::Linker<#self_ty>,
+        ) -> ::core::result::Result<(), ::wasmi_v1::errors::LinkerError> {
+            #( #definitions )*
+            ::core::result::Result::Ok(())
+        }
+    });
+    Ok(quote! { #item })
+}
+
+/// Returns `true` if `ty` names the `Caller` type.
+fn is_caller(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident == "Caller")
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// A visitor that routes all uses of `self` in a method body through the host data bound to `this`.
+///
+/// # Note
+///
+/// - Paths starting with the `self` module such as `self::foo` are kept as is.
+/// - Nested items are kept as is since their `self` refers to the nested item.
+/// - Closures are kept as is so that they capture explicitly borrowed parts of
+///   the host data instead of the host data as a whole.
+struct SelfRouter<'a> {
+    /// The identifier to which the host data is bound.
+    this: &'a Ident,
+    /// The span of the first routed use of `self` if any.
+    first_use: Option<Span>,
+}
+
+impl SelfRouter<'_> {
+    /// Returns the identifier of the host data located at the `self` token at `span`.
+    fn route(&mut self, span: Span) -> Ident {
+        self.first_use.get_or_insert(span);
+        Ident::new(&self.this.to_string(), self.this.span().located_at(span))
+    }
+
+    /// Routes all uses of `self` within the `tokens` of a macro invocation.
+    fn route_tokens(&mut self, tokens: TokenStream2) -> TokenStream2 {
+        let mut tokens = tokens.into_iter().peekable();
+        let mut output = TokenStream2::new();
+        while let Some(token) = tokens.next() {
+            let routed = match token {
+                TokenTree::Ident(ident) if ident == "self" => {
+                    let is_path = matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == ':');
+                    match is_path {
+                        true => TokenTree::Ident(ident),
+                        false => TokenTree::Ident(self.route(ident.span())),
+                    }
+                }
+                TokenTree::Group(group) => {
+                    let mut routed =
+                        Group::new(group.delimiter(), self.route_tokens(group.stream()));
+                    routed.set_span(group.span());
+                    TokenTree::Group(routed)
+                }
+                token => token,
+            };
+            output.extend(Some(routed));
+        }
+        output
+    }
+}
+
+impl VisitMut for SelfRouter<'_> {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Path(path) if path.qself.is_none() && path.path.is_ident("self") => {
+                let segment = &mut path.path.segments[0];
+                segment.ident = self.route(segment.ident.span());
+            }
+            Expr::Closure(_) => {}
+            _ => visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+
+    fn visit_item_mut(&mut self, _item: &mut Item) {}
+
+    fn visit_macro_mut(&mut self, mac: &mut Macro) {
+        mac.tokens = self.route_tokens(mem::take(&mut mac.tokens));
+    }
+}
+
+/// Returns the span of the first use of `ident` in `tokens` if any.
+fn find_ident(tokens: TokenStream2, ident: &Ident) -> Option<Span> {
+    tokens.into_iter().find_map(|token| match token {
+        TokenTree::Ident(token) if token == *ident => Some(token.span()),
+        TokenTree::Group(group) => find_ident(group.stream(), ident),
+        _ => None,
+    })
+}
+
+/// Rewrites `method` taking a `self` receiver and a `Caller` into an associated function.
+///
+/// The host data of its `Caller` parameter named `caller` is borrowed once at
+/// the start of the body of `method` and all uses of `self` are routed through it.
+///
+/// # Errors
+///
+/// If the body of `method` uses both `self` and `caller`.
+fn route_self_through_caller(
+    method: &mut ImplItemFn,
+    caller: &Ident,
+    receiver: &Receiver,
+) -> syn::Result<()> {
+    let mut inputs = mem::take(&mut method.sig.inputs).into_iter();
+    inputs.next();
+    method.sig.inputs = inputs.collect();
+    let this = Ident::new("this", Span::mixed_site());
+    let mut router = SelfRouter {
+        this: &this,
+        first_use: None,
+    };
+    router.visit_block_mut(&mut method.block);
+    if router.first_use.is_none() {
+        return Ok(());
+    }
+    if let Some(span) = find_ident(method.block.to_token_stream(), caller) {
+        return Err(Error::new(
+            span,
+            format!(
+                "a method with `self` receiver must not use both `self` and `{caller}` \
+                 since the host data is borrowed from `{caller}` for the whole method body",
+            ),
+        ));
+    }
+    let host_data = match receiver {
+        Receiver::RefMut => {
+            if let Some(FnArg::Typed(pat_type)) = method.sig.inputs.first_mut() {
+                if let Pat::Ident(pat) = &mut *pat_type.pat {
+                    pat.mutability.get_or_insert_with(Default::default);
+                }
+            }
+            quote! { #caller.host_data_mut() }
+        }
+        _ => quote! { #caller.host_data() },
+    };
+    method
+        .block
+        .stmts
+        .insert(0, syn::parse_quote! { let #this = #host_data; });
+    Ok(())
+}
+
+/// Expands the definition of the host function for `method` in the `linker`.
+fn expand_host_func(
+    module: &LitStr,
+    options: HostFuncOptions,
+    method: &mut ImplItemFn,
+) -> syn::Result<TokenStream2> {
+    let sig = &method.sig;
+    if !sig.generics.params.is_empty() {
+        return Err(Error::new(
+            sig.generics.span(),
+            "host functions must not be generic",
+        ));
+    }
+    if let Some(asyncness) = sig.asyncness {
+        return Err(Error::new(
+            asyncness.span(),
+            "host functions must not be async",
+        ));
+    }
+    let ident = sig.ident.clone();
+    let names = match options.name {
+        Some(name) => vec![name],
+        None => {
+            let aliases = doc_aliases(&method.attrs)?;
+            match aliases.is_empty() {
+                true => vec![LitStr::new(&ident.to_string(), ident.span())],
+                false => aliases,
+            }
+        }
+    };
+    let mut receiver = Receiver::None;
+    let mut caller = None;
+    let mut params = Vec::new();
+    for (n, input) in sig.inputs.iter().enumerate() {
+        match input {
+            FnArg::Receiver(recv) => {
+                if recv.reference.is_none() {
+                    return Err(Error::new(
+                        recv.span(),
+                        "host functions must take `self` by reference",
+                    ));
+                }
+                receiver = match recv.mutability {
+                    Some(_) => Receiver::RefMut,
+                    None => Receiver::Ref,
+                };
+            }
+            FnArg::Typed(pat_type) if n == 0 && is_caller(&pat_type.ty) => {
+                receiver = Receiver::Caller;
+            }
+            FnArg::Typed(pat_type)
+                if n == 1
+                    && matches!(receiver, Receiver::Ref | Receiver::RefMut)
+                    && is_caller(&pat_type.ty) =>
+            {
+                match &*pat_type.pat {
+                    Pat::Ident(pat) if pat.by_ref.is_none() && pat.subpat.is_none() => {
+                        caller = Some(pat.ident.clone());
+                    }
+                    pat => {
+                        return Err(Error::new(
+                            pat.span(),
+                            "the `Caller` of a method with `self` receiver must be bound to an identifier",
+                        ));
+                    }
+                }
+            }
+            FnArg::Typed(pat_type) => {
+                if is_caller(&pat_type.ty) {
+                    return Err(Error::new(
+                        pat_type.ty.span(),
+                        "the `Caller` must be the first parameter after the `self` receiver",
+                    ));
+                }
+                if let Pat::Ident(pat) = &*pat_type.pat {
+                    if pat.by_ref.is_some() {
+                        return Err(Error::new(pat.span(), "unsupported parameter pattern"));
+                    }
+                }
+                params.push((format_ident!("param{}", params.len()), pat_type.ty.clone()));
+            }
+        }
+    }
+    let param_idents = params.iter().map(|(ident, _)| ident).collect::<Vec<_>>();
+    let param_decls = params
+        .iter()
+        .map(|(ident, ty)| quote! { #ident: #ty })
+        .collect::<Vec<_>>();
+    let span = sig.span();
+    if let Some(caller) = &caller {
+        route_self_through_caller(method, caller, &receiver)?;
+        receiver = Receiver::Caller;
+    }
+    let call = match receiver {
+        Receiver::Ref => quote_spanned! {span=>
+            Self::#ident(caller.host_data(), #( #param_idents ),*)
+        },
+        Receiver::RefMut => quote_spanned! {span=>
+            Self::#ident(caller.host_data_mut(), #( #param_idents ),*)
+        },
+        Receiver::Caller => quote_spanned! {span=>
+            Self::#ident(caller, #( #param_idents ),*)
+        },
+        Receiver::None => quote_spanned! {span=>
+            Self::#ident(#( #param_idents ),*)
+        },
+    };
+    let call = quote_spanned! {span=>
+        ::wasmi_v1::HostModuleResults::into_results(#call)
+    };
+    let closure = match receiver {
+        Receiver::Ref | Receiver::Caller => quote_spanned! {span=>
+            |caller: ::wasmi_v1::Caller<'_, Self>, #( #param_decls ),*| #call
+        },
+        Receiver::RefMut => quote_spanned! {span=>
+            |mut caller: ::wasmi_v1::Caller<'_, Self>, #( #param_decls ),*| #call
+        },
+        Receiver::None => quote_spanned! {span=>
+            |#( #param_decls ),*| #call
+        },
+    };
+    let func = format_ident!("__{}", ident, span = Span::call_site());
+    Ok(quote! {
+        let #func = ::wasmi_v1::Func::wrap(&mut ctx, #closure);
+        #( linker.define(#module, #names, #func)?; )*
+    })
+}
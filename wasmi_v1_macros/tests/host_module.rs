@@ -0,0 +1,286 @@
+//! Tests for the `host_module` procedural macro.
+
+use wasmi_v1::{
+    core::{Trap, TrapCode},
+    Caller,
+    Engine,
+    Extern,
+    Instance,
+    Linker,
+    Module,
+    Store,
+};
+
+/// A type alias named `Result` that is not a `core::result::Result`.
+mod custom {
+    pub type Result = i32;
+}
+
+#[derive(Debug, Default)]
+struct Host {
+    counter: i32,
+    log: Vec<String>,
+}
+
+#[wasmi_v1::host_module("env")]
+impl Host {
+    /// Increases the counter and returns its new value.
+    fn bump(&mut self, delta: i32) -> i32 {
+        self.counter += delta;
+        self.counter
+    }
+
+    /// Returns the current value of the counter.
+    fn get(&self) -> i32 {
+        self.counter
+    }
+
+    /// Logs the UTF-8 encoded string at `ptr` with length `len`.
+    fn log(mut caller: Caller<Host>, ptr: u32, len: u32) -> Result<(), Trap> {
+        let memory = caller
+            .get_export("memory")
+            .and_then(Extern::into_memory)
+            .ok_or(TrapCode::MemoryAccessOutOfBounds)?;
+        let message = wasmi_v1::WasmPtr::<u8>::new(ptr)
+            .read_utf8_string(memory, &caller, len)
+            .map_err(|_| TrapCode::MemoryAccessOutOfBounds)?;
+        caller.host_data_mut().log.push(message);
+        Ok(())
+    }
+
+    /// Returns the number of logged messages plus the size of the caller's memory in pages.
+    fn stats(caller: Caller<Host>) -> u32 {
+        let memory = caller
+            .get_export("memory")
+            .and_then(Extern::into_memory)
+            .unwrap();
+        caller.host_data().log.len() as u32 + memory.current_pages(&caller).0 as u32
+    }
+
+    /// Sets the counter to the sum of `a` and `b`, logs it and returns the previous counter.
+    fn reset(&mut self, _caller: Caller<Host>, a: i32, b: i32) -> i32 {
+        /// A nested item whose `self` must not be routed through the `Caller`.
+        struct Pair(i32, i32);
+
+        impl Pair {
+            fn sum(&self) -> i32 {
+                self.0 + self.1
+            }
+        }
+
+        let previous = self.counter;
+        let counter = &mut self.counter;
+        let mut set = |value: i32| *counter = value;
+        set(Pair(a, b).sum());
+        self.log.push(format!("{}", self.counter));
+        previous
+    }
+
+    /// Returns the length of the given string.
+    #[wasmi(name = "strlen")]
+    fn str_len(message: &str) -> u32 {
+        message.len() as u32
+    }
+
+    /// Logs the given string.
+    fn append(&mut self, message: &str) {
+        self.log.push(message.into());
+    }
+
+    /// Converts the given bytes to ASCII upper case and returns the number of logged messages.
+    fn upper(caller: Caller<Host>, bytes: &mut [u8]) -> u32 {
+        bytes.make_ascii_uppercase();
+        caller.host_data().log.len() as u32
+    }
+
+    /// Returns the answer.
+    #[doc(alias = "answer")]
+    #[doc(alias("the_answer", "answer_v2"))]
+    fn get_answer() -> custom::Result {
+        42
+    }
+
+    /// Traps if `value` is zero.
+    fn check(value: i32) -> Result<i32, TrapCode> {
+        if value == 0 {
+            return Err(TrapCode::Unreachable);
+        }
+        Ok(value)
+    }
+
+    /// Not exposed to Wasm.
+    #[wasmi(skip)]
+    #[allow(dead_code)]
+    fn internal(&self) -> usize {
+        self.log.len()
+    }
+}
+
+fn setup() -> (Store<Host>, Instance) {
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (import "env" "bump" (func $bump (param i32) (result i32)))
+            (import "env" "get" (func $get (result i32)))
+            (import "env" "log" (func $log (param i32 i32)))
+            (import "env" "strlen" (func $strlen (param i32 i32) (result i32)))
+            (import "env" "check" (func $check (param i32) (result i32)))
+            (import "env" "stats" (func $stats (result i32)))
+            (import "env" "answer" (func $answer (result i32)))
+            (import "env" "the_answer" (func $the_answer (result i32)))
+            (import "env" "answer_v2" (func $answer_v2 (result i32)))
+            (import "env" "append" (func $append (param i32 i32)))
+            (import "env" "upper" (func $upper (param i32 i32) (result i32)))
+            (import "env" "reset" (func $reset (param i32 i32) (result i32)))
+            (memory (export "memory") 1)
+            (data (i32.const 0) "hello")
+            (func (export "run") (result i32)
+                (drop (call $bump (i32.const 40)))
+                (drop (call $bump (i32.const 2)))
+                (call $log (i32.const 0) (i32.const 5))
+                (call $get)
+            )
+            (func (export "strlen") (result i32)
+                (call $strlen (i32.const 0) (i32.const 5))
+            )
+            (func (export "check") (param i32) (result i32)
+                (call $check (local.get 0))
+            )
+            (func (export "stats") (result i32)
+                (call $stats)
+            )
+            (func (export "shout") (result i32)
+                (call $append (i32.const 0) (i32.const 5))
+                (call $upper (i32.const 0) (i32.const 5))
+            )
+            (func (export "reset") (param i32 i32) (result i32)
+                (call $reset (local.get 0) (local.get 1))
+            )
+            (func (export "answers") (result i32)
+                (i32.add
+                    (call $answer)
+                    (i32.add (call $the_answer) (call $answer_v2))
+                )
+            )
+        )
+        "#,
+    )
+    .unwrap();
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, Host::default());
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut linker = <Linker<Host>>::new();
+    Host::add_to_linker(&mut store, &mut linker).unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .ensure_no_start(&mut store)
+        .unwrap();
+    (store, instance)
+}
+
+#[test]
+fn host_module_works() {
+    let (mut store, instance) = setup();
+    let run = instance
+        .get_export(&store, "run")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<(), i32, _>(&store)
+        .unwrap();
+    assert_eq!(run.call(&mut store, ()).unwrap(), 42);
+    assert_eq!(store.state().log, vec![String::from("hello")]);
+    let strlen = instance
+        .get_export(&store, "strlen")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<(), i32, _>(&store)
+        .unwrap();
+    assert_eq!(strlen.call(&mut store, ()).unwrap(), 5);
+    let stats = instance
+        .get_export(&store, "stats")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<(), i32, _>(&store)
+        .unwrap();
+    assert_eq!(stats.call(&mut store, ()).unwrap(), 2);
+}
+
+#[test]
+fn host_module_uses_doc_aliases() {
+    let (mut store, instance) = setup();
+    let answers = instance
+        .get_export(&store, "answers")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<(), i32, _>(&store)
+        .unwrap();
+    assert_eq!(answers.call(&mut store, ()).unwrap(), 3 * 42);
+    assert_eq!(Host::get_answer(), 42);
+}
+
+#[test]
+fn host_module_borrows_with_caller() {
+    let (mut store, instance) = setup();
+    let shout = instance
+        .get_export(&store, "shout")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<(), i32, _>(&store)
+        .unwrap();
+    assert_eq!(shout.call(&mut store, ()).unwrap(), 1);
+    assert_eq!(store.state().log, vec![String::from("hello")]);
+    let memory = instance
+        .get_export(&store, "memory")
+        .and_then(Extern::into_memory)
+        .unwrap();
+    assert_eq!(&memory.data(&store)[..5], b"HELLO");
+}
+
+#[test]
+fn host_module_routes_self_through_caller() {
+    let (mut store, instance) = setup();
+    let reset = instance
+        .get_export(&store, "reset")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<(i32, i32), i32, _>(&store)
+        .unwrap();
+    assert_eq!(reset.call(&mut store, (40, 2)).unwrap(), 0);
+    assert_eq!(reset.call(&mut store, (1, 2)).unwrap(), 42);
+    assert_eq!(store.state().counter, 3);
+    assert_eq!(
+        store.state().log,
+        vec![String::from("42"), String::from("3")]
+    );
+}
+
+#[test]
+fn host_module_converts_traps() {
+    let (mut store, instance) = setup();
+    let check = instance
+        .get_export(&store, "check")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<i32, i32, _>(&store)
+        .unwrap();
+    assert_eq!(check.call(&mut store, 1).unwrap(), 1);
+    assert!(matches!(
+        check.call(&mut store, 0),
+        Err(Trap::Code(TrapCode::Unreachable))
+    ));
+}
+
+#[test]
+fn host_module_rejects_duplicates() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, Host::default());
+    let mut linker = <Linker<Host>>::new();
+    Host::add_to_linker(&mut store, &mut linker).unwrap();
+    assert!(Host::add_to_linker(&mut store, &mut linker).is_err());
+}
+
+#[test]
+fn host_module_compile_errors() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
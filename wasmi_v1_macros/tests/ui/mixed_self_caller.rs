@@ -0,0 +1,13 @@
+struct Host {
+    counter: i32,
+}
+
+#[wasmi_v1::host_module("env")]
+impl Host {
+    fn bump(&mut self, caller: wasmi_v1::Caller<Host>) -> i32 {
+        self.counter += 1;
+        caller.host_data().counter
+    }
+}
+
+fn main() {}